@@ -0,0 +1,90 @@
+// Time to extract each fixture's outline with each engine built, from the
+// bytes in memory, and the font engine's long report with its pages decoded
+// on one thread and on the whole rayon pool, quick mode against full
+// extraction on a long standard, and the line heuristics on synthetic plain
+// text:
+//
+//   cargo bench --bench extraction [-- <fixture name filter>]
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::{analyze_text, Extractor, TextOptions};
+use common::pdf_builder::{Page, PdfBuilder};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+fn extraction(c: &mut Criterion) {
+    let fixtures = common::fixtures();
+    for (engine, name) in [(Engine::Text, "text"), (Engine::Font, "font")] {
+        if !engine.is_built() {
+            continue;
+        }
+        let extractor = Extractor::builder().engine(engine).build().unwrap();
+        let mut group = c.benchmark_group(name);
+        for (fixture, pdf) in &fixtures {
+            group.throughput(Throughput::Bytes(pdf.len() as u64));
+            group.bench_with_input(BenchmarkId::from_parameter(fixture), pdf, |b, pdf| {
+                b.iter(|| extractor.extract_bytes(pdf).unwrap())
+            });
+        }
+        group.finish();
+    }
+}
+
+fn parallel_pages(c: &mut Criterion) {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let pdf = common::long_report();
+    let extractor = Extractor::builder().engine(Engine::Font).build().unwrap();
+    let mut group = c.benchmark_group("pages");
+    for (name, threads) in [("sequential", 1), ("parallel", rayon::current_num_threads())] {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+        group.bench_function(name, |b| b.iter(|| pool.install(|| extractor.extract_bytes(&pdf).unwrap())));
+    }
+    group.finish();
+}
+
+// A 100-page standard, one chapter a page, with a bookmark per chapter or
+// without any
+fn standard(bookmarks: bool) -> Vec<u8> {
+    (1..=100)
+        .fold(PdfBuilder::new(), |builder, chapter| {
+            let title = format!("{}. Clause {}", chapter, chapter);
+            let builder = builder.page(Page::new().heading(1, &title).body(40));
+            if bookmarks { builder.bookmark(1, &title, chapter) } else { builder }
+        })
+        .build()
+}
+
+fn quick_mode(c: &mut Criterion) {
+    let (plain, bookmarked) = (standard(false), standard(true));
+    let full = Extractor::default();
+    let quick = Extractor::builder().quick(15).build().unwrap();
+    let mut group = c.benchmark_group("quick");
+    group.sample_size(10);
+    group.bench_function("full", |b| b.iter(|| full.extract_bytes(&plain).unwrap()));
+    group.bench_function("first-pages", |b| b.iter(|| quick.extract_bytes(&plain).unwrap()));
+    group.bench_function("bookmarks", |b| b.iter(|| quick.extract_bytes(&bookmarked).unwrap()));
+    group.finish();
+}
+
+fn heuristics(c: &mut Criterion) {
+    let config = ExtractorConfig { max_headings: Some(usize::MAX), page_heading_limit: 0, ..ExtractorConfig::default() };
+    let options = TextOptions { config, ..TextOptions::default() };
+    let mut group = c.benchmark_group("heuristics");
+    group.sample_size(10);
+    // 5,000 headings, each repeated once, for the duplicate checks
+    let headings = common::many_headings(500, 10);
+    let repeated = format!("{}\x0C{}", headings, headings);
+    group.bench_function("5000-headings", |b| b.iter(|| analyze_text(&repeated, options.clone())));
+    // 50,000 lines, half of them headings, for the per-line analysis
+    let lines = common::many_headings(1000, 25);
+    group.throughput(Throughput::Elements(50_000));
+    group.bench_function("50000-lines", |b| b.iter(|| analyze_text(&lines, options.clone())));
+    group.finish();
+}
+
+criterion_group!(benches, extraction, parallel_pages, quick_mode, heuristics);
+criterion_main!(benches);
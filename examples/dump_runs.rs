@@ -0,0 +1,17 @@
+// Print every text run of a PDF as one JSON object per line:
+//
+//   cargo run --example dump_runs -- input/2.pdf > runs.jsonl
+use anyhow::{Context, Result};
+use std::io::Write;
+
+fn main() -> Result<()> {
+    let path = std::env::args().nth(1).context("usage: dump_runs <file.pdf>")?;
+    let bytes = std::fs::read(&path).with_context(|| format!("Failed to read {}", path))?;
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    for run in adobe1a::layout::extract_runs_from_bytes(&bytes)? {
+        serde_json::to_writer(&mut out, &run)?;
+        writeln!(out)?;
+    }
+    out.flush()?;
+    Ok(())
+}
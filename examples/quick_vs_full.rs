@@ -0,0 +1,63 @@
+// Time quick mode against full extraction on the same PDFs, and show what
+// quick mode gave up:
+//
+//   cargo run --release --example quick_vs_full -- big.pdf [runs] [quick pages]
+//
+// A directory runs every PDF in it. Each mode's time is the median of `runs`
+// (default 5) passes over all the files.
+use adobe1a::config::ExtractorConfig;
+use adobe1a::extract_outline;
+use adobe1a::timing::Stats;
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let input = PathBuf::from(args.next().context("usage: quick_vs_full <file.pdf|dir> [runs] [quick pages]")?);
+    let runs: usize = args.next().map(|n| n.parse()).transpose()?.unwrap_or(5).max(1);
+    let window: usize = args.next().map(|n| n.parse()).transpose()?.unwrap_or(15);
+
+    let files: Vec<PathBuf> = match input.is_dir() {
+        true => {
+            let mut files: Vec<PathBuf> = std::fs::read_dir(&input)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pdf")))
+                .collect();
+            files.sort();
+            files
+        }
+        false => vec![input],
+    };
+
+    let full = ExtractorConfig::default();
+    let quick = ExtractorConfig { quick: Some(window), ..ExtractorConfig::default() };
+    let (full_time, full_headings, _) = time(&files, &full, runs)?;
+    let (quick_time, quick_headings, partial) = time(&files, &quick, runs)?;
+
+    println!("{} files, median of {} runs", files.len(), runs);
+    println!("full:  {:>9.1?}  {} headings", full_time, full_headings);
+    println!("quick: {:>9.1?}  {} headings, {} partial ({} pages)", quick_time, quick_headings, partial, window);
+    println!("speedup: {:.1}x", full_time.as_secs_f64() / quick_time.as_secs_f64().max(f64::EPSILON));
+    Ok(())
+}
+
+// Median time over all files, with the heading and partial-output counts of
+// the last run
+fn time(files: &[PathBuf], config: &ExtractorConfig, runs: usize) -> Result<(Duration, usize, usize)> {
+    let mut times = Vec::with_capacity(runs);
+    let (mut headings, mut partial) = (0, 0);
+    for _ in 0..runs {
+        (headings, partial) = (0, 0);
+        let start = Instant::now();
+        for file in files {
+            let outline = extract_outline(file, config, &mut Stats::new())
+                .with_context(|| format!("Failed to process {}", file.display()))?;
+            headings += outline.outline.len();
+            partial += outline.partial as usize;
+        }
+        times.push(start.elapsed());
+    }
+    times.sort();
+    Ok((times[runs / 2], headings, partial))
+}
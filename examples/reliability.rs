@@ -0,0 +1,79 @@
+// Print a reliability table (precision per confidence bucket) for outlines
+// checked against reference outlines, to fit or re-fit `--calibration`:
+//
+//   cargo run --release --example reliability -- outputs/ references/ [buckets]
+//
+// Each `<name>.json` in the outputs directory is compared with the reference
+// of the same name, which needs only `text` and `page` for each heading.
+// Outputs written with `--explain` are bucketed by their raw scores, the
+// numbers a calibration curve maps from; others by their confidences.
+// Outputs with a `pages_missing_text` warning are left out, since pages the
+// text engine couldn't read would count against it.
+use adobe1a::calibration::reliability;
+use adobe1a::{output, Heading, Outline};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Deserialize)]
+struct Reference {
+    outline: Vec<ReferenceHeading>,
+}
+
+#[derive(Deserialize)]
+struct ReferenceHeading {
+    text: String,
+    page: usize,
+}
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let usage = "usage: reliability <outputs dir> <references dir> [buckets]";
+    let outputs = PathBuf::from(args.next().context(usage)?);
+    let references = PathBuf::from(args.next().context(usage)?);
+    let buckets: usize = args.next().map(|n| n.parse()).transpose()?.unwrap_or(10);
+
+    let (mut extracted, mut reference) = (Vec::new(), Vec::new());
+    let mut files = 0;
+    let mut skipped = 0;
+    for entry in std::fs::read_dir(&outputs)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().filter(|_| path.extension().is_some_and(|ext| ext == "json")) else {
+            continue;
+        };
+        let Ok(bytes) = std::fs::read(references.join(name)) else {
+            eprintln!("no reference for {}", path.display());
+            continue;
+        };
+        let outline: Outline = output::from_json(&std::fs::read(&path)?)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if outline.warnings.iter().any(|warning| warning.code == "pages_missing_text") {
+            eprintln!("skipping {}: some pages' text couldn't be extracted", path.display());
+            skipped += 1;
+            continue;
+        }
+        let truth: Reference = serde_json::from_slice(&bytes)
+            .with_context(|| format!("Failed to read reference {}", references.join(name).display()))?;
+        // Pages are offset per file so headings of different files never match
+        let offset = files * 1_000_000;
+        extracted.extend(outline.outline.into_iter().map(|heading| Heading { page: heading.page + offset, ..heading }));
+        reference.extend(truth.outline.into_iter().map(|heading| Heading {
+            text: heading.text,
+            page: heading.page + offset,
+            ..Heading::default()
+        }));
+        files += 1;
+    }
+
+    let table = reliability(&extracted, &reference, |h| h.raw_score.unwrap_or(h.confidence), buckets);
+    println!("{} files, {} headings, {} in the references", files, extracted.len(), reference.len());
+    if skipped > 0 {
+        println!("{} files left out for pages without extracted text", skipped);
+    }
+    println!("{:>11}  {:>8}  {:>7}  {:>9}", "score", "headings", "correct", "precision");
+    for bucket in table.iter().filter(|bucket| bucket.headings > 0) {
+        println!("{:.2}-{:.2}  {:>8}  {:>7}  {:>9.2}",
+            bucket.low, bucket.high, bucket.headings, bucket.correct, bucket.precision().unwrap_or(0.0));
+    }
+    Ok(())
+}
@@ -0,0 +1,36 @@
+// Print headings page by page as they are found, stopping once a given
+// number of headings has been printed:
+//
+//   cargo run --example stream_pages -- input/2.pdf 10
+use adobe1a::{ExtractEvent, Extractor};
+use anyhow::{Context, Result};
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let path = PathBuf::from(args.next().context("usage: stream_pages <file.pdf> [max headings]")?);
+    let limit: usize = args.next().map(|n| n.parse()).transpose()?.unwrap_or(usize::MAX);
+
+    let mut printed = 0;
+    let outline = Extractor::default().extract_pages(&path, |event| {
+        match event {
+            ExtractEvent::Title { title, .. } => println!("{}", title),
+            ExtractEvent::Page(page) => {
+                for warning in &page.warnings {
+                    eprintln!("warning: {}", warning.message);
+                }
+                for heading in page.headings {
+                    println!("  p.{} {} {}", page.page, heading.level, heading.text);
+                    printed += 1;
+                    if printed >= limit {
+                        return ControlFlow::Break(());
+                    }
+                }
+            }
+        }
+        ControlFlow::Continue(())
+    })?;
+    println!("{} headings", outline.outline.len());
+    Ok(())
+}
@@ -0,0 +1,184 @@
+// The PDF's own bookmarks (/Outlines), which authoring tools write from the
+// document's real heading structure
+
+use std::collections::HashMap;
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+
+use crate::font_utils::{page_box, upright_y, y_from_top};
+use crate::pages::decode_text_string;
+use crate::portfolio::collect_name_tree;
+use crate::trees::TreeWalk;
+use crate::Warning;
+
+pub struct Bookmark {
+    // 1 for a top-level bookmark
+    pub depth: usize,
+    pub title: String,
+    pub page: usize,
+    // Where on the page it opens, as a fraction of the page height from
+    // the top; None for the page as a whole
+    pub top: Option<f64>,
+}
+
+// A place in the document a bookmark or link opens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Destination {
+    pub page: usize,
+    // Fraction of the page height from the top edge, as the page is
+    // displayed; None when the destination shows the whole page or leaves
+    // the position as it was, or on a page turned a quarter, when it gives
+    // no left edge to place it by
+    pub top: Option<f64>,
+}
+
+// Every bookmark pointing at a page of `doc`, in reading order, with a
+// warning for each tree that had to be cut short. Bookmarks that open a link
+// or another file are left out, though their children aren't.
+pub fn bookmarks(doc: &Document) -> (Vec<Bookmark>, Vec<Warning>) {
+    let first = doc.catalog().ok()
+        .and_then(|catalog| catalog.get(b"Outlines").ok())
+        .and_then(|outlines| doc.dereference(outlines).ok())
+        .and_then(|(_, outlines)| outlines.as_dict().ok())
+        .and_then(|outlines| outlines.get(b"First").ok());
+    let Some(first) = first else {
+        return (Vec::new(), Vec::new());
+    };
+
+    let mut names_walk = TreeWalk::default();
+    let walker = Walker { doc, destinations: Destinations::new(doc, &mut names_walk) };
+    let mut bookmarks = Vec::new();
+    let mut walk = TreeWalk::default();
+    walker.walk(first, 1, &mut walk, &mut bookmarks);
+    let warnings = walk.warning("bookmark tree").into_iter()
+        .chain(names_walk.warning("named destination tree"))
+        .collect();
+    (bookmarks, warnings)
+}
+
+struct Walker<'a> {
+    doc: &'a Document,
+    destinations: Destinations<'a>,
+}
+
+impl Walker<'_> {
+    // One bookmark and its following siblings, each followed by its children.
+    // `walk` stops a /Next or /First that loops back.
+    fn walk(&self, first: &Object, depth: usize, walk: &mut TreeWalk, bookmarks: &mut Vec<Bookmark>) {
+        let mut item = Some(first);
+        while let Some(node) = item {
+            if !walk.enter(node, depth) {
+                return;
+            }
+            let Ok(dict) = self.doc.dereference(node).and_then(|(_, node)| node.as_dict()) else { return };
+
+            let title = dict.get(b"Title").ok()
+                .and_then(|title| self.doc.dereference(title).ok())
+                .and_then(|(_, title)| title.as_str().ok())
+                .map(|title| decode_text_string(title).split_whitespace().collect::<Vec<_>>().join(" "))
+                .unwrap_or_default();
+            if let (false, Some(target)) = (title.is_empty(), self.destinations.target(dict)) {
+                bookmarks.push(Bookmark { depth, title, page: target.page, top: target.top });
+            }
+            if let Ok(child) = dict.get(b"First") {
+                self.walk(child, depth + 1, walk, bookmarks);
+            }
+            item = dict.get(b"Next").ok();
+        }
+    }
+}
+
+// Resolves the destinations of a document's bookmarks and links to pages
+pub struct Destinations<'a> {
+    doc: &'a Document,
+    pages: HashMap<ObjectId, usize>,
+    named: HashMap<String, &'a Object>,
+}
+
+impl<'a> Destinations<'a> {
+    // Named destinations are read up front; `walk` guards their name tree
+    pub fn new(doc: &'a Document, walk: &mut TreeWalk) -> Self {
+        Destinations {
+            doc,
+            pages: doc.get_pages().into_iter().map(|(page, id)| (id, page as usize)).collect(),
+            named: named_destinations(doc, walk),
+        }
+    }
+
+    // Where a bookmark or link annotation leads, through /Dest or a GoTo action
+    pub fn target(&self, item: &Dictionary) -> Option<Destination> {
+        if let Ok(dest) = item.get(b"Dest") {
+            return self.destination(dest, true);
+        }
+        let (_, action) = self.doc.dereference(item.get(b"A").ok()?).ok()?;
+        let action = action.as_dict().ok()?;
+        if action.get(b"S").and_then(Object::as_name).ok()? != b"GoTo" {
+            return None;
+        }
+        self.destination(action.get(b"D").ok()?, true)
+    }
+
+    // An explicit destination is an array starting with the page; a named
+    // one is looked up once, and may be wrapped in a dictionary's /D
+    fn destination(&self, dest: &Object, follow_names: bool) -> Option<Destination> {
+        let (_, dest) = self.doc.dereference(dest).ok()?;
+        match dest {
+            Object::Array(array) => {
+                let page_id = array.first()?.as_reference().ok()?;
+                let page = *self.pages.get(&page_id)?;
+                let top = destination_top(array)
+                    .and_then(|(left, top)| upright_y(self.doc, page_id, left, top))
+                    .map(|y| y_from_top(y, &page_box(self.doc, page_id)));
+                Some(Destination { page, top })
+            }
+            Object::Dictionary(dict) => self.destination(dict.get(b"D").ok()?, false),
+            Object::Name(name) | Object::String(name, _) if follow_names => {
+                self.destination(self.named.get(&decode_text_string(name))?, false)
+            }
+            _ => None,
+        }
+    }
+}
+
+// The top edge of the view an explicit destination opens, with its left
+// edge where the destination gives one: [page /XYZ left top zoom], [page
+// /FitH top], [page /FitBH top] or [page /FitR left bottom right top]. A
+// null top keeps the current position. Both are in default user space.
+fn destination_top(array: &[Object]) -> Option<(Option<f64>, f64)> {
+    let (left, top) = match array.get(1)?.as_name().ok()? {
+        b"XYZ" => (Some(2), 3),
+        b"FitH" | b"FitBH" => (None, 2),
+        b"FitR" => (Some(2), 5),
+        _ => return None,
+    };
+    let coordinate = |index: usize| match array.get(index)? {
+        Object::Integer(value) => Some(*value as f64),
+        Object::Real(value) => Some(*value as f64),
+        _ => None,
+    };
+    Some((left.and_then(coordinate), coordinate(top)?))
+}
+
+// Named destinations from the catalog's /Dests dictionary (PDF 1.1) and its
+// /Names /Dests name tree
+fn named_destinations<'a>(doc: &'a Document, walk: &mut TreeWalk) -> HashMap<String, &'a Object> {
+    let Ok(catalog) = doc.catalog() else {
+        return HashMap::new();
+    };
+    let mut named = HashMap::new();
+    if let Some((_, Object::Dictionary(dests))) = catalog.get(b"Dests").ok().and_then(|d| doc.dereference(d).ok()) {
+        for (name, dest) in dests.iter() {
+            named.insert(decode_text_string(name), dest);
+        }
+    }
+    let tree = catalog.get(b"Names").ok()
+        .and_then(|names| doc.dereference(names).ok())
+        .and_then(|(_, names)| names.as_dict().ok())
+        .and_then(|names| names.get(b"Dests").ok());
+    if let Some(tree) = tree {
+        let mut entries = Vec::new();
+        collect_name_tree(doc, tree, 0, walk, &mut entries);
+        named.extend(entries);
+    }
+    named
+}
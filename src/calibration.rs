@@ -0,0 +1,179 @@
+//! Maps each engine's raw heading scores onto one confidence scale, so a
+//! single threshold means the same thing whichever engine ran. A curve is a
+//! piecewise-linear map through (raw, calibrated) points, and can be fitted
+//! from a [`reliability`] table of outlines checked against known headings.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::Heading;
+
+/// A non-decreasing piecewise-linear map from raw scores to confidences,
+/// both in 0..=1. Scores outside the first and last points take their
+/// values.
+///
+/// ```
+/// use adobe1a::calibration::Curve;
+///
+/// let curve = Curve::new(vec![(0.6, 0.3), (0.8, 0.5), (1.0, 0.95)])?;
+/// assert!((curve.apply(0.7) - 0.4).abs() < 1e-9);
+/// assert_eq!(curve.apply(0.8), 0.5);
+/// assert!((curve.apply(0.9) - 0.725).abs() < 1e-9);
+/// // Outside the points, the nearest one holds
+/// assert_eq!(curve.apply(0.2), 0.3);
+/// assert_eq!(curve.apply(1.0), 0.95);
+/// assert_eq!(Curve::identity().apply(0.73), 0.73);
+///
+/// let error = |points| Curve::new(points).unwrap_err();
+/// assert_eq!(error(vec![]), "a curve needs at least one point");
+/// assert_eq!(error(vec![(0.5, 0.5), (0.5, 0.6)]), "raw scores must increase from point to point, but 0.5 follows 0.5");
+/// assert_eq!(error(vec![(0.5, 0.6), (0.7, 0.4)]), "confidences may not decrease, but 0.4 follows 0.6");
+/// assert_eq!(error(vec![(0.5, 1.2)]), "1.2 is outside 0-1");
+/// # Ok::<(), String>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve {
+    points: Vec<(f64, f64)>,
+}
+
+impl Curve {
+    /// Checks that the points are in 0..=1, with raw scores increasing and
+    /// confidences never decreasing, so calibration keeps the engines' order
+    pub fn new(points: Vec<(f64, f64)>) -> std::result::Result<Curve, String> {
+        if points.is_empty() {
+            return Err("a curve needs at least one point".into());
+        }
+        if let Some(value) = points.iter().flat_map(|&(raw, calibrated)| [raw, calibrated]).find(|v| !(0.0..=1.0).contains(v)) {
+            return Err(format!("{} is outside 0-1", value));
+        }
+        for pair in points.windows(2) {
+            let ((raw0, cal0), (raw1, cal1)) = (pair[0], pair[1]);
+            if raw1 <= raw0 {
+                return Err(format!("raw scores must increase from point to point, but {} follows {}", raw1, raw0));
+            }
+            if cal1 < cal0 {
+                return Err(format!("confidences may not decrease, but {} follows {}", cal1, cal0));
+            }
+        }
+        Ok(Curve { points })
+    }
+
+    /// Leaves scores as they are
+    pub fn identity() -> Curve {
+        Curve { points: vec![(0.0, 0.0), (1.0, 1.0)] }
+    }
+
+    pub fn apply(&self, raw: f64) -> f64 {
+        let (first, last) = (self.points[0], self.points[self.points.len() - 1]);
+        if raw <= first.0 {
+            return first.1;
+        }
+        if raw >= last.0 {
+            return last.1;
+        }
+        let i = self.points.partition_point(|&(x, _)| x <= raw);
+        let ((x0, y0), (x1, y1)) = (self.points[i - 1], self.points[i]);
+        y0 + (y1 - y0) * (raw - x0) / (x1 - x0)
+    }
+}
+
+/// A curve for each engine. Bookmarks aren't scored and keep a confidence
+/// of 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    pub text: Curve,
+    pub font: Curve,
+}
+
+// Both curves are the identity until a labelled corpus is available to fit
+// them against; `reliability` shows how far off that is
+impl Default for Calibration {
+    fn default() -> Self {
+        Calibration { text: Curve::identity(), font: Curve::identity() }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CalibrationFile {
+    text: Option<Vec<(f64, f64)>>,
+    font: Option<Vec<(f64, f64)>>,
+}
+
+impl Calibration {
+    /// Reads curves from a JSON file such as
+    /// `{ "font": [[0.6, 0.3], [0.8, 0.5], [1.0, 0.95]] }`; an engine left
+    /// out keeps the default curve
+    pub fn load(path: &Path) -> Result<Calibration> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read calibration {}", path.display()))?;
+        let file: CalibrationFile = serde_json::from_str(&text)
+            .with_context(|| format!("Invalid calibration {}", path.display()))?;
+        let curve = |points: Option<Vec<(f64, f64)>>, engine: &str| match points {
+            Some(points) => Curve::new(points)
+                .map_err(|e| anyhow::anyhow!("Invalid calibration {}: {} curve: {}", path.display(), engine, e)),
+            None => Ok(Curve::identity()),
+        };
+        Ok(Calibration { text: curve(file.text, "text")?, font: curve(file.font, "font")? })
+    }
+
+    /// The curve for the engine named by a heading source ("text", "font")
+    pub fn curve(&self, engine: &str) -> Option<&Curve> {
+        match engine {
+            "text" => Some(&self.text),
+            "font" => Some(&self.font),
+            _ => None,
+        }
+    }
+}
+
+/// Precision within one confidence bucket
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    // Confidences in `low..high` (the last bucket includes 1.0)
+    pub low: f64,
+    pub high: f64,
+    pub headings: usize,
+    // Headings that match one in the reference outline
+    pub correct: usize,
+}
+
+impl Bucket {
+    pub fn precision(&self) -> Option<f64> {
+        (self.headings > 0).then(|| self.correct as f64 / self.headings as f64)
+    }
+}
+
+/// A reliability table: for each of `buckets` equal confidence ranges, how
+/// many of the `extracted` headings fall in it and how many of those are
+/// real, i.e. match a `reference` heading on the same page with the same
+/// text (ignoring case and spacing). A well-calibrated engine's precision
+/// tracks the bucket's confidence. `score` picks the number to bucket by,
+/// e.g. `|h| h.confidence` or the raw score.
+///
+/// ```
+/// use adobe1a::{calibration::reliability, Heading};
+///
+/// let heading = |text: &str, confidence| Heading { text: text.into(), page: 1, confidence, ..Default::default() };
+/// let extracted = [heading("Scope", 0.95), heading("Page 3 of 9", 0.9), heading("Terms", 0.3), heading("Figure 2:", 0.2)];
+/// let reference = [heading("SCOPE", 0.0), heading("Terms", 0.0)];
+/// let table = reliability(&extracted, &reference, |h| h.confidence, 2);
+/// assert_eq!((table[0].headings, table[0].correct), (2, 1));
+/// assert_eq!((table[1].headings, table[1].correct, table[1].precision()), (2, 1, Some(0.5)));
+/// ```
+pub fn reliability(extracted: &[Heading], reference: &[Heading], score: impl Fn(&Heading) -> f64, buckets: usize) -> Vec<Bucket> {
+    let buckets = buckets.max(1);
+    let key = |heading: &Heading| (heading.page, crate::sanitize::normalize_for_match(&heading.text, None));
+    let reference: std::collections::HashSet<_> = reference.iter().map(key).collect();
+    let mut table: Vec<Bucket> = (0..buckets)
+        .map(|i| Bucket { low: i as f64 / buckets as f64, high: (i + 1) as f64 / buckets as f64, headings: 0, correct: 0 })
+        .collect();
+    for heading in extracted {
+        let i = ((score(heading).clamp(0.0, 1.0) * buckets as f64) as usize).min(buckets - 1);
+        table[i].headings += 1;
+        table[i].correct += reference.contains(&key(heading)) as usize;
+    }
+    table
+}
@@ -0,0 +1,69 @@
+//! Trimming levels that hold a single heading. When detection finds one
+//! H1 ("1 Introduction") and everything else under it, a navigation tree
+//! built from the outline opens with a pointless single node.
+
+use crate::{Heading, Warning};
+
+/// Promotes the headings under a level's only heading, for each depth in
+/// `depths` (1 for H1) that has exactly one heading in the whole outline.
+/// Everything nested under it moves up a level, so it ends up beside its
+/// former children; the heading itself keeps its level. Depths are handled
+/// from the top down, so a level left with one heading by the collapse
+/// above it is collapsed in turn. Each collapse is returned as a warning.
+///
+/// ```
+/// use adobe1a::{collapse::collapse_singletons, Heading};
+///
+/// let outline = |headings: &[(&str, &str)]| -> Vec<Heading> {
+///     headings.iter().map(|&(level, text)| Heading { level: level.into(), text: text.into(), page: 1, ..Default::default() }).collect()
+/// };
+/// let levels = |headings: &[Heading]| -> Vec<String> { headings.iter().map(|h| h.level.clone()).collect() };
+///
+/// // The only H1 is all detection found of the top level
+/// let mut headings = outline(&[("H1", "1 Introduction"), ("H2", "2 Methods"), ("H3", "2.1 Sampling"), ("H2", "3 Results")]);
+/// let warnings = collapse_singletons(&mut headings, &[1, 2, 3, 4]);
+/// assert_eq!(levels(&headings), ["H1", "H1", "H2", "H1"]);
+/// assert_eq!(warnings.len(), 1);
+/// assert_eq!(warnings[0].code, "singleton_collapsed");
+/// assert_eq!(warnings[0].message, "\"1 Introduction\" is the only H1; the 3 headings under it were moved up a level");
+///
+/// // Off, or for other levels only, the outline is left alone
+/// let mut untouched = outline(&[("H1", "1 Introduction"), ("H2", "2 Methods"), ("H3", "2.1 Sampling"), ("H2", "3 Results")]);
+/// assert!(collapse_singletons(&mut untouched, &[]).is_empty());
+/// assert!(collapse_singletons(&mut untouched, &[3]).is_empty());
+/// assert_eq!(levels(&untouched), ["H1", "H2", "H3", "H2"]);
+///
+/// // A document with one chapter collapses too when asked, which is why
+/// // it's off by default
+/// let mut chapter = outline(&[("H1", "Chapter 1: Onboarding"), ("H2", "Accounts"), ("H2", "Equipment")]);
+/// collapse_singletons(&mut chapter, &[2]);
+/// assert_eq!(levels(&chapter), ["H1", "H2", "H2"]);
+/// collapse_singletons(&mut chapter, &[1]);
+/// assert_eq!(levels(&chapter), ["H1", "H1", "H1"]);
+///
+/// // A lone heading without anything under it has nothing to promote
+/// let mut flat = outline(&[("H2", "Notes"), ("H1", "Summary")]);
+/// assert!(collapse_singletons(&mut flat, &[1, 2]).is_empty());
+/// ```
+pub fn collapse_singletons(headings: &mut [Heading], depths: &[u8]) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let deepest = headings.iter().map(Heading::depth).max().unwrap_or(0);
+    for depth in 1..=deepest {
+        if !depths.contains(&depth) {
+            continue;
+        }
+        let mut at_depth = (0..headings.len()).filter(|&i| headings[i].depth() == depth);
+        let (Some(only), None) = (at_depth.next(), at_depth.next()) else { continue };
+        let under = headings[only + 1..].iter().take_while(|heading| heading.depth() > depth).count();
+        if under == 0 {
+            continue;
+        }
+        for heading in &mut headings[only + 1..=only + under] {
+            heading.level = format!("H{}", heading.depth() - 1);
+        }
+        warnings.push(Warning::new("singleton_collapsed", format!(
+            "\"{}\" is the only H{}; the {} heading{} under it {} moved up a level",
+            headings[only].text, depth, under, if under == 1 { "" } else { "s" }, if under == 1 { "was" } else { "were" })));
+    }
+    warnings
+}
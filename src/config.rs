@@ -0,0 +1,385 @@
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::calibration::Calibration;
+use crate::levels::LevelOverrides;
+use crate::lexicon::Lexicon;
+
+// Tuning knobs shared by both extraction engines
+#[derive(Debug, Clone)]
+pub struct ExtractorConfig {
+    pub engine: Engine,
+    pub hidden_text: HiddenText,
+    // Fraction of the page height at the top and bottom treated as running
+    // header/footer bands; text there never becomes a heading. 0 disables.
+    pub margin_band: f64,
+    // Confidence added to a heading in the upper third of a page when the
+    // previous page ended in body text, i.e. a section starting on a new page
+    pub top_of_page_bonus: f64,
+    // Confidence added to a line with a rule drawn just beneath it
+    pub underline_bonus: f64,
+    // Confidence added to a candidate drawn in a colour other than the
+    // document's dominant text colour
+    pub color_bonus: f64,
+    // Confidence added to a heading that an internal link or a bookmark
+    // lands on
+    pub link_bonus: f64,
+    // Confidence taken from a heading that starts in lowercase, which is only
+    // kept at all when its size, weight or position stands out
+    pub lowercase_penalty: f64,
+    // Share of a document's letters in uppercase above which it's taken to be
+    // set entirely in capitals (old typewritten or OCRed files), where
+    // capitalization says nothing about headings
+    pub all_caps_ratio: f64,
+    // Report scored title candidates alongside the chosen title
+    pub title_candidates: bool,
+    // Headings with fewer alphabetic characters than this after cleaning are dropped
+    pub min_heading_letters: usize,
+    // Word limits for a heading, and for one ending in a period (longer ones
+    // are sentences)
+    pub max_heading_words: usize,
+    pub max_sentence_words: usize,
+    // The font engine keeps only its most confident headings: this many, or
+    // when unset `headings_per_page` times the page count (at least
+    // MIN_HEADING_CAP), so a memo gets a tight cap and a long standard a loose one
+    pub max_headings: Option<usize>,
+    pub headings_per_page: f64,
+    // Most headings kept from one page of typical length (proportionally
+    // more on a fuller page) before the rest of its weakest are dropped as
+    // noise; headings continuing the document's numbering don't count. 0
+    // disables the check.
+    pub page_heading_limit: usize,
+    // Headings less confident than this are dropped from the output; 0
+    // keeps them all
+    pub min_confidence: f64,
+    // Look for several documents concatenated into one PDF and outline each
+    // under its own title
+    pub split_documents: bool,
+    // How the PDFs attached to a portfolio are read
+    pub portfolio: Portfolio,
+    // Read the attached PDF whose name matches this pattern (any case, `*`
+    // and `?` wildcards) instead of the PDF holding it
+    pub attachment: Option<String>,
+    // Only these pages are read; None reads them all
+    pub pages: Option<PageRanges>,
+    // Quick mode, for cataloguing: only the first this many pages are read,
+    // the PDF's bookmarks replace heading detection when it has any, and a
+    // plausible document info title wins over the first page's
+    pub quick: Option<usize>,
+    // Keep each heading's `sources`, which are otherwise left out of the output
+    pub include_sources: bool,
+    // Maps each engine's raw scores to the confidences in the output
+    pub calibration: Calibration,
+    // Keep each heading's `raw_score`, its confidence before calibration,
+    // and `detected_level`, its level before a level override
+    pub include_raw_scores: bool,
+    // Levels set by pattern, applied to headings as they're detected
+    pub level_overrides: LevelOverrides,
+    // Word and phrase lists used by the heuristics
+    pub lexicon: Lexicon,
+    // The documents' language as a BCP 47 tag ("tr-TR"), which decides how
+    // heading text is case-folded for matching; None takes each PDF's own
+    // `/Lang`, if it has one
+    pub language: Option<String>,
+    // Where the title comes from
+    pub title: TitleMode,
+    // Give each heading a snippet of the text under it, of up to this many
+    // characters; None leaves snippets out
+    pub snippet_chars: Option<usize>,
+    // Record where each heading starts in its page's text and runs
+    pub offsets: bool,
+    // Record how far down its page each heading is
+    pub positions: bool,
+    // Bytes a document's extraction may hold in its own buffers (decoded
+    // content streams, text runs, page text) before it gives up on reading
+    // the whole document: it's read again in quick mode, without snippets
+    // or offsets, and warned about. None sets no limit.
+    pub max_memory: Option<usize>,
+    // Casing of heading text, and whether the title is recased too
+    pub heading_case: CaseStyle,
+    pub normalize_title: bool,
+    // Give every heading a section number, made up where none is printed
+    pub synthesize_numbers: bool,
+    // Depths (1 for H1) whose only heading, when there's just one, has the
+    // headings under it moved up a level; empty leaves the outline alone
+    pub collapse_singletons: Vec<u8>,
+    // Drop the headings of a sparse first page with the title on it, which
+    // are the cover's name, address and date lines
+    pub cover_detection: bool,
+    // When a document is read as a memo or letter rather than a report, and
+    // the page count at or below which `Auto` does so
+    pub short_document: ShortDocument,
+    pub short_document_pages: usize,
+}
+
+impl Default for ExtractorConfig {
+    fn default() -> Self {
+        ExtractorConfig {
+            engine: Engine::Auto,
+            hidden_text: HiddenText::Auto,
+            margin_band: 0.06,
+            top_of_page_bonus: 0.05,
+            underline_bonus: 0.1,
+            color_bonus: 0.1,
+            link_bonus: 0.1,
+            lowercase_penalty: 0.1,
+            all_caps_ratio: 0.8,
+            title_candidates: false,
+            min_heading_letters: 2,
+            max_heading_words: 12,
+            max_sentence_words: 8,
+            max_headings: None,
+            headings_per_page: 1.5,
+            page_heading_limit: 8,
+            min_confidence: 0.0,
+            split_documents: false,
+            portfolio: Portfolio::Combine,
+            attachment: None,
+            pages: None,
+            quick: None,
+            include_sources: false,
+            calibration: Calibration::default(),
+            include_raw_scores: false,
+            level_overrides: LevelOverrides::default(),
+            lexicon: Lexicon::default(),
+            language: None,
+            title: TitleMode::Detect,
+            snippet_chars: None,
+            offsets: false,
+            positions: false,
+            max_memory: None,
+            heading_case: CaseStyle::Preserve,
+            normalize_title: false,
+            synthesize_numbers: false,
+            collapse_singletons: Vec::new(),
+            cover_detection: true,
+            short_document: ShortDocument::Auto,
+            short_document_pages: 3,
+        }
+    }
+}
+
+// Smallest adaptive heading cap, however short the document
+pub const MIN_HEADING_CAP: usize = 10;
+
+impl ExtractorConfig {
+    // How many headings the font engine keeps for a document of this length
+    pub fn heading_cap(&self, page_count: usize) -> usize {
+        self.max_headings.unwrap_or_else(|| {
+            ((page_count as f64 * self.headings_per_page).ceil() as usize).max(MIN_HEADING_CAP)
+        })
+    }
+
+    // Whether the document is searched for a title at all
+    pub fn detects_title(&self) -> bool {
+        self.title == TitleMode::Detect
+    }
+
+    // Whether a document of `page_count` pages is read as a memo or letter
+    pub fn is_short_document(&self, page_count: usize) -> bool {
+        match self.short_document {
+            ShortDocument::Auto => page_count <= self.short_document_pages,
+            ShortDocument::Always => true,
+            ShortDocument::Never => false,
+        }
+    }
+
+    // Whether page `page` (from 1) is read at all
+    pub fn includes_page(&self, page: usize) -> bool {
+        self.pages.as_ref().is_none_or(|pages| pages.contains(page))
+            && self.quick.is_none_or(|window| page <= window)
+    }
+}
+
+/// Pages to read, as inclusive ranges numbered from 1, parsed from a list
+/// like `1-20,450-470,500`. Collecting page numbers runs consecutive pages
+/// together, and the ranges print back as a list that parses.
+///
+/// ```
+/// use adobe1a::config::PageRanges;
+///
+/// let pages: PageRanges = "1-20, 450-470".parse().unwrap();
+/// assert!(pages.contains(20) && !pages.contains(21));
+/// assert!("20-1".parse::<PageRanges>().is_err());
+///
+/// let pages: PageRanges = [3, 5, 6, 7, 12].into_iter().collect();
+/// assert_eq!(pages.to_string(), "3, 5-7, 12");
+/// assert_eq!(pages.to_string().parse::<PageRanges>(), Ok(pages));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRanges(Vec<RangeInclusive<usize>>);
+
+impl PageRanges {
+    pub fn contains(&self, page: usize) -> bool {
+        self.0.iter().any(|range| range.contains(&page))
+    }
+
+    pub fn ranges(&self) -> &[RangeInclusive<usize>] {
+        &self.0
+    }
+}
+
+impl FromIterator<usize> for PageRanges {
+    fn from_iter<I: IntoIterator<Item = usize>>(pages: I) -> Self {
+        let mut ranges: Vec<RangeInclusive<usize>> = Vec::new();
+        for page in pages {
+            match ranges.last_mut() {
+                Some(range) if *range.end() + 1 == page => *range = *range.start()..=page,
+                _ => ranges.push(page..=page),
+            }
+        }
+        PageRanges(ranges)
+    }
+}
+
+impl std::fmt::Display for PageRanges {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, range) in self.0.iter().enumerate() {
+            let separator = if i == 0 { "" } else { ", " };
+            match range.start() == range.end() {
+                true => write!(f, "{}{}", separator, range.start())?,
+                false => write!(f, "{}{}-{}", separator, range.start(), range.end())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PageRanges {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let page = |n: &str| match n.trim().parse::<usize>() {
+            Ok(0) => Err("pages are numbered from 1".to_string()),
+            Ok(page) => Ok(page),
+            Err(_) => Err(format!("{:?} isn't a page number", n.trim())),
+        };
+        let ranges = s.split(',')
+            .map(|part| {
+                let range = match part.split_once('-') {
+                    Some((first, last)) => page(first)?..=page(last)?,
+                    None => page(part)?..=page(part)?,
+                };
+                if range.is_empty() {
+                    return Err(format!("{:?} is reversed; write {}-{}", part.trim(), range.end(), range.start()));
+                }
+                Ok(range)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(PageRanges(ranges))
+    }
+}
+
+/// Where an outline's title comes from. Anything but `Detect` skips the title
+/// detection of every reader: the first page's scoring, the document info
+/// title and quick mode's bookmarks alike.
+///
+/// ```
+/// use adobe1a::{config::{ExtractorConfig, TitleMode}, extract_outline, timing::Stats, TitleSource};
+///
+/// let title = |title: TitleMode, quick: Option<usize>| -> anyhow::Result<(String, TitleSource)> {
+///     let config = ExtractorConfig { title, quick, ..ExtractorConfig::default() };
+///     let outline = extract_outline("fixtures/nul-heading.pdf".as_ref(), &config, &mut Stats::new())?;
+///     Ok((outline.title, outline.title_source))
+/// };
+/// for quick in [None, Some(15)] {
+///     let fixed = TitleMode::Fixed("  Annual Report, FY 2024 ".to_string());
+///     assert_eq!(title(fixed, quick)?, ("  Annual Report, FY 2024 ".to_string(), TitleSource::Override));
+///     assert_eq!(title(TitleMode::Filename, quick)?, ("Nul Heading".to_string(), TitleSource::Filename));
+/// }
+/// // Detection reads the first page, or in quick mode the document info first
+/// assert_eq!(title(TitleMode::Detect, None)?.1, TitleSource::Content);
+/// assert_eq!(title(TitleMode::Detect, Some(15))?, ("Report".to_string(), TitleSource::Metadata));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TitleMode {
+    /// Detected in the document
+    #[default]
+    Detect,
+    /// This text, exactly as given
+    Fixed(String),
+    /// Derived from the file name, as when nothing is detected
+    Filename,
+}
+
+// Which extraction engine produces the outline. Engines left out of the
+// build by their cargo feature aren't offered on the command line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Engine {
+    // The text engine, falling back to the font engine when it finds no
+    // headings; whichever of the two is built when only one is
+    #[default]
+    Auto,
+    // pdf-extract text with line heuristics (feature `engine-text`)
+    #[cfg_attr(not(feature = "engine-text"), value(skip))]
+    Text,
+    // lopdf font-run analysis (feature `engine-font`)
+    #[cfg_attr(not(feature = "engine-font"), value(skip))]
+    Font,
+}
+
+impl Engine {
+    pub fn is_built(self) -> bool {
+        match self {
+            Engine::Auto => true,
+            Engine::Text => cfg!(feature = "engine-text"),
+            Engine::Font => cfg!(feature = "engine-font"),
+        }
+    }
+}
+
+// What to do with text drawn in an invisible render mode (Tr 3 or 7), which
+// is how OCR layers over scans and accessibility duplicates are stored. Only
+// the font engine sees render modes: pdf-extract's text holds every layer.
+// So `Include` and `Exclude` send `Engine::Auto` straight to the font
+// engine, and the text engine refuses them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HiddenText {
+    // Use invisible text only on pages that have no visible text at all
+    #[default]
+    Auto,
+    Include,
+    Exclude,
+}
+
+// How heading text is cased in the output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CaseStyle {
+    // As found in the document
+    #[default]
+    Preserve,
+    // "Risk Management for Small Teams"
+    Title,
+    // "Risk management for small teams"
+    Sentence,
+    // "RISK MANAGEMENT FOR SMALL TEAMS"
+    Upper,
+}
+
+// Whether a document is read as a memo or letter: its headings need no
+// blank lines around them, colon and keyword headings count for more, a
+// subject line ("RE: Contract Amendment No. 4") is its title and closes an
+// address block of no headings, and there are too few pages to tell
+// running headers and footers by their repeating
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ShortDocument {
+    // Documents of at most `short_document_pages` pages
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+// What to do with a PDF portfolio, whose content is in attached PDFs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Portfolio {
+    // One outline: the cover sheet's headings, then each attachment under
+    // an H1 named after it
+    #[default]
+    Combine,
+    // A separate outline per attachment, named after it
+    Split,
+    // Only the cover sheet
+    Ignore,
+}
@@ -0,0 +1,58 @@
+// Cover pages: a title block with the client's name, an address, "Submitted
+// by" and a date around it, all large, capitalized and on lines of their
+// own. Every one of those looks like a heading, so a cover page's headings
+// are dropped up to a numbered heading or a standard section name.
+
+use lopdf::Document;
+
+use crate::config::ExtractorConfig;
+use crate::functions::is_canonical_section;
+use crate::numbers::printed_number;
+use crate::{font_utils, Heading, Outline, TitleSource, Warning};
+
+// Characters of visible text page 1 may hold to read as a cover; a page of
+// running text has several times this many
+const COVER_MAX_CHARS: usize = 400;
+
+// A number in front of a cover line past this is a street number or a
+// year ("200 Main Street", "2024 Annual Report"), not a section's
+const MAX_SECTION_NUMBER: u32 = 99;
+
+// Drops the headings on page 1 when it reads as a cover page: the document
+// goes on past it, a title was found on it, and it holds little text. Only
+// the headings before the page's first numbered or standard section are
+// the cover's; the body can start on the same page. Returns a warning
+// saying how many were dropped, if any were.
+pub fn drop_cover_headings(outline: &mut Outline, doc: &Document, config: &ExtractorConfig) -> Option<Warning> {
+    let pages = doc.get_pages();
+    if !config.cover_detection || pages.len() < 2 || outline.title_source != TitleSource::Content {
+        return None;
+    }
+    let &page_id = pages.get(&1)?;
+    let chars: usize = font_utils::page_runs(doc, page_id, 1).iter()
+        .filter(|run| run.visible)
+        .map(|run| run.text.chars().filter(|c| !c.is_whitespace()).count())
+        .sum();
+    if chars >= COVER_MAX_CHARS {
+        return None;
+    }
+
+    let opens_section = |heading: &Heading| {
+        let numbered = printed_number(&heading.text)
+            .is_some_and(|number| number[0].parse::<u32>().map_or(true, |first| first <= MAX_SECTION_NUMBER));
+        numbered || is_canonical_section(&heading.text, &config.lexicon)
+    };
+    let body = outline.outline.iter()
+        .position(|heading| heading.page > 1 || (heading.page == 1 && opens_section(heading)))
+        .unwrap_or(outline.outline.len());
+    let before = outline.outline.len();
+    let mut index = 0;
+    outline.outline.retain(|heading| {
+        index += 1;
+        index > body || heading.page != 1
+    });
+    let dropped = before - outline.outline.len();
+    (dropped > 0).then(|| Warning::new("cover_page", format!(
+        "page 1 reads as a cover page ({} characters of text), so {} unnumbered heading{} on it {} dropped",
+        chars, dropped, if dropped == 1 { "" } else { "s" }, if dropped == 1 { "was" } else { "were" })))
+}
@@ -0,0 +1,205 @@
+//! Several documents concatenated into one PDF, such as a batch of memos
+//! scanned together. A page only starts a new document when independent
+//! signals agree, since splitting one document in two is worse than
+//! missing a boundary.
+
+use std::collections::HashMap;
+
+use lopdf::Document;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::ExtractorConfig;
+use crate::font_utils::{self, TextRun};
+use crate::{pages, Heading, Outline};
+
+/// A document found inside a PDF, spanning physical pages
+/// `first_page..=last_page` of it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SubDocument {
+    pub title: String,
+    pub first_page: usize,
+    pub last_page: usize,
+}
+
+impl SubDocument {
+    pub fn contains(&self, page: usize) -> bool {
+        (self.first_page..=self.last_page).contains(&page)
+    }
+}
+
+// How many of the boundary signals must agree on a page
+const MIN_SIGNALS: usize = 2;
+
+// A page whose text stops above this fraction of the page height ended
+// whatever was on it
+const ENDED_SHORT: f64 = 0.75;
+
+// A cover-like opening line is at least this many times the body size and
+// centred to within this fraction of the page width
+const OPENING_SIZE_RATIO: f64 = 1.3;
+const CENTERED_TOLERANCE: f64 = 0.1;
+
+// A memo's subject line names it better than anything else on the page
+static SUBJECT_LINE: Lazy<Regex> = Lazy::new(||
+    Regex::new(r"(?i)^\s*(?:subject|re)\s*:\s*(\S.*)$").unwrap());
+
+// Runs whose baselines are this close (in points) are on the same line
+const LINE_TOLERANCE: f64 = 2.0;
+
+// What the boundary signals look at on one page
+struct PageProfile {
+    // Size of the first line below the header band, and whether it's centred
+    opening: Option<(f64, bool)>,
+    // Fraction of the page height down to the lowest text above the footer band
+    bottom: f64,
+    // Header band text without digits, so a running page number doesn't
+    // read as a new letterhead
+    letterhead: String,
+}
+
+// The documents in `doc`, or nothing when it reads as a single document
+pub(crate) fn detect(doc: &Document, config: &ExtractorConfig) -> Vec<SubDocument> {
+    let pages = doc.get_pages();
+    let mut size_chars: HashMap<i64, usize> = HashMap::new();
+    let profiles: Vec<PageProfile> = pages.iter()
+        .map(|(&page, &page_id)| {
+            let runs: Vec<TextRun> = font_utils::skip_drop_caps(font_utils::page_runs(doc, page_id, page as usize)).into_iter()
+                .filter(|run| run.visible && !run.text.trim().is_empty())
+                .collect();
+            for run in &runs {
+                *size_chars.entry((run.size * 10.0).round() as i64).or_default() += run.text.trim().chars().count();
+            }
+            profile(&runs, &font_utils::page_box(doc, page_id), config.margin_band)
+        })
+        .collect();
+    let Some(body_size) = font_utils::most_common(size_chars).map(|size| size as f64 / 10.0) else {
+        return Vec::new();
+    };
+    let labels = pages::page_labels(doc);
+
+    let mut starts = vec![1];
+    for page in 2..=profiles.len() {
+        let (previous, current) = (&profiles[page - 2], &profiles[page - 1]);
+        let opens_cover = current.opening.is_some_and(|(size, centered)| {
+            centered && size >= body_size * OPENING_SIZE_RATIO
+        });
+        let signals = [
+            opens_cover && previous.bottom < ENDED_SHORT,
+            labels.as_ref().is_some_and(|labels| label_restarts(&labels[page - 2], &labels[page - 1])),
+            !current.letterhead.is_empty() && current.letterhead != previous.letterhead,
+        ];
+        if signals.iter().filter(|&&signal| signal).count() >= MIN_SIGNALS {
+            log::debug!("page {} starts a new document ({:?})", page, signals);
+            starts.push(page);
+        }
+    }
+    if starts.len() < 2 {
+        return Vec::new();
+    }
+
+    let page_numbers: Vec<u32> = pages.keys().copied().collect();
+    starts.iter().enumerate()
+        .map(|(i, &first_page)| {
+            let title = document_title(doc, page_numbers[first_page - 1], &profiles[first_page - 1], config)
+                .unwrap_or_else(|| format!("Document {}", i + 1));
+            let last_page = starts.get(i + 1).map_or(profiles.len(), |next| next - 1);
+            SubDocument { title, first_page, last_page }
+        })
+        .collect()
+}
+
+fn profile(runs: &[TextRun], page_box: &[f64; 4], band: f64) -> PageProfile {
+    let in_header = |run: &TextRun| band > 0.0 && font_utils::y_from_top(run.y, page_box) < band;
+    let in_footer = |run: &TextRun| band > 0.0 && font_utils::y_from_top(run.y, page_box) > 1.0 - band;
+    let body: Vec<&TextRun> = runs.iter().filter(|run| !in_header(run) && !in_footer(run)).collect();
+
+    // The opening line is the highest one on the page, y growing upwards
+    let opening = body.iter().map(|run| run.y).reduce(f64::max).map(|top| {
+        let line: Vec<&&TextRun> = body.iter().filter(|run| (run.y - top).abs() <= LINE_TOLERANCE).collect();
+        let size = line.iter().map(|run| run.size).fold(0.0, f64::max);
+        let x0 = line.iter().map(|run| run.x).fold(f64::INFINITY, f64::min);
+        let x1 = line.iter().map(|run| run.x + run.width).fold(f64::NEG_INFINITY, f64::max);
+        let page_width = page_box[2] - page_box[0];
+        let offset = ((x0 + x1) / 2.0 - (page_box[0] + page_box[2]) / 2.0).abs();
+        (size, offset <= page_width * CENTERED_TOLERANCE)
+    });
+    let bottom = body.iter()
+        .map(|run| font_utils::y_from_top(run.y, page_box))
+        .fold(0.0, f64::max);
+    let letterhead = runs.iter()
+        .filter(|run| in_header(run))
+        .flat_map(|run| run.text.split_whitespace())
+        .map(|word| word.chars().filter(|c| !c.is_ascii_digit()).collect::<String>().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    PageProfile { opening, bottom, letterhead }
+}
+
+// The subject line of a memo, else the best-scoring title line that isn't
+// the letterhead repeated on every page
+fn document_title(doc: &Document, page_number: u32, profile: &PageProfile, config: &ExtractorConfig) -> Option<String> {
+    let text = doc.extract_text(&[page_number]).unwrap_or_default();
+    if let Some(subject) = text.lines().find_map(|line| SUBJECT_LINE.captures(line)) {
+        return Some(subject[1].trim().to_string());
+    }
+    crate::page_title_scores(doc, page_number, config).into_iter()
+        .map(|(title, _)| title)
+        .find(|title| profile.letterhead.is_empty() || !profile.letterhead.contains(&title.to_lowercase()))
+}
+
+// Numbering starts over: "1", "i" or "A-1" after a different label
+fn label_restarts(previous: &str, current: &str) -> bool {
+    let last_token = |label: &str| label.rsplit(|c: char| !c.is_alphanumeric()).next().unwrap_or("").to_string();
+    matches!(last_token(current).as_str(), "1" | "i" | "I") && last_token(previous) != last_token(current)
+}
+
+// Puts each document's headings under an H1 with its title, one level down
+pub(crate) fn group(outline: &mut Outline, documents: Vec<SubDocument>) {
+    let headings = std::mem::take(&mut outline.outline);
+    for document in &documents {
+        outline.outline.push(Heading {
+            level: "H1".to_string(),
+            text: document.title.clone(),
+            page: document.first_page,
+            confidence: 1.0,
+            ..Default::default()
+        });
+        outline.outline.extend(headings.iter()
+            .filter(|heading| document.contains(heading.page))
+            .map(|heading| heading.clone().demoted()));
+    }
+    outline.documents = documents;
+}
+
+/// One outline per document of an outline extracted with
+/// `split_documents`, titled after the document. Page numbers count from
+/// the document's first page; its `documents` entry gives the pages it
+/// spans in the original PDF. Empty when no documents were found.
+pub fn split(outline: &Outline) -> Vec<Outline> {
+    outline.documents.iter()
+        .map(|document| {
+            // Grouping left the documents' own headings at H2 and below
+            let headings = outline.outline.iter()
+                .filter(|heading| heading.depth() > 1 && document.contains(heading.page))
+                .map(|heading| Heading {
+                    level: format!("H{}", heading.depth() - 1),
+                    page: heading.page - document.first_page + 1,
+                    ..heading.clone()
+                })
+                .collect();
+            Outline {
+                title: document.title.clone(),
+                outline: headings,
+                page_count: Some(document.last_page - document.first_page + 1),
+                documents: vec![document.clone()],
+                document_id: outline.document_id.clone(),
+                engine: outline.engine.clone(),
+                extractor_version: outline.extractor_version.clone(),
+                ..Outline::default()
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,405 @@
+// Character codes in a font's strings to Unicode. A simple font's codes are
+// single bytes, given meaning by its /Encoding: a base encoding (WinAnsi,
+// MacRoman or Standard) and a /Differences array naming the glyphs of some
+// codes, read through the Adobe Glyph List. Codes the encoding leaves
+// unnamed come from the font's /ToUnicode map when it has one. Composite
+// fonts' strings are still read as UTF-8.
+
+use std::collections::HashMap;
+
+use lopdf::{Dictionary, Document, Object};
+
+use crate::fonts::strip_subset;
+
+pub(crate) enum FontEncoding {
+    // Text for each of the 256 codes; None where nothing names the code
+    Simple(Vec<Option<String>>),
+    // A symbolic font with neither an encoding nor a ToUnicode map: its
+    // codes say nothing about what's drawn, so its text is skipped
+    Unmapped,
+    Utf8,
+}
+
+impl FontEncoding {
+    pub(crate) fn of(doc: &Document, font: &Dictionary) -> FontEncoding {
+        let subtype = font.get(b"Subtype").and_then(Object::as_name).unwrap_or_default();
+        if subtype == b"Type0" {
+            return FontEncoding::Utf8;
+        }
+        let encoding = font.get(b"Encoding").and_then(|encoding| doc.dereference(encoding)).ok().map(|(_, encoding)| encoding);
+        let (base, differences) = match encoding {
+            Some(Object::Name(name)) => (base_encoding(name), Vec::new()),
+            Some(Object::Dictionary(dict)) => (
+                dict.get(b"BaseEncoding").and_then(Object::as_name).ok().and_then(base_encoding),
+                dict.get(b"Differences").and_then(|d| doc.dereference(d)).and_then(|(_, d)| d.as_array())
+                    .map(|d| differences_names(doc, d)).unwrap_or_default(),
+            ),
+            _ => (None, Vec::new()),
+        };
+        let to_unicode = to_unicode(doc, font);
+        let differences: Vec<(u8, String)> = differences.into_iter()
+            .filter_map(|(code, name)| Some((code, glyph_text(&name)?)))
+            .collect();
+        let symbolic = subtype == b"Type3" || is_symbolic(doc, font);
+        if symbolic && base.is_none() && differences.is_empty() && to_unicode.is_empty() {
+            return FontEncoding::Unmapped;
+        }
+
+        // A font without a base encoding of its own is read as Standard;
+        // ToUnicode, when there is one, corrects the subsets that renumber
+        // their glyphs
+        let mut codes: Vec<Option<String>> = (0..=255u8)
+            .map(|code| base_char(base.unwrap_or(&STANDARD), code).map(expand_ligature))
+            .collect();
+        for (code, text) in to_unicode {
+            codes[code as usize] = Some(text.chars().map(expand_ligature).collect());
+        }
+        for (code, text) in differences {
+            codes[code as usize] = Some(text);
+        }
+        FontEncoding::Simple(codes)
+    }
+
+    // The text of a string shown in this font, None for an unmapped one.
+    // Codes nothing names are left out.
+    pub(crate) fn decode(&self, bytes: &[u8]) -> Option<String> {
+        match self {
+            FontEncoding::Simple(codes) => Some(bytes.iter().filter_map(|&code| codes[code as usize].as_deref()).collect()),
+            FontEncoding::Unmapped => None,
+            FontEncoding::Utf8 => Some(String::from_utf8_lossy(bytes).into_owned()),
+        }
+    }
+}
+
+// The base encodings by name; MacExpertEncoding's small capitals and old
+// style figures aren't worth a table
+fn base_encoding(name: &[u8]) -> Option<&'static [u16; 128]> {
+    match name {
+        b"WinAnsiEncoding" => Some(&WIN_ANSI),
+        b"MacRomanEncoding" => Some(&MAC_ROMAN),
+        b"StandardEncoding" => Some(&STANDARD),
+        _ => None,
+    }
+}
+
+// A code's character in a base encoding. Below 0x80 the encodings are
+// ASCII, except that Standard has curly quotes for ' and `.
+fn base_char(high: &[u16; 128], code: u8) -> Option<char> {
+    match code {
+        0x27 if std::ptr::eq(high, &STANDARD) => Some('\u{2019}'),
+        0x60 if std::ptr::eq(high, &STANDARD) => Some('\u{2018}'),
+        0x20..=0x7E => Some(code as char),
+        0x80..=0xFF => char::from_u32(high[code as usize - 0x80] as u32).filter(|&c| c != '\0'),
+        _ => None,
+    }
+}
+
+// The (code, glyph name) pairs of a /Differences array: each number is the
+// code of the name after it, and each further name takes the next code
+fn differences_names(doc: &Document, differences: &[Object]) -> Vec<(u8, String)> {
+    let mut names = Vec::new();
+    let mut code = None;
+    for item in differences {
+        match doc.dereference(item).map(|(_, item)| item) {
+            Ok(Object::Integer(next)) => code = u8::try_from(*next).ok(),
+            Ok(Object::Name(name)) => {
+                if let Some(current) = code {
+                    names.push((current, String::from_utf8_lossy(name).into_owned()));
+                }
+                code = code.and_then(|current| current.checked_add(1));
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+// The text a glyph name stands for under the Adobe Glyph List's rules: a
+// suffix after a period is dropped ("a.sc"), underscores join ligature
+// components ("f_f_i"), and "uniXXXX" and "uXXXX" give code points in hex.
+// Ligatures come out as their letters. None for a name the list doesn't
+// know, such as the "g42" of a subset font.
+fn glyph_text(name: &str) -> Option<String> {
+    let name = name.split('.').next().unwrap_or_default();
+    if name.is_empty() {
+        return None;
+    }
+    let mut text = String::new();
+    for component in name.split('_') {
+        if let Ok(i) = GLYPHS.binary_search_by(|&(glyph, _)| glyph.cmp(component)) {
+            text.push_str(&expand_ligature(char::from_u32(GLYPHS[i].1 as u32)?));
+        } else if let Some(hex) = component.strip_prefix("uni").filter(|hex| !hex.is_empty() && hex.len() % 4 == 0) {
+            for i in (0..hex.len()).step_by(4) {
+                text.push(char::from_u32(u32::from_str_radix(hex.get(i..i + 4)?, 16).ok()?)?);
+            }
+        } else if let Some(hex) = component.strip_prefix('u').filter(|hex| (4..=6).contains(&hex.len())) {
+            text.push(char::from_u32(u32::from_str_radix(hex, 16).ok()?)?);
+        } else {
+            return None;
+        }
+    }
+    Some(text)
+}
+
+// The Latin ligatures as the letters they join, so that headings match
+// and read as typed
+fn expand_ligature(c: char) -> String {
+    match c {
+        '\u{FB00}' => "ff".to_string(),
+        '\u{FB01}' => "fi".to_string(),
+        '\u{FB02}' => "fl".to_string(),
+        '\u{FB03}' => "ffi".to_string(),
+        '\u{FB04}' => "ffl".to_string(),
+        c => c.to_string(),
+    }
+}
+
+// Symbolic by the font descriptor's flags, or one of the well-known symbol
+// fonts, which have no descriptor when they're among the standard 14
+fn is_symbolic(doc: &Document, font: &Dictionary) -> bool {
+    let flags = font.get(b"FontDescriptor").and_then(|d| doc.dereference(d)).and_then(|(_, d)| d.as_dict())
+        .and_then(|d| d.get(b"Flags")).and_then(Object::as_i64);
+    if let Ok(flags) = flags {
+        return flags & 4 != 0 && flags & 32 == 0;
+    }
+    let name = font.get(b"BaseFont").and_then(Object::as_name_str).unwrap_or_default();
+    let name = strip_subset(name).unwrap_or(name);
+    ["Symbol", "ZapfDingbats", "Wingdings", "Webdings"].iter().any(|symbol| name.starts_with(symbol))
+}
+
+// The single-byte codes of the font's /ToUnicode CMap, from its bfchar and
+// bfrange sections; longer codes belong to composite fonts
+fn to_unicode(doc: &Document, font: &Dictionary) -> HashMap<u8, String> {
+    let stream = font.get(b"ToUnicode").and_then(|s| doc.dereference(s)).and_then(|(_, s)| s.as_stream());
+    let Some(cmap) = stream.ok().and_then(|stream| stream.decompressed_content().ok().or_else(|| Some(stream.content.clone()))) else {
+        return HashMap::new();
+    };
+    let tokens = cmap_tokens(&cmap);
+    let mut map = HashMap::new();
+    let mut section = "";
+    let mut i = 0;
+    while i < tokens.len() {
+        match (&tokens[i], section) {
+            (CMapToken::Word(word), _) if word == "beginbfchar" => section = "bfchar",
+            (CMapToken::Word(word), _) if word == "beginbfrange" => section = "bfrange",
+            (CMapToken::Word(word), _) if word.starts_with("end") => section = "",
+            (CMapToken::Hex(source), "bfchar") => {
+                if let (Some(code), Some(CMapToken::Hex(target))) = (single_byte(source), tokens.get(i + 1)) {
+                    map.insert(code, utf16(target));
+                }
+                i += 1;
+            }
+            (CMapToken::Hex(first), "bfrange") => {
+                let (Some(CMapToken::Hex(last)), Some(target)) = (tokens.get(i + 1), tokens.get(i + 2)) else { break };
+                if let (Some(first), Some(last)) = (single_byte(first), single_byte(last)) {
+                    for (offset, code) in (first..=last).enumerate() {
+                        let text = match target {
+                            CMapToken::Hex(start) => increment(start, offset).map(|units| utf16(&units)),
+                            CMapToken::Array(targets) => targets.get(offset).map(|units| utf16(units)),
+                            CMapToken::Word(_) => None,
+                        };
+                        map.extend(text.map(|text| (code, text)));
+                    }
+                }
+                i += 2;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    map
+}
+
+enum CMapToken {
+    // The bytes of a <hex> string
+    Hex(Vec<u8>),
+    // An array of hex strings
+    Array(Vec<Vec<u8>>),
+    Word(String),
+}
+
+// A CMap's hex strings, arrays of them and bare words; comments, names and
+// parenthesized strings, which bfchar and bfrange don't use, are words too
+fn cmap_tokens(cmap: &[u8]) -> Vec<CMapToken> {
+    let text = String::from_utf8_lossy(cmap);
+    let mut tokens = Vec::new();
+    let mut array: Option<Vec<Vec<u8>>> = None;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '<' if chars.peek() != Some(&'<') => {
+                let digits: String = chars.by_ref().take_while(|&c| c != '>').filter(char::is_ascii_hexdigit).collect();
+                let mut bytes: Vec<u8> = (0..digits.len() / 2)
+                    .filter_map(|i| u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16).ok())
+                    .collect();
+                // An odd last digit is followed by an implied 0
+                if digits.len() % 2 == 1 {
+                    bytes.extend(u8::from_str_radix(&format!("{}0", &digits[digits.len() - 1..]), 16).ok());
+                }
+                match &mut array {
+                    Some(array) => array.push(bytes),
+                    None => tokens.push(CMapToken::Hex(bytes)),
+                }
+            }
+            '[' => array = Some(Vec::new()),
+            ']' => tokens.extend(array.take().map(CMapToken::Array)),
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek().filter(|c| !c.is_whitespace() && !"<[]".contains(**c)) {
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.push(CMapToken::Word(word));
+            }
+        }
+    }
+    tokens
+}
+
+fn single_byte(code: &[u8]) -> Option<u8> {
+    match code {
+        [code] => Some(*code),
+        _ => None,
+    }
+}
+
+// UTF-16BE bytes as text, ligatures expanded
+fn utf16(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+    String::from_utf16_lossy(&units).chars().map(expand_ligature).collect()
+}
+
+// A bfrange target's bytes for the `offset`th code: its last byte counts up
+fn increment(start: &[u8], offset: usize) -> Option<Vec<u8>> {
+    let mut bytes = start.to_vec();
+    let last = bytes.last_mut()?;
+    *last = last.checked_add(u8::try_from(offset).ok()?)?;
+    Some(bytes)
+}
+
+// WinAnsiEncoding, codes 0x80 to 0xFF: Windows code page 1252
+static WIN_ANSI: [u16; 128] = [
+    0x20AC, 0x0000, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021,
+    0x02C6, 0x2030, 0x0160, 0x2039, 0x0152, 0x0000, 0x017D, 0x0000,
+    0x0000, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014,
+    0x02DC, 0x2122, 0x0161, 0x203A, 0x0153, 0x0000, 0x017E, 0x0178,
+    0x00A0, 0x00A1, 0x00A2, 0x00A3, 0x00A4, 0x00A5, 0x00A6, 0x00A7,
+    0x00A8, 0x00A9, 0x00AA, 0x00AB, 0x00AC, 0x00AD, 0x00AE, 0x00AF,
+    0x00B0, 0x00B1, 0x00B2, 0x00B3, 0x00B4, 0x00B5, 0x00B6, 0x00B7,
+    0x00B8, 0x00B9, 0x00BA, 0x00BB, 0x00BC, 0x00BD, 0x00BE, 0x00BF,
+    0x00C0, 0x00C1, 0x00C2, 0x00C3, 0x00C4, 0x00C5, 0x00C6, 0x00C7,
+    0x00C8, 0x00C9, 0x00CA, 0x00CB, 0x00CC, 0x00CD, 0x00CE, 0x00CF,
+    0x00D0, 0x00D1, 0x00D2, 0x00D3, 0x00D4, 0x00D5, 0x00D6, 0x00D7,
+    0x00D8, 0x00D9, 0x00DA, 0x00DB, 0x00DC, 0x00DD, 0x00DE, 0x00DF,
+    0x00E0, 0x00E1, 0x00E2, 0x00E3, 0x00E4, 0x00E5, 0x00E6, 0x00E7,
+    0x00E8, 0x00E9, 0x00EA, 0x00EB, 0x00EC, 0x00ED, 0x00EE, 0x00EF,
+    0x00F0, 0x00F1, 0x00F2, 0x00F3, 0x00F4, 0x00F5, 0x00F6, 0x00F7,
+    0x00F8, 0x00F9, 0x00FA, 0x00FB, 0x00FC, 0x00FD, 0x00FE, 0x00FF,
+];
+
+// MacRomanEncoding, codes 0x80 to 0xFF, as the PDF reference gives it
+static MAC_ROMAN: [u16; 128] = [
+    0x00C4, 0x00C5, 0x00C7, 0x00C9, 0x00D1, 0x00D6, 0x00DC, 0x00E1,
+    0x00E0, 0x00E2, 0x00E4, 0x00E3, 0x00E5, 0x00E7, 0x00E9, 0x00E8,
+    0x00EA, 0x00EB, 0x00ED, 0x00EC, 0x00EE, 0x00EF, 0x00F1, 0x00F3,
+    0x00F2, 0x00F4, 0x00F6, 0x00F5, 0x00FA, 0x00F9, 0x00FB, 0x00FC,
+    0x2020, 0x00B0, 0x00A2, 0x00A3, 0x00A7, 0x2022, 0x00B6, 0x00DF,
+    0x00AE, 0x00A9, 0x2122, 0x00B4, 0x00A8, 0x2260, 0x00C6, 0x00D8,
+    0x221E, 0x00B1, 0x2264, 0x2265, 0x00A5, 0x00B5, 0x2202, 0x2211,
+    0x220F, 0x03C0, 0x222B, 0x00AA, 0x00BA, 0x03A9, 0x00E6, 0x00F8,
+    0x00BF, 0x00A1, 0x00AC, 0x221A, 0x0192, 0x2248, 0x2206, 0x00AB,
+    0x00BB, 0x2026, 0x00A0, 0x00C0, 0x00C3, 0x00D5, 0x0152, 0x0153,
+    0x2013, 0x2014, 0x201C, 0x201D, 0x2018, 0x2019, 0x00F7, 0x25CA,
+    0x00FF, 0x0178, 0x2044, 0x00A4, 0x2039, 0x203A, 0xFB01, 0xFB02,
+    0x2021, 0x00B7, 0x201A, 0x201E, 0x2030, 0x00C2, 0x00CA, 0x00C1,
+    0x00CB, 0x00C8, 0x00CD, 0x00CE, 0x00CF, 0x00CC, 0x00D3, 0x00D4,
+    0x0000, 0x00D2, 0x00DA, 0x00DB, 0x00D9, 0x0131, 0x02C6, 0x02DC,
+    0x00AF, 0x02D8, 0x02D9, 0x02DA, 0x00B8, 0x02DD, 0x02DB, 0x02C7,
+];
+
+// StandardEncoding, codes 0x80 to 0xFF
+static STANDARD: [u16; 128] = [
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x00A1, 0x00A2, 0x00A3, 0x2044, 0x00A5, 0x0192, 0x00A7,
+    0x00A4, 0x0027, 0x201C, 0x00AB, 0x2039, 0x203A, 0xFB01, 0xFB02,
+    0x0000, 0x2013, 0x2020, 0x2021, 0x00B7, 0x0000, 0x00B6, 0x2022,
+    0x201A, 0x201E, 0x201D, 0x00BB, 0x2026, 0x2030, 0x0000, 0x00BF,
+    0x0000, 0x0060, 0x00B4, 0x02C6, 0x02DC, 0x00AF, 0x02D8, 0x02D9,
+    0x00A8, 0x0000, 0x02DA, 0x00B8, 0x0000, 0x02DD, 0x02DB, 0x02C7,
+    0x2014, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x00C6, 0x0000, 0x00AA, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0141, 0x00D8, 0x0152, 0x00BA, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x00E6, 0x0000, 0x0000, 0x0000, 0x0131, 0x0000, 0x0000,
+    0x0142, 0x00F8, 0x0153, 0x00DF, 0x0000, 0x0000, 0x0000, 0x0000,
+];
+
+// Unicode for the glyph names PDFs commonly use, from the Adobe Glyph
+// List, sorted by name for a binary search
+const GLYPHS: &[(&str, u16)] = &[
+    ("A", 0x0041), ("AE", 0x00C6), ("Aacute", 0x00C1), ("Abreve", 0x0102), ("Acircumflex", 0x00C2),
+    ("Adieresis", 0x00C4), ("Agrave", 0x00C0), ("Amacron", 0x0100), ("Aogonek", 0x0104), ("Aring", 0x00C5),
+    ("Atilde", 0x00C3), ("B", 0x0042), ("C", 0x0043), ("Cacute", 0x0106), ("Ccaron", 0x010C), ("Ccedilla", 0x00C7),
+    ("D", 0x0044), ("Dcaron", 0x010E), ("Dcroat", 0x0110), ("Delta", 0x2206), ("E", 0x0045), ("Eacute", 0x00C9),
+    ("Ecaron", 0x011A), ("Ecircumflex", 0x00CA), ("Edieresis", 0x00CB), ("Egrave", 0x00C8), ("Emacron", 0x0112),
+    ("Eogonek", 0x0118), ("Eth", 0x00D0), ("Euro", 0x20AC), ("F", 0x0046), ("G", 0x0047), ("Gbreve", 0x011E),
+    ("H", 0x0048), ("H18533", 0x25CF), ("H18543", 0x25AA), ("H22073", 0x25A1), ("I", 0x0049), ("Iacute", 0x00CD),
+    ("Icircumflex", 0x00CE), ("Idieresis", 0x00CF), ("Idotaccent", 0x0130), ("Igrave", 0x00CC), ("Imacron", 0x012A),
+    ("J", 0x004A), ("K", 0x004B), ("L", 0x004C), ("Lslash", 0x0141), ("M", 0x004D), ("N", 0x004E), ("Nacute", 0x0143),
+    ("Ncaron", 0x0147), ("Ntilde", 0x00D1), ("O", 0x004F), ("OE", 0x0152), ("Oacute", 0x00D3),
+    ("Ocircumflex", 0x00D4), ("Odieresis", 0x00D6), ("Ograve", 0x00D2), ("Ohungarumlaut", 0x0150),
+    ("Omacron", 0x014C), ("Omega", 0x03A9), ("Oslash", 0x00D8), ("Otilde", 0x00D5), ("P", 0x0050), ("Q", 0x0051),
+    ("R", 0x0052), ("Rcaron", 0x0158), ("S", 0x0053), ("Sacute", 0x015A), ("Scaron", 0x0160), ("Scedilla", 0x015E),
+    ("T", 0x0054), ("Tcaron", 0x0164), ("Thorn", 0x00DE), ("U", 0x0055), ("Uacute", 0x00DA), ("Ucircumflex", 0x00DB),
+    ("Udieresis", 0x00DC), ("Ugrave", 0x00D9), ("Uhungarumlaut", 0x0170), ("Umacron", 0x016A), ("Uring", 0x016E),
+    ("V", 0x0056), ("W", 0x0057), ("X", 0x0058), ("Y", 0x0059), ("Yacute", 0x00DD), ("Ydieresis", 0x0178),
+    ("Z", 0x005A), ("Zacute", 0x0179), ("Zcaron", 0x017D), ("Zdotaccent", 0x017B), ("a", 0x0061), ("aacute", 0x00E1),
+    ("abreve", 0x0103), ("acircumflex", 0x00E2), ("acute", 0x00B4), ("adieresis", 0x00E4), ("ae", 0x00E6),
+    ("agrave", 0x00E0), ("amacron", 0x0101), ("ampersand", 0x0026), ("aogonek", 0x0105), ("approxequal", 0x2248),
+    ("aring", 0x00E5), ("arrowdown", 0x2193), ("arrowleft", 0x2190), ("arrowright", 0x2192), ("arrowup", 0x2191),
+    ("asciicircum", 0x005E), ("asciitilde", 0x007E), ("asterisk", 0x002A), ("at", 0x0040), ("atilde", 0x00E3),
+    ("b", 0x0062), ("backslash", 0x005C), ("bar", 0x007C), ("braceleft", 0x007B), ("braceright", 0x007D),
+    ("bracketleft", 0x005B), ("bracketright", 0x005D), ("breve", 0x02D8), ("brokenbar", 0x00A6), ("bullet", 0x2022),
+    ("c", 0x0063), ("cacute", 0x0107), ("caron", 0x02C7), ("ccaron", 0x010D), ("ccedilla", 0x00E7),
+    ("cedilla", 0x00B8), ("cent", 0x00A2), ("checkmark", 0x2713), ("circle", 0x25CB), ("circumflex", 0x02C6),
+    ("colon", 0x003A), ("comma", 0x002C), ("copyright", 0x00A9), ("currency", 0x00A4), ("d", 0x0064),
+    ("dagger", 0x2020), ("daggerdbl", 0x2021), ("dcaron", 0x010F), ("dcroat", 0x0111), ("degree", 0x00B0),
+    ("dieresis", 0x00A8), ("divide", 0x00F7), ("dollar", 0x0024), ("dotaccent", 0x02D9), ("dotlessi", 0x0131),
+    ("e", 0x0065), ("eacute", 0x00E9), ("ecaron", 0x011B), ("ecircumflex", 0x00EA), ("edieresis", 0x00EB),
+    ("egrave", 0x00E8), ("eight", 0x0038), ("ellipsis", 0x2026), ("emacron", 0x0113), ("emdash", 0x2014),
+    ("endash", 0x2013), ("eogonek", 0x0119), ("equal", 0x003D), ("eth", 0x00F0), ("exclam", 0x0021),
+    ("exclamdown", 0x00A1), ("f", 0x0066), ("ff", 0xFB00), ("ffi", 0xFB03), ("ffl", 0xFB04), ("fi", 0xFB01),
+    ("figuredash", 0x2012), ("filledbox", 0x25A0), ("five", 0x0035), ("fl", 0xFB02), ("florin", 0x0192),
+    ("four", 0x0034), ("fraction", 0x2044), ("g", 0x0067), ("gbreve", 0x011F), ("germandbls", 0x00DF),
+    ("grave", 0x0060), ("greater", 0x003E), ("greaterequal", 0x2265), ("guillemotleft", 0x00AB),
+    ("guillemotright", 0x00BB), ("guilsinglleft", 0x2039), ("guilsinglright", 0x203A), ("h", 0x0068),
+    ("hungarumlaut", 0x02DD), ("hyphen", 0x002D), ("hyphentwo", 0x2010), ("i", 0x0069), ("iacute", 0x00ED),
+    ("icircumflex", 0x00EE), ("idieresis", 0x00EF), ("igrave", 0x00EC), ("imacron", 0x012B), ("infinity", 0x221E),
+    ("integral", 0x222B), ("j", 0x006A), ("k", 0x006B), ("l", 0x006C), ("less", 0x003C), ("lessequal", 0x2264),
+    ("logicalnot", 0x00AC), ("lozenge", 0x25CA), ("lslash", 0x0142), ("m", 0x006D), ("macron", 0x00AF),
+    ("middot", 0x00B7), ("minus", 0x2212), ("mu", 0x00B5), ("multiply", 0x00D7), ("n", 0x006E), ("nacute", 0x0144),
+    ("nbspace", 0x00A0), ("ncaron", 0x0148), ("nine", 0x0039), ("nonbreakingspace", 0x00A0), ("notequal", 0x2260),
+    ("ntilde", 0x00F1), ("numbersign", 0x0023), ("o", 0x006F), ("oacute", 0x00F3), ("ocircumflex", 0x00F4),
+    ("odieresis", 0x00F6), ("oe", 0x0153), ("ogonek", 0x02DB), ("ograve", 0x00F2), ("ohungarumlaut", 0x0151),
+    ("omacron", 0x014D), ("one", 0x0031), ("onehalf", 0x00BD), ("onequarter", 0x00BC), ("onesuperior", 0x00B9),
+    ("ordfeminine", 0x00AA), ("ordmasculine", 0x00BA), ("oslash", 0x00F8), ("otilde", 0x00F5), ("p", 0x0070),
+    ("paragraph", 0x00B6), ("parenleft", 0x0028), ("parenright", 0x0029), ("partialdiff", 0x2202),
+    ("percent", 0x0025), ("period", 0x002E), ("periodcentered", 0x00B7), ("perthousand", 0x2030), ("pi", 0x03C0),
+    ("plus", 0x002B), ("plusminus", 0x00B1), ("product", 0x220F), ("q", 0x0071), ("question", 0x003F),
+    ("questiondown", 0x00BF), ("quotedbl", 0x0022), ("quotedblbase", 0x201E), ("quotedblleft", 0x201C),
+    ("quotedblright", 0x201D), ("quoteleft", 0x2018), ("quoteright", 0x2019), ("quotesinglbase", 0x201A),
+    ("quotesingle", 0x0027), ("r", 0x0072), ("radical", 0x221A), ("rcaron", 0x0159), ("registered", 0x00AE),
+    ("ring", 0x02DA), ("s", 0x0073), ("sacute", 0x015B), ("scaron", 0x0161), ("scedilla", 0x015F),
+    ("section", 0x00A7), ("semicolon", 0x003B), ("seven", 0x0037), ("sfthyphen", 0x00AD), ("six", 0x0036),
+    ("slash", 0x002F), ("softhyphen", 0x00AD), ("space", 0x0020), ("sterling", 0x00A3), ("summation", 0x2211),
+    ("t", 0x0074), ("tcaron", 0x0165), ("thorn", 0x00FE), ("three", 0x0033), ("threequarters", 0x00BE),
+    ("threesuperior", 0x00B3), ("tilde", 0x02DC), ("trademark", 0x2122), ("triagrt", 0x25BA), ("two", 0x0032),
+    ("twosuperior", 0x00B2), ("u", 0x0075), ("uacute", 0x00FA), ("ucircumflex", 0x00FB), ("udieresis", 0x00FC),
+    ("ugrave", 0x00F9), ("uhungarumlaut", 0x0171), ("umacron", 0x016B), ("underscore", 0x005F), ("uring", 0x016F),
+    ("v", 0x0076), ("w", 0x0077), ("x", 0x0078), ("y", 0x0079), ("yacute", 0x00FD), ("ydieresis", 0x00FF),
+    ("yen", 0x00A5), ("z", 0x007A), ("zacute", 0x017A), ("zcaron", 0x017E), ("zdotaccent", 0x017C), ("zero", 0x0030),
+];
@@ -0,0 +1,99 @@
+//! Scores an extracted outline against a reference one, such as the
+//! expected outputs shipped with a benchmark corpus. An extracted heading is
+//! right when the reference has one at the same level on the same page with
+//! the same text, ignoring case and spacing; each reference heading matches
+//! at most one extracted heading.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::sanitize::normalize_for_match;
+use crate::Heading;
+
+/// How an outline compares with its reference
+#[derive(Debug, Clone, Serialize)]
+pub struct Evaluation {
+    /// The share of extracted headings that are right; 1 when none were extracted
+    #[serde(serialize_with = "crate::serialize_score")]
+    pub precision: f64,
+    /// The share of reference headings that were found; 1 when there are none
+    #[serde(serialize_with = "crate::serialize_score")]
+    pub recall: f64,
+    #[serde(serialize_with = "crate::serialize_score")]
+    pub f1: f64,
+    /// Reference headings that weren't found, in document order
+    pub missed: Vec<Mismatch>,
+    /// Extracted headings the reference doesn't have, in document order
+    pub unexpected: Vec<Mismatch>,
+}
+
+/// A heading on one side of the comparison with no match on the other
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Mismatch {
+    pub level: String,
+    pub text: String,
+    pub page: usize,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} \"{}\" (page {})", self.level, self.text, self.page)
+    }
+}
+
+impl From<&Heading> for Mismatch {
+    fn from(heading: &Heading) -> Self {
+        Mismatch { level: heading.level.clone(), text: heading.text.clone(), page: heading.page }
+    }
+}
+
+/// Compares `extracted` headings with the `reference` ones.
+///
+/// ```
+/// use adobe1a::evaluation::evaluate;
+/// use adobe1a::Heading;
+///
+/// let heading = |level: &str, text: &str, page| Heading { level: level.into(), text: text.into(), page, ..Default::default() };
+/// let reference = [heading("H1", "Scope", 1), heading("H2", "Budget", 2), heading("H2", "Timeline", 3)];
+/// let extracted = [heading("H1", "SCOPE ", 1), heading("H1", "Budget", 2), heading("H2", "Timeline", 3), heading("H3", "Page 3 of 9", 3)];
+/// let evaluation = evaluate(&extracted, &reference);
+/// assert_eq!((evaluation.precision, evaluation.recall), (0.5, 2.0 / 3.0));
+/// assert!((evaluation.f1 - 4.0 / 7.0).abs() < 1e-9);
+/// // A heading at the wrong level is missed at its own and unexpected at the other
+/// assert_eq!(evaluation.missed[0].to_string(), "H2 \"Budget\" (page 2)");
+/// let unexpected: Vec<String> = evaluation.unexpected.iter().map(|m| m.to_string()).collect();
+/// assert_eq!(unexpected, ["H1 \"Budget\" (page 2)", "H3 \"Page 3 of 9\" (page 3)"]);
+///
+/// assert_eq!(evaluate(&[], &[]).f1, 1.0);
+/// assert_eq!(evaluate(&[], &reference).f1, 0.0);
+/// ```
+pub fn evaluate(extracted: &[Heading], reference: &[Heading]) -> Evaluation {
+    let key = |heading: &Heading| (heading.level.clone(), heading.page, normalize_for_match(&heading.text, None));
+    let mut unmatched: HashMap<_, usize> = HashMap::new();
+    for heading in reference {
+        *unmatched.entry(key(heading)).or_default() += 1;
+    }
+    let mut unexpected = Vec::new();
+    for heading in extracted {
+        match unmatched.get_mut(&key(heading)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => unexpected.push(Mismatch::from(heading)),
+        }
+    }
+    // Of reference headings sharing a key, the first ones count as missed
+    let mut missed = Vec::new();
+    for heading in reference {
+        if let Some(count) = unmatched.get_mut(&key(heading)).filter(|count| **count > 0) {
+            *count -= 1;
+            missed.push(Mismatch::from(heading));
+        }
+    }
+
+    let matched = (extracted.len() - unexpected.len()) as f64;
+    let share = |total: usize| if total == 0 { 1.0 } else { matched / total as f64 };
+    let (precision, recall) = (share(extracted.len()), share(reference.len()));
+    let f1 = if precision + recall > 0.0 { 2.0 * precision * recall / (precision + recall) } else { 0.0 };
+    Evaluation { precision, recall, f1, missed, unexpected }
+}
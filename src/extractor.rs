@@ -0,0 +1,665 @@
+// Extraction with a fixed configuration, including page-by-page results for
+// callers that show headings as they arrive
+
+use std::path::Path;
+use std::sync::Arc;
+use anyhow::Result;
+use crate::calibration::Calibration;
+use crate::config::{CaseStyle, Engine, ExtractorConfig, HiddenText, PageRanges, Portfolio, ShortDocument, TitleMode};
+use crate::levels::LevelOverrides;
+use crate::lexicon::Lexicon;
+use crate::timing::Stats;
+use crate::{Heading, Outline, TitleSource, Warning};
+
+/// What [`Extractor::extract_pages`] reports as it goes
+#[derive(Debug, Clone)]
+pub enum ExtractEvent {
+    /// The document title, sent once, as soon as the first page is done
+    Title { title: String, source: TitleSource },
+    /// One page's results, sent in page order
+    Page(PageResult),
+}
+
+/// The headings found on one page, and anything that went wrong reading it
+#[derive(Debug, Clone, Default)]
+pub struct PageResult {
+    pub page: usize,
+    pub headings: Vec<Heading>,
+    pub warnings: Vec<Warning>,
+}
+
+/// Extracts outlines with one configuration. An extractor is `Send` and
+/// `Sync`, and extracting only reads it, so one can serve any number of
+/// threads at once (by reference or in an `Arc`) without them waiting on
+/// each other. Nothing is cached between documents.
+#[derive(Debug, Clone, Default)]
+pub struct Extractor {
+    config: ExtractorConfig,
+    post_process: Option<PostProcess>,
+}
+
+// A caller's closure; shared, so the extractor stays cheap to clone
+#[derive(Clone)]
+struct PostProcess(Arc<dyn Fn(Outline) -> Outline + Send + Sync>);
+
+impl std::fmt::Debug for PostProcess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PostProcess(..)")
+    }
+}
+
+impl Extractor {
+    /// An extractor with `config` as given, unchecked; see
+    /// [`Extractor::builder`] for one that is checked
+    pub fn new(config: ExtractorConfig) -> Self {
+        Extractor { config, post_process: None }
+    }
+
+    pub fn builder() -> ExtractorBuilder {
+        ExtractorBuilder::default()
+    }
+
+    /// Runs `f` on each finished outline before returning it, replacing any
+    /// earlier post-processing. [`crate::transform::Transform`] covers the
+    /// usual clean-ups.
+    ///
+    /// ```
+    /// use adobe1a::{transform::Transform, Extractor};
+    ///
+    /// let transform = Transform::parse(r#"[
+    ///     { "op": "strip_numbers" },
+    ///     { "op": "min_confidence", "value": 0.85 },
+    ///     { "op": "drop_levels", "levels": ["H3", "H4"] },
+    ///     { "op": "rename_title_regex", "pattern": "^Annual (.*)$", "replacement": "$1 (annual)" },
+    ///     { "op": "limit", "count": 2 }
+    /// ]"#)?;
+    /// let extractor = Extractor::default().post_process(move |outline| transform.apply(outline));
+    /// let outline = extractor.extract_path("fixtures/nul-heading.pdf".as_ref())?;
+    /// assert_eq!(outline.title, "Report (annual)");
+    /// assert_eq!(outline.outline.len(), 2);
+    /// assert!(outline.outline.iter().any(|heading| heading.text == "Introduction"));
+    /// assert!(outline.outline.iter().all(|heading| heading.confidence >= 0.85 && heading.depth() < 3));
+    ///
+    /// let extractor = extractor.post_process(|mut outline| {
+    ///     outline.title = outline.title.to_uppercase();
+    ///     outline
+    /// });
+    /// assert_eq!(extractor.extract_path("fixtures/nul-heading.pdf".as_ref())?.title, "ANNUAL REPORT");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn post_process(self, f: impl Fn(Outline) -> Outline + Send + Sync + 'static) -> Self {
+        Extractor { post_process: Some(PostProcess(Arc::new(f))), ..self }
+    }
+
+    pub fn config(&self) -> &ExtractorConfig {
+        &self.config
+    }
+
+    /// The whole outline at once, as [`crate::extract_outline`] returns it
+    pub fn extract_path(&self, source: &Path) -> Result<Outline> {
+        Ok(self.finish(crate::extract_outline(source, &self.config, &mut Stats::new())?))
+    }
+
+    /// The outline of a PDF held in memory. Without a file name, a title
+    /// taken from the name is "Untitled".
+    pub fn extract_bytes(&self, bytes: &[u8]) -> Result<Outline> {
+        Ok(self.finish(crate::extract_outline_from_bytes(bytes, "Untitled", &self.config, &mut Stats::new())?))
+    }
+
+    fn finish(&self, outline: Outline) -> Outline {
+        match &self.post_process {
+            Some(PostProcess(f)) => f(outline),
+            None => outline,
+        }
+    }
+
+    /// Extract one page at a time, passing each page's headings to `sink` as
+    /// soon as the page is done, with the title after the first page.
+    /// Returning [`std::ops::ControlFlow::Break`] from `sink` stops extraction; either
+    /// way the result holds everything sent so far. Post-processing applies
+    /// to the result, not to what `sink` is sent.
+    ///
+    /// Pages outside the configured `pages`, or past the quick-mode window,
+    /// are skipped without being reported. Quick mode's bookmarks aren't
+    /// used here: headings are always detected page by page.
+    ///
+    /// This always uses the font engine, whatever the configured engine: the
+    /// text engine only works on the whole document. Without the whole
+    /// document, the colour bonus compares against the pages read so far, and
+    /// neither the heading cap nor the per-page limit is applied, so the
+    /// headings can differ from [`Extractor::extract_path`]'s. A chapter
+    /// number left alone at the foot of a page isn't joined to the title
+    /// opening the next. Headings get no snippet, since a section can run
+    /// onto pages not yet read, and no offsets or positions.
+    ///
+    /// ```no_run
+    /// use std::ops::ControlFlow;
+    /// use adobe1a::{ExtractEvent, Extractor};
+    ///
+    /// let extractor = Extractor::default();
+    /// let outline = extractor.extract_pages("input/2.pdf".as_ref(), |event| {
+    ///     if let ExtractEvent::Page(page) = event {
+    ///         println!("page {}: {} headings", page.page, page.headings.len());
+    ///         if page.page == 3 {
+    ///             return ControlFlow::Break(());
+    ///         }
+    ///     }
+    ///     ControlFlow::Continue(())
+    /// })?;
+    /// assert!(outline.outline.iter().all(|h| h.page <= 3));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    #[cfg(feature = "engine-font")]
+    pub fn extract_pages(&self, source: &Path, mut sink: impl FnMut(ExtractEvent) -> std::ops::ControlFlow<()>) -> Result<Outline> {
+        use crate::font_utils::PageClassifier;
+        use crate::functions::HeadingDedupe;
+        use crate::sanitize::{normalize_case, sanitize_text};
+        use crate::validation::CandidateFilter;
+
+        let bytes = crate::read_input(source)?;
+        let name = source.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Untitled");
+        let bytes = crate::pdf_from_header(&bytes)?;
+        let attachment = match &self.config.attachment {
+            Some(pattern) => Some(crate::portfolio::find_attachment(bytes, pattern)?),
+            None => None,
+        };
+        let (bytes, name) = match &attachment {
+            Some(attachment) => (&attachment.bytes[..], attachment.name.rsplit_once('.').map_or(attachment.name.as_str(), |(stem, _)| stem)),
+            None => (bytes, name),
+        };
+        let mut doc = lopdf::Document::load_mem(bytes);
+        let tree_warning = doc.as_mut().ok().and_then(crate::trees::repair_page_tree);
+        crate::check_content(bytes, &doc)?;
+        let doc = doc?;
+        let mut label_walk = crate::trees::TreeWalk::default();
+        let labels = crate::pages::page_labels_walk(&doc, &mut label_walk);
+        let filter = CandidateFilter::new(&self.config);
+        let mut classifier = PageClassifier::new(&self.config);
+        let language = self.config.language.clone().or_else(|| crate::document_language(&doc));
+        let mut dedupe = HeadingDedupe::new(language.as_deref());
+        let mut ids = crate::ids::HeadingIds::default();
+        let mut outline = Outline {
+            page_count: Some(doc.get_pages().len()),
+            partial: self.config.quick.is_some_and(|window| doc.get_pages().len() > window),
+            document_id: crate::ids::document_id(bytes),
+            engine: vec!["font".to_string()],
+            extractor_version: crate::EXTRACTOR_VERSION.to_string(),
+            language,
+            ..Outline::default()
+        };
+        if let Some(attachment) = &attachment {
+            outline.metadata.attachment = Some(sanitize_text(&attachment.name));
+            outline.warnings.extend(attachment.warning.clone());
+        }
+        outline.warnings.extend(crate::check_page_ranges(&self.config, outline.page_count)?);
+        outline.warnings.extend(tree_warning.into_iter().chain(label_walk.warning("page label tree")));
+        let mut title_sent = false;
+
+        for (page, page_id) in doc.get_pages() {
+            let page = page as usize;
+            if !self.config.includes_page(page) {
+                continue;
+            }
+            let mut result = PageResult { page, ..PageResult::default() };
+            match classifier.classify(&doc, page_id, page) {
+                Some(candidates) => {
+                    result.headings = candidates.into_iter()
+                        .filter_map(|candidate| crate::candidate_heading(candidate, &filter, &self.config.lexicon))
+                        .map(|heading| Heading { text: sanitize_text(&heading.text), ..heading })
+                        .filter(|heading| filter.text_rejection(&heading.text).is_none() && dedupe.is_new(heading))
+                        .map(|mut heading| {
+                            crate::calibrate(&mut heading, "font", &self.config);
+                            self.config.level_overrides.apply(&mut heading);
+                            heading
+                        })
+                        .filter(|heading| heading.confidence >= self.config.min_confidence)
+                        .map(|mut heading| {
+                            if !self.config.include_sources {
+                                heading.sources.clear();
+                            }
+                            if !self.config.include_raw_scores {
+                                heading.raw_score = None;
+                                heading.detected_level = None;
+                            }
+                            heading
+                        })
+                        .map(|heading| Heading {
+                            id: ids.next(&heading),
+                            text: normalize_case(&heading.text, self.config.heading_case, &self.config.lexicon.stopwords),
+                            page_label: labels.as_ref().and_then(|l| l.get(page - 1)).map(|label| sanitize_text(label)),
+                            ..heading
+                        })
+                        .collect();
+                }
+                None => result.warnings.push(Warning::new(
+                    "unreadable_page",
+                    format!("the content of page {} could not be decoded", page),
+                )),
+            }
+            if !title_sent {
+                title_sent = true;
+                self.set_title(&mut outline, &doc, name);
+                let event = ExtractEvent::Title { title: outline.title.clone(), source: outline.title_source };
+                if sink(event).is_break() {
+                    break;
+                }
+            }
+            // The page joins the result only once it has been sent
+            let (headings, warnings) = (result.headings.clone(), result.warnings.clone());
+            let stop = sink(ExtractEvent::Page(result)).is_break();
+            outline.outline.extend(headings);
+            outline.warnings.extend(warnings);
+            if stop {
+                break;
+            }
+        }
+        if !title_sent {
+            self.set_title(&mut outline, &doc, name);
+        }
+        Ok(self.finish(outline))
+    }
+
+    #[cfg(feature = "engine-font")]
+    fn set_title(&self, outline: &mut Outline, doc: &lopdf::Document, name: &str) {
+        self.detect_title(outline, doc, name);
+        if self.config.normalize_title && !matches!(self.config.title, TitleMode::Fixed(_)) {
+            let stopwords = &self.config.lexicon.stopwords;
+            outline.title = crate::sanitize::normalize_case(&outline.title, self.config.heading_case, stopwords);
+        }
+    }
+
+    #[cfg(feature = "engine-font")]
+    fn detect_title(&self, outline: &mut Outline, doc: &lopdf::Document, name: &str) {
+        use crate::sanitize::sanitize_text;
+
+        if !self.config.detects_title() {
+            (outline.title, outline.title_source) = crate::configured_title(&self.config, name);
+            return;
+        }
+        let metadata = match self.config.quick.is_some() || !self.config.includes_page(1) {
+            true => crate::plausible_metadata_title(doc).map(|title| sanitize_text(&title)).filter(|title| !title.is_empty()),
+            false => None,
+        };
+        if let Some(title) = metadata {
+            (outline.title, outline.title_source) = (title, TitleSource::Metadata);
+            return;
+        }
+        let scores = match self.config.includes_page(1) {
+            true => crate::first_page_title_scores(doc, &self.config),
+            false => Vec::new(),
+        };
+        let title = scores.first().map(|(title, _)| sanitize_text(title)).filter(|title| !title.is_empty());
+        (outline.title, outline.title_source) = crate::resolve_title(title, name, &self.config.lexicon);
+    }
+}
+
+/// Builds an [`Extractor`] one setting at a time, starting from
+/// [`ExtractorConfig::default`], and checks the settings together when
+/// built. The command line builds its extractor this way too.
+///
+/// ```
+/// use adobe1a::{config::{Engine, TitleMode}, BuildError, Extractor};
+///
+/// let extractor = Extractor::builder()
+///     .min_confidence(0.9)
+///     .max_headings(200)
+///     .title(TitleMode::Fixed("Board Meeting".to_string()))
+///     .build()?;
+/// let outline = extractor.extract_path("fixtures/agenda.pdf".as_ref())?;
+/// assert_eq!(outline.title, "Board Meeting");
+/// assert!(!outline.outline.is_empty() && outline.outline.iter().all(|h| h.confidence >= 0.9));
+///
+/// let bytes = std::fs::read("fixtures/agenda.pdf")?;
+/// assert_eq!(extractor.extract_bytes(&bytes)?.outline, outline.outline);
+///
+/// let error = Extractor::builder().min_confidence(1.5).build().unwrap_err();
+/// assert_eq!(error.to_string(), "`min_confidence` is 1.5, but it must be from 0 to 1");
+/// let error = Extractor::builder().pages("20-30".parse()?).quick(15).build().unwrap_err();
+/// assert_eq!(error, BuildError::PagesOutsideQuickWindow { window: 15 });
+/// // An engine left out of the build is refused
+/// assert_eq!(Extractor::builder().engine(Engine::Text).build().is_ok(), Engine::Text.is_built());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ExtractorBuilder {
+    config: ExtractorConfig,
+    post_process: Option<PostProcess>,
+}
+
+impl ExtractorBuilder {
+    pub fn engine(mut self, engine: Engine) -> Self {
+        self.config.engine = engine;
+        self
+    }
+
+    /// What to do with invisible text, such as an OCR layer. Anything but
+    /// [`HiddenText::Auto`] needs the font engine: [`Engine::Auto`] then
+    /// uses it directly, and [`Engine::Text`] fails to build.
+    ///
+    /// ```
+    /// use adobe1a::config::{Engine, HiddenText};
+    /// use adobe1a::{BuildError, Extractor};
+    ///
+    /// let text = Extractor::builder().engine(Engine::Text).hidden_text(HiddenText::Exclude).build();
+    /// if Engine::Text.is_built() {
+    ///     assert_eq!(text.err(), Some(BuildError::FontEngineOnly { knob: "hidden_text" }));
+    /// }
+    /// ```
+    pub fn hidden_text(mut self, hidden_text: HiddenText) -> Self {
+        self.config.hidden_text = hidden_text;
+        self
+    }
+
+    /// Fraction of the page height at the top and bottom ignored as
+    /// running headers and footers, below 0.5; 0 disables
+    pub fn margin_band(mut self, margin_band: f64) -> Self {
+        self.config.margin_band = margin_band;
+        self
+    }
+
+    pub fn top_of_page_bonus(mut self, bonus: f64) -> Self {
+        self.config.top_of_page_bonus = bonus;
+        self
+    }
+
+    pub fn underline_bonus(mut self, bonus: f64) -> Self {
+        self.config.underline_bonus = bonus;
+        self
+    }
+
+    pub fn color_bonus(mut self, bonus: f64) -> Self {
+        self.config.color_bonus = bonus;
+        self
+    }
+
+    /// Confidence added to a heading an internal link or a bookmark lands
+    /// on, which is marked `referenced`
+    pub fn link_bonus(mut self, bonus: f64) -> Self {
+        self.config.link_bonus = bonus;
+        self
+    }
+
+    pub fn lowercase_penalty(mut self, penalty: f64) -> Self {
+        self.config.lowercase_penalty = penalty;
+        self
+    }
+
+    pub fn all_caps_ratio(mut self, ratio: f64) -> Self {
+        self.config.all_caps_ratio = ratio;
+        self
+    }
+
+    pub fn title_candidates(mut self, title_candidates: bool) -> Self {
+        self.config.title_candidates = title_candidates;
+        self
+    }
+
+    pub fn min_heading_letters(mut self, letters: usize) -> Self {
+        self.config.min_heading_letters = letters;
+        self
+    }
+
+    /// Word limits for a heading, and for one ending in a period
+    pub fn max_heading_words(mut self, words: usize, sentence_words: usize) -> Self {
+        self.config.max_heading_words = words;
+        self.config.max_sentence_words = sentence_words;
+        self
+    }
+
+    /// A fixed cap on the font engine's headings, in place of one that grows
+    /// with the page count
+    pub fn max_headings(mut self, max_headings: usize) -> Self {
+        self.config.max_headings = Some(max_headings);
+        self
+    }
+
+    pub fn headings_per_page(mut self, headings_per_page: f64) -> Self {
+        self.config.headings_per_page = headings_per_page;
+        self
+    }
+
+    /// Most headings kept from one page of ordinary length, its most
+    /// confident, before the page is named in a `crowded_page` warning; 0
+    /// disables the per-page limit
+    pub fn page_heading_limit(mut self, limit: usize) -> Self {
+        self.config.page_heading_limit = limit;
+        self
+    }
+
+    /// Headings less confident than this, from 0 to 1, are dropped
+    pub fn min_confidence(mut self, min_confidence: f64) -> Self {
+        self.config.min_confidence = min_confidence;
+        self
+    }
+
+    pub fn split_documents(mut self, split_documents: bool) -> Self {
+        self.config.split_documents = split_documents;
+        self
+    }
+
+    pub fn portfolio(mut self, portfolio: Portfolio) -> Self {
+        self.config.portfolio = portfolio;
+        self
+    }
+
+    pub fn pages(mut self, pages: PageRanges) -> Self {
+        self.config.pages = Some(pages);
+        self
+    }
+
+    /// Quick mode, reading only the first `window` pages
+    pub fn quick(mut self, window: usize) -> Self {
+        self.config.quick = Some(window);
+        self
+    }
+
+    pub fn include_sources(mut self, include_sources: bool) -> Self {
+        self.config.include_sources = include_sources;
+        self
+    }
+
+    pub fn include_raw_scores(mut self, include_raw_scores: bool) -> Self {
+        self.config.include_raw_scores = include_raw_scores;
+        self
+    }
+
+    pub fn calibration(mut self, calibration: Calibration) -> Self {
+        self.config.calibration = calibration;
+        self
+    }
+
+    pub fn level_overrides(mut self, level_overrides: LevelOverrides) -> Self {
+        self.config.level_overrides = level_overrides;
+        self
+    }
+
+    pub fn lexicon(mut self, lexicon: Lexicon) -> Self {
+        self.config.lexicon = lexicon;
+        self
+    }
+
+    /// The documents' language as a BCP 47 tag, such as "tr-TR", instead of
+    /// each PDF's own; see [`crate::sanitize::normalize_for_match`]
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.config.language = Some(language.into());
+        self
+    }
+
+    pub fn title(mut self, title: TitleMode) -> Self {
+        self.config.title = title;
+        self
+    }
+
+    /// Snippets of up to `max_chars` characters
+    pub fn snippet_chars(mut self, max_chars: usize) -> Self {
+        self.config.snippet_chars = Some(max_chars);
+        self
+    }
+
+    /// Record each heading's `page_offset` and `run_index`, which
+    /// [`crate::sections::page_texts`] and [`crate::layout::extract_runs`]
+    /// can be sliced at
+    pub fn offsets(mut self, offsets: bool) -> Self {
+        self.config.offsets = offsets;
+        self
+    }
+
+    /// Record each heading's `y_fraction`, how far down its page it is
+    pub fn positions(mut self, positions: bool) -> Self {
+        self.config.positions = positions;
+        self
+    }
+
+    /// Read the attached PDF whose name matches `pattern` instead of the PDF
+    /// holding it. The match ignores case, and `*` and `?` are wildcards;
+    /// extraction fails, listing the attachments, when nothing matches. The
+    /// outline's `metadata.attachment` records the name.
+    pub fn attachment(mut self, pattern: impl Into<String>) -> Self {
+        self.config.attachment = Some(pattern.into());
+        self
+    }
+
+    /// Most bytes a document's extraction may hold in its own buffers. A
+    /// document that needs more is read again in quick mode, without
+    /// snippets or offsets, and the outline warns `memory_limit`. Pages
+    /// streamed with [`Extractor::extract_pages`] are read one at a time
+    /// and aren't limited.
+    pub fn max_memory(mut self, bytes: usize) -> Self {
+        self.config.max_memory = Some(bytes);
+        self
+    }
+
+    /// Recase heading text, and the title with `normalize_title`, as [`crate::sanitize::normalize_case`] does
+    pub fn heading_case(mut self, heading_case: CaseStyle) -> Self {
+        self.config.heading_case = heading_case;
+        self
+    }
+
+    pub fn normalize_title(mut self, normalize_title: bool) -> Self {
+        self.config.normalize_title = normalize_title;
+        self
+    }
+
+    pub fn synthesize_numbers(mut self, synthesize_numbers: bool) -> Self {
+        self.config.synthesize_numbers = synthesize_numbers;
+        self
+    }
+
+    /// Drop the headings of a sparse first page taken for a cover, as by default
+    pub fn cover_detection(mut self, cover_detection: bool) -> Self {
+        self.config.cover_detection = cover_detection;
+        self
+    }
+
+    /// Read documents as memos and letters, with their subject line as the
+    /// title: always, never, or for those of at most
+    /// [`ExtractorBuilder::short_document_pages`] pages
+    pub fn short_document(mut self, short_document: ShortDocument) -> Self {
+        self.config.short_document = short_document;
+        self
+    }
+
+    /// The most pages a document can have and still be read as a memo or
+    /// letter under [`ShortDocument::Auto`]; 3 by default
+    pub fn short_document_pages(mut self, pages: usize) -> Self {
+        self.config.short_document_pages = pages;
+        self
+    }
+
+    /// Collapse the levels at these depths (1 for H1) when they hold a
+    /// single heading, as [`crate::collapse::collapse_singletons`] does
+    pub fn collapse_singletons(mut self, depths: impl IntoIterator<Item = u8>) -> Self {
+        self.config.collapse_singletons = depths.into_iter().collect();
+        self
+    }
+
+    /// As [`Extractor::post_process`]
+    pub fn post_process(mut self, f: impl Fn(Outline) -> Outline + Send + Sync + 'static) -> Self {
+        self.post_process = Some(PostProcess(Arc::new(f)));
+        self
+    }
+
+    pub fn build(self) -> std::result::Result<Extractor, BuildError> {
+        let config = &self.config;
+        if !config.engine.is_built() {
+            return Err(BuildError::EngineNotBuilt(config.engine));
+        }
+        if !(0.0..0.5).contains(&config.margin_band) {
+            return Err(BuildError::OutOfRange { knob: "margin_band", value: config.margin_band, expected: "from 0 to under 0.5" });
+        }
+        let share = |knob, value: f64| match (0.0..=1.0).contains(&value) {
+            true => Ok(()),
+            false => Err(BuildError::OutOfRange { knob, value, expected: "from 0 to 1" }),
+        };
+        share("top_of_page_bonus", config.top_of_page_bonus)?;
+        share("underline_bonus", config.underline_bonus)?;
+        share("color_bonus", config.color_bonus)?;
+        share("link_bonus", config.link_bonus)?;
+        share("lowercase_penalty", config.lowercase_penalty)?;
+        share("all_caps_ratio", config.all_caps_ratio)?;
+        share("min_confidence", config.min_confidence)?;
+        if !(config.headings_per_page > 0.0 && config.headings_per_page.is_finite()) {
+            return Err(BuildError::OutOfRange { knob: "headings_per_page", value: config.headings_per_page, expected: "above 0" });
+        }
+        let counts = [
+            ("max_heading_words", Some(config.max_heading_words)),
+            ("max_sentence_words", Some(config.max_sentence_words)),
+            ("max_headings", config.max_headings),
+            ("quick", config.quick),
+            ("snippet_chars", config.snippet_chars),
+            ("max_memory", config.max_memory),
+        ];
+        if let Some((knob, _)) = counts.into_iter().find(|&(_, count)| count == Some(0)) {
+            return Err(BuildError::OutOfRange { knob, value: 0.0, expected: "at least 1" });
+        }
+        let font_engine = match config.engine {
+            Engine::Auto => Engine::Font.is_built(),
+            engine => engine == Engine::Font,
+        };
+        if config.hidden_text != HiddenText::Auto && !font_engine {
+            return Err(BuildError::FontEngineOnly { knob: "hidden_text" });
+        }
+        if config.collapse_singletons.contains(&0) {
+            return Err(BuildError::OutOfRange { knob: "collapse_singletons", value: 0.0, expected: "a depth of at least 1" });
+        }
+        if let (Some(pages), Some(window)) = (&config.pages, config.quick) {
+            if !(1..=window).any(|page| pages.contains(page)) {
+                return Err(BuildError::PagesOutsideQuickWindow { window });
+            }
+        }
+        Ok(Extractor { config: self.config, post_process: self.post_process })
+    }
+}
+
+/// Why [`ExtractorBuilder::build`] refused its settings
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    /// The engine's cargo feature is off in this build
+    EngineNotBuilt(Engine),
+    /// A setting outside the values it can take
+    OutOfRange { knob: &'static str, value: f64, expected: &'static str },
+    /// None of the requested pages are among the first `window` that quick
+    /// mode reads, so nothing would be read
+    PagesOutsideQuickWindow { window: usize },
+    /// A setting only the font engine can honour, with the text engine
+    /// chosen
+    FontEngineOnly { knob: &'static str },
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::EngineNotBuilt(engine) => {
+                let engine = format!("{:?}", engine).to_lowercase();
+                write!(f, "the {} engine isn't part of this build (cargo feature engine-{})", engine, engine)
+            }
+            BuildError::OutOfRange { knob, value, expected } => write!(f, "`{}` is {}, but it must be {}", knob, value, expected),
+            BuildError::PagesOutsideQuickWindow { window } => write!(f, "none of the requested pages are among the first {} that quick mode reads", window),
+            BuildError::FontEngineOnly { knob } => write!(f, "`{}` needs the font engine, which the text engine can't stand in for", knob),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
@@ -1,14 +1,52 @@
-use lopdf::{Document, Object, content::Content};
-use std::collections::HashMap;
+use lopdf::{Dictionary, Document, Object, ObjectId, content::Content};
+use serde::Serialize;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::time::{Duration, Instant};
+use crate::config::{ExtractorConfig, HiddenText};
+use crate::encoding::FontEncoding;
+use crate::fonts::{strip_subset, FontInfo};
+use crate::functions::{completes_marker, is_bare_marker, join_hyphenated, opens_canonical_section, section_heading_level, standalone_words, strip_list_marker, LineInfo};
+use crate::validation::{CandidateFilter, MAX_HEADING_LEN, MIN_HEADING_LEN};
+use crate::memory::MemoryBudget;
+use crate::timing::{Stage, Stats};
+use crate::{APPENDIX_HEADING, MAX_HEADING_DEPTH, NUMBERED_HEADING};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TextRun {
     pub text: String,
     pub size: f64,
     pub page: usize,
-    pub font_name: String,
+    // The font's /BaseFont without any subset prefix ("Helvetica-Bold"), or
+    // the resource name ("F1") when the page doesn't define it
+    pub font: String,
     pub is_bold: bool,
     pub is_italic: bool,
+    // Weight from 100 to 900 estimated from the font name, 400 for regular
+    pub weight: u16,
+    // False for text drawn with an invisible render mode (Tr 3 or 7)
+    pub visible: bool,
+    // Baseline start and estimated advance width in upright page space:
+    // default user space, turned by the page's /Rotate
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    // Approximate fill colour the text was painted with
+    pub color: Rgb,
+}
+
+// Colour as RGB components in [0, 1]
+pub type Rgb = [f64; 3];
+
+const BLACK: Rgb = [0.0, 0.0, 0.0];
+
+// A thin horizontal line drawn on the page (a stroked segment or a flat
+// rectangle), in default user space
+#[derive(Debug, Clone, Copy)]
+pub struct Rule {
+    pub x0: f64,
+    pub x1: f64,
+    pub y: f64,
 }
 
 #[derive(Debug, Clone)]
@@ -17,137 +55,797 @@ pub struct HeadingCandidate {
     pub level: String,
     pub page: usize,
     pub confidence: f64,
+    // Position in document order, used to break ties deterministically
+    pub seq: usize,
+    // Vertical position as a fraction of the page height, 0.0 at the top
+    pub y: f64,
+    // Left edge in upright page space, in points
+    pub x: f64,
+    pub color: Rgb,
+    pub size: f64,
+    // Bold or underlined, i.e. styled apart from body text other than by colour
+    pub emphasized: bool,
+    // Large or bold enough next to the page's body text, or short and alone
+    // at the top of the page, to be a heading even when it starts in lowercase
+    pub stands_out: bool,
+    // No text above it, or below it, on its page but what the margin bands hold
+    pub opens_page: bool,
+    pub ends_page: bool,
+}
+
+// Affine transform [a b c d e f] as used by `cm` and `Tm`
+type Matrix = [f64; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+// m1 × m2 in PDF's row-vector convention: apply m1, then m2
+fn multiply(m1: &Matrix, m2: &Matrix) -> Matrix {
+    [
+        m1[0] * m2[0] + m1[1] * m2[2],
+        m1[0] * m2[1] + m1[1] * m2[3],
+        m1[2] * m2[0] + m1[3] * m2[2],
+        m1[2] * m2[1] + m1[3] * m2[3],
+        m1[4] * m2[0] + m1[5] * m2[2] + m2[4],
+        m1[4] * m2[1] + m1[5] * m2[3] + m2[5],
+    ]
+}
+
+fn translation(tx: f64, ty: f64) -> Matrix {
+    [1.0, 0.0, 0.0, 1.0, tx, ty]
+}
+
+fn transform_point(m: &Matrix, x: f64, y: f64) -> (f64, f64) {
+    (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5])
+}
+
+fn number(obj: &Object) -> Option<f64> {
+    match obj {
+        Object::Real(r) => Some(*r as f64),
+        Object::Integer(i) => Some(*i as f64),
+        _ => None,
+    }
+}
+
+fn numbers(operands: &[Object]) -> Option<Vec<f64>> {
+    operands.iter().map(number).collect()
+}
+
+// A page attribute from the page itself or, failing that, the nearest
+// ancestor in the page tree that sets it (/Rotate and the page boxes are
+// inheritable)
+fn inherited_attribute<'a>(doc: &'a Document, page_id: ObjectId, key: &[u8]) -> Option<&'a Object> {
+    let mut node = doc.get_dictionary(page_id).ok()?;
+    // Bounded so a page tree with a /Parent cycle can't loop forever
+    for _ in 0..32 {
+        if let Ok(obj) = node.get(key) {
+            return doc.dereference(obj).ok().map(|(_, obj)| obj);
+        }
+        node = node.get(b"Parent").and_then(Object::as_reference)
+            .and_then(|parent| doc.get_dictionary(parent))
+            .ok()?;
+    }
+    None
+}
+
+// The page's /Rotate as 0, 90, 180 or 270 degrees clockwise
+fn page_rotation(doc: &Document, page_id: ObjectId) -> i64 {
+    inherited_attribute(doc, page_id, b"Rotate")
+        .and_then(|obj| obj.as_i64().ok())
+        .map_or(0, |degrees| (degrees / 90).rem_euclid(4) * 90)
+}
+
+// Maps default user space to upright page space: the page as it is
+// displayed once /Rotate is applied, with y growing upwards as usual.
+// Unrotated pages keep their coordinates.
+fn upright_transform(media: &[f64; 4], rotation: i64) -> Matrix {
+    let [x0, y0, x1, y1] = *media;
+    match rotation {
+        // The left edge is displayed at the top, the bottom edge on the left
+        90 => [0.0, -1.0, 1.0, 0.0, -y0, x1],
+        180 => [-1.0, 0.0, 0.0, -1.0, x1, y1],
+        // The right edge is displayed at the top, the top edge on the left
+        270 => [0.0, 1.0, -1.0, 0.0, y1, -x0],
+        _ => IDENTITY,
+    }
+}
+
+// Visible page area as [llx, lly, urx, ury] in upright page space (see
+// `upright_transform`)
+pub(crate) fn page_box(doc: &Document, page_id: ObjectId) -> [f64; 4] {
+    let media = media_box(doc, page_id);
+    let (width, height) = (media[2] - media[0], media[3] - media[1]);
+    match page_rotation(doc, page_id) {
+        90 | 270 => [0.0, 0.0, height, width],
+        180 => [0.0, 0.0, width, height],
+        _ => media,
+    }
+}
+
+// The height in upright page space (see `upright_transform`) of a point
+// given in default user space. On a page turned a quarter it's the point's
+// x that ends up vertical, so without one there's no answer.
+pub(crate) fn upright_y(doc: &Document, page_id: ObjectId, x: Option<f64>, y: f64) -> Option<f64> {
+    let rotation = page_rotation(doc, page_id);
+    let x = match rotation {
+        90 | 270 => x?,
+        _ => x.unwrap_or_default(),
+    };
+    Some(transform_point(&upright_transform(&media_box(doc, page_id), rotation), x, y).1)
+}
+
+// Visible page area in default user space as [llx, lly, urx, ury]: the
+// CropBox when present, otherwise the MediaBox, otherwise US Letter
+fn media_box(doc: &Document, page_id: ObjectId) -> [f64; 4] {
+    for key in [&b"CropBox"[..], b"MediaBox"] {
+        let rect = inherited_attribute(doc, page_id, key)
+            .and_then(|obj| obj.as_array().ok())
+            .and_then(|arr| numbers(arr));
+        if let Some(rect) = rect.filter(|r| r.len() == 4) {
+            let (x0, x1) = (rect[0].min(rect[2]), rect[0].max(rect[2]));
+            let (y0, y1) = (rect[1].min(rect[3]), rect[1].max(rect[3]));
+            if y1 > y0 {
+                return [x0, y0, x1, y1];
+            }
+        }
+    }
+    [0.0, 0.0, 612.0, 792.0]
+}
+
+// Fraction of the page height from the top edge, clamped to [0, 1]
+pub(crate) fn y_from_top(y: f64, page_box: &[f64; 4]) -> f64 {
+    ((page_box[3] - y) / (page_box[3] - page_box[1])).clamp(0.0, 1.0)
+}
+
+// Graphics/text state tracked while scanning a content stream
+struct TextState {
+    font_size: f64,
+    font_name: String,
+    render_mode: i64,
+    leading: f64,
+    ctm: Matrix,
+    fill_color: Rgb,
+    // Saved by q/Q: the parts of the graphics state tracked here
+    saved: Vec<(Matrix, Rgb)>,
+    text_matrix: Matrix,
+    line_matrix: Matrix,
+    // Current point and the not-yet-painted horizontal segments of the path
+    // under construction, both in upright page space
+    current_point: (f64, f64),
+    pending_rules: Vec<Rule>,
+}
+
+impl TextState {
+    fn new() -> Self {
+        TextState {
+            font_size: 12.0,
+            font_name: String::new(),
+            render_mode: 0,
+            leading: 0.0,
+            ctm: IDENTITY,
+            fill_color: BLACK,
+            saved: Vec::new(),
+            text_matrix: IDENTITY,
+            line_matrix: IDENTITY,
+            current_point: (0.0, 0.0),
+            pending_rules: Vec::new(),
+        }
+    }
+
+    // Td: start a new line offset from the start of the current one
+    fn move_line(&mut self, tx: f64, ty: f64) {
+        self.line_matrix = multiply(&translation(tx, ty), &self.line_matrix);
+        self.text_matrix = self.line_matrix;
+    }
+
+    // Move along the baseline after showing text. Glyph widths aren't read
+    // from the font, so an average advance of half an em per character is used.
+    fn advance(&mut self, text_space_units: f64) {
+        let tx = text_space_units * self.font_size;
+        self.text_matrix = multiply(&translation(tx, 0.0), &self.text_matrix);
+    }
+
+    // `advance` is the run's width in text space units, as passed to `advance`
+    fn run(&self, text: String, advance: f64, page: usize) -> TextRun {
+        let font = FontInfo::parse(&self.font_name);
+        let origin = multiply(&self.text_matrix, &self.ctm);
+        TextRun {
+            text,
+            size: self.font_size,
+            page,
+            font: self.font_name.clone(),
+            is_bold: font.is_bold(),
+            is_italic: font.italic,
+            weight: font.weight,
+            // 3 is "neither fill nor stroke", 7 adds the text to the clip path only
+            visible: self.render_mode != 3 && self.render_mode != 7,
+            x: origin[4],
+            y: origin[5],
+            width: advance * self.font_size * origin[0].hypot(origin[1]),
+            color: self.fill_color,
+        }
+    }
+
+    // `l`: keep the segment if it is horizontal once transformed
+    fn line_to(&mut self, x: f64, y: f64) {
+        let (x0, y0) = self.current_point;
+        let (x1, y1) = transform_point(&self.ctm, x, y);
+        if (y1 - y0).abs() <= 1.0 && (x1 - x0).abs() > 1.0 {
+            self.pending_rules.push(Rule { x0: x0.min(x1), x1: x0.max(x1), y: (y0 + y1) / 2.0 });
+        }
+        self.current_point = (x1, y1);
+    }
+
+    // `re`: a rectangle flat enough to read as a line
+    fn rectangle(&mut self, x: f64, y: f64, w: f64, h: f64) {
+        let (ax, ay) = transform_point(&self.ctm, x, y);
+        let (bx, by) = transform_point(&self.ctm, x + w, y + h);
+        let (width, height) = ((bx - ax).abs(), (by - ay).abs());
+        if height <= MAX_RULE_THICKNESS && width > height * 10.0 {
+            self.pending_rules.push(Rule { x0: ax.min(bx), x1: ax.max(bx), y: (ay + by) / 2.0 });
+        }
+        self.current_point = (ax, ay);
+    }
+}
+
+// Gray, RGB or CMYK components to RGB
+fn device_color(components: &[f64]) -> Option<Rgb> {
+    let c = |v: f64| v.clamp(0.0, 1.0);
+    match *components {
+        [gray] => Some([c(gray); 3]),
+        [r, g, b] => Some([c(r), c(g), c(b)]),
+        [cyan, magenta, yellow, black] => {
+            let k = 1.0 - c(black);
+            Some([(1.0 - c(cyan)) * k, (1.0 - c(magenta)) * k, (1.0 - c(yellow)) * k])
+        }
+        _ => None,
+    }
+}
+
+// Colours are bucketed to 1/16 steps per channel when counting, so
+// antialiasing-style near-duplicates land together
+type ColorKey = [u8; 3];
+
+fn color_key(color: &Rgb) -> ColorKey {
+    color.map(|v| (v * 15.0).round() as u8)
+}
+
+// Headings whose colour is further than this (largest channel difference)
+// from the dominant text colour get the colour bonus
+const COLOR_DIFFERENCE: f64 = 0.2;
+
+// A line at least this many times the page's body size stands out on size alone
+const STANDOUT_SIZE_RATIO: f64 = 1.3;
+
+// Most words the topmost line of a page may have to stand out by position
+const STANDOUT_TOP_LINE_WORDS: usize = 4;
+
+// Confidence given to a canonical section name however it's styled
+const CANONICAL_SECTION_CONFIDENCE: f64 = 0.85;
+
+// Italic lines at body size on a line of their own, in front of roman text,
+// are taken for headings once a document has at least this many of them
+// and they make up at least half of its italic lines. Below that, italics
+// are emphasis or quotations.
+const MIN_ITALIC_HEADINGS: usize = 3;
+
+// Confidence of an italic heading: just enough to be kept
+const ITALIC_HEADING_CONFIDENCE: f64 = 0.65;
+
+// Most words an italic line may have to read as a heading
+const MAX_ITALIC_HEADING_WORDS: usize = 6;
+
+// Rectangles thicker than this (in points) are boxes, not rules
+const MAX_RULE_THICKNESS: f64 = 2.5;
+
+// Average glyph advance in text space units (fraction of the font size)
+const AVERAGE_GLYPH_WIDTH: f64 = 0.5;
+
+// Text runs and painted horizontal rules from one page's content stream
+struct PageContent {
+    runs: Vec<TextRun>,
+    rules: Vec<Rule>,
+    // False when the content stream couldn't be loaded or decoded
+    readable: bool,
+    // Length of the decoded content stream
+    content_bytes: usize,
+    // Fonts whose text was skipped, having no mapping to Unicode
+    unmapped_fonts: BTreeSet<String>,
 }
 
-// Extract text runs with their font size and style from a PDF
-pub fn extract_runs(doc: &Document) -> Vec<TextRun> {
+// Scan a single page's content stream; the text state is local to the page
+fn extract_page_runs(doc: &Document, page_id: ObjectId, current_page: usize) -> PageContent {
     let mut runs = Vec::new();
+    let mut rules = Vec::new();
+    let mut readable = false;
+    let mut content_bytes = 0;
+    let mut unmapped_fonts = BTreeSet::new();
 
-    for (page_idx, (&_page_no, &page_id)) in doc.get_pages().iter().enumerate() {
-        let current_page = page_idx + 1;
-
-        // Get the page content stream and decode operations
-        if let Ok(content_data) = doc.get_page_content(page_id) {
-            if let Ok(content) = Content::decode(&content_data) {
-                let mut cur_font_size = 12.0_f64;
-                let mut cur_font_name = String::new();
-
-                for op in content.operations {
-                    match op.operator.as_ref() {
-                        "Tf" => {
-                            // "Tf" has operands: font-name, font-size
-                            if op.operands.len() == 2 {
-                                // Extract font name
-                                if let Object::Name(name) = &op.operands[0] {
-                                    cur_font_name = String::from_utf8_lossy(name).to_string();
-                                }
-                                
-                                // Extract font size
-                                let sz_opt = match &op.operands[1] {
-                                    Object::Real(r) => Some(*r as f64),
-                                    Object::Integer(i) => Some(*i as f64),
-                                    _ => None,
-                                };
-                                if let Some(sz) = sz_opt {
-                                    cur_font_size = sz;
-                                }
+    let fonts = page_fonts(doc, page_id);
+    let mut encoding = None;
+
+    // Get the page content stream and decode operations
+    if let Ok(content_data) = doc.get_page_content(page_id) {
+        content_bytes = content_data.len();
+        if let Ok(content) = Content::decode(&content_data) {
+            readable = true;
+            let mut state = TextState::new();
+            // Runs and rules come out in upright page space, so "top of the
+            // page" means the same on landscape and rotated pages
+            state.ctm = upright_transform(&media_box(doc, page_id), page_rotation(doc, page_id));
+
+            for op in content.operations {
+                match op.operator.as_ref() {
+                    "q" => state.saved.push((state.ctm, state.fill_color)),
+                    "Q" => {
+                        if let Some((ctm, color)) = state.saved.pop() {
+                            state.ctm = ctm;
+                            state.fill_color = color;
+                        }
+                    }
+                    "cm" => {
+                        if let Some(m) = numbers(&op.operands).filter(|m| m.len() == 6) {
+                            state.ctm = multiply(&[m[0], m[1], m[2], m[3], m[4], m[5]], &state.ctm);
+                        }
+                    }
+                    "BT" => {
+                        state.text_matrix = IDENTITY;
+                        state.line_matrix = IDENTITY;
+                    }
+                    "Tm" => {
+                        if let Some(m) = numbers(&op.operands).filter(|m| m.len() == 6) {
+                            state.line_matrix = [m[0], m[1], m[2], m[3], m[4], m[5]];
+                            state.text_matrix = state.line_matrix;
+                        }
+                    }
+                    "Td" | "TD" => {
+                        if let Some(t) = numbers(&op.operands).filter(|t| t.len() == 2) {
+                            if op.operator == "TD" {
+                                state.leading = -t[1];
                             }
+                            state.move_line(t[0], t[1]);
+                        }
+                    }
+                    "TL" => {
+                        if let Some(leading) = op.operands.first().and_then(number) {
+                            state.leading = leading;
+                        }
+                    }
+                    "T*" => state.move_line(0.0, -state.leading),
+                    // "Tf" has operands: font-name, font-size
+                    "Tf" if op.operands.len() == 2 => {
+                        // Extract font name
+                        if let Object::Name(name) = &op.operands[0] {
+                            state.font_name = fonts.get(name).map(|font| font.name.clone())
+                                .unwrap_or_else(|| String::from_utf8_lossy(name).to_string());
+                            encoding = fonts.get(name).map(|font| &font.encoding);
+                        }
+
+                        // Extract font size
+                        if let Some(sz) = number(&op.operands[1]) {
+                            state.font_size = sz;
+                        }
+                    }
+                    // Fill colour. Only device spaces are understood; for
+                    // `sc`/`scn` the component count stands in for the space,
+                    // and pattern or separation fills leave the colour as is.
+                    "g" | "rg" | "k" | "sc" | "scn" => {
+                        if let Some(color) = numbers(&op.operands).and_then(|c| device_color(&c)) {
+                            state.fill_color = color;
                         }
-                        "Tj" => {
-                            // Single string operand
-                            if let Some(text_obj) = op.operands.get(0) {
-                                if let Some(text) = try_decode_text(text_obj, doc) {
-                                    if !text.trim().is_empty() {
-                                        let (is_bold, is_italic) = analyze_font_style(&cur_font_name);
-                                        runs.push(TextRun { 
-                                            text, 
-                                            size: cur_font_size, 
-                                            page: current_page,
-                                            font_name: cur_font_name.clone(),
-                                            is_bold,
-                                            is_italic,
-                                        });
-                                    }
+                    }
+                    // Selecting a colour space resets the colour to its initial value
+                    "cs" => state.fill_color = BLACK,
+                    "Tr" => {
+                        if let Some(Ok(mode)) = op.operands.first().map(|o| o.as_i64()) {
+                            state.render_mode = mode;
+                        }
+                    }
+                    "Tj" | "'" | "\"" => {
+                        // ' and " move to the next line first; the string is the last operand
+                        if op.operator != "Tj" {
+                            state.move_line(0.0, -state.leading);
+                        }
+                        if let Some(text_obj) = op.operands.last() {
+                            if let Some(text) = decode_text(text_obj, encoding, &state.font_name, &mut unmapped_fonts) {
+                                let width = text.chars().count() as f64 * AVERAGE_GLYPH_WIDTH;
+                                if !text.trim().is_empty() {
+                                    runs.push(state.run(text, width, current_page));
                                 }
+                                state.advance(width);
                             }
                         }
-                        "TJ" => {
-                            // Array of strings and numbers
-                            if let Some(text_obj) = op.operands.get(0) {
-                                if let Object::Array(items) = text_obj {
-                                    let mut combined = String::new();
-                                    for item in items {
-                                        if let Some(s) = try_decode_text(item, doc) {
-                                            combined.push_str(&s);
-                                        }
-                                    }
-                                    if !combined.trim().is_empty() {
-                                        let (is_bold, is_italic) = analyze_font_style(&cur_font_name);
-                                        runs.push(TextRun { 
-                                            text: combined, 
-                                            size: cur_font_size, 
-                                            page: current_page,
-                                            font_name: cur_font_name.clone(),
-                                            is_bold,
-                                            is_italic,
-                                        });
-                                    }
+                    }
+                    "TJ" => {
+                        // Array of strings and numbers
+                        if let Some(Object::Array(items)) = op.operands.first() {
+                            let mut combined = String::new();
+                            let mut width = 0.0;
+                            for item in items {
+                                if let Some(s) = decode_text(item, encoding, &state.font_name, &mut unmapped_fonts) {
+                                    width += s.chars().count() as f64 * AVERAGE_GLYPH_WIDTH;
+                                    combined.push_str(&s);
+                                } else if let Some(adjust) = number(item) {
+                                    // Positioning adjustments are in thousandths of an em
+                                    width -= adjust / 1000.0;
                                 }
                             }
+                            if !combined.trim().is_empty() {
+                                runs.push(state.run(combined, width, current_page));
+                            }
+                            state.advance(width);
+                        }
+                    }
+                    // Path construction: only straight horizontals are kept
+                    "m" => {
+                        if let Some(p) = numbers(&op.operands).filter(|p| p.len() == 2) {
+                            state.current_point = transform_point(&state.ctm, p[0], p[1]);
+                        }
+                    }
+                    "l" => {
+                        if let Some(p) = numbers(&op.operands).filter(|p| p.len() == 2) {
+                            state.line_to(p[0], p[1]);
+                        }
+                    }
+                    "c" | "v" | "y" => {
+                        if let Some(p) = numbers(&op.operands).filter(|p| p.len() >= 2) {
+                            let n = p.len();
+                            state.current_point = transform_point(&state.ctm, p[n - 2], p[n - 1]);
+                        }
+                    }
+                    "re" => {
+                        if let Some(r) = numbers(&op.operands).filter(|r| r.len() == 4) {
+                            state.rectangle(r[0], r[1], r[2], r[3]);
                         }
-                        _ => {}
                     }
+                    // Painting operators; `n` ends the path without drawing it
+                    "S" | "s" | "f" | "F" | "f*" | "B" | "B*" | "b" | "b*" => {
+                        rules.append(&mut state.pending_rules);
+                    }
+                    "n" => state.pending_rules.clear(),
+                    _ => {}
                 }
             }
         }
     }
 
-    runs
+    PageContent { runs, rules, readable, content_bytes, unmapped_fonts }
 }
 
-fn try_decode_text(obj: &Object, _doc: &Document) -> Option<String> {
-    match obj {
-        Object::String(bytes, _) => {
-            Some(String::from_utf8_lossy(&bytes).to_string())
+// The text runs of one page, for the public layout API
+pub fn page_runs(doc: &Document, page_id: ObjectId, page: usize) -> Vec<TextRun> {
+    extract_page_runs(doc, page_id, page).runs
+}
+
+// Horizontally overlapping rules closer than this (in points) are chained
+// together; a chain of three or more is taken to be table grid lines
+const GRID_NEIGHBOUR_DISTANCE: f64 = 30.0;
+
+// The box spanned by one table's grid lines, in upright page space
+#[derive(Debug, Clone, Copy)]
+struct Grid {
+    x0: f64,
+    x1: f64,
+    bottom: f64,
+    top: f64,
+}
+
+impl Grid {
+    fn contains(&self, x: f64, y: f64) -> bool {
+        (self.bottom..=self.top).contains(&y) && (self.x0 - 1.0..=self.x1).contains(&x)
+    }
+}
+
+// Separate rules that belong to a dense stack of horizontals, i.e. a table,
+// from those that may underline a heading
+fn split_rules(rules: Vec<Rule>) -> (Vec<Rule>, Vec<Grid>) {
+    let near = |a: &Rule, b: &Rule| {
+        (a.y - b.y).abs() <= GRID_NEIGHBOUR_DISTANCE && a.x0 < b.x1 && b.x0 < a.x1
+    };
+
+    // Flood-fill each rule's chain of neighbours
+    let mut chain = vec![usize::MAX; rules.len()];
+    let mut chain_sizes = Vec::new();
+    for start in 0..rules.len() {
+        if chain[start] != usize::MAX {
+            continue;
         }
-        _ => None,
+        let id = chain_sizes.len();
+        chain[start] = id;
+        let mut stack = vec![start];
+        let mut size = 0;
+        while let Some(i) = stack.pop() {
+            size += 1;
+            for j in 0..rules.len() {
+                if chain[j] == usize::MAX && near(&rules[i], &rules[j]) {
+                    chain[j] = id;
+                    stack.push(j);
+                }
+            }
+        }
+        chain_sizes.push(size);
     }
+
+    let mut grids: Vec<Option<Grid>> = vec![None; chain_sizes.len()];
+    let mut heading_rules = Vec::new();
+    for (rule, id) in rules.into_iter().zip(chain) {
+        if chain_sizes[id] < 3 {
+            heading_rules.push(rule);
+            continue;
+        }
+        let grid = grids[id].get_or_insert(Grid { x0: rule.x0, x1: rule.x1, bottom: rule.y, top: rule.y });
+        grid.x0 = grid.x0.min(rule.x0);
+        grid.x1 = grid.x1.max(rule.x1);
+        grid.bottom = grid.bottom.min(rule.y);
+        grid.top = grid.top.max(rule.y);
+    }
+    (heading_rules, grids.into_iter().flatten().collect())
 }
 
-// Analyze font style based on font name
-fn analyze_font_style(font_name: &str) -> (bool, bool) {
-    let font_lower = font_name.to_lowercase();
-    
-    let is_bold = font_lower.contains("bold") || 
-                  font_lower.contains("black") || 
-                  font_lower.contains("heavy") ||
-                  font_lower.contains("extrabold") ||
-                  font_lower.contains("semibold");
-    
-    let is_italic = font_lower.contains("italic") || 
-                    font_lower.contains("oblique");
-    
-    (is_bold, is_italic)
+// Runs on baselines this close (in points) share a row
+const ROW_TOLERANCE: f64 = 2.0;
+
+// Runs in a row further apart than this many times the font size are in
+// different cells
+const CELL_GAP: f64 = 1.5;
+
+// Cell left edges within this many points of each other are one column
+const COLUMN_ALIGNMENT: f64 = 3.0;
+
+// Rows further apart than this many times the font size aren't consecutive
+const ROW_SPACING: f64 = 3.0;
+
+// Fewest consecutive aligned rows that make a table without grid lines
+const MIN_TABLE_ROWS: usize = 3;
+
+// Text in a table at least this many times the size of the table's own text
+// is a title set over it rather than a cell
+const TABLE_TITLE_SIZE_RATIO: f64 = 1.4;
+
+// One row of a page's text split into cells at wide gaps
+struct Row {
+    y: f64,
+    size: f64,
+    runs: Vec<usize>,
+    // Left edge of each cell, and whether the cell is a figure
+    cells: Vec<(f64, bool)>,
+}
+
+// "1,204.5", "(32)", "$4.1m", "12%"
+fn is_figure(text: &str) -> bool {
+    let digits = text.chars().filter(|c| c.is_ascii_digit()).count();
+    let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+    digits > 0 && letters <= 1
+}
+
+// Whether each run sits in a table: inside a ruled grid, or in a stack of
+// consecutive rows whose cells line up. Prose set in two columns lines up
+// too, so rows need three shared columns, or one when both hold figures.
+// Runs much larger than the table's text (a title above the grid) are left
+// out.
+fn table_runs(runs: &[TextRun], grids: &[Grid]) -> Vec<bool> {
+    let mut order: Vec<usize> = (0..runs.len()).collect();
+    order.sort_by(|&a, &b| runs[b].y.total_cmp(&runs[a].y).then(runs[a].x.total_cmp(&runs[b].x)));
+    let mut rows: Vec<Row> = Vec::new();
+    for i in order {
+        let run = &runs[i];
+        match rows.last_mut().filter(|row| (row.y - run.y).abs() <= ROW_TOLERANCE) {
+            Some(row) => {
+                row.runs.push(i);
+                row.size = row.size.max(run.size);
+            }
+            None => rows.push(Row { y: run.y, size: run.size, runs: vec![i], cells: Vec::new() }),
+        }
+    }
+    for row in &mut rows {
+        row.runs.sort_by(|&a, &b| runs[a].x.total_cmp(&runs[b].x));
+        let mut cell_end = f64::NEG_INFINITY;
+        let mut cell = String::new();
+        for &i in &row.runs {
+            let run = &runs[i];
+            if run.x - cell_end > CELL_GAP * run.size {
+                if let Some(last) = row.cells.last_mut() {
+                    last.1 = is_figure(&cell);
+                }
+                row.cells.push((run.x, false));
+                cell.clear();
+            }
+            cell.push_str(&run.text);
+            cell_end = cell_end.max(run.x + run.width);
+        }
+        if let Some(last) = row.cells.last_mut() {
+            last.1 = is_figure(&cell);
+        }
+    }
+
+    // Designs box and stripe text with rules too; a grid is a table only
+    // when it holds at least two rows of two or more cells
+    let grids: Vec<&Grid> = grids.iter()
+        .filter(|grid| {
+            rows.iter()
+                .filter(|row| row.cells.iter().filter(|&&(x, _)| grid.contains(x, row.y)).count() >= 2)
+                .count() >= 2
+        })
+        .collect();
+    let mut in_table: Vec<bool> = runs.iter().map(|run| grids.iter().any(|grid| grid.contains(run.x, run.y))).collect();
+
+    let lines_up = |a: &Row, b: &Row| {
+        if a.cells.len() < 2 || b.cells.len() < 2 || a.y - b.y > ROW_SPACING * a.size.max(b.size) {
+            return false;
+        }
+        let shared = a.cells.iter()
+            .filter(|(x, _)| b.cells.iter().any(|(other, _)| (x - other).abs() <= COLUMN_ALIGNMENT))
+            .count();
+        let figures = |row: &Row| row.cells.iter().any(|&(_, figure)| figure);
+        shared >= 3 || (shared >= 1 && figures(a) && figures(b))
+    };
+    let mut start = 0;
+    for end in 1..=rows.len() {
+        if end < rows.len() && lines_up(&rows[end - 1], &rows[end]) {
+            continue;
+        }
+        if end - start >= MIN_TABLE_ROWS {
+            for row in &rows[start..end] {
+                for &i in &row.runs {
+                    in_table[i] = true;
+                }
+            }
+        }
+        start = end;
+    }
+
+    // The table's text size, by characters
+    let mut size_chars: HashMap<i64, usize> = HashMap::new();
+    for (run, _) in runs.iter().zip(&in_table).filter(|(_, &table)| table) {
+        *size_chars.entry((run.size * 10.0).round() as i64).or_default() += run.text.trim().chars().count();
+    }
+    if let Some(table_size) = most_common(size_chars) {
+        let table_size = table_size as f64 / 10.0;
+        for (run, table) in runs.iter().zip(&mut in_table) {
+            *table &= run.size < table_size * TABLE_TITLE_SIZE_RATIO;
+        }
+    }
+    in_table
+}
+
+// Whether a rule sits just under the run's baseline and spans roughly the
+// same width as its text. Widths are estimated from an average glyph
+// advance, so the match is loose.
+fn is_underlined(run: &TextRun, rules: &[Rule]) -> bool {
+    if run.width <= 0.0 {
+        return false;
+    }
+    rules.iter().any(|rule| {
+        let drop = run.y - rule.y;
+        let ratio = (rule.x1 - rule.x0) / run.width;
+        (-1.0..=run.size).contains(&drop)
+            && (0.6..=2.5).contains(&ratio)
+            && rule.x0 <= run.x + run.size
+            && rule.x1 >= run.x
+    })
+}
+
+// Apply the hidden-text policy to one page's runs. When invisible runs are
+// kept, those duplicating a visible run's text are still dropped.
+fn filter_hidden_runs(runs: Vec<TextRun>, policy: HiddenText) -> Vec<TextRun> {
+    let has_visible = runs.iter().any(|run| run.visible);
+    let keep_hidden = match policy {
+        HiddenText::Include => true,
+        HiddenText::Exclude => false,
+        HiddenText::Auto => !has_visible,
+    };
+    if !keep_hidden {
+        return runs.into_iter().filter(|run| run.visible).collect();
+    }
+
+    let visible_texts: HashSet<String> = runs.iter()
+        .filter(|run| run.visible)
+        .map(|run| run.text.trim().to_string())
+        .collect();
+    runs.into_iter()
+        .filter(|run| run.visible || !visible_texts.contains(run.text.trim()))
+        .collect()
+}
+
+// A drop cap is a letter at least this many times the size of the body
+// text set beside it
+const DROP_CAP_SIZE_RATIO: f64 = 2.0;
+
+// Leaves out drop caps: the initial letter, several lines tall, that some
+// reports open a paragraph with. Left in, one reads as the largest text on
+// its page, a title or a one-letter heading, and counts towards the sizes
+// that body text is told apart by.
+pub(crate) fn skip_drop_caps(runs: Vec<TextRun>) -> Vec<TextRun> {
+    let drop_caps: Vec<bool> = runs.iter().map(|run| is_drop_cap(run, &runs)).collect();
+    if !drop_caps.contains(&true) {
+        return runs;
+    }
+    runs.into_iter().zip(drop_caps)
+        .filter(|(_, drop_cap)| !drop_cap)
+        .map(|(run, _)| run)
+        .collect()
+}
+
+// One letter, perhaps with an opening quote, and beside it text less than
+// half its size: starting just right of it, between its baseline and its
+// top, where the paragraph's first lines are indented to make room
+fn is_drop_cap(run: &TextRun, runs: &[TextRun]) -> bool {
+    let text = run.text.trim();
+    if !run.visible || text.chars().count() > 2 || text.chars().filter(|c| c.is_alphabetic()).count() != 1 {
+        return false;
+    }
+    let right = run.x + run.width;
+    runs.iter().any(|body| {
+        body.visible
+            && body.size * DROP_CAP_SIZE_RATIO <= run.size
+            && body.text.trim().chars().count() > 1
+            && (run.y - body.size * 0.5..=run.y + run.size).contains(&body.y)
+            && (run.x..=right + run.size).contains(&body.x)
+    })
+}
+
+// The text of a string operand in the current font. A font not among the
+// page's resources has its bytes read as UTF-8; one whose codes map to
+// nothing gives no text, and is noted in `unmapped`.
+fn decode_text(obj: &Object, encoding: Option<&FontEncoding>, font: &str, unmapped: &mut BTreeSet<String>) -> Option<String> {
+    let Object::String(bytes, _) = obj else {
+        return None;
+    };
+    match encoding {
+        Some(encoding) => {
+            let text = encoding.decode(bytes);
+            if text.is_none() && !bytes.is_empty() {
+                unmapped.insert(font.to_string());
+            }
+            text
+        }
+        None => Some(String::from_utf8_lossy(bytes).to_string()),
+    }
+}
+
+// A font resource: its base font name and how its strings decode
+struct PageFont {
+    name: String,
+    encoding: FontEncoding,
+}
+
+// The fonts by resource name, from the /Resources the page uses: its
+// own or, when it has none, the nearest ancestor's. lopdf's
+// `get_page_fonts` misses resources written inline on a Pages node and a
+// /Font entry that is a reference.
+fn page_fonts(doc: &Document, page_id: ObjectId) -> HashMap<Vec<u8>, PageFont> {
+    let fonts = inherited_attribute(doc, page_id, b"Resources")
+        .and_then(|resources| resources.as_dict().ok())
+        .and_then(|resources| resources.get(b"Font").ok())
+        .and_then(|fonts| doc.dereference(fonts).ok())
+        .and_then(|(_, fonts)| fonts.as_dict().ok());
+    let Some(fonts) = fonts else {
+        return HashMap::new();
+    };
+    fonts.iter()
+        .filter_map(|(resource, font)| {
+            let font = doc.dereference(font).ok()?.1.as_dict().ok()?;
+            Some((resource.clone(), PageFont { name: base_font_name(font)?, encoding: FontEncoding::of(doc, font) }))
+        })
+        .collect()
+}
+
+// "ABCDEF+Helvetica-Bold" -> "Helvetica-Bold"
+fn base_font_name(font: &Dictionary) -> Option<String> {
+    let name = font.get(b"BaseFont").and_then(Object::as_name_str).ok()?;
+    Some(strip_subset(name).unwrap_or(name).to_string())
 }
 
 // Classify heading level based on font size and style (similar to Python approach)
 pub fn classify_heading(size: f64, is_bold: bool, is_italic: bool) -> (String, f64) {
-    let mut confidence: f64 = 0.0;
-    let level;
-
-    if size > 15.0 {
-        level = "H1".to_string();
-        confidence = 0.9;
-    } else if size > 12.0 && size <= 15.0 {
-        level = "H2".to_string();
-        confidence = 0.8;
-    } else if size > 10.0 && size <= 12.0 {
-        level = "H3".to_string();
-        confidence = 0.6;
+    let (level, mut confidence) = if size > 15.0 {
+        ("H1".to_string(), 0.9_f64)
+    } else if size > 12.0 {
+        ("H2".to_string(), 0.8)
+    } else if size > 10.0 {
+        ("H3".to_string(), 0.6)
     } else {
-        level = "Body Text".to_string();
-        confidence = 0.1;
-    }
+        ("Body Text".to_string(), 0.1)
+    };
 
     // Boost confidence if text is bold or italic
     if is_bold {
@@ -161,87 +859,516 @@ pub fn classify_heading(size: f64, is_bold: bool, is_italic: bool) -> (String, f
 }
 
 // Extract heading candidates with confidence scores
-pub fn extract_heading_candidates(doc: &Document) -> Vec<HeadingCandidate> {
-    let runs = extract_runs(doc);
+//
+// Each page's runs are classified and dropped before moving on, so peak memory
+// follows the largest page rather than the whole document. Pages are processed
+// in parallel and their candidates concatenated in page order. Also returns
+// the characters of text on each page read, and the fonts whose text was
+// skipped. Once `budget` is exceeded the pages not yet started are skipped.
+pub(crate) fn extract_heading_candidates(doc: &Document, config: &ExtractorConfig, stats: &mut Stats, budget: &MemoryBudget) -> DocumentCandidates {
+    // Pages outside the requested ranges are never decoded
+    let pages: Vec<(usize, ObjectId)> = doc.get_pages().into_values()
+        .enumerate()
+        .map(|(page_idx, page_id)| (page_idx + 1, page_id))
+        .filter(|&(page, _)| config.includes_page(page))
+        .collect();
+
+    let page_results: Vec<Option<(PageCandidates, Duration, Duration)>> = pages
+        .par_iter()
+        .map(|&(page, page_id)| {
+            if budget.exceeded() {
+                return None;
+            }
+            let start = Instant::now();
+            let content = extract_page_runs(doc, page_id, page);
+            let held = content.content_bytes + content.runs.iter().map(|run| run.text.len()).sum::<usize>();
+            budget.charge(held);
+            let runs = skip_drop_caps(filter_hidden_runs(content.runs, config.hidden_text));
+            let (rules, grids) = split_rules(content.rules);
+            let extracted = Instant::now();
+            let mut result = page_heading_candidates(runs, &rules, &grids, page, &page_box(doc, page_id), config);
+            result.unmapped_fonts = content.unmapped_fonts;
+            budget.release(held);
+            Some((result, extracted - start, extracted.elapsed()))
+        })
+        .collect();
+    let (page_results, pages): (Vec<_>, Vec<_>) = page_results.into_iter().zip(pages)
+        .filter_map(|(result, page)| Some((result?, page)))
+        .unzip();
+
+    // Dominant text colour and body size across the document, weighted by characters
+    let mut color_chars: HashMap<ColorKey, usize> = HashMap::new();
+    let mut size_chars: HashMap<i64, usize> = HashMap::new();
+    for (page, _, _) in &page_results {
+        merge_counts(&mut color_chars, &page.color_chars);
+        merge_counts(&mut size_chars, &page.size_chars);
+    }
+    let (dominant_color, body_size) = dominant_style(color_chars, size_chars);
+
+    // A document that sets its headings in italics does it throughout; they
+    // go one level below the deepest styled heading, at H3 or H4
+    let italic_headings: usize = page_results.iter().map(|(page, _, _)| page.italic_headings.len()).sum();
+    let italic_lines: usize = page_results.iter().map(|(page, _, _)| page.italic_lines).sum();
+    let italic_level = (italic_headings >= MIN_ITALIC_HEADINGS && italic_headings * 2 >= italic_lines).then(|| {
+        let deepest = page_results.iter()
+            .flat_map(|(page, _, _)| &page.candidates)
+            .filter_map(|candidate| candidate.level.strip_prefix('H')?.parse::<u8>().ok())
+            .max()
+            .unwrap_or(0);
+        format!("H{}", (deepest + 1).clamp(3, MAX_HEADING_DEPTH))
+    });
+    if let Some(level) = &italic_level {
+        log::debug!("{} italic lines read as {} headings", italic_headings, level);
+    }
+
     let mut candidates = Vec::new();
-    
-    // Group runs by page and line (approximate)
-    let mut page_lines: HashMap<usize, Vec<String>> = HashMap::new();
-    let mut page_run_info: HashMap<(usize, String), (f64, bool, bool)> = HashMap::new();
-    
-    for run in runs {
-        let text = run.text.trim();
-        if text.len() <= 3 || text.len() > 150 { // Better length filtering like Python
+    let mut page_chars = BTreeMap::new();
+    let mut unmapped_fonts: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut previous_ends_mid_section = false;
+    let mut previous_page = 0;
+    for ((mut page, run_time, classify_time), &(page_num, _)) in page_results.into_iter().zip(&pages) {
+        for font in std::mem::take(&mut page.unmapped_fonts) {
+            unmapped_fonts.entry(font).or_default().push(page_num);
+        }
+        stats.add(Stage::RunExtraction, run_time);
+        stats.add(Stage::CandidateClassification, classify_time);
+        // Nothing is known about the page before one that follows skipped pages
+        let follows_previous = previous_page + 1 == page_num && previous_ends_mid_section;
+        apply_page_bonuses(&mut page, follows_previous, dominant_color, body_size, config);
+        previous_ends_mid_section = page.ends_mid_section;
+        previous_page = page_num;
+        page_chars.insert(page_num, page.size_chars.values().sum());
+        if let Some(level) = &italic_level {
+            page.candidates.extend(page.italic_headings.drain(..).map(|candidate| HeadingCandidate { level: level.clone(), ..candidate }));
+            page.candidates.sort_by_key(|candidate| candidate.seq);
+        }
+        candidates.extend(page.candidates);
+    }
+    join_split_headings(&mut candidates);
+    for (seq, candidate) in candidates.iter_mut().enumerate() {
+        candidate.seq = seq;
+    }
+    DocumentCandidates { candidates, page_chars, unmapped_fonts }
+}
+
+// What the font engine reads from a document's pages
+pub(crate) struct DocumentCandidates {
+    pub(crate) candidates: Vec<HeadingCandidate>,
+    // Characters of text on each page read
+    pub(crate) page_chars: BTreeMap<usize, usize>,
+    // The pages on which each font's text was skipped, the font being
+    // symbolic with nothing to map its codes to Unicode
+    pub(crate) unmapped_fonts: BTreeMap<String, Vec<usize>>,
+}
+
+// Joins a heading that is only its marker ("CHAPTER 4"), alone at the foot
+// of a page, to the title opening the next page ("IMPLEMENTATION"), as one
+// heading on the marker's page. The title has to be the next page's first
+// candidate, unnumbered and shaped like a title.
+fn join_split_headings(candidates: &mut Vec<HeadingCandidate>) {
+    let mut i = 0;
+    while i + 1 < candidates.len() {
+        let (marker, title) = (&candidates[i], &candidates[i + 1]);
+        let split = marker.ends_page && title.opens_page && title.page == marker.page + 1
+            && is_bare_marker(&marker.text) && completes_marker(&title.text);
+        if split {
+            let title = candidates.remove(i + 1);
+            let marker = &mut candidates[i];
+            log::debug!("{:?} on page {} is titled {:?} from the next page", marker.text, marker.page, title.text);
+            marker.text = format!("{} {}", marker.text.trim(), title.text.trim());
+            marker.confidence = marker.confidence.max(title.confidence);
+            marker.emphasized |= title.emphasized;
+            marker.stands_out |= title.stands_out;
+            marker.ends_page = title.ends_page;
+        }
+        i += 1;
+    }
+}
+
+// The largest visible text on the first page, joined in drawing order, scored
+// 0..1 by how much larger it is than that page's most common size (twice the
+// size or more scores 1)
+pub fn largest_text(doc: &Document, config: &ExtractorConfig) -> Option<(String, f64)> {
+    let page_id = *doc.get_pages().values().next()?;
+    let page_box = page_box(doc, page_id);
+    let band = config.margin_band;
+    let runs: Vec<TextRun> = skip_drop_caps(extract_page_runs(doc, page_id, 1).runs).into_iter()
+        .filter(|run| run.visible && !run.text.trim().is_empty())
+        .filter(|run| {
+            let y = y_from_top(run.y, &page_box);
+            band <= 0.0 || (band..=1.0 - band).contains(&y)
+        })
+        .collect();
+
+    let mut size_chars: HashMap<i64, usize> = HashMap::new();
+    for run in &runs {
+        *size_chars.entry((run.size * 10.0).round() as i64).or_default() += run.text.trim().chars().count();
+    }
+    let body_size = most_common(size_chars)? as f64 / 10.0;
+    let largest = runs.iter().map(|run| run.size).fold(0.0, f64::max);
+    if largest <= body_size || body_size <= 0.0 {
+        return None;
+    }
+
+    let text = runs.iter()
+        .filter(|run| (run.size - largest).abs() < 0.1)
+        .map(|run| run.text.trim())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some((text, (largest / body_size - 1.0).clamp(0.0, 1.0)))
+}
+
+// Key with the highest count; ties go to the smallest key so the pick is stable
+pub(crate) fn most_common<K: Ord>(counts: HashMap<K, usize>) -> Option<K> {
+    counts.into_iter()
+        .max_by(|(key_a, a), (key_b, b)| a.cmp(b).then(key_b.cmp(key_a)))
+        .map(|(key, _)| key)
+}
+
+fn merge_counts<K: Copy + Eq + std::hash::Hash>(total: &mut HashMap<K, usize>, page: &HashMap<K, usize>) {
+    for (key, chars) in page {
+        *total.entry(*key).or_default() += chars;
+    }
+}
+
+// The most used text colour and font size from per-character counts
+fn dominant_style(color_chars: HashMap<ColorKey, usize>, size_chars: HashMap<i64, usize>) -> (Rgb, f64) {
+    let dominant_color = most_common(color_chars)
+        .map(|key| key.map(|v| v as f64 / 15.0))
+        .unwrap_or(BLACK);
+    let body_size = most_common(size_chars).map(|key| key as f64 / 10.0).unwrap_or(0.0);
+    (dominant_color, body_size)
+}
+
+// The confidence bonuses that depend on more than the page itself
+fn apply_page_bonuses(page: &mut PageCandidates, previous_ends_mid_section: bool, dominant_color: Rgb, body_size: f64, config: &ExtractorConfig) {
+    // A heading opening a page right after a page of running text is
+    // most likely a new section
+    if previous_ends_mid_section {
+        let topmost = page.candidates.iter_mut()
+            .filter(|c| c.y < 1.0 / 3.0)
+            .min_by(|a, b| a.y.total_cmp(&b.y));
+        if let Some(candidate) = topmost {
+            candidate.confidence = (candidate.confidence + config.top_of_page_bonus).min(1.0);
+        }
+    }
+
+    // Colour only adds weight: hyperlinks are coloured too, so it needs a
+    // second signal (larger than body text, bold or underlined) to count
+    for candidate in &mut page.candidates {
+        if candidate.size <= body_size + 0.25 && !candidate.emphasized {
             continue;
         }
-        
-        page_lines.entry(run.page).or_insert_with(Vec::new).push(text.to_string());
-        page_run_info.insert((run.page, text.to_string()), (run.size, run.is_bold, run.is_italic));
+        let difference = candidate.color.iter()
+            .zip(dominant_color.iter())
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        if difference > COLOR_DIFFERENCE {
+            candidate.confidence = (candidate.confidence + config.color_bonus).min(1.0);
+        }
     }
-    
-    for (page_num, lines) in page_lines {
-        for line in lines {
-            if let Some((size, is_bold, is_italic)) = page_run_info.get(&(page_num, line.clone())) {
-                let (level, confidence) = classify_heading(*size, *is_bold, *is_italic);
-                
-                if confidence > 0.5 && is_good_heading_candidate(&line) {
-                    candidates.push(HeadingCandidate {
-                        text: line,
-                        level,
-                        page: page_num,
-                        confidence,
-                    });
-                }
-            }
+}
+
+// Classifies one page at a time, in page order, for callers that want
+// results before the whole document is read. The document's dominant colour
+// and body size aren't known until the end, so the colour bonus compares
+// against the pages seen so far, and headings set in italics, which take
+// the whole document to recognize, are left out; results can differ
+// slightly from `extract_heading_candidates`.
+pub struct PageClassifier<'a> {
+    config: &'a ExtractorConfig,
+    previous_ends_mid_section: bool,
+    color_chars: HashMap<ColorKey, usize>,
+    size_chars: HashMap<i64, usize>,
+    // `seq` for the next candidate, continuing across pages
+    next_seq: usize,
+}
+
+impl<'a> PageClassifier<'a> {
+    pub fn new(config: &'a ExtractorConfig) -> Self {
+        PageClassifier {
+            config,
+            previous_ends_mid_section: false,
+            color_chars: HashMap::new(),
+            size_chars: HashMap::new(),
+            next_seq: 0,
         }
     }
-    
-    candidates
+
+    // The page's candidates, or None when its content stream can't be read
+    pub fn classify(&mut self, doc: &Document, page_id: ObjectId, page_num: usize) -> Option<Vec<HeadingCandidate>> {
+        let content = extract_page_runs(doc, page_id, page_num);
+        if !content.readable {
+            return None;
+        }
+        let runs = skip_drop_caps(filter_hidden_runs(content.runs, self.config.hidden_text));
+        let (rules, grids) = split_rules(content.rules);
+        let mut page = page_heading_candidates(runs, &rules, &grids, page_num, &page_box(doc, page_id), self.config);
+
+        merge_counts(&mut self.color_chars, &page.color_chars);
+        merge_counts(&mut self.size_chars, &page.size_chars);
+        let (dominant_color, body_size) = dominant_style(self.color_chars.clone(), self.size_chars.clone());
+        apply_page_bonuses(&mut page, self.previous_ends_mid_section, dominant_color, body_size, self.config);
+        self.previous_ends_mid_section = page.ends_mid_section;
+        for candidate in &mut page.candidates {
+            candidate.seq = self.next_seq;
+            self.next_seq += 1;
+        }
+        Some(page.candidates)
+    }
 }
 
-// Additional validation for heading candidates
-fn is_good_heading_candidate(text: &str) -> bool {
-    let text = text.trim();
-    
-    // Length constraints similar to Python approach
-    if text.len() < 4 || text.len() > 100 {
-        return false;
+// Indents further apart than this many times the font size are columns
+// rather than nesting
+const MAX_INDENT_STEP: f64 = 4.0;
+
+// Unnumbered candidates at the same level, set at two or more indents with
+// at least two candidates each, are a nested list of headings ("• Risk
+// Register" with "– Vendor Exposure" under it): each indent past the first
+// goes one level deeper. Numbered headings keep their level, as numbering
+// says more about nesting than indentation does.
+fn apply_indent_tiers(candidates: &mut [HeadingCandidate]) {
+    // Keyed in level order, so the tiers are settled the same way every run
+    let mut levels: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, candidate) in candidates.iter().enumerate() {
+        let text = strip_list_marker(candidate.text.trim()).0;
+        let numbered = NUMBERED_HEADING.is_match(text) || section_heading_level(text).is_some() || APPENDIX_HEADING.is_match(text);
+        if !numbered && candidate.level.starts_with('H') {
+            levels.entry(candidate.level.clone()).or_default().push(i);
+        }
     }
-    
-    // Skip sentences (typically end with periods and have many words)
-    let word_count = text.split_whitespace().count();
-    if text.ends_with('.') && word_count > 8 {
-        return false;
+
+    for mut members in levels.into_values() {
+        members.sort_by(|&a, &b| candidates[a].x.total_cmp(&candidates[b].x));
+        // Indents as (left edge, candidates), from the left
+        let mut tiers: Vec<(f64, usize)> = Vec::new();
+        let mut tier_of = Vec::with_capacity(members.len());
+        for &i in &members {
+            let x = candidates[i].x;
+            match tiers.last_mut().filter(|(tier_x, _)| x - *tier_x <= COLUMN_ALIGNMENT) {
+                Some(tier) => tier.1 += 1,
+                None => tiers.push((x, 1)),
+            }
+            tier_of.push(tiers.len() - 1);
+        }
+        let size = members.iter().map(|&i| candidates[i].size).fold(0.0, f64::max);
+        let consistent = tiers.len() >= 2
+            && tiers.iter().all(|&(_, count)| count >= 2)
+            && tiers.windows(2).all(|pair| pair[1].0 - pair[0].0 <= MAX_INDENT_STEP * size);
+        if !consistent {
+            continue;
+        }
+        for (&i, tier) in members.iter().zip(tier_of) {
+            let candidate = &mut candidates[i];
+            let depth = candidate.level.strip_prefix('H').and_then(|n| n.parse::<usize>().ok()).unwrap_or(1);
+            candidate.level = format!("H{}", (depth + tier).min(MAX_HEADING_DEPTH as usize));
+        }
     }
+}
+
+struct PageCandidates {
+    candidates: Vec<HeadingCandidate>,
+    // The lowest text on the page (outside the footer band) is body text
+    ends_mid_section: bool,
+    // Characters of text drawn in each colour on this page
+    color_chars: HashMap<ColorKey, usize>,
+    // Characters of text at each font size, in tenths of a point
+    size_chars: HashMap<i64, usize>,
+    // Italic lines shaped like headings, kept only if the document turns
+    // out to set its headings in italics, and how many italic body-size
+    // lines the page has in all
+    italic_headings: Vec<HeadingCandidate>,
+    italic_lines: usize,
+    // Symbolic fonts whose text was left out, as nothing maps their codes
+    unmapped_fonts: BTreeSet<String>,
+}
+
+// Style of one line of text
+struct LineStyle {
+    size: f64,
+    is_bold: bool,
+    is_italic: bool,
+    // Italic and alone on its line, with roman text on the next
+    italic_lead: bool,
+    underlined: bool,
+    y: f64,
+    x: f64,
+    color: Rgb,
+}
+
+fn page_heading_candidates(runs: Vec<TextRun>, rules: &[Rule], grids: &[Grid], page_num: usize, page_box: &[f64; 4], config: &ExtractorConfig) -> PageCandidates {
+    debug_assert!(runs.iter().all(|run| run.page == page_num), "run buffer must hold a single page");
+
+    let mut candidates = Vec::new();
+    let band = config.margin_band;
+    let filter = CandidateFilter::new(config);
     
-    // Skip very long sentences or prose
-    if word_count > 12 {
-        return false;
+    // Group runs by line (approximate)
+    let mut lines: Vec<(String, LineStyle)> = Vec::new();
+    let mut lowest: Option<(f64, f64)> = None;
+    let mut highest = f64::INFINITY;
+    let mut color_chars: HashMap<ColorKey, usize> = HashMap::new();
+    let mut size_chars: HashMap<i64, usize> = HashMap::new();
+    let in_table = table_runs(&runs, grids);
+    let italic_leads = italic_lead_runs(&runs);
+
+    for ((run, in_table), italic_lead) in runs.into_iter().zip(in_table).zip(italic_leads) {
+        // Running headers, footers and page numbers live in the margin bands
+        let y = y_from_top(run.y, page_box);
+        if band > 0.0 && (y < band || y > 1.0 - band) {
+            continue;
+        }
+        if lowest.is_none_or(|(lowest_y, _)| y > lowest_y) {
+            lowest = Some((y, run.size));
+        }
+        highest = highest.min(y);
+
+        let text = run.text.trim();
+        let chars = text.chars().count();
+        *color_chars.entry(color_key(&run.color)).or_default() += chars;
+        *size_chars.entry((run.size * 10.0).round() as i64).or_default() += chars;
+        // Table cells count towards the page's style but are never headings
+        if in_table || !(MIN_HEADING_LEN..=MAX_HEADING_LEN).contains(&text.len()) {
+            continue;
+        }
+        
+        let style = LineStyle {
+            size: run.size,
+            is_bold: run.is_bold,
+            is_italic: run.is_italic,
+            italic_lead,
+            underlined: is_underlined(&run, rules),
+            y,
+            x: run.x,
+            color: run.color,
+        };
+        lines.push((text.to_string(), style));
     }
     
-    // Skip common non-heading patterns
-    let text_lower = text.to_lowercase();
-    let prose_indicators = [
-        "it is expected", "must be completed", "will be issued", "are expected",
-        "the planning process", "specifically", "during", "suitable for",
-        "include", "secure the full", "expected that", "approved by"
-    ];
-    
-    for indicator in &prose_indicators {
-        if text_lower.contains(indicator) {
-            return false;
+    // A line hyphenated onto the next one in the same style is one line
+    let words = standalone_words(lines.iter().map(|(text, _)| text.as_str()));
+    let mut joined: Vec<(String, LineStyle)> = Vec::with_capacity(lines.len());
+    for (text, style) in lines {
+        let whole = joined.last()
+            .filter(|(_, previous)| (previous.size - style.size).abs() < 0.1 && previous.is_bold == style.is_bold)
+            .and_then(|(previous, _)| join_hyphenated(previous, &text, &words));
+        match (whole, joined.last_mut()) {
+            (Some(whole), Some(last)) => last.0 = whole,
+            _ => joined.push((text, style)),
         }
     }
-    
-    // Skip text that starts with lowercase (usually continuation of sentences)
-    if text.chars().next().map_or(false, |c| c.is_lowercase()) {
-        return false;
+    let lines = joined;
+
+    let body_size = most_common(size_chars.clone()).map(|key| key as f64 / 10.0).unwrap_or(0.0);
+    let top_y = lines.iter().map(|(_, style)| style.y).fold(f64::INFINITY, f64::min);
+
+    // A canonical section name ("Executive summary") followed by body text is
+    // an H1 early in a document however it's styled
+    let canonical: Vec<bool> = lines.iter().enumerate()
+        .map(|(i, (line, _))| {
+            let next = lines.get(i + 1).filter(|(_, next)| (next.size - body_size).abs() < 0.5 && !next.is_bold);
+            opens_canonical_section(line, next.map(|(text, _)| text.as_str()), page_num, &config.lexicon)
+        })
+        .collect();
+
+    // Every line is classified on its own style, even when its text repeats
+    let mut italic_headings = Vec::new();
+    let mut italic_lines = 0;
+    for (seq, ((line, style), canonical)) in lines.into_iter().zip(canonical).enumerate() {
+        let (mut level, mut confidence) = classify_heading(style.size, style.is_bold, style.is_italic);
+        if canonical {
+            level = "H1".to_string();
+            confidence = confidence.max(CANONICAL_SECTION_CONFIDENCE);
+        }
+        if style.underlined {
+            confidence = (confidence + config.underline_bonus).min(1.0);
+        }
+
+        let info = LineInfo::new(line.trim());
+        let stands_out = style.size >= body_size * STANDOUT_SIZE_RATIO
+            || (style.is_bold && style.size > body_size + 0.25)
+            || (style.y == top_y && info.words.len() <= STANDOUT_TOP_LINE_WORDS);
+        if info.text.starts_with(|c: char| c.is_lowercase()) {
+            confidence -= config.lowercase_penalty;
+        }
+
+        let body_italic = style.is_italic && !style.is_bold && (style.size - body_size).abs() < 0.5;
+        italic_lines += usize::from(body_italic);
+        let accepted = canonical || (confidence > 0.5 && filter.accepts_styled(&info, stands_out));
+        let italic_heading = !accepted && body_italic && style.italic_lead && is_italic_heading_shaped(&info, &filter);
+        let candidate = HeadingCandidate {
+            text: line,
+            level,
+            page: page_num,
+            confidence,
+            // The line's place on the page until the document's order is known
+            seq,
+            y: style.y,
+            x: style.x,
+            color: style.color,
+            size: style.size,
+            emphasized: style.is_bold || style.underlined,
+            stands_out,
+            opens_page: style.y <= highest,
+            ends_page: lowest.is_some_and(|(lowest_y, _)| style.y >= lowest_y),
+        };
+        if accepted {
+            candidates.push(candidate);
+        } else if italic_heading {
+            italic_headings.push(HeadingCandidate { confidence: ITALIC_HEADING_CONFIDENCE, ..candidate });
+        }
     }
-    
-    // Skip numeric lists that are too detailed/long
-    if text.starts_with(char::is_numeric) && word_count > 6 {
-        return false;
+
+    // Of the candidates sharing a text on this page ("Overview" as a heading
+    // and again in a cross-reference) only the most confident is kept
+    let mut best: HashMap<&str, usize> = HashMap::new();
+    for (i, candidate) in candidates.iter().enumerate() {
+        best.entry(candidate.text.as_str())
+            .and_modify(|kept| if candidate.confidence > candidates[*kept].confidence { *kept = i })
+            .or_insert(i);
     }
+    let keep: HashSet<usize> = best.into_values().collect();
+    let mut candidates: Vec<HeadingCandidate> = candidates.into_iter().enumerate()
+        .filter(|(i, _)| keep.contains(i))
+        .map(|(_, candidate)| candidate)
+        .collect();
+    apply_indent_tiers(&mut candidates);
     
-    true
+    let ends_mid_section = lowest.is_some_and(|(y, size)| {
+        y > 0.5 && classify_heading(size, false, false).0 == "Body Text"
+    });
+    PageCandidates { candidates, ends_mid_section, color_chars, size_chars, italic_headings, italic_lines, unmapped_fonts: BTreeSet::new() }
+}
+
+// A short, capitalized line that doesn't end like a sentence: "Sampling
+// frame", but not a quotation or a run-in phrase
+fn is_italic_heading_shaped(info: &LineInfo, filter: &CandidateFilter) -> bool {
+    info.words.len() <= MAX_ITALIC_HEADING_WORDS
+        && !info.text.trim_end().ends_with(['.', ',', ';', ':'])
+        && filter.accepts(info)
+}
+
+// For each run, whether it's italic, alone on its line (or sharing it only
+// with other italic runs) and followed by roman text on a lower line: how
+// a heading set in italics sits, as opposed to emphasis inside a sentence
+fn italic_lead_runs(runs: &[TextRun]) -> Vec<bool> {
+    let mut baselines: Vec<(f64, bool)> = runs.iter()
+        .filter(|run| !run.text.trim().is_empty())
+        .map(|run| (run.y, run.is_italic))
+        .collect();
+    baselines.sort_by(|a, b| a.0.total_cmp(&b.0));
+    runs.iter().enumerate()
+        .map(|(i, run)| {
+            if !run.is_italic || run.text.trim().is_empty() {
+                return false;
+            }
+            let tolerance = run.size * 0.5;
+            let from = baselines.partition_point(|&(y, _)| y < run.y - tolerance);
+            let alone = baselines[from..].iter()
+                .take_while(|&&(y, _)| y <= run.y + tolerance)
+                .all(|&(_, italic)| italic);
+            let next = runs[i + 1..].iter().find(|next| !next.text.trim().is_empty());
+            alone && next.is_some_and(|next| !next.is_italic && next.y < run.y - tolerance && next.y > run.y - run.size * 3.0)
+        })
+        .collect()
 }
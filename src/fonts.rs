@@ -0,0 +1,253 @@
+//! What a font's PDF name (/BaseFont) says about it. Names follow the
+//! PostScript habit of a family, then a style after a hyphen or comma
+//! ("Arial,BoldItalic", "HelveticaNeue-CondensedBold"), often behind a
+//! six-letter subset tag ("ABCDEF+") when only the glyphs used are embedded.
+
+use serde::Serialize;
+
+/// Weight of regular text on the usual 100 (thin) to 900 (black) scale
+pub const REGULAR: u16 = 400;
+
+/// Weight from which a font counts as bold: semibold and up
+pub const BOLD: u16 = 600;
+
+// Style words and their weights. Words that only mean regular are listed
+// so they aren't taken for part of the family after a separator.
+const WEIGHTS: &[(&str, u16)] = &[
+    ("thin", 100), ("hairline", 100), ("extralight", 200), ("ultralight", 200), ("light", 300),
+    ("regular", 400), ("roman", 400), ("book", 400), ("normal", 400),
+    ("medium", 500), ("semibold", 600), ("demibold", 600), ("demi", 600),
+    ("bold", 700), ("extrabold", 800), ("ultrabold", 800), ("black", 900), ("heavy", 900),
+];
+
+// Abbreviations only trusted in the style part, where "Lt" can't be a
+// piece of the family name
+const WEIGHT_ABBREVIATIONS: &[(&str, u16)] = &[
+    ("th", 100), ("xlt", 200), ("lt", 300), ("rg", 400), ("reg", 400), ("md", 500), ("med", 500),
+    ("sb", 600), ("sbd", 600), ("smbd", 600), ("bd", 700), ("xbd", 800), ("blk", 900), ("hv", 900),
+];
+
+const ITALIC: &[&str] = &["italic", "oblique", "slanted", "inclined", "kursiv"];
+const ITALIC_ABBREVIATIONS: &[&str] = &["it", "ita", "obl"];
+
+const CONDENSED: &[&str] = &["condensed", "narrow", "compressed", "semicondensed", "extracondensed"];
+const CONDENSED_ABBREVIATIONS: &[&str] = &["cond", "cn", "cd", "comp"];
+
+// Parts of a weight written as two words ("Semi Bold", "ExtraLight")
+const WEIGHT_PREFIXES: &[&str] = &["semi", "demi", "extra", "ultra"];
+
+// Vendor tags at the end of a family name ("ArialMT", "TimesNewRomanPS")
+const VENDOR_TAGS: &[&str] = &["PSMT", "MT", "PS"];
+
+/// A font name taken apart. Parsing never fails: a name with nothing to go
+/// on (a resource name like "F1") is a regular, upright family of its own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FontInfo {
+    /// The name without the subset tag, style or vendor tag ("Arial")
+    pub family: String,
+    /// Estimated weight, from 100 to 900; [`REGULAR`] when none is named
+    pub weight: u16,
+    pub italic: bool,
+    /// Condensed or narrow
+    pub condensed: bool,
+    /// The font is embedded as a subset, behind a tag such as "ABCDEF+"
+    pub subset: bool,
+}
+
+impl FontInfo {
+    /// Takes apart a /BaseFont name. Style words count as whole words, so
+    /// "Blackadder" isn't black, and abbreviations ("Bd", "It", "Cn") only
+    /// after the hyphen or comma that ends the family. A name in a single
+    /// case, which can't be split into words, is searched for the bold and
+    /// italic words anywhere in it.
+    ///
+    /// ```
+    /// use adobe1a::fonts::FontInfo;
+    ///
+    /// let cases: &[(&str, &str, u16, bool, bool, bool)] = &[
+    ///     // name, family, weight, italic, condensed, subset
+    ///     ("Helvetica", "Helvetica", 400, false, false, false),
+    ///     ("Helvetica-Bold", "Helvetica", 700, false, false, false),
+    ///     ("Helvetica-BoldOblique", "Helvetica", 700, true, false, false),
+    ///     ("Times-Roman", "Times", 400, false, false, false),
+    ///     ("Times-BoldItalic", "Times", 700, true, false, false),
+    ///     ("Courier-Oblique", "Courier", 400, true, false, false),
+    ///     ("ArialMT", "Arial", 400, false, false, false),
+    ///     ("Arial-BoldMT", "Arial", 700, false, false, false),
+    ///     ("Arial,BoldItalic", "Arial", 700, true, false, false),
+    ///     ("ArialNarrow-Bold", "Arial", 700, false, true, false),
+    ///     ("Arial-Black", "Arial", 900, false, false, false),
+    ///     ("ArialBlack", "Arial", 900, false, false, false),
+    ///     ("TimesNewRomanPSMT", "TimesNewRoman", 400, false, false, false),
+    ///     ("TimesNewRomanPS-BoldItalicMT", "TimesNewRoman", 700, true, false, false),
+    ///     ("TimesNewRoman,Italic", "TimesNewRoman", 400, true, false, false),
+    ///     ("ABCDEF+HelveticaNeue-CondensedBold", "HelveticaNeue", 700, false, true, true),
+    ///     ("BAAAAA+Calibri", "Calibri", 400, false, false, true),
+    ///     ("QRSTUV+Calibri-Light", "Calibri", 300, false, false, true),
+    ///     ("Calibri-BoldItalic", "Calibri", 700, true, false, false),
+    ///     ("Cambria,Bold", "Cambria", 700, false, false, false),
+    ///     ("MyriadPro-SemiboldCond", "MyriadPro", 600, false, true, false),
+    ///     ("MyriadPro-It", "MyriadPro", 400, true, false, false),
+    ///     ("MinionPro-BoldIt", "MinionPro", 700, true, false, false),
+    ///     ("OpenSans-SemiBold", "OpenSans", 600, false, false, false),
+    ///     ("OpenSans-ExtraBoldItalic", "OpenSans", 800, true, false, false),
+    ///     ("Roboto-Medium", "Roboto", 500, false, false, false),
+    ///     ("Roboto-Thin", "Roboto", 100, false, false, false),
+    ///     ("Montserrat-ExtraLight", "Montserrat", 200, false, false, false),
+    ///     ("HelveticaNeue-UltraLight", "HelveticaNeue", 200, false, false, false),
+    ///     ("Lato-Hairline", "Lato", 100, false, false, false),
+    ///     ("Lato-Heavy", "Lato", 900, false, false, false),
+    ///     ("SourceSansPro-Black", "SourceSansPro", 900, false, false, false),
+    ///     ("Futura-CondensedExtraBold", "Futura", 800, false, true, false),
+    ///     ("Univers-Condensed", "Univers", 400, false, true, false),
+    ///     ("HelveticaNeueLTStd-BdCn", "HelveticaNeueLTStd", 700, false, true, false),
+    ///     ("HelveticaNeueLTStd-Lt", "HelveticaNeueLTStd", 300, false, false, false),
+    ///     ("Frutiger-BoldCn", "Frutiger", 700, false, true, false),
+    ///     ("SegoeUI-Semibold", "SegoeUI", 600, false, false, false),
+    ///     ("Georgia-Bold Italic", "Georgia", 700, true, false, false),
+    ///     ("BookAntiqua", "BookAntiqua", 400, false, false, false),
+    ///     ("Blackadder", "Blackadder", 400, false, false, false),
+    ///     ("LucidaSans-Demi", "LucidaSans", 600, false, false, false),
+    ///     ("helveticabold", "helveticabold", 700, false, false, false),
+    ///     ("Symbol", "Symbol", 400, false, false, false),
+    ///     ("F1", "F1", 400, false, false, false),
+    /// ];
+    /// for &(name, family, weight, italic, condensed, subset) in cases {
+    ///     assert_eq!(FontInfo::parse(name), FontInfo { family: family.into(), weight, italic, condensed, subset }, "{}", name);
+    /// }
+    /// assert!(FontInfo::parse("Arial-BoldMT").is_bold() && !FontInfo::parse("Roboto-Medium").is_bold());
+    /// ```
+    pub fn parse(name: &str) -> FontInfo {
+        let (subset, name) = match strip_subset(name) {
+            Some(rest) => (true, rest),
+            None => (false, name),
+        };
+
+        // The family runs to the first separator, or to the first style
+        // word before it
+        let separator = name.find(['-', ',', ' ', '_']).unwrap_or(name.len());
+        let words = split_words(name);
+        let family_end = words.iter()
+            .skip(1)
+            .find(|&&(start, word)| start >= separator || starts_style(&words, word))
+            .map_or(separator, |&(start, _)| start.min(separator));
+        let mut family = name[..family_end].trim_end_matches(['-', ',', ' ', '_']);
+        if let Some(tag) = VENDOR_TAGS.iter().find(|tag| family.len() > tag.len() && family.ends_with(**tag)) {
+            family = &family[..family.len() - tag.len()];
+        }
+
+        let style: Vec<String> = words.iter()
+            .filter(|(start, _)| *start >= family_end)
+            .map(|(_, word)| word.to_lowercase())
+            .collect();
+        let after_separator = |start: usize| start >= separator;
+        let abbreviations: Vec<String> = words.iter()
+            .filter(|(start, _)| after_separator(*start))
+            .map(|(_, word)| word.to_lowercase())
+            .collect();
+
+        let mut weight = None;
+        for (i, word) in style.iter().enumerate() {
+            let joined = match (WEIGHT_PREFIXES.contains(&word.as_str()), style.get(i + 1)) {
+                (true, Some(next)) => format!("{}{}", word, next),
+                _ => word.clone(),
+            };
+            weight = lookup(WEIGHTS, &joined).or_else(|| lookup(WEIGHTS, word));
+            if weight.is_some() {
+                break;
+            }
+        }
+        let weight = weight.or_else(|| abbreviations.iter().find_map(|word| lookup(WEIGHT_ABBREVIATIONS, word)));
+        let mut italic = style.iter().any(|word| ITALIC.contains(&word.as_str()))
+            || abbreviations.iter().any(|word| ITALIC_ABBREVIATIONS.contains(&word.as_str()));
+        let condensed = style.iter().any(|word| CONDENSED.iter().any(|condensed| word.ends_with(condensed)))
+            || abbreviations.iter().any(|word| CONDENSED_ABBREVIATIONS.contains(&word.as_str()));
+
+        // Without case changes to split at, the words can't be told apart
+        let single_case = !name.chars().any(char::is_uppercase) || !name.chars().any(char::is_lowercase);
+        let weight = match weight {
+            None if single_case => {
+                let lower = name.to_lowercase();
+                italic |= ITALIC.iter().any(|word| lower.contains(word));
+                ["semibold", "extrabold", "bold", "black", "heavy"].into_iter()
+                    .find(|word| lower.contains(word))
+                    .and_then(|word| lookup(WEIGHTS, word))
+            }
+            weight => weight,
+        };
+
+        FontInfo {
+            family: family.to_string(),
+            weight: weight.unwrap_or(REGULAR),
+            italic,
+            condensed,
+            subset,
+        }
+    }
+
+    /// Semibold or heavier
+    pub fn is_bold(&self) -> bool {
+        self.weight >= BOLD
+    }
+}
+
+/// The name after its subset tag ("ABCDEF+Helvetica" -> "Helvetica"), or
+/// None when it has none
+pub fn strip_subset(name: &str) -> Option<&str> {
+    match name.split_once('+') {
+        Some((tag, rest)) if tag.len() == 6 && tag.chars().all(|c| c.is_ascii_uppercase()) => Some(rest),
+        _ => None,
+    }
+}
+
+fn lookup(table: &[(&str, u16)], word: &str) -> Option<u16> {
+    table.iter().find(|(name, _)| *name == word).map(|&(_, weight)| weight)
+}
+
+// Whether a word in the family part already starts the style: a weight
+// other than regular, an italic or a width. Regular's words ("Roman",
+// "Book") stay in the family, as in "TimesNewRoman".
+fn starts_style(words: &[(usize, &str)], word: &str) -> bool {
+    let word = word.to_lowercase();
+    let joined_weight = WEIGHT_PREFIXES.contains(&word.as_str())
+        && words.iter().any(|(_, next)| lookup(WEIGHTS, &format!("{}{}", word, next.to_lowercase())).is_some());
+    lookup(WEIGHTS, &word).is_some_and(|weight| weight != REGULAR)
+        || joined_weight
+        || ITALIC.contains(&word.as_str())
+        || CONDENSED.contains(&word.as_str())
+}
+
+// The words of a name with their byte offsets, split at separators and
+// case changes: "HelveticaNeueLTStd-BdCn" -> Helvetica, Neue, LT, Std, Bd, Cn
+fn split_words(name: &str) -> Vec<(usize, &str)> {
+    let chars: Vec<(usize, char)> = name.char_indices().collect();
+    let mut words = Vec::new();
+    let mut start: Option<usize> = None;
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if let Some(s) = start.take() {
+                words.push((s, &name[s..offset]));
+            }
+            continue;
+        }
+        let previous = i.checked_sub(1).map(|i| chars[i].1).filter(|p| p.is_alphanumeric());
+        let next = chars.get(i + 1).map(|&(_, c)| c);
+        // A capital starts a word after a lowercase letter or digit, and
+        // ends a run of capitals when a lowercase letter follows it ("LTStd")
+        let boundary = c.is_uppercase() && previous.is_some_and(|p| {
+            !p.is_uppercase() || next.is_some_and(char::is_lowercase)
+        });
+        match start {
+            Some(s) if boundary => {
+                words.push((s, &name[s..offset]));
+                start = Some(offset);
+            }
+            None => start = Some(offset),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        words.push((s, &name[s..]));
+    }
+    words
+}
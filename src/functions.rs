@@ -1,8 +1,105 @@
 use regex::Regex;
 use once_cell::sync::Lazy;
-use crate::{Heading, TITLE_PATTERN, NUMBERED_HEADING, APPENDIX_HEADING, SECTION_HEADING, COLON_HEADING};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::{Heading, Warning, NUMBERED_HEADING, APPENDIX_HEADING, SECTION_HEADING, COLON_HEADING};
+use crate::config::ExtractorConfig;
+use crate::validation::CandidateFilter;
+use crate::lexicon::{mentions, Lexicon};
+use crate::sanitize::normalize_for_match;
 
-pub fn extract_document_title(lines: &[&str], _first_page_text: &str) -> String {
+static TRAILING_PAGE_NUMBER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+\d{1,3}$").unwrap());
+static DOTTED_LEADERS: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s*\.{3,}\s*\d*$").unwrap());
+// A bullet, dash or arrow opening a list item: "• ", "◦ ", "– ", "➤ ". ASCII
+// markers and U+FFFD (a bullet glyph that couldn't be decoded) need a space
+// after them, so "-5%" and "*Note" keep theirs.
+static LIST_MARKER: Lazy<Regex> = Lazy::new(||
+    Regex::new(r"^\s*(?:[•◦▪▫‣⁃∙·●○■□►▸▹➢➣➤➔→✓✔–—]\s*|[-*+\u{FFFD}]\s+)").unwrap());
+// Version and copy markers in file names: "v3", "v1.2", "rev2", "(2)", "final", ...
+static FILENAME_NOISE: Lazy<Regex> = Lazy::new(||
+    Regex::new(r"(?i)\b(?:v\d+(?:\.\d+)*|rev\d*|final|draft|copy(?:\s+of)?|clean)\b|\(\d+\)").unwrap());
+
+// Per-line facts shared by the heading predicates, computed once per line so
+// the hot path doesn't lowercase and re-split the same text in every check
+pub struct LineInfo<'a> {
+    // The line without any list marker in front of it
+    pub text: &'a str,
+    // A list marker was stripped
+    pub bulleted: bool,
+    pub lower: String,
+    pub words: Vec<&'a str>,
+    pub char_count: usize,
+    pub non_letter_count: usize,
+    pub numeric_count: usize,
+    pub is_all_caps: bool,
+}
+
+impl<'a> LineInfo<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let (text, bulleted) = strip_list_marker(text);
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let mut char_count = 0;
+        let mut non_letter_count = 0;
+        let mut numeric_count = 0;
+        let mut is_all_caps = true;
+        for c in text.chars() {
+            char_count += 1;
+            if !c.is_alphabetic() {
+                non_letter_count += 1;
+            }
+            if c.is_numeric() {
+                numeric_count += 1;
+            }
+            // Same as comparing against `text.to_uppercase()`, without building it
+            if is_all_caps {
+                let mut upper = c.to_uppercase();
+                is_all_caps = upper.next() == Some(c) && upper.next().is_none();
+            }
+        }
+
+        LineInfo {
+            text,
+            bulleted,
+            lower: text.to_lowercase(),
+            words,
+            char_count,
+            non_letter_count,
+            numeric_count,
+            is_all_caps,
+        }
+    }
+}
+
+// `line` without a leading list marker, and whether it had one. A line
+// that is nothing but a marker is left alone.
+pub(crate) fn strip_list_marker(line: &str) -> (&str, bool) {
+    match LIST_MARKER.find(line) {
+        Some(marker) if marker.end() < line.len() => (&line[marker.end()..], true),
+        _ => (line, false),
+    }
+}
+
+// Share of the alphabetic characters in `text` that are uppercase; 0 when
+// there are none
+pub(crate) fn uppercase_ratio(text: &str) -> f64 {
+    let (upper, letters) = text.chars()
+        .filter(|c| c.is_alphabetic())
+        .fold((0, 0), |(upper, letters), c| (upper + c.is_uppercase() as usize, letters + 1));
+    if letters == 0 { 0.0 } else { upper as f64 / letters as f64 }
+}
+
+/// Scored title candidates from the first page's lines, best first. Empty
+/// when nothing on the page looks like a title. In a document set entirely in
+/// capitals (`all_caps`), capitalization earns a line nothing.
+///
+/// ```
+/// use adobe1a::lexicon::Lexicon;
+/// use adobe1a::titles::extract_document_title;
+///
+/// let lines = ["Page 1", "Annual Report on Harbour Operations", "Prepared by the board."];
+/// let scored = extract_document_title(&lines, &Lexicon::default(), false);
+/// assert_eq!(scored[0].0, "Annual Report on Harbour Operations");
+/// ```
+pub fn extract_document_title(lines: &[&str], lexicon: &Lexicon, all_caps: bool) -> Vec<(String, i32)> {
     let mut candidate_titles = Vec::new();
     
     for (i, line) in lines.iter().take(20).enumerate() {
@@ -31,38 +128,25 @@ pub fn extract_document_title(lines: &[&str], _first_page_text: &str) -> String
         
         let words: Vec<&str> = line.split_whitespace().collect();
         let capitalized_words = words.iter()
-            .filter(|word| word.chars().next().map_or(false, |c| c.is_uppercase()))
+            .filter(|word| word.chars().next().is_some_and(|c| c.is_uppercase()))
             .count();
         
-        if capitalized_words > words.len() / 2 && words.len() >= 2 {
+        if !all_caps && capitalized_words > words.len() / 2 && words.len() >= 2 {
             score += 20;
         }
         
-        if line == line.to_uppercase() && line.len() <= 80 {
+        if !all_caps && line == line.to_uppercase() && line.len() <= 80 {
             score += 10;
         }
         
         let line_lower = line.to_lowercase();
-        let title_indicators = [
-            "foundation", "guide", "manual", "handbook", "report", "study",
-            "analysis", "overview", "introduction", "specification", "standard",
-            "requirements", "proposal", "plan", "strategy", "framework",
-            "methodology", "principles", "best practices", "guidelines"
-        ];
-        
-        for indicator in &title_indicators {
-            if line_lower.contains(indicator) {
+        for indicator in &lexicon.title_indicators {
+            if line_lower.contains(indicator.as_str()) {
                 score += 10;
             }
         }
         
-        let content_indicators = [
-            "the following", "this document", "as described", "according to",
-            "it is", "there are", "you will", "we recommend", "please note"
-        ];
-        
-        let has_content_indicators = content_indicators.iter()
-            .any(|&indicator| line_lower.contains(indicator));
+        let has_content_indicators = mentions(&line_lower, &lexicon.content_indicators);
         
         if has_content_indicators {
             score -= 20;
@@ -77,23 +161,200 @@ pub fn extract_document_title(lines: &[&str], _first_page_text: &str) -> String
         }
     }
     
-    candidate_titles.sort_by(|a, b| b.1.cmp(&a.1));
+    candidate_titles.sort_by_key(|c| std::cmp::Reverse(c.1));
     
-    if let Some((title, _)) = candidate_titles.first() {
-        return title.clone();
+    if !candidate_titles.is_empty() {
+        return candidate_titles;
     }
     
+    // Nothing scored; settle for the first plausible line
     for line in lines.iter().take(15) {
         let line = line.trim();
         if line.len() > 10 && line.len() < 150 && 
            !line.starts_with("Page ") && 
            !line.contains("http") &&
-           line.chars().next().map_or(false, |c| c.is_uppercase()) {
-            return line.to_string();
+           line.chars().next().is_some_and(|c| c.is_uppercase()) {
+            return vec![(line.to_string(), 0)];
+        }
+    }
+
+    Vec::new()
+}
+
+// A memo's or letter's title candidates: the subject line's text ("RE:
+// Contract Amendment No. 4") goes first, and the other label lines of its
+// address block ("TO: All Department Heads") are dropped, as they name
+// people rather than the document
+pub(crate) fn short_document_titles(scores: &mut Vec<(String, i32)>, lines: &[&str], lexicon: &Lexicon) {
+    scores.retain(|(line, _)| field(line).is_none());
+    if let Some((_, subject)) = subject_line(lines, lexicon) {
+        let best = scores.first().map_or(0, |(_, score)| *score);
+        scores.insert(0, (subject.to_string(), best + 1));
+    }
+}
+
+// The index and text of a memo's or letter's subject line among the first
+// page's lines, which closes its address block
+pub(crate) fn subject_line<'a>(lines: &[&'a str], lexicon: &Lexicon) -> Option<(usize, &'a str)> {
+    lines.iter().take(20).enumerate()
+        .filter_map(|(i, line)| Some((i, field(line.trim())?)))
+        .find(|(_, (label, value))| lexicon.subject_labels.contains(&label.trim().to_lowercase()) && !value.trim().is_empty())
+        .map(|(i, (_, value))| (i, value.trim()))
+}
+
+/// Turns a file stem like "final_RFP_v3_CLEAN_(2)" into a presentable title
+/// ("RFP"): separators become spaces, version and copy markers are dropped and
+/// words are title cased, with `stopwords` (lowercase, usually the lexicon's)
+/// kept lowercase after the first word. Acronyms and tokens with digits
+/// (years, model numbers) are kept as written. A stem that is nothing but
+/// noise is returned trimmed.
+///
+/// ```
+/// use adobe1a::{lexicon::Lexicon, titles::title_from_filename};
+///
+/// let stopwords = Lexicon::default().stopwords;
+/// assert_eq!(title_from_filename("annual-report_2023_FINAL", &stopwords), "Annual Report 2023");
+/// ```
+pub fn title_from_filename(stem: &str, stopwords: &[String]) -> String {
+    let spaced = stem.replace('_', " ");
+    let stripped = FILENAME_NOISE.replace_all(&spaced, " ");
+    let spaced = split_camel_case(&stripped.replace(['-', '.'], " "));
+
+    let words: Vec<String> = spaced.split_whitespace()
+        .enumerate()
+        .map(|(i, word)| {
+            let lower = word.to_lowercase();
+            let letters = word.chars().filter(|c| c.is_alphabetic()).count();
+            let is_acronym = letters > 1 && letters <= 5 && !word.chars().any(|c| c.is_lowercase());
+            if word.chars().any(|c| c.is_ascii_digit()) || is_acronym {
+                word.to_string()
+            } else if i > 0 && stopwords.contains(&lower) {
+                lower
+            } else {
+                let mut chars = lower.chars();
+                chars.next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+        })
+        .collect();
+
+    if words.is_empty() {
+        // Nothing but noise; better the raw name than an empty title
+        return stem.trim().to_string();
+    }
+    words.join(" ")
+}
+
+// "STEMPathwaysFlyer" -> "STEM Pathways Flyer"
+fn split_camel_case(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    for (i, &c) in chars.iter().enumerate() {
+        if i > 0 && c.is_uppercase() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || (prev.is_uppercase() && next_is_lower) {
+                out.push(' ');
+            }
         }
+        out.push(c);
+    }
+    out
+}
+
+// Chapters and parts are H1, sections H2. The number has to be capitalized
+// like the keyword and a trailing title must not carry on a sentence, so
+// "Chapter one of this agreement" and "Section 2 describes the ..." are prose.
+pub(crate) fn section_heading_level(line: &str) -> Option<&'static str> {
+    let caps = SECTION_HEADING.captures(line)?;
+    if caps[2].starts_with(|c: char| c.is_lowercase()) {
+        return None;
+    }
+    if let Some(title) = caps.get(3) {
+        let title = title.as_str().trim();
+        if title.starts_with(|c: char| c.is_lowercase()) || title.ends_with('.') {
+            return None;
+        }
+    }
+    match caps[1].to_ascii_lowercase().as_str() {
+        "section" => Some("H2"),
+        _ => Some("H1"),
+    }
+}
+
+// A heading that is nothing but its marker: "CHAPTER 4", "Section 2.1",
+// "Part Two", "Appendix B" or a bare number
+pub(crate) fn is_bare_marker(text: &str) -> bool {
+    let text = text.trim().trim_end_matches(['.', ':']).trim_end();
+    if let Some(caps) = SECTION_HEADING.captures(text) {
+        return caps.get(3).is_none();
     }
+    APPENDIX_HEADING.find(text).is_some_and(|m| m.end() == text.len())
+        || (!text.is_empty() && text.chars().all(|c| c.is_ascii_digit() || c == '.'))
+}
+
+// A line that can be the title of a heading left as a bare marker at the
+// foot of the previous page: capitalized, not a sentence, and without a
+// marker of its own
+pub(crate) fn completes_marker(line: &str) -> bool {
+    let info = LineInfo::new(line.trim());
+    let text = info.text;
+    !info.bulleted && text.starts_with(char::is_uppercase) && is_title_case(text) && !reads_like_sentence(text)
+        && !NUMBERED_HEADING.is_match(text) && !APPENDIX_HEADING.is_match(text)
+        && section_heading_level(text).is_none() && !is_bare_marker(text)
+}
+
+// Pages on which a canonical section name ("Executive Summary", "Abstract")
+// is a heading on its text alone
+const CANONICAL_SECTION_PAGES: usize = 10;
+
+// Words in the line after a canonical section name for it to read as body text
+// rather than the next entry of a contents list
+const CANONICAL_SECTION_BODY_WORDS: usize = 6;
+
+// The line is nothing but one of the lexicon's canonical section names, in
+// any case and with or without a trailing colon
+pub(crate) fn is_canonical_section(line: &str, lexicon: &Lexicon) -> bool {
+    // The lexicon's names are lowercase English, which folding leaves as
+    // they are, and no language's own folding applies to them
+    let name = normalize_for_match(line.trim().trim_end_matches(':'), None);
+    lexicon.canonical_sections.contains(&name)
+}
 
-    "Untitled Document".to_string()
+// A canonical section name early in the document with body text after it is
+// a heading whatever its style; in a contents list the next line is another
+// short entry
+pub(crate) fn opens_canonical_section(line: &str, next: Option<&str>, page: usize, lexicon: &Lexicon) -> bool {
+    page <= CANONICAL_SECTION_PAGES && is_canonical_section(line, lexicon) &&
+        next.is_some_and(|next| next.split_whitespace().count() >= CANONICAL_SECTION_BODY_WORDS)
+}
+
+// Most colon headings one page may contribute; past that they're a list of labels
+const MAX_COLON_HEADINGS_PER_PAGE: usize = 3;
+
+// Confidence a memo or letter adds to its colon and keyword headings, the
+// clearest signals in a document too short for the others
+const SHORT_DOCUMENT_BOOST: f64 = 0.1;
+
+// Words on the line after a heading that make it a paragraph's first line
+const PARAGRAPH_WORDS: usize = 6;
+
+// Other label lines around a line that make it part of a form
+// ("Date of submission:", "Prepared for: City Council", ...)
+const FORM_FIELD_NEIGHBOURS: usize = 2;
+
+// What earlier lines of a document established that later lines depend on
+#[derive(Default)]
+pub struct HeadingState {
+    // The document is set entirely in capitals, so all-caps and title-case
+    // lines aren't headings on that alone
+    pub all_caps_document: bool,
+    numbering: NumberingContext,
+    // Colon headings accepted so far on each page
+    colon_headings: HashMap<usize, usize>,
+    // The document is a memo or letter, read with the short-document rules
+    pub short_document: bool,
 }
 
 pub fn analyze_potential_heading(
@@ -101,94 +362,147 @@ pub fn analyze_potential_heading(
     line_index: usize,
     all_lines: &[&str],
     page: usize,
+    filter: &CandidateFilter,
+    lexicon: &Lexicon,
+    state: &mut HeadingState,
+) -> Option<Heading> {
+    let heading = classify_line(line, line_index, all_lines, page, filter, lexicon, state)?;
+    Some(Heading { continued: is_continuation(line, lexicon), ..heading })
+}
+
+fn classify_line(
+    line: &str,
+    line_index: usize,
+    all_lines: &[&str],
+    page: usize,
+    filter: &CandidateFilter,
+    lexicon: &Lexicon,
+    state: &mut HeadingState,
 ) -> Option<Heading> {
     let line = line.trim();
-    
-    if line.len() < 3 || line.len() > 150 {
+
+    // "Executive summary" set in body style is still the section everyone looks for
+    let next = all_lines[line_index + 1..].iter().copied().find(|l| !l.trim().is_empty());
+    // Short documents lean on the colon and keyword rules
+    let boost = if state.short_document { SHORT_DOCUMENT_BOOST } else { 0.0 };
+    if opens_canonical_section(line, next, page, lexicon) {
+        return Some(Heading {
+            level: "H1".to_string(),
+            text: clean_heading_text(line, lexicon),
+            page,
+            confidence: 0.85 + boost, // High confidence for canonical sections
+            ..Default::default()
+        });
+    }
+
+    let info = LineInfo::new(line);
+    if !filter.accepts(&info) {
         return None;
     }
-    
-    if is_excluded_text(line) {
+    if is_form_value(line_index, all_lines) {
         return None;
     }
+    // Numbering and capitals are read past a list marker: "• 2. Scope"
+    let line = info.text;
 
     if NUMBERED_HEADING.is_match(line) {
-        return Some(Heading {
-            level: determine_numbered_level(line),
-            text: clean_heading_text(line),
-            page,
-            confidence: 0.9, // High confidence for numbered headings
-        });
+        if let Some(level) = state.numbering.level(line) {
+            return Some(Heading {
+                level,
+                text: clean_heading_text(line, lexicon),
+                page,
+                confidence: 0.9, // High confidence for numbered headings
+                ..Default::default()
+            });
+        }
     }
 
-    if SECTION_HEADING.is_match(line) {
+    if let Some(level) = section_heading_level(line) {
         return Some(Heading {
-            level: "H1".to_string(),
-            text: clean_heading_text(line),
+            level: level.to_string(),
+            text: clean_heading_text(line, lexicon),
             page,
             confidence: 0.85, // High confidence for section headings
+            ..Default::default()
         });
     }
 
     if APPENDIX_HEADING.is_match(line) {
         return Some(Heading {
             level: "H1".to_string(),
-            text: clean_heading_text(line),
+            text: clean_heading_text(line, lexicon),
             page,
             confidence: 0.85, // High confidence for appendix headings
+            ..Default::default()
         });
     }
 
-    if line == line.to_uppercase() && line.len() > 5 {
-        let word_count = line.split_whitespace().count();
-        if word_count >= 2 && word_count <= 8 {
-            let is_isolated = is_line_isolated(line_index, all_lines);
-            if is_isolated {
-                return Some(Heading {
-                    level: "H1".to_string(),
-                    text: clean_heading_text(line),
-                    page,
-                    confidence: 0.8, // Good confidence for uppercase isolated headings
-                });
-            }
+    let word_count = info.words.len();
+    // In a document set in capitals only a keyword ("BACKGROUND") still
+    // marks a capitalized line as a heading
+    let caps_say_nothing = state.all_caps_document && !mentions(&info.lower, &lexicon.h1_indicators);
+
+    if !caps_say_nothing && info.is_all_caps && line.len() > 5 && (2..=8).contains(&word_count) {
+        let is_isolated = is_line_isolated(line_index, all_lines) ||
+                         (state.short_document && stands_apart(line_index, all_lines));
+        if is_isolated {
+            return Some(Heading {
+                level: "H1".to_string(),
+                text: clean_heading_text(line, lexicon),
+                page,
+                confidence: 0.8, // Good confidence for uppercase isolated headings
+                ..Default::default()
+            });
         }
     }
 
-    if line.ends_with(':') && !line.ends_with("::") {
-        let word_count = line.split_whitespace().count();
-        if word_count >= 2 && word_count <= 10 && line.len() >= 8 && line.len() <= 80 {
-            let has_heading_context = is_line_isolated(line_index, all_lines) ||
-                                    has_following_content(line_index, all_lines);
-            if has_heading_context {
-                return Some(Heading {
-                    level: "H2".to_string(),
-                    text: clean_heading_text(line),
-                    page,
-                    confidence: 0.75, // Good confidence for colon headings
-                });
-            }
+    if COLON_HEADING.is_match(line) &&
+       (2..=10).contains(&word_count) && (8..=80).contains(&line.len()) {
+        // A label among other labels, or one followed by its short value
+        // ("Jane Doe", "March 3, 2025"), is a form field rather than a heading;
+        // short lowercase lines are list items under a real heading. A label
+        // with a paragraph under it ends the form instead.
+        let in_form = form_field_neighbours(line_index, all_lines) >= FORM_FIELD_NEIGHBOURS
+            && !paragraph_follows(line_index, all_lines);
+        let next_is_value = all_lines.get(line_index + 1).is_some_and(|next| {
+            (1..=3).contains(&next.split_whitespace().count()) && !next.starts_with(|c: char| c.is_lowercase())
+        });
+        let has_heading_context = has_blank_after(line_index, all_lines) ||
+                                has_following_content(line_index, all_lines) ||
+                                (state.short_document && paragraph_follows(line_index, all_lines));
+        let colon_headings = state.colon_headings.entry(page).or_default();
+        if !in_form && !next_is_value && has_heading_context && *colon_headings < MAX_COLON_HEADINGS_PER_PAGE {
+            *colon_headings += 1;
+            return Some(Heading {
+                level: "H2".to_string(),
+                text: clean_heading_text(line, lexicon),
+                page,
+                confidence: 0.75 + boost, // Good confidence for colon headings
+                ..Default::default()
+            });
         }
     }
 
-    let words: Vec<&str> = line.split_whitespace().collect();
-    if words.len() >= 2 && words.len() <= 8 {
-        let capitalized_words = words.iter()
-            .filter(|word| word.chars().next().map_or(false, |c| c.is_uppercase()))
-            .count();
+    // A memo's or letter's headings may be a single word ("Questions")
+    let single_word = state.short_document && word_count == 1 && line.starts_with(char::is_uppercase);
+    if !caps_say_nothing && ((2..=8).contains(&word_count) && follows_title_case(&info.words, &lexicon.stopwords) || single_word) {
+        let min_len = if single_word { 4 } else { 10 };
+        let is_well_formed = line.len() >= min_len && line.len() <= 80 &&
+                           (is_line_isolated(line_index, all_lines) ||
+                            (state.short_document && stands_apart(line_index, all_lines))) &&
+                           has_meaningful_words(&info.words, &lexicon.stopwords);
         
-        if capitalized_words >= words.len() - 1 && capitalized_words >= 2 {
-            let is_well_formed = line.len() >= 10 && line.len() <= 80 &&
-                               is_line_isolated(line_index, all_lines) &&
-                               has_meaningful_words(&words);
-            
-            if is_well_formed {
-                return Some(Heading {
-                    level: determine_heading_level_by_content(line),
-                    text: clean_heading_text(line),
-                    page,
-                    confidence: 0.65, // Moderate confidence for capitalized headings
-                });
-            }
+        if is_well_formed {
+            let level = determine_heading_level_by_content(&info.lower, lexicon);
+            // A keyword ("Background", "Summary") is what marks an H1
+            let keyword_boost = if level == "H1" { boost } else { 0.0 };
+            return Some(Heading {
+                level,
+                text: clean_heading_text(line, lexicon),
+                page,
+                confidence: 0.65 + keyword_boost, // Moderate confidence for capitalized headings
+                ..Default::default()
+            });
         }
     }
 
@@ -198,12 +512,60 @@ pub fn analyze_potential_heading(
 fn is_line_isolated(line_index: usize, all_lines: &[&str]) -> bool {
     let has_blank_before = line_index == 0 || 
                           all_lines.get(line_index.saturating_sub(1))
-                          .map_or(true, |l| l.trim().is_empty());
-    let has_blank_after = line_index >= all_lines.len().saturating_sub(1) || 
-                         all_lines.get(line_index + 1)
-                         .map_or(true, |l| l.trim().is_empty());
+                          .is_none_or(|l| l.trim().is_empty());
     
-    has_blank_before && has_blank_after
+    has_blank_before && has_blank_after(line_index, all_lines)
+}
+
+fn has_blank_after(line_index: usize, all_lines: &[&str]) -> bool {
+    line_index >= all_lines.len().saturating_sub(1) || 
+        all_lines.get(line_index + 1)
+        .is_none_or(|l| l.trim().is_empty())
+}
+
+// Blank lines don't survive into the lines weighed, so in a memo or letter
+// a line stands apart when it follows a finished sentence or a label line
+// ("From: Facilities") and a paragraph follows it. The line opening a page
+// is more often a title or running header.
+fn stands_apart(line_index: usize, all_lines: &[&str]) -> bool {
+    let after_block = line_index.checked_sub(1).is_some_and(|previous| {
+        let previous = all_lines[previous].trim();
+        previous.ends_with(['.', '!', '?']) || field(previous).is_some()
+    });
+    after_block && paragraph_follows(line_index, all_lines)
+}
+
+fn paragraph_follows(line_index: usize, all_lines: &[&str]) -> bool {
+    all_lines.get(line_index + 1).is_some_and(|next| next.split_whitespace().count() >= PARAGRAPH_WORDS)
+}
+
+// The label and value of a label line ("Prepared for:", "Date: 3 March")
+fn field(line: &str) -> Option<(&str, &str)> {
+    line.split_once(':').filter(|(label, rest)| {
+        label.starts_with(char::is_alphabetic) && label.split_whitespace().count() <= 4 && !rest.starts_with("//")
+    })
+}
+
+// Label lines in the unbroken run of labels and short values around the
+// given line; a blank line ends the run
+fn form_field_neighbours(line_index: usize, all_lines: &[&str]) -> usize {
+    let is_field = |line: &str| field(line).is_some();
+    let in_form = |line: &str| !line.is_empty() && (is_field(line) || line.split_whitespace().count() <= 3);
+
+    let before = all_lines[..line_index].iter().rev().take_while(|l| in_form(l.trim()));
+    let after = all_lines[line_index + 1..].iter().take_while(|l| in_form(l.trim()));
+    before.chain(after).filter(|l| is_field(l.trim())).count()
+}
+
+// The short value under a bare label in a form ("Contact Officer:" over
+// "R. Alvarez", "Estimated Value:" over "2.4 million")
+fn is_form_value(line_index: usize, all_lines: &[&str]) -> bool {
+    let Some(previous) = line_index.checked_sub(1).map(|i| all_lines[i].trim()) else {
+        return false;
+    };
+    let bare_label = field(previous).is_some_and(|(_, value)| value.trim().is_empty());
+    bare_label && all_lines[line_index].split_whitespace().count() <= 3
+        && form_field_neighbours(line_index, all_lines) >= FORM_FIELD_NEIGHBOURS
 }
 
 fn has_following_content(line_index: usize, all_lines: &[&str]) -> bool {
@@ -211,191 +573,644 @@ fn has_following_content(line_index: usize, all_lines: &[&str]) -> bool {
         let next_line = next_line.trim();
         return !next_line.is_empty() && 
                next_line.len() > 20 && 
-               next_line.chars().next().map_or(false, |c| c.is_lowercase());
+               next_line.chars().next().is_some_and(|c| c.is_lowercase());
     }
     false
 }
 
-fn has_meaningful_words(words: &[&str]) -> bool {
+fn has_meaningful_words(words: &[&str], stopwords: &[String]) -> bool {
     let meaningful_count = words.iter()
-        .filter(|word| word.len() > 3 && 
-                      !["The", "And", "For", "With", "From", "That", "This", "Into", "Upon"].contains(word))
+        .filter(|word| word.len() > 3 && !is_stopword(word, stopwords))
         .count();
     
     meaningful_count >= words.len() / 2
 }
 
-fn determine_heading_level_by_content(line: &str) -> String {
-    let line_lower = line.to_lowercase();
-    
-    let h1_indicators = [
-        "introduction", "overview", "summary", "conclusion", "background",
-        "methodology", "results", "discussion", "abstract", "executive summary"
-    ];
-    
-    let h2_indicators = [
-        "objectives", "requirements", "scope", "limitations", "assumptions",
-        "definitions", "terminology", "approach", "process", "procedure"
-    ];
-    
-    for indicator in &h1_indicators {
-        if line_lower.contains(indicator) {
-            return "H1".to_string();
-        }
+// Title case as style guides write it: the first and last words and every
+// word other than a stopword start with a capital, and at least two words
+// do. Hyphenated compounds go by their first part ("Long-term Plans"),
+// acronyms are capitalized already, and words without letters ("2024", "&")
+// don't count either way.
+pub(crate) fn follows_title_case(words: &[&str], stopwords: &[String]) -> bool {
+    let first_letter = |word: &str| word.chars().find(|c| c.is_alphabetic());
+    let capitalized = |word: &str| first_letter(word).is_some_and(char::is_uppercase);
+    let lowercase = |word: &str| first_letter(word).is_some_and(char::is_lowercase);
+
+    let (Some(first), Some(last)) = (words.first(), words.last()) else {
+        return false;
+    };
+    if lowercase(first) || lowercase(last) {
+        return false;
     }
-    
-    for indicator in &h2_indicators {
-        if line_lower.contains(indicator) {
-            return "H2".to_string();
-        }
+    let stray_lowercase = words.iter().any(|word| lowercase(word) && !is_stopword(word, stopwords));
+    !stray_lowercase && words.iter().filter(|word| capitalized(word)).count() >= 2
+}
+
+fn is_stopword(word: &str, stopwords: &[String]) -> bool {
+    let bare = word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+    stopwords.contains(&bare)
+}
+
+// Expects the already-lowercased line
+fn determine_heading_level_by_content(line_lower: &str, lexicon: &Lexicon) -> String {
+    if mentions(line_lower, &lexicon.h1_indicators) {
+        return "H1".to_string();
     }
-    
     "H2".to_string()
 }
 
-pub fn establish_hierarchy(headings: Vec<Heading>) -> Vec<Heading> {
-    let mut unique_headings = Vec::new();
-    let mut seen_texts: std::collections::HashSet<String> = std::collections::HashSet::new();
-    
-    for heading in &headings {
-        let normalized_text = heading.text.to_lowercase().trim().to_string();
-        
-        let text_without_numbers = heading.text.chars()
-            .filter(|c| !c.is_ascii_digit() && *c != '.' && *c != ':')
-            .collect::<String>()
-            .trim()
-            .to_lowercase();
-            
-        let is_duplicate = seen_texts.iter().any(|seen| {
-            let seen_without_numbers = seen.chars()
-                .filter(|c| !c.is_ascii_digit() && *c != '.' && *c != ':')
-                .collect::<String>()
-                .trim()
-                .to_lowercase();
-            seen_without_numbers == text_without_numbers && 
-            !text_without_numbers.is_empty() &&
-            text_without_numbers.len() > 5  
-        });
-        
-        if !is_duplicate {
-            seen_texts.insert(normalized_text);
-            unique_headings.push(heading.clone());
+pub fn establish_hierarchy(headings: Vec<Heading>, lang: Option<&str>) -> Vec<Heading> {
+    let mut dedupe = HeadingDedupe::new(lang);
+    let mut unique_headings: Vec<Heading> = headings.into_iter()
+        .filter(|heading| dedupe.is_new(heading))
+        .collect();
+    unique_headings.sort_by_key(|h| h.page);
+    unique_headings
+}
+
+// Drops repeats of a heading already kept, comparing number-stripped keys
+// folded for the document's language. Short keys are always kept; "1. A"
+// and "2. A" aren't the same heading.
+#[derive(Default)]
+pub struct HeadingDedupe {
+    lang: Option<String>,
+    // Keys of the headings kept so far, so each new heading is a single hash
+    // lookup instead of a rescan of everything seen
+    seen_keys: HashSet<String>,
+    // Their whole texts, numbers and all, for a continued heading too short
+    // for its key to tell it apart
+    seen_texts: HashSet<String>,
+}
+
+impl HeadingDedupe {
+    pub fn new(lang: Option<&str>) -> Self {
+        HeadingDedupe { lang: lang.map(str::to_string), ..HeadingDedupe::default() }
+    }
+
+    pub fn is_new(&mut self, heading: &Heading) -> bool {
+        let key = dedupe_key(&heading.text, self.lang.as_deref());
+        let text = normalize_for_match(&heading.text, self.lang.as_deref());
+        if key.len() > 5 && self.seen_keys.contains(&key) {
+            return false;
+        }
+        // A continuation merges into the heading it carries on
+        if heading.continued && self.seen_texts.contains(&text) {
+            return false;
         }
+        self.seen_keys.insert(key);
+        self.seen_texts.insert(text);
+        true
     }
-    
-    unique_headings.sort_by(|a, b| a.page.cmp(&b.page));
-    unique_headings
 }
 
-pub fn is_excluded_text(line: &str) -> bool {
-    let line_lower = line.to_lowercase();
-    
-    let generic_exclusions = [
-        "www.", "http", "@", "©", "copyright", "page ",
-        "table of contents", "index", "references", "bibliography",
-        "acknowledgments", "acknowledgements", "preface", "foreword"
-    ];
-    
-    if generic_exclusions.iter().any(|&exclusion| line_lower.contains(exclusion)) {
-        return true;
+// Lowercased words that appear on their own, not as part of a hyphenated
+// word or broken across a line, anywhere in `text`
+pub(crate) fn standalone_words<'a>(text: impl IntoIterator<Item = &'a str>) -> HashSet<String> {
+    text.into_iter()
+        .flat_map(str::split_whitespace)
+        .filter(|word| !word.contains('-'))
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty() && word.chars().all(char::is_alphabetic))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+// `line` and `next` joined when a word is hyphenated across them:
+// "Implementa-" and "tion Plan" make "Implementation Plan". The hyphen stays
+// when it belongs to a compound, i.e. the word already has one
+// ("state-of-" + "the-art") or the part before it is a word of its own
+// elsewhere in the document ("decision-" + "making"). None when `next`
+// doesn't carry on the word.
+pub(crate) fn join_hyphenated(line: &str, next: &str, words: &HashSet<String>) -> Option<String> {
+    let stem = line.strip_suffix('-')?;
+    let fragment = stem.rsplit(char::is_whitespace).next()?;
+    if !fragment.ends_with(char::is_alphabetic) || !next.starts_with(char::is_lowercase) {
+        return None;
     }
-    
-    let non_letter_count = line.chars().filter(|c| !c.is_alphabetic()).count();
-    let total_chars = line.chars().count();
-    
-    if total_chars > 0 && non_letter_count as f64 / total_chars as f64 > 0.7 {
-        return true;
+    let first_part = fragment.rsplit('-').next().unwrap_or(fragment).to_lowercase();
+    let second_part = next.split(|c: char| !c.is_alphabetic()).next().unwrap_or("");
+    let is_compound = fragment.contains('-') || (
+        first_part.chars().count() >= 3 && second_part.chars().count() >= 3 && words.contains(&first_part)
+    );
+    Some(if is_compound { format!("{}-{}", stem, next) } else { format!("{}{}", stem, next) })
+}
+
+// Lines with words hyphenated across a line break joined back up
+pub(crate) fn dehyphenate(lines: &[&str], words: &HashSet<String>) -> Vec<String> {
+    dehyphenate_indexed(lines, words).into_iter().map(|(_, line)| line).collect()
+}
+
+// `dehyphenate`, with the index in `lines` that each joined line starts at
+pub(crate) fn dehyphenate_indexed(lines: &[&str], words: &HashSet<String>) -> Vec<(usize, String)> {
+    let mut joined: Vec<(usize, String)> = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        match joined.last_mut() {
+            Some((_, previous)) => match join_hyphenated(previous, line, words) {
+                Some(whole) => *previous = whole,
+                None => joined.push((i, line.to_string())),
+            },
+            None => joined.push((i, line.to_string())),
+        }
     }
-    
-    if line.trim().len() < 3 {
-        return true;
+    joined
+}
+
+// Columns a tab indents by
+const TAB_WIDTH: usize = 4;
+
+// Fewest headings at each indent for indentation to be taken as structure
+const MIN_HEADINGS_PER_INDENT: usize = 2;
+
+// Width of a line's leading whitespace, in columns
+pub(crate) fn leading_columns(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+// The depth a heading's own marker spells out: the components of "2.1.3",
+// or the keyword of "Chapter 3" and "Appendix B". A single number or letter
+// says nothing about depth on its own.
+fn explicit_depth(text: &str) -> Option<usize> {
+    let token = text.split_whitespace().next()?.trim_end_matches(['.', ')']);
+    let components: Vec<&str> = token.split('.').collect();
+    if components.len() > 1 && components.iter().all(|c| !c.is_empty() && c.chars().all(|ch| ch.is_ascii_digit())) {
+        return Some(components.len());
     }
-    
-    if line.trim().len() < 20 && (
-        line_lower.starts_with("page ") ||
-        line_lower.contains("chapter ") ||
-        line_lower.matches(char::is_numeric).count() > line.len() / 3
-    ) {
-        return true;
+    if let Some(level) = section_heading_level(text) {
+        return level.strip_prefix('H').and_then(|n| n.parse().ok());
     }
-    
-    if (line.contains("$") || line.contains("€") || line.contains("£")) &&
-       line.matches(char::is_numeric).count() > 2 {
-        return true;
+    APPENDIX_HEADING.is_match(text).then_some(1)
+}
+
+// Re-levels the text engine's headings by the indentation its lines keep
+// as leading spaces (`indents[i]`, in columns, belongs to `headings[i]`)
+// when it reads as structure and not layout noise: two or more indents with
+// at least two headings each, the first heading at the shallowest, no
+// indent entered without the one above it, and deeper indents for every
+// deeper explicit depth ("2.1" under "2").
+// Headings at the shallowest indent set the top level, and each deeper
+// indent goes one level further. Headings whose marker spells out their
+// depth keep it.
+pub(crate) fn apply_indentation(headings: &mut [Heading], indents: &[usize]) {
+    debug_assert_eq!(headings.len(), indents.len());
+    let mut columns = indents.to_vec();
+    columns.sort_unstable();
+    columns.dedup();
+    if columns.len() < 2 {
+        return;
     }
-    
-    let prose_patterns = [
-        "the following", "as mentioned", "according to", "it should be noted",
-        "please refer", "see section", "as shown in", "this chapter",
-        "in this document", "the purpose of", "it is important"
-    ];
-    
-    if prose_patterns.iter().any(|&pattern| line_lower.contains(pattern)) {
-        return true;
+    let ranks: Vec<usize> = indents.iter()
+        .map(|indent| columns.binary_search(indent).unwrap_or(0))
+        .collect();
+
+    let mut counts = vec![0; columns.len()];
+    for &rank in &ranks {
+        counts[rank] += 1;
     }
-    
-    if line.ends_with(',') || line.ends_with("and") || line.ends_with("or") || 
-       line.ends_with("the") || line.ends_with("of") || line.ends_with("in") ||
-       line.ends_with("to") || line.ends_with("for") || line.ends_with("with") {
-        return true;
+    if counts.iter().any(|&count| count < MIN_HEADINGS_PER_INDENT) {
+        return;
     }
-    
-    if line.chars().next().map_or(false, |c| c.is_lowercase()) &&
-       !line.starts_with('(') && !line.starts_with('[') {
-        return true;
+    let mut previous = 0;
+    for &rank in &ranks {
+        if rank > previous + 1 {
+            return;
+        }
+        previous = rank;
+    }
+    if ranks.first() != Some(&0) {
+        return;
+    }
+    let explicit: Vec<(usize, usize)> = headings.iter().zip(&ranks)
+        .filter_map(|(heading, &rank)| explicit_depth(&heading.text).map(|depth| (depth, rank)))
+        .collect();
+    if explicit.iter().any(|a| explicit.iter().any(|b| a.0.cmp(&b.0) != a.1.cmp(&b.1))) {
+        return;
+    }
+
+    let top = headings.iter().zip(&ranks)
+        .filter(|(_, &rank)| rank == 0)
+        .map(|(heading, _)| heading.depth() as usize)
+        .min()
+        .unwrap_or(1);
+    for (heading, &rank) in headings.iter_mut().zip(&ranks) {
+        if explicit_depth(&heading.text).is_none() {
+            heading.level = format!("H{}", (top + rank).min(crate::MAX_HEADING_DEPTH as usize));
+        }
     }
-    
-    false
 }
 
-pub fn determine_numbered_level(line: &str) -> String {
-    let raw_prefix = line.trim_start();
-    let re = Regex::new(r"^(?P<prefix>(?:\d+\.)+\d+|\d+|[A-Za-z]{1,2}|[IVXLCDM]+)[\.)]?").unwrap();
-    let captures = re.captures(raw_prefix);
+// Characters of text on a page of ordinary prose; a fuller page may hold
+// proportionally more headings before it counts as crowded
+const TYPICAL_PAGE_CHARS: usize = 2500;
+
+// A decimal heading number: "3", "3.2", "3.2.1."
+pub(crate) static SEQUENCE_NUMBER: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d{1,3}(?:\.\d{1,3})*)\.?(?:\s|$)").unwrap());
 
-    let prefix = captures.and_then(|c| c.name("prefix")).map(|m| m.as_str()).unwrap_or("");
+// Indices of the headings to drop from pages that produced more than
+// `config.page_heading_limit` of them (more on pages with more than a
+// typical page's text, by `page_chars`), keeping each such page's most
+// confident, with a warning naming the page. OCR damage and heavy design can
+// make one page yield dozens. Decimal-numbered headings that continue the
+// document's numbering ("3.2" after "3.1" or "3") are never dropped, and
+// don't count towards the limit.
+pub(crate) fn crowded_page_drops(headings: &[Heading], page_chars: &BTreeMap<usize, usize>, config: &ExtractorConfig) -> (HashSet<usize>, Vec<Warning>) {
+    let mut drops = HashSet::new();
+    let mut warnings = Vec::new();
+    if config.page_heading_limit == 0 {
+        return (drops, warnings);
+    }
+
+    let in_sequence = numbering_sequence(headings);
+    // In page order, so the warnings come out in it
+    let mut pages: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (i, heading) in headings.iter().enumerate() {
+        if !in_sequence.contains(&i) {
+            pages.entry(heading.page).or_default().push(i);
+        }
+    }
+    for (page, mut members) in pages {
+        let chars = page_chars.get(&page).copied().unwrap_or(0);
+        let scale = (chars as f64 / TYPICAL_PAGE_CHARS as f64).max(1.0);
+        let limit = (config.page_heading_limit as f64 * scale).round() as usize;
+        if members.len() <= limit {
+            continue;
+        }
+        // Most confident first; ties keep the earlier heading
+        members.sort_by(|&a, &b| headings[b].confidence.total_cmp(&headings[a].confidence).then(a.cmp(&b)));
+        warnings.push(Warning::new("crowded_page", format!(
+            "page {} produced {} headings; only the {} most confident were kept",
+            page, members.len(), limit)));
+        drops.extend(members.split_off(limit));
+    }
+    (drops, warnings)
+}
 
-    // Numeric decimal hierarchy e.g. 1.2.3 => level = components - 1 (root is H1)
-    if prefix.chars().next().map_or(false, |c| c.is_numeric()) {
-        let components = prefix.split('.').count();
-        return match components {
-            1 => "H1".to_string(),
-            2 => "H2".to_string(),
-            3 => "H3".to_string(),
-            _ => "H4".to_string(),
+// Indices of the decimal-numbered headings that continue the numbering
+// before them: the next number at the same depth ("3.2" after "3.1"), the
+// first one below ("3.1.1" after "3.1"), or the next one further up ("4"
+// after "3.2"). A number that doesn't fit leaves the sequence where it was.
+fn numbering_sequence(headings: &[Heading]) -> HashSet<usize> {
+    let mut in_sequence = HashSet::new();
+    let mut previous: Option<Vec<u32>> = None;
+    for (i, heading) in headings.iter().enumerate() {
+        let text = strip_list_marker(heading.text.trim()).0;
+        let Some(number) = SEQUENCE_NUMBER.captures(text) else { continue };
+        let number: Vec<u32> = number[1].split('.').filter_map(|part| part.parse().ok()).collect();
+        let fits = match &previous {
+            None => true,
+            Some(previous) => {
+                let depth = number.len();
+                let child = depth == previous.len() + 1 && number[..depth - 1] == previous[..] && number[depth - 1] == 1;
+                let next = depth <= previous.len()
+                    && number[..depth - 1] == previous[..depth - 1]
+                    && number[depth - 1] == previous[depth - 1] + 1;
+                child || next
+            }
         };
+        if fits {
+            in_sequence.insert(i);
+            previous = Some(number);
+        }
     }
+    in_sequence
+}
+
+// Heading text with digits, periods and colons removed and folded for
+// matching in `lang`, so that "1.2 Scope" and "SCOPE:" compare equal
+pub(crate) fn dedupe_key(text: &str, lang: Option<&str>) -> String {
+    let bare: String = text.chars()
+        .filter(|c| !c.is_ascii_digit() && *c != '.' && *c != ':')
+        .collect();
+    normalize_for_match(&bare, lang)
+}
+
+// Ways of numbering headings: "1.2", "IV", "iv", "B", "b"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Numbering {
+    Decimal,
+    UpperRoman,
+    LowerRoman,
+    UpperLetter,
+    LowerLetter,
+}
 
-    // Alphabetic (A, B, C ...) treat as H2 beneath previous H1
-    if prefix.chars().next().map_or(false, |c| c.is_alphabetic()) && prefix.len() <= 2 {
-        return "H2".to_string();
+// Gives numbered headings their levels, one document at a time. Decimal
+// numbers carry their own depth ("1.2" is H2). Other schemes nest under the
+// enclosing numbered heading: "A." under "IV." is H2, "a." under that H3,
+// and lowercase romans sit one below uppercase ones. Letters and lowercase
+// romans with nothing enclosing them are H2, uppercase romans H1.
+#[derive(Default)]
+pub struct NumberingContext {
+    // Schemes of the enclosing numbered headings, outermost first, with the
+    // level each was given
+    open: Vec<(Numbering, usize)>,
+    // The last value seen in each scheme, to tell "C." after "B." (a letter)
+    // from "C." the numeral
+    last: HashMap<Numbering, u32>,
+}
+
+impl NumberingContext {
+    // The level of a line starting with a heading number, or None when the
+    // prefix isn't really one: a malformed numeral ("IC", "DID"), a word like
+    // "Dr.", or "I." starting a sentence. The text engine has no font
+    // information, so a lone "I." counts only before a capitalized word.
+    pub fn level(&mut self, line: &str) -> Option<String> {
+        let (scheme, value, depth) = self.parse(line.trim_start())?;
+        self.last.insert(scheme, value);
+
+        if let Some(position) = self.open.iter().position(|(open, _)| *open == scheme) {
+            self.open.truncate(position);
+        }
+        // "I." after a run of "A." .. "D." starts a new part rather than
+        // nesting in the last letter
+        if scheme == Numbering::UpperRoman {
+            self.open.retain(|(open, _)| *open == Numbering::Decimal);
+        }
+        let parent = self.open.last().map(|(_, level)| *level);
+        let level = match scheme {
+            Numbering::Decimal => depth,
+            Numbering::UpperRoman => parent.map_or(1, |p| p + 1),
+            _ => parent.map_or(2, |p| p + 1),
+        }.min(crate::MAX_HEADING_DEPTH as usize);
+        self.open.push((scheme, level));
+        Some(format!("H{}", level))
+    }
+
+    // The prefix's scheme, its value within the scheme, and for decimals the
+    // number of components
+    fn parse(&self, line: &str) -> Option<(Numbering, u32, usize)> {
+        let (token, rest) = line.split_once(char::is_whitespace)?;
+        let rest = rest.trim_start();
+        if rest.is_empty() {
+            return None;
+        }
+        let (body, delimited) = match token.strip_suffix(['.', ')']) {
+            Some(body) => (body, true),
+            None => (token, false),
+        };
+
+        let components: Vec<&str> = body.split('.').collect();
+        if components.iter().all(|c| !c.is_empty() && c.chars().all(|ch| ch.is_ascii_digit())) {
+            let value = components[0].parse().unwrap_or(0);
+            return Some((Numbering::Decimal, value, components.len()));
+        }
+
+        if !delimited {
+            return None;
+        }
+        let upper = body.chars().all(|c| c.is_ascii_uppercase());
+        if !upper && !body.chars().all(|c| c.is_ascii_lowercase()) {
+            return None;
+        }
+        let (roman, letter) = if upper {
+            (Numbering::UpperRoman, Numbering::UpperLetter)
+        } else {
+            (Numbering::LowerRoman, Numbering::LowerLetter)
+        };
+
+        let numeral = roman_value(body);
+        let ordinal = letter_ordinal(body);
+        let follows_letter = ordinal.is_some_and(|o| self.last.get(&letter) == Some(&(o - 1)));
+        let is_numeral = match (numeral, ordinal) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            // "C." is the letter after "B.", "V." the numeral after "IV.",
+            // and a lone "I." a numeral unless it follows "H."
+            (Some(n), Some(_)) if body.len() == 1 => {
+                !follows_letter && (n == 1 || self.last.get(&roman) == Some(&(n - 1)))
+            }
+            (Some(_), Some(_)) => !follows_letter,
+        };
+        if !is_numeral {
+            // Initials ("J. Smith reported") and list markers inside prose
+            // look just like letters; only a letter continuing a sequence or
+            // introducing title-case text counts, and never a sentence
+            let ordinal = ordinal?;
+            if reads_like_sentence(rest) || !(follows_letter || is_title_case(rest)) {
+                return None;
+            }
+            return Some((letter, ordinal, 0));
+        }
+        let numeral = numeral?;
+        if numeral == 1 && upper && !rest.starts_with(|c: char| c.is_uppercase()) {
+            return None;
+        }
+        Some((roman, numeral, 0))
     }
+}
 
-    // Roman numerals -> assume H2 as well
-    let roman_re = Regex::new(r"^[IVXLCDM]+$").unwrap();
-    if roman_re.is_match(prefix) {
-        return "H2".to_string();
+// Position of an enumeration letter: "a" is 1, "z" 26, "aa" 27, "bb" 28, ...
+fn letter_ordinal(body: &str) -> Option<u32> {
+    let bytes = body.to_ascii_lowercase().into_bytes();
+    let ordinal = (*bytes.first()? - b'a') as u32 + 1;
+    match bytes.len() {
+        1 => Some(ordinal),
+        2 if bytes[0] == bytes[1] => Some(26 + ordinal),
+        _ => None,
     }
+}
 
-    // Default fallback
-    "H2".to_string()
+// Every word longer than three letters is capitalized
+pub(crate) fn is_title_case(text: &str) -> bool {
+    text.split_whitespace()
+        .filter(|word| word.chars().filter(|c| c.is_alphabetic()).count() > 3)
+        .all(|word| !word.starts_with(|c: char| c.is_lowercase()))
+}
+
+// Text after an enumeration marker that continues a sentence: it starts in
+// lowercase, ends with a period or runs long
+pub(crate) fn reads_like_sentence(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_lowercase())
+        || text.ends_with('.')
+        || text.split_whitespace().count() > 8
 }
 
-pub fn clean_heading_text(text: &str) -> String {
-    let text = text.trim();
+const ROMAN_NUMERALS: [(&str, u32); 13] = [
+    ("m", 1000), ("cm", 900), ("d", 500), ("cd", 400), ("c", 100), ("xc", 90),
+    ("l", 50), ("xl", 40), ("x", 10), ("ix", 9), ("v", 5), ("iv", 4), ("i", 1),
+];
+
+// The value of a well-formed roman numeral in one case ("XIV", "xiv"), or
+// None for anything else ("IC", "VX", "IIII")
+pub(crate) fn roman_value(text: &str) -> Option<u32> {
+    let lower = text.to_ascii_lowercase();
+    if lower.is_empty() {
+        return None;
+    }
+    let mut value = 0;
+    let mut rest = lower.as_str();
+    for (numeral, amount) in ROMAN_NUMERALS {
+        // At most three of a one-letter numeral and one of a two-letter one
+        let limit = if numeral.len() == 1 { 3 } else { 1 };
+        let mut count = 0;
+        while count < limit {
+            match rest.strip_prefix(numeral) {
+                Some(stripped) => {
+                    rest = stripped;
+                    value += amount;
+                    count += 1;
+                }
+                None => break,
+            }
+        }
+    }
+    // Canonical form only: re-encoding must give back the same text, which
+    // rules out orders like "IIX" that the greedy scan above would accept
+    (rest.is_empty() && to_roman(value) == lower).then_some(value)
+}
+
+pub(crate) fn to_roman(mut value: u32) -> String {
+    let mut out = String::new();
+    for (numeral, amount) in ROMAN_NUMERALS {
+        while value >= amount {
+            out.push_str(numeral);
+            value -= amount;
+        }
+    }
+    out
+}
+
+/// A heading's text as the outline gives it, without what its line carries
+/// besides: a list marker, a closing colon, page number or dotted leaders,
+/// a continuation marker from `lexicon.continuation_markers`, a dangling
+/// dash and a single closing period. The period stays after an
+/// abbreviation ("Inc.", "approx.") or initialism ("U.S."), and on a
+/// heading that is only a number.
+///
+/// ```
+/// use adobe1a::{lexicon::Lexicon, sanitize::clean_heading_text};
+///
+/// let lexicon = Lexicon::default();
+/// for (text, cleaned) in [
+///     // A closing period, once
+///     ("3.2 Project Timeline.", "3.2 Project Timeline"),
+///     ("Section 3.2.1.", "Section 3.2.1"),
+///     ("Appendix B.", "Appendix B"),
+///     ("Why Now?", "Why Now?"),
+///     // Not one closing an abbreviation or initialism, or a bare number
+///     ("Northwind Holdings Inc.", "Northwind Holdings Inc."),
+///     ("Costs (approx.", "Costs (approx."),
+///     ("Tools, Materials, etc.", "Tools, Materials, etc."),
+///     ("Operations in the U.S.", "Operations in the U.S."),
+///     ("3.", "3."),
+///     ("2.1.", "2.1."),
+///     // An ellipsis stays; three or more periods are dotted leaders
+///     ("What Comes Next…", "What Comes Next…"),
+///     ("Introduction .......... 4", "Introduction"),
+///     // A trailing number is a page number, unless it's the heading's own
+///     ("Site Survey 14", "Site Survey"),
+///     ("CHAPTER 4", "CHAPTER 4"),
+///     ("Appendix 2", "Appendix 2"),
+///     // A dash with a space before it, but not a hyphen ending a word
+///     ("Scope of Work –", "Scope of Work"),
+///     ("Scope of Work —", "Scope of Work"),
+///     ("Scope of Work - ", "Scope of Work"),
+///     ("Pre- and Post-", "Pre- and Post-"),
+///     // Continuation markers, in any case
+///     ("Introduction (continued)", "Introduction"),
+///     ("INTRODUCTION (CONT'D)", "INTRODUCTION"),
+///     ("3. Budget - continued", "3. Budget"),
+///     ("3. Budget – Continued", "3. Budget"),
+///     ("Work Continued", "Work Continued"),
+///     // Any of them together, in any order
+///     ("• 4.1 Risks (continued): 12", "4.1 Risks"),
+///     ("Scope of Work – (cont.).", "Scope of Work"),
+/// ] {
+///     assert_eq!(clean_heading_text(text, &lexicon), cleaned, "{:?}", text);
+/// }
+/// ```
+pub fn clean_heading_text(text: &str, lexicon: &Lexicon) -> String {
+    clean(text, lexicon).0
+}
+
+// Whether a heading's line ends in a continuation marker, once cleaned up
+pub(crate) fn is_continuation(text: &str, lexicon: &Lexicon) -> bool {
+    clean(text, lexicon).1
+}
+
+// `clean_heading_text`, and whether a continuation marker was cut off
+fn clean(text: &str, lexicon: &Lexicon) -> (String, bool) {
+    let text = strip_list_marker(text.trim()).0.trim();
     
-   
-    let mut cleaned = if text.ends_with(':') {
-        text[..text.len()-1].trim().to_string()
-    } else {
-        text.to_string()
+    // Trim on borrowed slices and only allocate for the final output
+    let mut cleaned = match text.strip_suffix(':') {
+        Some(stripped) => stripped.trim(),
+        None => text,
     };
 
-    let page_number_regex = Regex::new(r"\s+\d{1,3}$").unwrap();
-    cleaned = page_number_regex.replace(&cleaned, "").to_string();
+    // "CHAPTER 4" ends in its own number, not a page number
+    if let Some(m) = TRAILING_PAGE_NUMBER.find(cleaned).filter(|_| !is_bare_marker(cleaned)) {
+        cleaned = &cleaned[..m.start()];
+    }
     
-    let dotted_leaders_regex = Regex::new(r"\s*\.{3,}\s*\d*$").unwrap();
-    cleaned = dotted_leaders_regex.replace(&cleaned, "").to_string();
+    if let Some(m) = DOTTED_LEADERS.find(cleaned) {
+        cleaned = &cleaned[..m.start()];
+    }
+
+    // Markers, dashes, colons and periods can close a heading in any order:
+    // "Scope of Work – (continued):"
+    let mut continued = false;
+    loop {
+        let before = cleaned;
+        if let Some(stripped) = strip_continuation(cleaned, &lexicon.continuation_markers) {
+            cleaned = stripped.trim_end();
+            continued = true;
+        }
+        cleaned = strip_dangling_dash(cleaned);
+        cleaned = cleaned.strip_suffix(':').unwrap_or(cleaned).trim_end();
+        cleaned = strip_closing_period(cleaned);
+        if cleaned == before {
+            break;
+        }
+    }
     
-    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+    let mut output = String::with_capacity(cleaned.len());
+    for word in cleaned.split_whitespace() {
+        if !output.is_empty() {
+            output.push(' ');
+        }
+        output.push_str(word);
+    }
+    (output, continued)
+}
+
+// `text` without the continuation marker it ends in, matched ignoring case,
+// if it ends in one of `markers` (lowercased): "Scope (continued)" is
+// "Scope" carried on from an earlier page
+fn strip_continuation<'a>(text: &'a str, markers: &[String]) -> Option<&'a str> {
+    markers.iter().find_map(|marker| {
+        let start = text.char_indices().rev().nth(marker.chars().count().checked_sub(1)?)?.0;
+        let opens_word = marker.starts_with(|c: char| !c.is_alphanumeric())
+            || text[..start].ends_with(char::is_whitespace);
+        (opens_word && text[start..].to_lowercase() == *marker).then(|| &text[..start])
+    })
+}
+
+// Without a dash left standing at the end ("Scope of Work –"); a hyphen
+// ending a word ("Post-") stays
+fn strip_dangling_dash(mut text: &str) -> &str {
+    while let Some(stripped) = text.strip_suffix(['-', '–', '—']) {
+        if !stripped.ends_with(char::is_whitespace) {
+            break;
+        }
+        text = stripped.trim_end();
+    }
+    text
+}
+
+// Without a closing period, unless it ends an abbreviation or
+// initialism ("Inc.", "U.S."), an ellipsis, or a heading that is only a number
+fn strip_closing_period(text: &str) -> &str {
+    let Some(stripped) = text.strip_suffix('.') else {
+        return text;
+    };
+    let word = stripped.rsplit(char::is_whitespace).next().unwrap_or(stripped)
+        .trim_matches(|c: char| !c.is_alphanumeric());
+    let keeps = stripped.ends_with('.')
+        || crate::sections::ABBREVIATIONS.contains(&word.to_lowercase().as_str())
+        || (word.contains('.') && word.chars().any(char::is_alphabetic))
+        || stripped.chars().all(|c| c.is_ascii_digit() || c == '.');
+    if keeps { text } else { stripped.trim_end() }
 }
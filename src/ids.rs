@@ -0,0 +1,81 @@
+// Stable identifiers derived from content. FNV-1a is used rather than the
+// standard library's hasher because its output must not change between Rust
+// releases or runs: the ids are persisted in output files.
+
+use std::collections::HashMap;
+use crate::Heading;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = FNV_OFFSET;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+// Identifies the input file's exact contents
+pub fn document_id(bytes: &[u8]) -> String {
+    format!("{:016x}", fnv1a(bytes))
+}
+
+// Pages are grouped in runs of this many for heading ids, so a heading that
+// moves a page or two usually keeps its id
+const PAGE_BUCKET: usize = 10;
+
+// Gives headings ids that survive re-extraction. The id hashes the heading's
+// level, its text (lowercased, punctuation and spacing ignored) and its page
+// bucket, so adding, removing or reordering other headings leaves it alone.
+// It changes when the heading's text or level changes, or when it moves into
+// another bucket of PAGE_BUCKET pages. Headings that hash the same get "-2",
+// "-3", ... in document order, so only those can shift each other.
+#[derive(Default)]
+pub struct HeadingIds {
+    occurrences: HashMap<String, usize>,
+}
+
+impl HeadingIds {
+    // The id for the next heading in document order
+    pub fn next(&mut self, heading: &Heading) -> String {
+        let text: Vec<String> = heading.text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        let bucket = heading.page.saturating_sub(1) / PAGE_BUCKET;
+        let key = format!("{}\0{}\0{}", heading.level, text.join(" "), bucket);
+        let base = format!("h-{:08x}", fnv1a(key.as_bytes()) as u32);
+
+        let count = self.occurrences.entry(base.clone()).or_default();
+        *count += 1;
+        match *count {
+            1 => base,
+            n => format!("{}-{}", base, n),
+        }
+    }
+}
+
+/// Set the id of every heading, in order. Adding a heading elsewhere in the
+/// outline leaves the others' ids as they were:
+///
+/// ```
+/// use adobe1a::{ids::assign_heading_ids, Heading};
+///
+/// let h = |level: &str, text: &str, page| Heading { level: level.into(), text: text.into(), page, ..Default::default() };
+/// let mut before = vec![h("H1", "Introduction", 1), h("H2", "Scope", 2), h("H1", "Budget", 7)];
+/// let mut after = vec![h("H1", "Introduction", 1), h("H2", "Background", 2), h("H2", "Scope", 3), h("H1", "Budget", 8)];
+/// assign_heading_ids(&mut before);
+/// assign_heading_ids(&mut after);
+/// assert_eq!(before[0].id, after[0].id);
+/// assert_eq!(before[1].id, after[2].id);
+/// assert_eq!(before[2].id, after[3].id);
+/// ```
+pub fn assign_heading_ids(headings: &mut [Heading]) {
+    let mut ids = HeadingIds::default();
+    for heading in headings {
+        heading.id = ids.next(heading);
+    }
+}
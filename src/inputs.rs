@@ -0,0 +1,78 @@
+//! The files a batch run processes: the PDFs in a directory, or the paths
+//! listed in a manifest file.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// The PDFs directly inside `dir` (by extension, in any case), sorted by path
+pub fn pdfs_in_dir(dir: &Path) -> Result<Vec<PathBuf>> {
+    files_in_dir(dir, &["pdf"])
+}
+
+/// Likewise the `.txt` files, for plain-text inputs
+pub fn text_files_in_dir(dir: &Path) -> Result<Vec<PathBuf>> {
+    files_in_dir(dir, &["txt"])
+}
+
+/// Likewise the `.md` and `.markdown` files
+pub fn markdown_files_in_dir(dir: &Path) -> Result<Vec<PathBuf>> {
+    files_in_dir(dir, &["md", "markdown"])
+}
+
+fn files_in_dir(dir: &Path, extensions: &[&str]) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension()
+            .is_some_and(|ext| extensions.iter().any(|wanted| ext.eq_ignore_ascii_case(wanted))))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// The paths listed in the UTF-8 file `list`, one per line, in list order.
+/// Blank lines and lines starting with `#` are skipped, and so is a path
+/// listed a second time. Whitespace around a path is trimmed; spaces inside
+/// it are kept. Relative paths are taken from the list file's directory.
+///
+/// Paths aren't checked here: a batch run reports a missing file or one that
+/// isn't a PDF as that file's failure and carries on.
+///
+/// ```
+/// use adobe1a::{config::ExtractorConfig, extract_outline, inputs::read_input_list, timing::Stats};
+/// use adobe1a::summary::{FileStatus, FileSummary};
+///
+/// let dir = std::env::temp_dir().join(format!("adobe1a-input-list-{}", std::process::id()));
+/// std::fs::create_dir_all(dir.join("année 2024"))?;
+/// std::fs::copy("fixtures/nul-heading.pdf", dir.join("année 2024/annual report.pdf"))?;
+/// std::fs::copy("fixtures/renamed-docx.pdf", dir.join("minutes.pdf"))?;
+/// let absolute = dir.join("minutes.pdf");
+/// std::fs::write(dir.join("queue.txt"), format!(
+///     "# from the work queue\n\nannée 2024/annual report.pdf\r\n  missing.pdf\nminutes.pdf\n{}\n", absolute.display()))?;
+///
+/// let inputs = read_input_list(&dir.join("queue.txt"))?;
+/// assert_eq!(inputs, [dir.join("année 2024/annual report.pdf"), dir.join("missing.pdf"), dir.join("minutes.pdf")]);
+///
+/// let statuses: Vec<FileStatus> = inputs.iter()
+///     .map(|pdf| match extract_outline(pdf, &ExtractorConfig::default(), &mut Stats::new()) {
+///         Ok(outline) => FileSummary::succeeded(pdf, &outline, Stats::default()).status,
+///         Err(e) => FileSummary::failed(pdf, &e).status,
+///     })
+///     .collect();
+/// assert_eq!(statuses, [FileStatus::Ok, FileStatus::Failed, FileStatus::NotPdf]);
+/// std::fs::remove_dir_all(&dir)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn read_input_list(list: &Path) -> Result<Vec<PathBuf>> {
+    let contents = std::fs::read_to_string(list)
+        .with_context(|| format!("Failed to read input list {}", list.display()))?;
+    let base = list.parent().unwrap_or(Path::new(""));
+    let mut seen = HashSet::new();
+    Ok(contents.trim_start_matches('\u{feff}').lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| base.join(line))
+        .filter(|path| seen.insert(path.clone()))
+        .collect())
+}
@@ -0,0 +1,101 @@
+//! Test reports in the JUnit XML format CI servers display, so that a run
+//! over a benchmark corpus shows up as a suite of test cases.
+
+use std::fmt::Write as _;
+use std::time::Duration;
+
+/// One test case of a report
+#[derive(Debug, Clone)]
+pub struct TestCase {
+    pub name: String,
+    pub time: Duration,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Passed,
+    /// The test ran and its check failed: a one-line message, and details
+    Failed { message: String, details: String },
+    /// The test couldn't run
+    Error { message: String },
+    Skipped { message: String },
+}
+
+/// A report of `cases` as one suite named `suite`. Text is escaped for
+/// XML, and characters XML can't hold at all, such as most control
+/// characters, are left out.
+///
+/// ```
+/// use std::time::Duration;
+/// use adobe1a::junit::{report, Outcome, TestCase};
+///
+/// let case = |name: &str, outcome| TestCase { name: name.into(), time: Duration::from_millis(1250), outcome };
+/// let xml = report("outlines", &[
+///     case("a.pdf", Outcome::Passed),
+///     case("b.pdf", Outcome::Failed { message: "F1 0.50 < 0.80".into(), details: "missed: H1 \"Q&A\"".into() }),
+/// ]);
+/// assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"outlines\" tests=\"2\" failures=\"1\" errors=\"0\" skipped=\"0\" time=\"2.500\">"));
+/// assert!(xml.contains("<testcase name=\"a.pdf\" classname=\"outlines\" time=\"1.250\"/>"));
+/// assert!(xml.contains("<failure message=\"F1 0.50 &lt; 0.80\" type=\"failure\">missed: H1 &quot;Q&amp;A&quot;</failure>"));
+/// ```
+pub fn report(suite: &str, cases: &[TestCase]) -> String {
+    let count = |matches: fn(&Outcome) -> bool| cases.iter().filter(|case| matches(&case.outcome)).count();
+    let failures = count(|outcome| matches!(outcome, Outcome::Failed { .. }));
+    let errors = count(|outcome| matches!(outcome, Outcome::Error { .. }));
+    let skipped = count(|outcome| matches!(outcome, Outcome::Skipped { .. }));
+    let time = seconds(cases.iter().map(|case| case.time).sum());
+    let suite = escape(suite);
+    let counts = format!("tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\"", cases.len(), failures, errors, skipped, time);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(xml, "<testsuites name=\"{}\" {}>", suite, counts);
+    let _ = writeln!(xml, "  <testsuite name=\"{}\" {}>", suite, counts);
+    for case in cases {
+        let _ = write!(xml, "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"", escape(&case.name), suite, seconds(case.time));
+        let _ = match &case.outcome {
+            Outcome::Passed => writeln!(xml, "/>"),
+            Outcome::Failed { message, details } => writeln!(xml,
+                ">\n      <failure message=\"{}\" type=\"failure\">{}</failure>\n    </testcase>", escape(message), escape_with(details, false)),
+            Outcome::Error { message } => writeln!(xml,
+                ">\n      <error message=\"{}\" type=\"error\"/>\n    </testcase>", escape(message)),
+            Outcome::Skipped { message } => writeln!(xml,
+                ">\n      <skipped message=\"{}\"/>\n    </testcase>", escape(message)),
+        };
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn seconds(time: Duration) -> String {
+    format!("{:.3}", time.as_secs_f64())
+}
+
+// For attribute values
+fn escape(text: &str) -> String {
+    escape_with(text, true)
+}
+
+// Within an attribute's value, line breaks and tabs are written as
+// references, which a reader would otherwise fold into spaces; in element
+// text they're kept as they are
+fn escape_with(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' if attribute => escaped.push_str("&#10;"),
+            '\r' if attribute => escaped.push_str("&#13;"),
+            '\t' if attribute => escaped.push_str("&#9;"),
+            '\n' | '\r' | '\t' => escaped.push(c),
+            // Not allowed anywhere in an XML 1.0 document, even as references
+            '\u{0}'..='\u{1F}' | '\u{FFFE}' | '\u{FFFF}' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,62 @@
+//! Positioned text runs straight from page content streams, for callers
+//! doing their own layout analysis.
+//!
+//! Runs come back ordered by page, then in the order the content stream
+//! draws them. They are unfiltered: invisible text is included with
+//! `visible: false`, and header/footer bands are not applied.
+//!
+//! Handled:
+//! - `Tj`, `TJ`, `'` and `"` text showing, with `TJ` kerning in the width
+//! - text positioning (`Tm`, `Td`, `TD`, `T*`, `TL`) and `cm`, with `q`/`Q`
+//! - font size and /BaseFont from `Tf`, with any subset prefix removed
+//! - bold, italic and weight from the font name, as
+//!   [`crate::fonts::FontInfo::parse`] reads it
+//! - render modes 3 and 7 as invisible text
+//! - simple fonts' encodings: WinAnsi, MacRoman and Standard, /Differences
+//!   arrays by glyph name, and ToUnicode maps of single-byte codes. Runs in
+//!   symbolic fonts with none of these (Symbol, ZapfDingbats, Type 3) are
+//!   left out, as their codes stand for no characters
+//! - fill colour in DeviceGray, DeviceRGB and DeviceCMYK
+//! - page /Rotate, inherited through the page tree: `x`/`y` are in the
+//!   page's upright (as displayed) orientation
+//!
+//! Not handled:
+//! - CID fonts' CMaps: string bytes in Type 0 fonts are read as UTF-8, so
+//!   their text comes out garbled
+//! - glyph widths: `width` assumes half an em per character
+//! - form XObjects and annotations: their text is not visited
+//! - rotated and skewed text: `x`/`y` are the run's origin, `size` is the `Tf` size
+//! - pattern, separation and ICC colours, which leave the previous colour
+
+use anyhow::Result;
+use lopdf::{Document, ObjectId};
+use rayon::prelude::*;
+
+pub use crate::font_utils::{Rgb, TextRun};
+
+/// Every text run in `doc`, ordered by page then drawing order
+pub fn extract_runs(doc: &Document) -> Vec<TextRun> {
+    // Pages are decoded in parallel, as the font engine does, and their runs
+    // concatenated in page order
+    let pages: Vec<(u32, ObjectId)> = doc.get_pages().into_iter().collect();
+    let page_runs: Vec<Vec<TextRun>> = pages
+        .par_iter()
+        .map(|&(page, page_id)| crate::font_utils::page_runs(doc, page_id, page as usize))
+        .collect();
+    page_runs.concat()
+}
+
+/// [`extract_runs`] on a PDF held in memory
+///
+/// ```no_run
+/// let bytes = std::fs::read("input/2.pdf")?;
+/// for run in adobe1a::layout::extract_runs_from_bytes(&bytes)? {
+///     println!("p{} {:.0},{:.0} {} {:.1}pt {:?}", run.page, run.x, run.y, run.font, run.size, run.text);
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn extract_runs_from_bytes(bytes: &[u8]) -> Result<Vec<TextRun>> {
+    let mut doc = Document::load_mem(crate::pdf_from_header(bytes)?)?;
+    crate::trees::repair_page_tree(&mut doc);
+    Ok(extract_runs(&doc))
+}
@@ -0,0 +1,110 @@
+//! Heading levels set by pattern, for a house style the level heuristics
+//! can't know about, such as "PART 2" standing above numbered sections
+//! rather than beside them. Rules are tried in order against each detected
+//! heading's text, and the first that matches sets its level.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{Heading, MAX_HEADING_DEPTH};
+
+/// A rule putting headings whose text matches `pattern` at `level`
+#[derive(Debug, Clone)]
+pub struct LevelOverride {
+    pattern: Regex,
+    level: String,
+}
+
+impl LevelOverride {
+    /// Checks that the pattern is a valid regex and the level one the
+    /// outline uses, "H1" to "H4"
+    pub fn new(pattern: &str, level: &str) -> std::result::Result<LevelOverride, String> {
+        let compiled = Regex::new(pattern).map_err(|e| format!("invalid pattern `{}`: {}", pattern, e))?;
+        let valid = level.strip_prefix('H')
+            .and_then(|depth| depth.parse::<u8>().ok())
+            .is_some_and(|depth| (1..=MAX_HEADING_DEPTH).contains(&depth));
+        if !valid {
+            return Err(format!("`{}` isn't a heading level from H1 to H{}", level, MAX_HEADING_DEPTH));
+        }
+        Ok(LevelOverride { pattern: compiled, level: level.to_string() })
+    }
+}
+
+/// Rules setting heading levels by pattern, where the first rule to match a
+/// heading's text wins. Headings no rule matches keep the level they were
+/// given, and so do bookmarks, which carry the author's own levels.
+///
+/// ```
+/// use adobe1a::levels::{LevelOverride, LevelOverrides};
+///
+/// let rule = |pattern, level| LevelOverride::new(pattern, level).unwrap();
+/// let overrides = LevelOverrides::new(vec![
+///     rule(r"^PART \d+", "H1"),
+///     rule(r"^\d+\.\d+ ", "H3"),
+///     rule(r"^\d+", "H2"),
+/// ]);
+/// assert_eq!(overrides.level("PART 2 Delivery"), Some("H1"));
+/// // The last rule matches too, but the first match wins
+/// assert_eq!(overrides.level("3.1 Staffing"), Some("H3"));
+/// assert_eq!(overrides.level("3. Results"), Some("H2"));
+/// assert_eq!(overrides.level("Appendix A"), None);
+/// assert!(LevelOverrides::default().is_empty());
+///
+/// assert_eq!(LevelOverride::new(r"^\d+", "H5").unwrap_err(), "`H5` isn't a heading level from H1 to H4");
+/// assert!(LevelOverride::new(r"^PART (\d+", "H1").unwrap_err().starts_with("invalid pattern `^PART (\\d+`"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LevelOverrides {
+    rules: Vec<LevelOverride>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleFile {
+    pattern: String,
+    level: String,
+}
+
+impl LevelOverrides {
+    pub fn new(rules: Vec<LevelOverride>) -> LevelOverrides {
+        LevelOverrides { rules }
+    }
+
+    /// Reads rules from a JSON file such as
+    /// `[{ "pattern": "^PART \\d+", "level": "H1" }]`, reporting the first
+    /// rule with an invalid pattern or level
+    pub fn load(path: &Path) -> Result<LevelOverrides> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read level overrides {}", path.display()))?;
+        let file: Vec<RuleFile> = serde_json::from_str(&text)
+            .with_context(|| format!("Invalid level overrides {}", path.display()))?;
+        let rules = file.into_iter().enumerate()
+            .map(|(i, rule)| LevelOverride::new(&rule.pattern, &rule.level)
+                .map_err(|e| anyhow::anyhow!("Invalid level overrides {}: rule {}: {}", path.display(), i + 1, e)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(LevelOverrides { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The level the first rule matching `text` sets, if any rule does
+    pub fn level(&self, text: &str) -> Option<&str> {
+        let text = text.trim();
+        self.rules.iter()
+            .find(|rule| rule.pattern.is_match(text))
+            .map(|rule| rule.level.as_str())
+    }
+
+    // Sets the heading's level when a rule matches it, keeping the level
+    // detection gave it in `detected_level`
+    pub(crate) fn apply(&self, heading: &mut Heading) {
+        if let Some(level) = self.level(&heading.text) {
+            heading.detected_level = Some(std::mem::replace(&mut heading.level, level.to_string()));
+        }
+    }
+}
@@ -0,0 +1,113 @@
+// The word and phrase lists the heuristics match against. The defaults are
+// embedded from lexicon.json; `--lexicon` can extend or replace any list.
+// All entries are matched against lowercased text.
+
+use std::path::Path;
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+// Parsed by the first thread to need it; others arriving meanwhile wait for
+// that once, and after it reading is lock-free
+static DEFAULT: Lazy<Lexicon> = Lazy::new(|| {
+    serde_json::from_str(include_str!("lexicon.json")).expect("embedded lexicon.json is valid")
+});
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Lexicon {
+    // A capitalized line containing one of these is an H1 rather than an H2
+    pub h1_indicators: Vec<String>,
+    // Raise or lower a first-page line's score as the title
+    pub title_indicators: Vec<String>,
+    pub content_indicators: Vec<String>,
+    // A line containing one of these is running prose, not a heading
+    pub prose_phrases: Vec<String>,
+    // Markup and boilerplate that never make a useful outline entry
+    pub excluded_substrings: Vec<String>,
+    // Section names that are headings on their own early in a document,
+    // however they're styled; never boilerplate
+    pub canonical_sections: Vec<String>,
+    // Words a heading doesn't end on; a line ending in one was cut mid-sentence
+    pub trailing_connectives: Vec<String>,
+    // What ends a heading repeated on a later page ("Scope (continued)");
+    // it's cut off, and the heading merged into its first occurrence
+    pub continuation_markers: Vec<String>,
+    // Short function words title case leaves in lowercase ("Scope of the Assessment")
+    pub stopwords: Vec<String>,
+    // Labels of a memo's or letter's subject line ("RE:", "Subject:"), whose
+    // text is the title of a short document
+    pub subject_labels: Vec<String>,
+}
+
+impl Default for Lexicon {
+    fn default() -> Self {
+        DEFAULT.clone()
+    }
+}
+
+// A change to one list: `extend` adds entries, `replace` swaps the whole
+// list out first. Both may be given.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ListOverride {
+    replace: Option<Vec<String>>,
+    #[serde(default)]
+    extend: Vec<String>,
+}
+
+impl ListOverride {
+    fn apply(self, list: &mut Vec<String>) {
+        if let Some(replacement) = self.replace {
+            *list = replacement;
+        }
+        list.extend(self.extend);
+        for entry in list.iter_mut() {
+            *entry = entry.to_lowercase();
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LexiconOverrides {
+    h1_indicators: ListOverride,
+    title_indicators: ListOverride,
+    content_indicators: ListOverride,
+    prose_phrases: ListOverride,
+    excluded_substrings: ListOverride,
+    canonical_sections: ListOverride,
+    trailing_connectives: ListOverride,
+    continuation_markers: ListOverride,
+    stopwords: ListOverride,
+    subject_labels: ListOverride,
+}
+
+impl Lexicon {
+    // The defaults with the overrides in a JSON file applied, e.g.
+    // `{ "h1_indicators": { "extend": ["charter"] } }`
+    pub fn load(path: &Path) -> Result<Lexicon> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read lexicon {}", path.display()))?;
+        let overrides: LexiconOverrides = serde_json::from_str(&text)
+            .with_context(|| format!("Invalid lexicon {}", path.display()))?;
+
+        let mut lexicon = Lexicon::default();
+        overrides.h1_indicators.apply(&mut lexicon.h1_indicators);
+        overrides.title_indicators.apply(&mut lexicon.title_indicators);
+        overrides.content_indicators.apply(&mut lexicon.content_indicators);
+        overrides.prose_phrases.apply(&mut lexicon.prose_phrases);
+        overrides.excluded_substrings.apply(&mut lexicon.excluded_substrings);
+        overrides.canonical_sections.apply(&mut lexicon.canonical_sections);
+        overrides.trailing_connectives.apply(&mut lexicon.trailing_connectives);
+        overrides.continuation_markers.apply(&mut lexicon.continuation_markers);
+        overrides.stopwords.apply(&mut lexicon.stopwords);
+        overrides.subject_labels.apply(&mut lexicon.subject_labels);
+        Ok(lexicon)
+    }
+}
+
+// Whether `text` (already lowercased) contains any entry of `list`
+pub fn mentions(text: &str, list: &[String]) -> bool {
+    list.iter().any(|entry| text.contains(entry.as_str()))
+}
@@ -0,0 +1,1457 @@
+//! Extracts a PDF's title and outline of H1 to H4 headings.
+//!
+//! Output is deterministic: the same bytes, settings and crate version give
+//! byte-identical JSON on every run and platform, apart from the timings
+//! `--stats-json` embeds. Scores are written to three decimals, so the
+//! last bits of floating point maths, which differ between platforms, don't
+//! reach the output, and nothing written depends on a hash map's order.
+
+use lopdf::Document;
+use serde::{Serialize, Deserialize};
+use std::collections::HashSet;
+use std::path::Path;
+use anyhow::Result;
+use regex::Regex;
+use once_cell::sync::Lazy;
+
+#[cfg(not(any(feature = "engine-text", feature = "engine-font")))]
+compile_error!("enable at least one of the `engine-text` and `engine-font` features");
+
+pub mod config;
+pub mod timing;
+pub mod summary;
+pub mod status;
+pub mod ids;
+pub mod output;
+pub mod layout;
+pub mod extractor;
+pub mod lexicon;
+pub mod portfolio;
+pub mod documents;
+pub mod lint;
+pub mod merge;
+pub mod sanitize;
+pub mod inputs;
+pub mod transform;
+pub mod calibration;
+pub mod levels;
+pub mod text;
+pub mod markdown;
+pub mod titles;
+pub mod xmp;
+pub mod sections;
+pub mod numbers;
+pub mod quality;
+pub mod collapse;
+pub mod fonts;
+pub mod evaluation;
+pub mod junit;
+// Much of these serves a single engine and goes unused when that engine's
+// feature is off
+#[cfg_attr(not(feature = "engine-text"), allow(dead_code))]
+mod functions;
+#[cfg_attr(not(feature = "engine-font"), allow(dead_code))]
+mod font_utils;
+mod pages;
+mod bookmarks;
+mod links;
+mod cover;
+mod trees;
+mod validation;
+mod memory;
+mod encoding;
+
+use config::{CaseStyle, Engine, ExtractorConfig, HiddenText, Portfolio, TitleMode};
+use lexicon::Lexicon;
+use memory::MemoryBudget;
+use sections::PageText;
+
+pub use extractor::{BuildError, ExtractEvent, Extractor, ExtractorBuilder, PageResult};
+pub use text::{analyze_text, TextOptions};
+use timing::{Stage, Stats};
+use validation::CandidateFilter;
+
+// Each pattern is compiled once, by whichever thread first needs it; a
+// compiled `Regex` is then matched from any number of threads at once
+pub static TITLE_PATTERN: Lazy<Regex> = Lazy::new(|| 
+    Regex::new(r"(?i)^\s*(RFP|Request\s+for\s+Proposal|Proposal|Scope\s+of\s+Work)\s*:?\s*(.*)$").unwrap());
+pub static NUMBERED_HEADING: Lazy<Regex> = Lazy::new(||
+    // Matches headings that begin with multi-level decimals like "1.", "1.2.", etc.,
+    // single decimals with text ("1 Introduction"), roman numerals in either case ("IV. Scope", "iv. Details"),
+    // or alpha enumerations such as "A. Background" or "b) Goals".
+    Regex::new(r"^\s*(?:((?:\d+\.)+\d*|\d+)[\.)]?\s+.+|[A-Za-z]{1,2}[\.)]\s+.+|(?i:[IVXLCDM]+)[\.)]?\s+.+)").unwrap());
+// "Chapter 3", "Section 4.1: Scope", "PART TWO — DEFINITIONS", "Chapter Twenty-One".
+// Spelled-out numbers run to fifty and ordinals to twentieth; group 3 is any title
+// that follows.
+pub static SECTION_HEADING: Lazy<Regex> = Lazy::new(|| {
+    let units = "one|two|three|four|five|six|seven|eight|nine";
+    let cardinals = format!(
+        "ten|eleven|twelve|thirteen|fourteen|fifteen|sixteen|seventeen|eighteen|nineteen\
+         |(?:twenty|thirty|forty|fifty)(?:[-\\s](?:{units}))?|{units}"
+    );
+    let ordinals = "first|second|third|fourth|fifth|sixth|seventh|eighth|ninth|tenth|eleventh\
+         |twelfth|thirteenth|fourteenth|fifteenth|sixteenth|seventeenth|eighteenth|nineteenth|twentieth";
+    Regex::new(&format!(
+        r"^\s*(Chapter|CHAPTER|Section|SECTION|Part|PART)\s+(\d+(?:\.\d+)*|[IVXLCDM]+|[A-Z]|(?i:{cardinals}|{ordinals}))\b(?:\s*[:.\-–—]?\s*(.+))?$"
+    ))
+    .unwrap()
+});
+pub static APPENDIX_HEADING: Lazy<Regex> = Lazy::new(|| 
+    Regex::new(r"^\s*Appendix\s+([A-Z0-9]+)").unwrap());
+// A capitalized line ending in its only colon: "Background:", "Phase 2 Goals:"
+pub static COLON_HEADING: Lazy<Regex> = Lazy::new(|| 
+    Regex::new(r"^[A-Z][^:]*[^:\s]:$").unwrap());
+
+/// One entry of the outline.
+///
+/// With `include_sources` set, `sources` names what found the heading:
+/// `"text"` or `"font"` for an engine, or `"bookmarks"` for a quick-mode
+/// outline taken from the PDF's bookmarks.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct Heading {
+    // Stable across re-extraction; see `ids::HeadingIds`
+    #[serde(default)]
+    pub id: String,
+    pub level: String,
+    pub text: String,
+    pub page: usize,
+    // Outputs written before headings were scored have no confidence; their
+    // headings read as fully confident, so a threshold keeps them
+    #[serde(default = "unscored_confidence", serialize_with = "serialize_score")]
+    pub confidence: f64,
+    // The label printed on the page ("vii", "A-3") when the PDF defines labels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_label: Option<String>,
+    // In an outline merged from several volumes, where `page` counts through
+    // all of them, the page within the heading's own volume
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_page: Option<usize>,
+    // What found the heading ("text", "font", "bookmarks"); empty unless
+    // `include_sources` is set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
+    // The engine's own score, before calibration turned it into
+    // `confidence`; None unless `include_raw_scores` is set
+    #[serde(default, skip_serializing_if = "Option::is_none", serialize_with = "serialize_optional_score")]
+    pub raw_score: Option<f64>,
+    // The level detection gave the heading before a level override set
+    // `level`; None unless `include_raw_scores` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_level: Option<String>,
+    // The opening of the first paragraph under the heading; only filled in
+    // with --with-snippets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    // Characters before the heading in its page's text, as
+    // `sections::page_texts` gives it; only filled in with --with-offsets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_offset: Option<usize>,
+    // The index among its page's runs, in `layout::extract_runs` order, of
+    // the run the heading starts in; only filled in with --with-offsets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_index: Option<usize>,
+    // How far down its page the heading is, as a fraction of the page's
+    // height from 0 at the top, for a viewer to scroll to: the top of the
+    // run it starts in, or a bookmark's destination. Measured on the page
+    // as displayed (its CropBox, turned by /Rotate). Only filled in with
+    // --with-positions, and left out where the position isn't known.
+    #[serde(default, skip_serializing_if = "Option::is_none", serialize_with = "serialize_optional_score")]
+    pub y_fraction: Option<f64>,
+    // The section number, printed in front of the heading or made up from
+    // its place in the outline; only filled in with --synthesize-numbers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<String>,
+    // `number` was made up rather than printed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub synthetic: bool,
+    // An internal link or a bookmark lands on the heading, which earned it
+    // `link_bonus` confidence
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub referenced: bool,
+    // Its line marked it as carried on from an earlier page ("Scope
+    // (continued)"); such a heading is merged into its first occurrence, so
+    // only one whose first occurrence wasn't found is left
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub continued: bool,
+}
+
+fn unscored_confidence() -> f64 {
+    1.0
+}
+
+// Decimal places scores are written with. The last bits of a score depend
+// on the platform's maths library (`powf`, `exp`), so they're rounded away
+// where the outline is serialized rather than left to differ between builds.
+const SCORE_DECIMALS: i32 = 3;
+
+pub(crate) fn round_score(score: f64) -> f64 {
+    let scale = 10f64.powi(SCORE_DECIMALS);
+    (score * scale).round() / scale
+}
+
+fn serialize_score<S: serde::Serializer>(score: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(round_score(*score))
+}
+
+fn serialize_optional_score<S: serde::Serializer>(score: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error> {
+    score.map(round_score).serialize(serializer)
+}
+
+// Deepest heading level used; numbering or nesting below it is folded into it
+pub const MAX_HEADING_DEPTH: u8 = 4;
+
+// Pages read in quick mode by a document over its memory limit
+const MEMORY_QUICK_PAGES: usize = 15;
+
+// Recorded in every outline, so a result can be traced to the build that made it
+pub const EXTRACTOR_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Outline {
+    pub title: String,
+    // Left out for the usual case, a title detected in the text
+    #[serde(default, skip_serializing_if = "TitleSource::is_content")]
+    pub title_source: TitleSource,
+    pub outline: Vec<Heading>,
+    // Runner-up titles, only filled in with --title-candidates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub title_candidates: Vec<TitleCandidate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<Warning>,
+    // Pages in the PDF, when its page tree could be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_count: Option<usize>,
+    // The documents found in a concatenated PDF with --split-documents
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub documents: Vec<documents::SubDocument>,
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub stats: Option<Stats>,
+    // Hash of the input file, used by --resume to tell whether an existing
+    // output still belongs to its input. Every PDF's output has one; text
+    // and Markdown inputs leave it out.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub document_id: String,
+    // Quick mode stopped before the last page, so headings may be missing
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
+    // Title, authors and keywords from the PDF's XMP packet or info dictionary
+    #[serde(default, skip_serializing_if = "xmp::Metadata::is_empty")]
+    pub metadata: xmp::Metadata,
+    // What produced the headings ("text", "font", "bookmarks", "markdown");
+    // a merged or combined outline lists each one its parts used
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub engine: Vec<String>,
+    // The adobe1a version that wrote the outline; --resume reprocesses
+    // outputs written by any other
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub extractor_version: String,
+    // How far the outline can be trusted, from 0 to 100, and the signals
+    // behind it; see `quality::assess`. None in outputs written before
+    // outlines were scored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_score: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_breakdown: Option<quality::QualityBreakdown>,
+    // The document's language as a BCP 47 tag, given or from the PDF's
+    // `/Lang`, which heading text is matched in; see
+    // `sanitize::normalize_for_match`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+impl Outline {
+    // Adds the engines behind a merged-in outline to this one's
+    pub(crate) fn add_engines(&mut self, engines: Vec<String>) {
+        for engine in engines {
+            if !self.engine.contains(&engine) {
+                self.engine.push(engine);
+            }
+        }
+    }
+}
+
+/// A level of the outline, spelled in [`Heading::level`] as "H1" to "H4"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HeadingLevel {
+    H1,
+    H2,
+    H3,
+    H4,
+}
+
+impl HeadingLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HeadingLevel::H1 => "H1",
+            HeadingLevel::H2 => "H2",
+            HeadingLevel::H3 => "H3",
+            HeadingLevel::H4 => "H4",
+        }
+    }
+}
+
+impl std::fmt::Display for HeadingLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Heading {
+    /// The heading's nesting depth: 1 for "H1", 2 for "H2", and so on.
+    /// A level that isn't of that form counts as top level.
+    ///
+    /// ```
+    /// use adobe1a::Heading;
+    ///
+    /// let heading = Heading { level: "H3".into(), text: "Scope".into(), ..Default::default() };
+    /// assert_eq!(heading.depth(), 3);
+    /// ```
+    pub fn depth(&self) -> u8 {
+        self.level.strip_prefix('H')
+            .and_then(|n| n.parse::<u8>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(1)
+    }
+
+    // The same heading one level further down, short of MAX_HEADING_DEPTH
+    pub(crate) fn demoted(self) -> Heading {
+        Heading { level: format!("H{}", (self.depth() + 1).min(MAX_HEADING_DEPTH)), ..self }
+    }
+}
+
+/// A heading with the headings nested under it, as built by [`Outline::to_tree`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadingNode {
+    pub heading: Heading,
+    pub children: Vec<HeadingNode>,
+}
+
+impl Outline {
+    /// True when no headings were found
+    ///
+    /// ```
+    /// use adobe1a::Outline;
+    ///
+    /// assert!(Outline::default().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.outline.is_empty()
+    }
+
+    /// Reads an outline written by any version of this crate, in either key
+    /// style. Fields added since a file was written take their defaults, and
+    /// embedded `stats` are skipped. Samples of each output shape so far are
+    /// kept in `fixtures/outputs`:
+    ///
+    /// ```
+    /// use adobe1a::Outline;
+    ///
+    /// let mut samples: Vec<_> = std::fs::read_dir("fixtures/outputs")?.map(|entry| entry.unwrap().path()).collect();
+    /// samples.sort();
+    /// assert!(samples.len() >= 10);
+    /// for path in samples {
+    ///     let outline = Outline::from_json_str(&std::fs::read_to_string(&path)?)?;
+    ///     assert!(!outline.title.is_empty() && !outline.is_empty(), "{}", path.display());
+    ///     // Written back and read again, nothing changes
+    ///     let json = outline.to_json_string(false)?;
+    ///     assert_eq!(Outline::from_json_str(&json)?.to_json_string(false)?, json);
+    /// }
+    ///
+    /// // The original output shape, before confidences
+    /// let outline = Outline::from_json_str(&std::fs::read_to_string("fixtures/outputs/00-challenge.json")?)?;
+    /// assert_eq!(outline.outline[1].text, "Project Governance");
+    /// assert_eq!(outline.outline[1].confidence, 1.0);
+    /// let outline = Outline::from_json_str(&std::fs::read_to_string("fixtures/outputs/04-camel-keys.json")?)?;
+    /// assert_eq!(outline.title_source, adobe1a::TitleSource::Content);
+    /// assert!(!outline.title_candidates.is_empty());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn from_json_str(json: &str) -> Result<Outline> {
+        output::from_json(json.as_bytes())
+    }
+
+    /// The outline as JSON with snake_case keys, indented when `pretty`;
+    /// [`output::to_json`] writes camelCase keys too. Fields at their
+    /// defaults are left out, `title_source` when the title was detected in
+    /// the text among them.
+    ///
+    /// ```
+    /// use adobe1a::{Outline, TitleSource};
+    ///
+    /// let outline = Outline { title: "Annual Report".into(), ..Outline::default() };
+    /// assert_eq!(outline.to_json_string(false)?, r#"{"title":"Annual Report","outline":[]}"#);
+    /// let named = Outline { title_source: TitleSource::Filename, document_id: "9f2c".into(), ..outline };
+    /// assert_eq!(named.to_json_string(false)?,
+    ///     r#"{"title":"Annual Report","title_source":"filename","outline":[],"document_id":"9f2c"}"#);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn to_json_string(&self, pretty: bool) -> Result<String> {
+        Ok(match pretty {
+            true => serde_json::to_string_pretty(self)?,
+            false => serde_json::to_string(self)?,
+        })
+    }
+
+    /// The headings at `level`, in document order
+    ///
+    /// ```
+    /// # use adobe1a::{Heading, HeadingLevel, Outline};
+    /// # let h = |level: &str, text: &str| Heading { level: level.into(), text: text.into(), ..Default::default() };
+    /// let outline = Outline { outline: vec![h("H1", "Intro"), h("H2", "Scope"), h("H1", "Plan")], ..Default::default() };
+    /// let top: Vec<&str> = outline.iter_level(HeadingLevel::H1).map(|h| h.text.as_str()).collect();
+    /// assert_eq!(top, ["Intro", "Plan"]);
+    /// assert_eq!(outline.iter_level(HeadingLevel::H3).count(), 0);
+    /// ```
+    pub fn iter_level(&self, level: HeadingLevel) -> impl Iterator<Item = &Heading> + '_ {
+        self.outline.iter().filter(move |heading| heading.level == level.as_str())
+    }
+
+    /// The first heading whose text matches `text` the way duplicate headings
+    /// are matched: ignoring case, in the outline's language, numbering,
+    /// periods and colons
+    ///
+    /// ```
+    /// # use adobe1a::{Heading, Outline};
+    /// # let h = |level: &str, text: &str| Heading { level: level.into(), text: text.into(), ..Default::default() };
+    /// let outline = Outline { outline: vec![h("H1", "1. Introduction"), h("H2", "1.1 Scope:")], ..Default::default() };
+    /// assert_eq!(outline.find("scope").map(|h| h.level.as_str()), Some("H2"));
+    /// assert!(outline.find("Budget").is_none());
+    ///
+    /// // In Turkish, I is the capital of dotless ı
+    /// let mut outline = Outline { outline: vec![h("H1", "Birinci Kısım")], ..Default::default() };
+    /// assert!(outline.find("BIRINCI KISIM").is_none());
+    /// outline.language = Some("tr-TR".into());
+    /// assert!(outline.find("BİRİNCİ KISIM").is_some());
+    /// ```
+    pub fn find(&self, text: &str) -> Option<&Heading> {
+        let lang = self.language.as_deref();
+        let key = functions::dedupe_key(text, lang);
+        self.outline.iter().find(|heading| functions::dedupe_key(&heading.text, lang) == key)
+    }
+
+    /// The headings on page `page`, in document order
+    ///
+    /// ```
+    /// # use adobe1a::{Heading, Outline};
+    /// let on = |page, text: &str| Heading { level: "H1".into(), text: text.into(), page, ..Default::default() };
+    /// let outline = Outline { outline: vec![on(1, "Intro"), on(2, "Scope"), on(2, "Plan")], ..Default::default() };
+    /// assert_eq!(outline.headings_on_page(2).count(), 2);
+    /// ```
+    pub fn headings_on_page(&self, page: usize) -> impl Iterator<Item = &Heading> + '_ {
+        self.outline.iter().filter(move |heading| heading.page == page)
+    }
+
+    /// The deepest heading's [`Heading::depth`], or 0 when there are none
+    ///
+    /// ```
+    /// # use adobe1a::{Heading, Outline};
+    /// # let h = |level: &str| Heading { level: level.into(), text: "x".into(), ..Default::default() };
+    /// let outline = Outline { outline: vec![h("H1"), h("H3"), h("H2")], ..Default::default() };
+    /// assert_eq!(outline.max_depth(), 3);
+    /// assert_eq!(Outline::default().max_depth(), 0);
+    /// ```
+    pub fn max_depth(&self) -> u8 {
+        self.outline.iter().map(Heading::depth).max().unwrap_or(0)
+    }
+
+    /// The headings nested by depth. Each heading goes under the closest
+    /// earlier heading that is shallower than it; one with none, such as an
+    /// H2 before the first H1, becomes a root.
+    ///
+    /// ```
+    /// # use adobe1a::{Heading, Outline};
+    /// # let h = |level: &str, text: &str| Heading { level: level.into(), text: text.into(), ..Default::default() };
+    /// let outline = Outline { outline: vec![h("H1", "Intro"), h("H2", "Scope"), h("H3", "Terms"), h("H1", "Plan")], ..Default::default() };
+    /// let tree = outline.to_tree();
+    /// assert_eq!(tree.len(), 2);
+    /// assert_eq!(tree[0].children[0].heading.text, "Scope");
+    /// assert_eq!(tree[0].children[0].children[0].heading.text, "Terms");
+    /// ```
+    pub fn to_tree(&self) -> Vec<HeadingNode> {
+        // Nodes still open for children, outermost first
+        let mut stack: Vec<HeadingNode> = Vec::new();
+        let mut roots = Vec::new();
+        let close = |stack: &mut Vec<HeadingNode>, roots: &mut Vec<HeadingNode>| {
+            if let Some(node) = stack.pop() {
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => roots.push(node),
+                }
+            }
+        };
+        for heading in &self.outline {
+            while stack.last().is_some_and(|open| open.heading.depth() >= heading.depth()) {
+                close(&mut stack, &mut roots);
+            }
+            stack.push(HeadingNode { heading: heading.clone(), children: Vec::new() });
+        }
+        while !stack.is_empty() {
+            close(&mut stack, &mut roots);
+        }
+        roots
+    }
+
+    /// The headings of `tree` in document order, undoing [`Outline::to_tree`]
+    ///
+    /// ```
+    /// # use adobe1a::{Heading, Outline};
+    /// # let h = |level: &str, text: &str| Heading { level: level.into(), text: text.into(), ..Default::default() };
+    /// let outline = Outline { outline: vec![h("H2", "Notes"), h("H1", "Intro"), h("H2", "Scope")], ..Default::default() };
+    /// assert_eq!(Outline::flatten(&outline.to_tree()), outline.outline);
+    /// ```
+    pub fn flatten(tree: &[HeadingNode]) -> Vec<Heading> {
+        let mut headings = Vec::new();
+        for node in tree {
+            headings.push(node.heading.clone());
+            headings.extend(Outline::flatten(&node.children));
+        }
+        headings
+    }
+}
+
+impl<'a> IntoIterator for &'a Outline {
+    type Item = &'a Heading;
+    type IntoIter = std::slice::Iter<'a, Heading>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.outline.iter()
+    }
+}
+
+// Where the title came from
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TitleSource {
+    // Detected in the document's text
+    #[default]
+    Content,
+    // Nothing was detected, so it was derived from the file name
+    Filename,
+    // The first page wasn't read, so it's the document info /Title
+    Metadata,
+    // Given by the caller, e.g. with `--title`
+    Override,
+}
+
+impl TitleSource {
+    fn is_content(&self) -> bool {
+        *self == TitleSource::Content
+    }
+}
+
+// A possible title and how strongly it was suggested. Scores are normalized
+// to 0..1 so candidates from different sources can be ranked together.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TitleCandidate {
+    pub text: String,
+    #[serde(serialize_with = "serialize_score")]
+    pub score: f64,
+    // "heuristic" (first-page line scoring), "font_size" (largest text on the
+    // first page) or "metadata" (the document info /Title)
+    pub source: String,
+}
+
+impl TitleCandidate {
+    fn new(text: impl Into<String>, score: f64, source: &str) -> Self {
+        TitleCandidate { text: text.into(), score, source: source.to_string() }
+    }
+}
+
+// Heuristic line scores top out around this many points
+const MAX_HEURISTIC_TITLE_SCORE: f64 = 80.0;
+
+fn heuristic_title_candidates(scored: &[(String, i32)]) -> Vec<TitleCandidate> {
+    scored.iter()
+        .map(|(text, score)| {
+            TitleCandidate::new(text.as_str(), (*score as f64 / MAX_HEURISTIC_TITLE_SCORE).clamp(0.0, 1.0), "heuristic")
+        })
+        .collect()
+}
+
+// Add the font-size, metadata and XMP candidates to the heuristic ones, then keep
+// the best five distinct texts
+fn collect_title_candidates(doc: &Document, heuristic: Vec<TitleCandidate>, config: &ExtractorConfig) -> Vec<TitleCandidate> {
+    let mut candidates = heuristic;
+    let largest = match config.includes_page(1) {
+        true => font_utils::largest_text(doc, config),
+        false => None,
+    };
+    if let Some((text, score)) = largest {
+        candidates.push(TitleCandidate::new(text, score, "font_size"));
+    }
+    if let Some((text, score)) = pages::metadata_title(doc) {
+        candidates.push(TitleCandidate::new(text, score, "metadata"));
+    }
+    if let Some((text, score)) = xmp::scored_title(doc) {
+        candidates.push(TitleCandidate::new(text, score, "xmp"));
+    }
+
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    let mut seen = HashSet::new();
+    candidates.retain(|c| seen.insert(sanitize::normalize_for_match(&c.text, config.language.as_deref())));
+    candidates.truncate(5);
+    candidates
+}
+
+// Use the detected title, falling back to a cleaned-up file name
+fn resolve_title(title: Option<String>, name: &str, lexicon: &Lexicon) -> (String, TitleSource) {
+    match title {
+        Some(title) => (title, TitleSource::Content),
+        None => (functions::title_from_filename(name, &lexicon.stopwords), TitleSource::Filename),
+    }
+}
+
+// The title `config` sets without looking at the document
+fn configured_title(config: &ExtractorConfig, name: &str) -> (String, TitleSource) {
+    match &config.title {
+        TitleMode::Fixed(title) => (title.clone(), TitleSource::Override),
+        _ => resolve_title(None, name, &config.lexicon),
+    }
+}
+
+// Something the consumer should know about how reliable the outline is
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Warning {
+    pub code: String,
+    pub message: String,
+}
+
+impl Warning {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Warning { code: code.to_string(), message: message.into() }
+    }
+}
+
+/// The input is a PDF with nothing in it to outline, as opposed to one that
+/// couldn't be read. Extraction returns it inside the `anyhow::Error`; find
+/// it with `downcast_ref` (or in `chain()`, once context has been added).
+///
+/// A PDF whose pages hold no text isn't an error: its outline is empty, with
+/// a `no_text` warning.
+///
+/// ```
+/// # use adobe1a::{config::ExtractorConfig, extract_outline_from_bytes, timing::Stats, NoTextContent};
+/// let error = extract_outline_from_bytes(include_bytes!("../fixtures/zero-pages.pdf"), "empty", &ExtractorConfig::default(), &mut Stats::new()).unwrap_err();
+/// assert_eq!(error.downcast_ref(), Some(&NoTextContent::NoPages));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoTextContent {
+    // The file is a PDF header, perhaps with comments, and nothing else
+    HeaderOnly,
+    // The page tree has no pages
+    NoPages,
+}
+
+impl std::fmt::Display for NoTextContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            NoTextContent::HeaderOnly => "the file has a PDF header but nothing after it",
+            NoTextContent::NoPages => "the PDF has no pages",
+        })
+    }
+}
+
+impl std::error::Error for NoTextContent {}
+
+/// The input isn't a PDF at all: there's no `%PDF-` header in its first
+/// 1024 bytes, where readers allow it to follow some junk. Usually it's
+/// another kind of document renamed to `.pdf`; the variant says which, when
+/// its signature is recognized. Like [`NoTextContent`], it's returned inside
+/// the `anyhow::Error`, before either engine runs.
+///
+/// ```
+/// # use adobe1a::{config::ExtractorConfig, extract_outline_from_bytes, timing::Stats, NotAPdf};
+/// let error = extract_outline_from_bytes(b"<!DOCTYPE html><html></html>", "page", &ExtractorConfig::default(), &mut Stats::new()).unwrap_err();
+/// assert_eq!(error.downcast_ref(), Some(&NotAPdf::Html));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotAPdf {
+    // PK\x03\x04: DOCX, XLSX, PPTX, ODT and EPUB files are all ZIPs
+    Zip,
+    // The compound file format of DOC, XLS and PPT files
+    Ole,
+    Html,
+    // Nothing recognizable, such as plain text
+    Unknown,
+}
+
+impl NotAPdf {
+    fn detect(bytes: &[u8]) -> NotAPdf {
+        let start = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+        let start = &start[start.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(start.len())..];
+        let start = String::from_utf8_lossy(&start[..start.len().min(16)]).to_ascii_lowercase();
+        if bytes.starts_with(b"PK\x03\x04") {
+            NotAPdf::Zip
+        } else if bytes.starts_with(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]) {
+            NotAPdf::Ole
+        } else if start.starts_with("<!doctype html") || start.starts_with("<html") {
+            NotAPdf::Html
+        } else {
+            NotAPdf::Unknown
+        }
+    }
+}
+
+impl std::fmt::Display for NotAPdf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            NotAPdf::Zip => "not a PDF: the file is a ZIP archive, such as a DOCX, XLSX or EPUB file",
+            NotAPdf::Ole => "not a PDF: the file is an older Office document (DOC, XLS or PPT)",
+            NotAPdf::Html => "not a PDF: the file is an HTML page",
+            NotAPdf::Unknown => "not a PDF: there's no %PDF- header in its first 1024 bytes",
+        })
+    }
+}
+
+impl std::error::Error for NotAPdf {}
+
+// `bytes` from the PDF header on, or `NotAPdf` when there's no header where
+// readers look for one. Neither parser skips junk before the header itself.
+fn pdf_from_header(bytes: &[u8]) -> std::result::Result<&[u8], NotAPdf> {
+    match bytes.windows(5).take(1024).position(|window| window == b"%PDF-") {
+        Some(start) => Ok(&bytes[start..]),
+        None => Err(NotAPdf::detect(bytes)),
+    }
+}
+
+// Fails with `NoTextContent` for a header-only file (which doesn't parse) or
+// a PDF without pages
+fn check_content(bytes: &[u8], doc: &lopdf::Result<Document>) -> Result<()> {
+    match doc {
+        Ok(doc) if doc.get_pages().is_empty() => Err(NoTextContent::NoPages.into()),
+        Ok(_) => Ok(()),
+        Err(_) => {
+            let header_only = String::from_utf8_lossy(bytes).lines()
+                .all(|line| line.trim().is_empty() || line.trim_start().starts_with('%'));
+            match header_only {
+                true => Err(NoTextContent::HeaderOnly.into()),
+                false => Ok(()),
+            }
+        }
+    }
+}
+
+// Whether any page `config` reads draws text at all, visible or not. Only
+// asked when no headings were found, so its pages are decoded again, one
+// at a time, up to the first with text.
+fn has_text(doc: &Document, config: &ExtractorConfig) -> bool {
+    doc.get_pages().into_iter()
+        .filter(|&(page, _)| config.includes_page(page as usize))
+        .any(|(page, page_id)| font_utils::page_runs(doc, page_id, page as usize).iter().any(|run| !run.text.trim().is_empty()))
+}
+
+/// [`extract_outline_from_bytes`] on the file at `pdf_path`
+///
+/// ```
+/// use adobe1a::{config::ExtractorConfig, extract_outline, timing::Stats};
+///
+/// let outline = extract_outline("fixtures/agenda.pdf".as_ref(), &ExtractorConfig::default(), &mut Stats::new())?;
+/// assert!(!outline.outline.is_empty());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn extract_outline(pdf_path: &Path, config: &ExtractorConfig, stats: &mut Stats) -> Result<Outline> {
+    // Read the file once and hand the same bytes to both engines
+    let bytes = stats.time(Stage::FileRead, || read_input(pdf_path))?;
+    let name = pdf_path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Untitled");
+
+    extract_outline_from_bytes(&bytes, name, config, stats)
+}
+
+#[cfg(not(feature = "mmap"))]
+pub fn read_input(path: &Path) -> Result<Vec<u8>> {
+    Ok(std::fs::read(path)?)
+}
+
+#[cfg(feature = "mmap")]
+pub fn read_input(path: &Path) -> Result<memmap2::Mmap> {
+    let file = std::fs::File::open(path)?;
+    // SAFETY: the map is sound only while nothing else modifies the file
+    // until the map is dropped at the end of this extraction. Reading a page
+    // of a mapping whose file was truncated raises SIGBUS, and a file
+    // rewritten in place changes bytes already parsed. Nothing here can rule
+    // that out, so the `mmap` feature is only for inputs no other process
+    // writes while they are being read.
+    Ok(unsafe { memmap2::Mmap::map(&file)? })
+}
+
+/// Extracts the outline of the PDF in `bytes`. `name` is used as the title
+/// when none can be detected in the document.
+///
+/// ```
+/// use adobe1a::{config::ExtractorConfig, extract_outline_from_bytes, timing::Stats};
+///
+/// let bytes = std::fs::read("fixtures/agenda.pdf")?;
+/// let outline = extract_outline_from_bytes(&bytes, "agenda", &ExtractorConfig::default(), &mut Stats::new())?;
+/// assert!(outline.outline.iter().any(|heading| heading.text == "3. Any Other Business"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn extract_outline_from_bytes(bytes: &[u8], name: &str, config: &ExtractorConfig, stats: &mut Stats) -> Result<Outline> {
+    let bytes = pdf_from_header(bytes)?;
+    // A named attachment is read instead, the PDF holding it only searched
+    if let Some(pattern) = &config.attachment {
+        let attachment = stats.time(Stage::LopdfLoad, || portfolio::find_attachment(bytes, pattern))?;
+        let name = attachment.name.rsplit_once('.').map_or(attachment.name.as_str(), |(stem, _)| stem);
+        let config = ExtractorConfig { attachment: None, ..config.clone() };
+        let mut outline = extract_outline_from_bytes(&attachment.bytes, name, &config, stats)?;
+        outline.metadata.attachment = Some(sanitize::sanitize_text(&attachment.name));
+        outline.warnings.extend(attachment.warning);
+        return Ok(outline);
+    }
+    // Parsed once up front: the page count and every step after the engines
+    // come from it, and the font engine reuses the document as its fallback
+    let mut doc = stats.time(Stage::LopdfLoad, || Document::load_mem(bytes));
+    let tree_warning = doc.as_mut().ok().and_then(trees::repair_page_tree);
+    // pdf-extract parses the file again on its own, so it gets the repaired
+    // page tree written back out
+    let repaired;
+    let engine_bytes = match (&tree_warning, &mut doc) {
+        (Some(_), Ok(doc)) => {
+            let mut out = Vec::new();
+            doc.save_to(&mut out)?;
+            repaired = out;
+            &repaired[..]
+        }
+        _ => bytes,
+    };
+    check_content(bytes, &doc)?;
+    let page_count = doc.as_ref().ok().map(|d| d.get_pages().len());
+    let range_warnings = check_page_ranges(config, page_count)?;
+
+    // Heading text is matched in the PDF's own language when none is given
+    let with_language;
+    let config = match (&config.language, doc.as_ref().ok().and_then(document_language)) {
+        (None, Some(language)) => {
+            with_language = ExtractorConfig { language: Some(language), ..config.clone() };
+            &with_language
+        }
+        _ => config,
+    };
+
+    let budget = MemoryBudget::new(config.max_memory);
+    let (outline, from_bookmarks) = read_headings(engine_bytes, &doc, name, config, stats, &budget)?;
+
+    // A document that needs more memory than it's allowed is read again in
+    // quick mode, its first pages only, and without the text under its
+    // headings. That reading isn't limited: it's what is left to try.
+    let degraded;
+    let (config, mut outline, from_bookmarks) = match budget.limit().filter(|_| budget.exceeded()) {
+        Some(limit) => {
+            let window = config.quick.map_or(MEMORY_QUICK_PAGES, |window| window.min(MEMORY_QUICK_PAGES));
+            degraded = ExtractorConfig { quick: Some(window), snippet_chars: None, offsets: false, ..config.clone() };
+            let unlimited = MemoryBudget::new(None);
+            let (mut outline, from_bookmarks) = read_headings(engine_bytes, &doc, name, &degraded, stats, &unlimited)?;
+            stats.record_memory(unlimited.peak());
+            outline.warnings.push(Warning::new("memory_limit", format!(
+                "reading the document held more than {} at once, its memory limit; only its first {} pages were read, in quick mode, without snippets or offsets",
+                memory::describe(limit), window)));
+            (&degraded, outline, from_bookmarks)
+        }
+        None => (config, outline, from_bookmarks),
+    };
+    outline.page_count = page_count;
+    outline.warnings.extend(range_warnings);
+    outline.warnings.extend(tree_warning);
+    if let (Ok(doc), true) = (&doc, outline.outline.is_empty()) {
+        if !has_text(doc, config) {
+            let pages = match config.pages.is_some() || config.quick.is_some() {
+                true => "the pages read",
+                false => "the PDF's pages",
+            };
+            outline.warnings.push(Warning::new("no_text", format!("{} hold no text; a scan needs OCR first", pages)));
+        }
+    }
+    outline.partial = !from_bookmarks && config.quick.is_some_and(|window| page_count.is_none_or(|count| count > window));
+
+    if let (Ok(doc), true) = (&doc, config.detects_title() && (config.quick.is_some() || !config.includes_page(1))) {
+        if let Some(title) = plausible_metadata_title(doc) {
+            (outline.title, outline.title_source) = (title, TitleSource::Metadata);
+        }
+    }
+
+    if let Ok(doc) = &doc {
+        let (metadata, warning) = xmp::document_metadata(doc);
+        outline.metadata = metadata;
+        outline.warnings.extend(warning);
+    }
+
+    drop_empty_headings(&mut outline, config);
+    if let (Ok(doc), false) = (&doc, from_bookmarks) {
+        let warning = cover::drop_cover_headings(&mut outline, doc, config);
+        outline.warnings.extend(warning);
+    }
+
+    // Bookmarks that are the outline already found every heading they point at
+    if let (Ok(doc), false) = (&doc, from_bookmarks) {
+        stats.time(Stage::RunExtraction, || {
+            let targets = links::link_targets(doc);
+            links::mark_referenced(&mut outline.outline, &targets, doc, config.link_bonus);
+        });
+    }
+
+    if let (Ok(doc), true) = (&doc, config.snippet_chars.is_some() || config.offsets) {
+        let pages = stats.time(Stage::TextExtraction, || page_texts(engine_bytes, doc, config, &budget));
+        if let Some(limit) = budget.limit().filter(|_| budget.exceeded()) {
+            outline.warnings.push(Warning::new("memory_limit", format!(
+                "the text under the headings took more than {}, the document's memory limit; snippets and offsets were left out",
+                memory::describe(limit))));
+        } else {
+            if let Some(max_chars) = config.snippet_chars {
+                sections::attach_snippets(&mut outline.outline, &pages, max_chars);
+            }
+            if config.offsets {
+                sections::attach_offsets(&mut outline.outline, &pages, doc);
+            }
+        }
+    }
+    if let (Ok(doc), true) = (&doc, config.positions) {
+        stats.time(Stage::RunExtraction, || sections::attach_positions(&mut outline.outline, doc));
+    }
+    stats.record_memory(budget.peak());
+
+    // The engines leave their heuristic scores behind; extend or drop them
+    match &doc {
+        Ok(doc) if config.title_candidates => {
+            let heuristic = std::mem::take(&mut outline.title_candidates);
+            outline.title_candidates = collect_title_candidates(doc, heuristic, config);
+        }
+        _ => outline.title_candidates.clear(),
+    }
+
+    if let (Ok(doc), true) = (&doc, config.split_documents) {
+        let mut documents = stats.time(Stage::RunExtraction, || documents::detect(doc, config));
+        documents.retain(|document| (document.first_page..=document.last_page).any(|page| config.includes_page(page)));
+        if !documents.is_empty() {
+            documents::group(&mut outline, documents);
+        }
+    }
+
+    let mut label_walk = trees::TreeWalk::default();
+    if let Some(labels) = doc.as_ref().ok().and_then(|doc| pages::page_labels_walk(doc, &mut label_walk)) {
+        for heading in &mut outline.outline {
+            heading.page_label = labels.get(heading.page.saturating_sub(1)).cloned();
+        }
+    }
+    outline.warnings.extend(label_walk.warning("page label tree"));
+
+    // Attached PDFs come after the cover sheet, keeping their own page labels
+    if let (Ok(doc), Portfolio::Combine) = (&doc, config.portfolio) {
+        let (parts, warnings) = portfolio::parts_of(doc, config, stats);
+        outline.warnings.extend(warnings);
+        portfolio::combine(&mut outline, parts);
+    }
+
+    // The bookmarks are the table of contents the outline is checked
+    // against, unless they are the outline
+    let toc = match (&doc, from_bookmarks) {
+        (Ok(doc), false) => bookmark_headings(doc, config).0,
+        _ => Vec::new(),
+    };
+    finish_outline(&mut outline, name, config, bytes, &toc);
+    Ok(outline)
+}
+
+// The headings `config` finds, and whether they are the PDF's bookmarks:
+// quick mode takes the bookmarks as they are when the PDF has them, without
+// running either engine
+fn read_headings(
+    bytes: &[u8],
+    doc: &lopdf::Result<Document>,
+    name: &str,
+    config: &ExtractorConfig,
+    stats: &mut Stats,
+    budget: &MemoryBudget,
+) -> Result<(Outline, bool)> {
+    let bookmarked = match (doc, config.quick) {
+        (Ok(doc), Some(_)) => stats.time(Stage::LineHeuristics, || bookmark_outline(doc, name, config)),
+        _ => None,
+    };
+    match bookmarked {
+        Some(outline) => Ok((outline, true)),
+        None => Ok((run_engine(config.engine, bytes, doc, name, config, stats, budget)?, false)),
+    }
+}
+
+// Cleaning can leave a candidate like "...... 12" with nothing in it, and so
+// can taking out control characters
+fn drop_empty_headings(outline: &mut Outline, config: &ExtractorConfig) {
+    let filter = CandidateFilter::new(config);
+    outline.outline.retain_mut(|heading| {
+        heading.text = sanitize::sanitize_text(&heading.text);
+        match filter.text_rejection(&heading.text) {
+            Some(reason) => {
+                log::debug!("dropping heading {:?} on page {}: {}", heading.text, heading.page, reason);
+                false
+            }
+            None => true,
+        }
+    });
+}
+
+// The last touches on every outline: titled as configured, sanitized,
+// stripped of what the configuration leaves out, identified by the input's
+// `bytes` and scored, against the document's table of contents `toc` when
+// it has one
+fn finish_outline(outline: &mut Outline, name: &str, config: &ExtractorConfig, bytes: &[u8], toc: &[Heading]) {
+    if !config.detects_title() {
+        (outline.title, outline.title_source) = configured_title(config, name);
+        outline.title_candidates.clear();
+    }
+    sanitize::sanitize_outline(outline, name, &config.lexicon);
+    outline.outline.retain(|heading| heading.confidence >= config.min_confidence);
+    let collapsed = collapse::collapse_singletons(&mut outline.outline, &config.collapse_singletons);
+    outline.warnings.extend(collapsed);
+    if config.heading_case != CaseStyle::Preserve {
+        let stopwords = &config.lexicon.stopwords;
+        for heading in &mut outline.outline {
+            heading.text = sanitize::normalize_case(&heading.text, config.heading_case, stopwords);
+        }
+        if config.normalize_title {
+            outline.title = sanitize::normalize_case(&outline.title, config.heading_case, stopwords);
+        }
+    }
+    // A given title is kept exactly as given
+    if let TitleMode::Fixed(title) = &config.title {
+        outline.title = title.clone();
+    }
+    for heading in &mut outline.outline {
+        if !config.include_sources {
+            heading.sources.clear();
+        }
+        if !config.include_raw_scores {
+            heading.raw_score = None;
+            heading.detected_level = None;
+        }
+    }
+    outline.document_id = ids::document_id(bytes);
+    outline.extractor_version = EXTRACTOR_VERSION.to_string();
+    if outline.language.is_none() {
+        outline.language = config.language.clone();
+    }
+    ids::assign_heading_ids(&mut outline.outline);
+    if config.synthesize_numbers {
+        numbers::synthesize_numbers(&mut outline.outline);
+    }
+    let quality = quality::assess(outline, toc);
+    outline.quality_score = Some(quality.score());
+    outline.quality_breakdown = Some(quality);
+}
+
+// Warns about requested pages past the end of the document, and fails when
+// none of the requested pages exist
+fn check_page_ranges(config: &ExtractorConfig, page_count: Option<usize>) -> Result<Vec<Warning>> {
+    let (Some(pages), Some(page_count)) = (&config.pages, page_count) else {
+        return Ok(Vec::new());
+    };
+    if !(1..=page_count).any(|page| pages.contains(page)) {
+        anyhow::bail!("none of the requested pages exist in this {}-page document", page_count);
+    }
+    Ok(pages.ranges().iter()
+        .filter(|range| *range.end() > page_count)
+        .map(|range| Warning::new("pages_out_of_range", format!(
+            "{} requested but the document ends at page {}", match range.start() == range.end() {
+                true => format!("page {} was", range.start()),
+                false => format!("pages {}-{} were", range.start(), range.end()),
+            }, page_count)))
+        .collect())
+}
+
+// The language the catalog's `/Lang` gives for the whole document, e.g. "tr-TR"
+pub(crate) fn document_language(doc: &Document) -> Option<String> {
+    let lang = doc.catalog().ok()?.get(b"Lang").ok()?.as_str().ok()?;
+    Some(sanitize::sanitize_text(&String::from_utf8_lossy(lang))).filter(|lang| !lang.is_empty())
+}
+
+// The document info title, unless it's a placeholder or a generated name
+// ("Microsoft Word - report.docx"). Used when the first page isn't read, and
+// first in quick mode.
+fn plausible_metadata_title(doc: &Document) -> Option<String> {
+    pages::metadata_title(doc)
+        .filter(|(_, score)| *score >= titles::PLAUSIBLE)
+        .map(|(title, _)| title)
+}
+
+// The bookmarks as the outline, one level per nesting depth, or None when
+// there are none. The first page is only scored for a title when the
+// metadata has no plausible one.
+fn bookmark_outline(doc: &Document, name: &str, config: &ExtractorConfig) -> Option<Outline> {
+    let (headings, warnings) = bookmark_headings(doc, config);
+    if headings.is_empty() {
+        return None;
+    }
+    log::debug!("{}: using {} bookmarks as the outline", name, headings.len());
+
+    let title_scores = match (config.detects_title(), doc.get_pages().keys().next()) {
+        (true, Some(&first_page)) if plausible_metadata_title(doc).is_none() => page_title_scores(doc, first_page, config),
+        _ => Vec::new(),
+    };
+    let (title, title_source) = resolve_title(title_scores.first().map(|(t, _)| t.clone()), name, &config.lexicon);
+    Some(Outline {
+        title,
+        title_source,
+        title_candidates: heuristic_title_candidates(&title_scores),
+        outline: headings,
+        warnings,
+        engine: vec!["bookmarks".to_string()],
+        ..Outline::default()
+    })
+}
+
+// The bookmarks on the pages asked for as headings, one level per nesting
+// depth
+fn bookmark_headings(doc: &Document, config: &ExtractorConfig) -> (Vec<Heading>, Vec<Warning>) {
+    let (bookmarks, warnings) = bookmarks::bookmarks(doc);
+    let headings = bookmarks.into_iter()
+        .filter(|bookmark| config.pages.as_ref().is_none_or(|pages| pages.contains(bookmark.page)))
+        .map(|bookmark| Heading {
+            level: format!("H{}", bookmark.depth.min(MAX_HEADING_DEPTH as usize)),
+            text: bookmark.title,
+            page: bookmark.page,
+            confidence: 1.0,
+            sources: vec!["bookmarks".to_string()],
+            y_fraction: bookmark.top.filter(|_| config.positions),
+            ..Default::default()
+        })
+        .collect();
+    (headings, warnings)
+}
+
+// An engine left out of the build takes its inputs but never reads them
+#[allow(clippy::only_used_in_recursion)]
+fn run_engine(
+    engine: Engine,
+    bytes: &[u8],
+    doc: &lopdf::Result<Document>,
+    name: &str,
+    config: &ExtractorConfig,
+    stats: &mut Stats,
+    budget: &MemoryBudget,
+) -> Result<Outline> {
+    match engine {
+        Engine::Auto => {
+            // Only the font engine tells invisible text apart
+            if !Engine::Text.is_built() || (config.hidden_text != HiddenText::Auto && Engine::Font.is_built()) {
+                return run_engine(Engine::Font, bytes, doc, name, config, stats, budget);
+            }
+            match run_engine(Engine::Text, bytes, doc, name, config, stats, budget) {
+                Ok(outline) if !outline.outline.is_empty() => Ok(outline),
+                text if !Engine::Font.is_built() => text,
+                _ => run_engine(Engine::Font, bytes, doc, name, config, stats, budget),
+            }
+        }
+        #[cfg(feature = "engine-text")]
+        Engine::Text if config.hidden_text != HiddenText::Auto => {
+            anyhow::bail!("the text engine can't tell invisible text apart; --include-hidden-text and --exclude-hidden-text need the font engine")
+        }
+        #[cfg(feature = "engine-text")]
+        Engine::Text => try_pdf_extract(bytes, doc, name, config, stats, budget)
+            .map(|outline| attributed(outline, "text", config)),
+        #[cfg(feature = "engine-font")]
+        Engine::Font => extract_with_lopdf(doc.as_ref().map_err(|e| anyhow::anyhow!("{}", e))?, name, config, stats, budget)
+            .map(|outline| attributed(outline, "font", config)),
+        #[allow(unreachable_patterns)]
+        engine => anyhow::bail!("this build doesn't include the {:?} engine", engine),
+    }
+}
+
+// `outline` with `engine` recorded on it and every heading, its scores
+// calibrated and its levels overridden. Headings another engine filled in
+// keep their own.
+fn attributed(mut outline: Outline, engine: &str, config: &ExtractorConfig) -> Outline {
+    if !outline.engine.iter().any(|known| known == engine) {
+        outline.engine.insert(0, engine.to_string());
+    }
+    for heading in outline.outline.iter_mut().filter(|heading| heading.sources.is_empty()) {
+        calibrate(heading, engine, config);
+        config.level_overrides.apply(heading);
+    }
+    outline
+}
+
+fn calibrate(heading: &mut Heading, engine: &str, config: &ExtractorConfig) {
+    heading.sources = vec![engine.to_string()];
+    heading.raw_score = Some(heading.confidence);
+    if let Some(curve) = config.calibration.curve(engine) {
+        heading.confidence = curve.apply(heading.confidence);
+    }
+}
+
+#[cfg(feature = "engine-text")]
+fn try_pdf_extract(
+    bytes: &[u8],
+    doc: &lopdf::Result<Document>,
+    name: &str,
+    config: &ExtractorConfig,
+    stats: &mut Stats,
+    budget: &MemoryBudget,
+) -> Result<Outline> {
+    let pages = stats.time(Stage::TextExtraction, || extracted_pages(bytes, config, budget))?;
+    let held: usize = pages.iter().map(|page| page.text.len()).sum();
+    let outline = outline_from_extracted(&pages, doc, name, config, stats, budget);
+    budget.release(held);
+    outline
+}
+
+// `budget` only goes to the font engine, which fills in pages
+#[cfg(feature = "engine-text")]
+#[cfg_attr(not(feature = "engine-font"), allow(unused_variables))]
+fn outline_from_extracted(
+    pages: &[PageText],
+    doc: &lopdf::Result<Document>,
+    name: &str,
+    config: &ExtractorConfig,
+    stats: &mut Stats,
+    budget: &MemoryBudget,
+) -> Result<Outline> {
+    // A page pdf-extract can't read comes back empty, most often for a font
+    // encoding it doesn't support, and is named in a `pages_missing_text`
+    // warning. In auto mode the font engine reads those pages instead.
+    let missing = match doc {
+        Ok(doc) => pages_missing_text(doc, pages, config),
+        Err(_) => Vec::new(),
+    };
+    let fills_missing = cfg!(feature = "engine-font") && config.engine == Engine::Auto;
+    let missing_warning = (!missing.is_empty()).then(|| {
+        let pages: config::PageRanges = missing.iter().copied().collect();
+        Warning::new("pages_missing_text", format!(
+            "pdf-extract returned no text for page{} {}, although lopdf reads text there; {}",
+            if missing.len() == 1 { "" } else { "s" },
+            pages,
+            if fills_missing { "headings there come from the font engine" } else { "headings there are missing" }))
+    });
+
+    // Whether the document really has no text is checked once both engines
+    // have had their turn
+    if pages.iter().all(|page| page.text.trim().is_empty()) {
+        let (title, title_source) = resolve_title(None, name, &config.lexicon);
+        return Ok(Outline { title, title_source, warnings: missing_warning.into_iter().collect(), ..Outline::default() });
+    }
+
+    let page_count = doc.as_ref().map_or(pages.len(), |doc| doc.get_pages().len());
+    let mut outline = text::outline_from_pages(pages, page_count, name, config, stats);
+    #[cfg(feature = "engine-font")]
+    if let (Ok(doc), true, false) = (doc, fills_missing, missing.is_empty()) {
+        fill_missing_pages(&mut outline, doc, &missing, name, config, stats, budget)?;
+    }
+    outline.warnings.extend(missing_warning);
+    Ok(outline)
+}
+
+// pdf-extract's text of every page up to the last one `config` reads, with
+// the pages it skips left empty. pdf-extract's text of a whole document has
+// no page breaks, so each page is decoded on its own, and the pages come
+// from pdf-extract's own parse of the file: they're exact even when lopdf
+// here can't read it. A page pdf-extract fails on is left empty too, rather
+// than failing the document, and so is every page after `budget` is
+// exceeded. The text is charged to `budget` as held.
+#[cfg(feature = "engine-text")]
+fn extracted_pages(bytes: &[u8], config: &ExtractorConfig, budget: &MemoryBudget) -> Result<Vec<PageText>> {
+    let mut doc = pdf_extract::Document::load_mem(bytes)?;
+    if doc.is_encrypted() {
+        doc.decrypt("")?;
+    }
+    let page_count = doc.get_pages().len();
+    let last = (1..=page_count).rev().find(|&page| config.includes_page(page)).unwrap_or(0);
+    Ok((1..=last)
+        .map(|page| {
+            let mut text = String::new();
+            if config.includes_page(page) && !budget.exceeded() {
+                let mut output = pdf_extract::PlainTextOutput::new(&mut text);
+                if let Err(e) = pdf_extract::output_doc_page(&doc, &mut output, page as u32) {
+                    log::debug!("pdf-extract couldn't read page {}: {}", page, e);
+                    text.clear();
+                }
+                budget.charge(text.len());
+            }
+            PageText { page, text }
+        })
+        .collect())
+}
+
+#[cfg(not(feature = "engine-text"))]
+fn extracted_pages(_bytes: &[u8], _config: &ExtractorConfig, _budget: &MemoryBudget) -> Result<Vec<PageText>> {
+    Ok(Vec::new())
+}
+
+// The text of each page up to the last one `config` reads, for finding the
+// text under each heading: pdf-extract's when it's built and reads the page,
+// otherwise rebuilt from the page's runs. Pages past the point `budget` is
+// exceeded are left empty.
+fn page_texts(bytes: &[u8], doc: &Document, config: &ExtractorConfig, budget: &MemoryBudget) -> Vec<PageText> {
+    let pages = doc.get_pages();
+    let extracted = extracted_pages(bytes, config, budget).unwrap_or_default();
+    let last = pages.keys().rev().map(|&page| page as usize).find(|&page| config.includes_page(page)).unwrap_or(0);
+    pages.into_iter()
+        .map(|(page, page_id)| (page as usize, page_id))
+        .take_while(|&(page, _)| page <= last)
+        .map(|(page, page_id)| match extracted.get(page - 1).filter(|extracted| !extracted.text.trim().is_empty()) {
+            Some(extracted) => extracted.clone(),
+            None if config.includes_page(page) && !budget.exceeded() => {
+                let text = sections::page_text(&font_utils::page_runs(doc, page_id, page));
+                budget.charge(text.len());
+                PageText { page, text }
+            }
+            None => PageText { page, text: String::new() },
+        })
+        .collect()
+}
+
+// The pages `config` reads whose text pdf-extract returned nothing for,
+// although lopdf finds text on them
+#[cfg(feature = "engine-text")]
+fn pages_missing_text(doc: &Document, pages: &[PageText], config: &ExtractorConfig) -> Vec<usize> {
+    doc.get_pages().into_iter()
+        .map(|(page, page_id)| (page as usize, page_id))
+        .filter(|&(page, _)| config.includes_page(page) && pages.get(page - 1).is_none_or(|extracted| extracted.text.trim().is_empty()))
+        .filter(|&(page, page_id)| font_utils::page_runs(doc, page_id, page).iter().any(|run| !run.text.trim().is_empty()))
+        .map(|(page, _)| page)
+        .collect()
+}
+
+// The font engine's headings for `missing`, in page order among the text
+// engine's
+#[cfg(all(feature = "engine-text", feature = "engine-font"))]
+fn fill_missing_pages(outline: &mut Outline, doc: &Document, missing: &[usize], name: &str, config: &ExtractorConfig, stats: &mut Stats, budget: &MemoryBudget) -> Result<()> {
+    let config = ExtractorConfig {
+        pages: Some(missing.iter().copied().collect()),
+        title: TitleMode::Filename,
+        ..config.clone()
+    };
+    let filled = attributed(extract_with_lopdf(doc, name, &config, stats, budget)?, "font", &config);
+    outline.outline.extend(filled.outline);
+    outline.outline.sort_by_key(|heading| heading.page);
+    outline.warnings.extend(filled.warnings);
+    outline.engine = filled.engine;
+    Ok(())
+}
+
+#[cfg(feature = "engine-font")]
+fn extract_with_lopdf(doc: &Document, name: &str, config: &ExtractorConfig, stats: &mut Stats, budget: &MemoryBudget) -> Result<Outline> {
+    // Use the new font-based approach
+    let read = font_utils::extract_heading_candidates(doc, config, stats, budget);
+    let (heading_candidates, page_chars) = (read.candidates, read.page_chars);
+    let filter = CandidateFilter::new(config);
+    
+    // Convert font-based candidates to our Heading format and filter,
+    // keeping each candidate's document-order index for tie-breaking
+    let (seqs, headings): (Vec<usize>, Vec<Heading>) = heading_candidates.into_iter()
+        .filter_map(|candidate| {
+            let seq = candidate.seq;
+            candidate_heading(candidate, &filter, &config.lexicon).map(|heading| (seq, heading))
+        })
+        .unzip();
+    let (drops, mut warnings) = functions::crowded_page_drops(&headings, &page_chars, config);
+    warnings.extend(read.unmapped_fonts.into_iter().map(|(font, pages)| {
+        let noun = if pages.len() == 1 { "page" } else { "pages" };
+        let pages: config::PageRanges = pages.into_iter().collect();
+        Warning::new("unmapped_font", format!(
+            "text in the symbolic font {} was skipped on {} {}, as nothing maps its codes to characters",
+            font, noun, pages))
+    }));
+    let mut headings: Vec<(usize, Heading)> = seqs.into_iter()
+        .zip(headings)
+        .enumerate()
+        .filter(|(i, _)| !drops.contains(i))
+        .map(|(_, pair)| pair)
+        .collect();
+
+    // Sort by confidence and take top candidates to avoid noise; equal
+    // confidences fall back to document order so output is reproducible
+    headings.sort_by(|(a_seq, a), (b_seq, b)| {
+        b.confidence.total_cmp(&a.confidence)
+            .then(a.page.cmp(&b.page))
+            .then(a_seq.cmp(b_seq))
+    });
+    
+    // Keep only the most confident headings to avoid overwhelming output,
+    // and say so when that drops any
+    let pages_read = (1..=doc.get_pages().len()).filter(|&page| config.includes_page(page)).count();
+    let cap = config.heading_cap(pages_read);
+    if headings.len() > cap {
+        let cutoff = headings[cap - 1].1.confidence;
+        warnings.push(Warning::new("headings_capped", format!(
+            "{} of {} heading candidates were dropped by the cap of {}; the lowest confidence kept is {:.2}",
+            headings.len() - cap, headings.len(), cap, cutoff)));
+        headings.truncate(cap);
+    }
+    
+    // Sort back by document order
+    headings.sort_by_key(|(seq, _)| *seq);
+    let headings: Vec<Heading> = headings.into_iter().map(|(_, h)| h).collect();
+
+    // Extract title from the first page only, when it's read at all
+    let title_scores = match config.includes_page(1) && config.detects_title() {
+        true => stats.time(Stage::LineHeuristics, || first_page_title_scores(doc, config)),
+        false => Vec::new(),
+    };
+
+    let (title, title_source) = resolve_title(title_scores.first().map(|(t, _)| t.clone()), name, &config.lexicon);
+    Ok(Outline {
+        title,
+        title_source,
+        title_candidates: heuristic_title_candidates(&title_scores),
+        outline: stats.time(Stage::Hierarchy, || functions::establish_hierarchy(headings, config.language.as_deref())),
+        warnings,
+        page_count: None,
+        documents: Vec::new(),
+        stats: None,
+        document_id: String::new(),
+        partial: false,
+        metadata: xmp::Metadata::default(),
+        engine: Vec::new(),
+        extractor_version: String::new(),
+        quality_score: None,
+        quality_breakdown: None,
+        language: None,
+    })
+}
+
+// A font-engine candidate as a heading, if it passes the filters
+#[cfg(feature = "engine-font")]
+fn candidate_heading(candidate: font_utils::HeadingCandidate, filter: &CandidateFilter, lexicon: &lexicon::Lexicon) -> Option<Heading> {
+    let accepted = candidate.text.len() > 3 &&
+        candidate.confidence > 0.6 && // Higher confidence threshold
+        filter.accepts_styled(&functions::LineInfo::new(&candidate.text), candidate.stands_out);
+    accepted.then(|| Heading {
+        level: candidate.level,
+        text: functions::clean_heading_text(&candidate.text, lexicon),
+        continued: functions::is_continuation(&candidate.text, lexicon),
+        page: candidate.page,
+        confidence: candidate.confidence,
+        ..Default::default()
+    })
+}
+
+// Title scores for the lines of the first page, as lopdf reads its text
+#[cfg(feature = "engine-font")]
+fn first_page_title_scores(doc: &Document, config: &ExtractorConfig) -> Vec<(String, i32)> {
+    match doc.get_pages().keys().next() {
+        Some(&page_number) => page_title_scores(doc, page_number, config),
+        None => Vec::new(),
+    }
+}
+
+// Title scores for the lines of one page (numbered from 1). Capitals only
+// set a title apart on a page that isn't all capitals.
+fn page_title_scores(doc: &Document, page_number: u32, config: &ExtractorConfig) -> Vec<(String, i32)> {
+    let Ok(text) = doc.extract_text(&[page_number]) else {
+        return Vec::new();
+    };
+    // lopdf marks text in fonts it can't decode instead of failing
+    let lines: Vec<&str> = text.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.contains("Unimplemented?"))
+        .collect();
+    if lines.is_empty() {
+        return Vec::new();
+    }
+    let joined = functions::dehyphenate(&lines, &functions::standalone_words(lines.iter().copied()));
+    let lines: Vec<&str> = joined.iter().map(String::as_str).collect();
+    let all_caps = functions::uppercase_ratio(&text) > config.all_caps_ratio;
+    let mut scores = functions::extract_document_title(&lines, &config.lexicon, all_caps);
+    if config.is_short_document(doc.get_pages().len()) {
+        functions::short_document_titles(&mut scores, &lines, &config.lexicon);
+    }
+    scores
+}
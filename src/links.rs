@@ -0,0 +1,106 @@
+// Headings something else in the PDF points at: an internal link (a /Link
+// annotation with a GoTo destination) or a bookmark. Authors point links
+// and bookmarks at real sections, so a heading one lands on is almost
+// certainly real.
+
+use std::collections::BTreeMap;
+
+use lopdf::{Document, Object};
+
+use crate::bookmarks::{self, Destination, Destinations};
+use crate::font_utils::{self, page_box, y_from_top};
+use crate::sections;
+use crate::trees::TreeWalk;
+use crate::Heading;
+
+// A link landing this far above a heading's line, as a share of the page
+// height, still points at it; viewers are usually sent a little above
+const LINK_REACH: f64 = 0.1;
+// A link landing this far below the top of a heading's line is inside it
+const LINK_TOLERANCE: f64 = 0.02;
+
+// Where the document's bookmarks and internal links lead
+pub fn link_targets(doc: &Document) -> Vec<Destination> {
+    let (bookmarks, _) = bookmarks::bookmarks(doc);
+    let mut targets: Vec<Destination> = bookmarks.into_iter()
+        .map(|bookmark| Destination { page: bookmark.page, top: bookmark.top })
+        .collect();
+
+    let destinations = Destinations::new(doc, &mut TreeWalk::default());
+    for page_id in doc.get_pages().into_values() {
+        let annotations = doc.get_dictionary(page_id).ok()
+            .and_then(|page| page.get(b"Annots").ok())
+            .and_then(|annotations| doc.dereference(annotations).ok())
+            .and_then(|(_, annotations)| annotations.as_array().ok());
+        for annotation in annotations.into_iter().flatten() {
+            let Some(annotation) = doc.dereference(annotation).ok().and_then(|(_, a)| a.as_dict().ok()) else { continue };
+            if annotation.get(b"Subtype").and_then(Object::as_name).is_ok_and(|subtype| subtype == b"Link") {
+                targets.extend(destinations.target(annotation));
+            }
+        }
+    }
+    targets
+}
+
+// Marks each heading a target lands on as `referenced` and adds `bonus` to
+// its confidence, once however many point at it. A target with a position
+// lands on the first heading whose line starts at it or a little below; one
+// that shows the whole page lands on the page's first heading.
+pub fn mark_referenced(headings: &mut [Heading], targets: &[Destination], doc: &Document, bonus: f64) {
+    let mut tops_by_page: BTreeMap<usize, Vec<Option<f64>>> = BTreeMap::new();
+    for target in targets {
+        tops_by_page.entry(target.page).or_default().push(target.top);
+    }
+    let pages = doc.get_pages();
+    for (page, tops) in tops_by_page {
+        let on_page: Vec<usize> = (0..headings.len()).filter(|&i| headings[i].page == page).collect();
+        let Some(&page_id) = pages.get(&(page as u32)) else { continue };
+        if on_page.is_empty() {
+            continue;
+        }
+        // The page's lines are only read when a target needs them
+        let lines = match tops.iter().any(Option::is_some) {
+            true => heading_tops(doc, page_id, page, on_page.iter().map(|&i| headings[i].text.as_str())),
+            false => Vec::new(),
+        };
+        for top in tops {
+            let hit = match top {
+                None => on_page.first().copied(),
+                Some(top) => on_page.iter().zip(&lines)
+                    .filter_map(|(&i, line)| Some((i, (*line)?)))
+                    .filter(|&(_, line)| (top - LINK_TOLERANCE..=top + LINK_REACH).contains(&line))
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(i, _)| i),
+            };
+            if let Some(heading) = hit.map(|i| &mut headings[i]).filter(|heading| !heading.referenced) {
+                heading.referenced = true;
+                heading.confidence = (heading.confidence + bonus).min(1.0);
+            }
+        }
+    }
+}
+
+// The top of each heading's line on the page, as a fraction of the page
+// height from the top, or None where its line can't be found. Lines are
+// rebuilt from the runs as for snippets, hidden text included, since the
+// headings of a scan's OCR layer are invisible.
+fn heading_tops<'a>(doc: &Document, page_id: lopdf::ObjectId, page: usize, texts: impl Iterator<Item = &'a str>) -> Vec<Option<f64>> {
+    let page_box = page_box(doc, page_id);
+    let mut lines: Vec<(String, f64)> = Vec::new();
+    let mut previous: Option<(f64, f64)> = None;
+    for run in font_utils::page_runs(doc, page_id, page).iter().filter(|run| !run.text.trim().is_empty()) {
+        let top = y_from_top(run.y + run.size, &page_box);
+        match (previous, lines.last_mut()) {
+            (Some((y, size)), Some((text, line_top))) if (y - run.y).abs() <= size.min(run.size) * 0.5 => {
+                text.push(' ');
+                text.push_str(run.text.trim());
+                *line_top = line_top.min(top);
+            }
+            _ => lines.push((run.text.trim().to_string(), top)),
+        }
+        previous = Some((run.y, run.size));
+    }
+
+    let line_texts: Vec<&str> = lines.iter().map(|(text, _)| text.as_str()).collect();
+    texts.map(|text| sections::find_heading(&line_texts, 0, line_texts.len(), text).map(|(start, _)| lines[start].1)).collect()
+}
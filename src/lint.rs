@@ -0,0 +1,136 @@
+//! Checks on an outline JSON file written earlier, possibly by an older
+//! version, without the PDF it came from. Parsing goes through the same
+//! serde types as extraction, so the checks follow the schema.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::sanitize::normalize_for_match;
+use crate::{output, Outline};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// One problem found in an outline file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub severity: Severity,
+    // Short name of the check, e.g. "page_order"
+    pub rule: &'static str,
+    // Position of the heading in the outline, from 1; None for the file as a whole
+    pub heading: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: ", self.severity, self.rule)?;
+        if let Some(heading) = self.heading {
+            write!(f, "heading {}: ", heading)?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LintOptions {
+    // How a level skipped on the way down (H1 followed by H3) is reported
+    pub level_jumps: Severity,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        LintOptions { level_jumps: Severity::Warning }
+    }
+}
+
+/// Checks an outline file's contents, in either key style. A file that
+/// doesn't parse gives a single `schema` error.
+///
+/// ```
+/// use adobe1a::lint::{lint, LintOptions, Severity};
+///
+/// let json = br#"{"title": "Plan", "outline": [
+///     {"level": "H1", "text": "Scope", "page": 2, "confidence": 0.9},
+///     {"level": "H2", "text": " ", "page": 1, "confidence": 1.5}
+/// ]}"#;
+/// let findings = lint(json, &LintOptions::default());
+/// let rules: Vec<_> = findings.iter().map(|f| (f.severity, f.rule)).collect();
+/// assert_eq!(rules, [
+///     (Severity::Error, "empty_text"),
+///     (Severity::Warning, "page_order"),
+///     (Severity::Error, "confidence"),
+/// ]);
+/// ```
+pub fn lint(json: &[u8], options: &LintOptions) -> Vec<Finding> {
+    let outline: Outline = match output::from_json(json) {
+        Ok(outline) => outline,
+        Err(e) => return vec![Finding {
+            severity: Severity::Error,
+            rule: "schema",
+            heading: None,
+            message: format!("not a valid outline: {}", e),
+        }],
+    };
+
+    let mut findings = Vec::new();
+    let mut seen = HashSet::new();
+    let mut previous_depth = 0;
+    let mut previous_page = 0;
+    for (i, heading) in outline.outline.iter().enumerate() {
+        let mut report = |severity, rule, message: String| {
+            findings.push(Finding { severity, rule, heading: Some(i + 1), message });
+        };
+        let text = heading.text.trim();
+        if text.is_empty() {
+            report(Severity::Error, "empty_text", "heading text is empty".to_string());
+        }
+
+        match heading.level.strip_prefix('H').and_then(|n| n.parse::<u8>().ok()).filter(|&n| n > 0) {
+            Some(depth) => {
+                if depth > previous_depth + 1 {
+                    report(options.level_jumps, "level_jump", format!(
+                        "{} {:?} follows {}", heading.level, text, match previous_depth {
+                            0 => "the start of the outline".to_string(),
+                            depth => format!("an H{}", depth),
+                        }));
+                }
+                previous_depth = depth;
+            }
+            None => report(Severity::Error, "level", format!("level {:?} isn't H1, H2, ...", heading.level)),
+        }
+
+        if heading.page == 0 {
+            report(Severity::Error, "page_range", "pages are numbered from 1, not 0".to_string());
+        } else if let Some(page_count) = outline.page_count.filter(|&count| heading.page > count) {
+            report(Severity::Error, "page_range", format!(
+                "page {} is past the end of the {}-page document", heading.page, page_count));
+        }
+        if heading.page < previous_page {
+            report(Severity::Warning, "page_order", format!(
+                "page {} comes after a heading on page {}", heading.page, previous_page));
+        }
+        previous_page = heading.page;
+
+        if !text.is_empty() && !seen.insert((normalize_for_match(text, outline.language.as_deref()), heading.page)) {
+            report(Severity::Warning, "duplicate", format!("{:?} appears twice on page {}", text, heading.page));
+        }
+
+        if !(0.0..=1.0).contains(&heading.confidence) {
+            report(Severity::Error, "confidence", format!("confidence {} is outside 0..1", heading.confidence));
+        }
+    }
+    findings
+}
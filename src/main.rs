@@ -1,182 +1,1019 @@
-use lopdf::Document;
-use pdf_extract;
-use serde::{Serialize, Deserialize};
-use std::path::PathBuf;
-use clap::Parser;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use anyhow::{Context, Result};
+use rayon::prelude::*;
 use regex::Regex;
-use once_cell::sync::Lazy;
-
-mod functions;
-mod font_utils;
-
-pub static TITLE_PATTERN: Lazy<Regex> = Lazy::new(|| 
-    Regex::new(r"(?i)^\s*(RFP|Request\s+for\s+Proposal|Proposal|Scope\s+of\s+Work)\s*:?\s*(.*)$").unwrap());
-pub static NUMBERED_HEADING: Lazy<Regex> = Lazy::new(||
-    // Matches headings that begin with multi-level decimals like "1.", "1.2.", etc.,
-    // single decimals with text ("1 Introduction"), roman numerals ("IV. Scope"),
-    // or alpha enumerations such as "A. Background" or "b) Goals".
-    Regex::new(r"^\s*(?:((?:\d+\.)+\d*|\d+)[\.)]?\s+.+|[A-Za-z]{1,2}[\.)]\s+.+|[IVXLCDM]+[\.)]?\s+.+)").unwrap());
-pub static SECTION_HEADING: Lazy<Regex> = Lazy::new(|| 
-    Regex::new(r"^\s*(Chapter|Section|Part)\s+([A-Z0-9]+)").unwrap());
-pub static APPENDIX_HEADING: Lazy<Regex> = Lazy::new(|| 
-    Regex::new(r"^\s*Appendix\s+([A-Z0-9]+)").unwrap());
-pub static COLON_HEADING: Lazy<Regex> = Lazy::new(|| 
-    Regex::new(r"^[A-Z][A-Za-z\s]+:$").unwrap());
-
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
-pub struct Heading {
-    pub level: String,
-    pub text: String,
-    pub page: usize,
-    pub confidence: f64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Outline {
-    pub title: String,
-    pub outline: Vec<Heading>,
-}
+
+use adobe1a::{analyze_text, extract_outline_from_bytes, ids, inputs, output, read_input, Extractor, NoTextContent, NotAPdf, Outline, TextOptions, EXTRACTOR_VERSION};
+use adobe1a::calibration::Calibration;
+use adobe1a::evaluation::{self, Evaluation};
+use adobe1a::levels::LevelOverrides;
+use adobe1a::config::{CaseStyle, Engine, ExtractorConfig, HiddenText, PageRanges, Portfolio, ShortDocument, TitleMode};
+use adobe1a::lexicon::Lexicon;
+use adobe1a::lint::{self, LintOptions, Severity};
+use adobe1a::markdown::analyze_markdown;
+use adobe1a::merge;
+use adobe1a::output::{KeyStyle, OutputFormat, SortOrder};
+use adobe1a::{documents, portfolio};
+use adobe1a::status::StatusFile;
+use adobe1a::summary::{BatchSummary, FileStatus, FileSummary};
+use adobe1a::timing::{Stage, Stats};
+use adobe1a::transform::Transform;
 
 #[derive(Parser)]
-struct Args {
-    #[arg(short, long)]
-    input: PathBuf,
-    #[arg(short, long)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Extract outlines from PDFs (the default when no subcommand is given)
+    Extract(Box<ExtractArgs>),
+    /// Check outline JSON files written earlier and exit non-zero on errors
+    Lint(LintArgs),
+    /// Combine the outlines of several PDFs, read as consecutive volumes, into one
+    Merge(MergeArgs),
+    /// Print a completion script for the given shell to stdout
+    Completions {
+        shell: clap_complete::Shell,
+    },
+}
+
+#[derive(clap::Args)]
+struct MergeArgs {
+    /// The merged outline JSON file to write
     output: PathBuf,
+    /// The volumes in reading order: PDFs, or outline JSON files already extracted from them
+    #[arg(required = true)]
+    volumes: Vec<PathBuf>,
+    /// Extraction engine for PDF volumes
+    #[arg(long, value_enum, default_value_t = Engine::Auto, env = "ADOBE1A_ENGINE")]
+    engine: Engine,
+    /// Print the merged outline as an indented tree to stdout
+    #[arg(long, env = "ADOBE1A_PRINT_TREE")]
+    print_tree: bool,
+    /// Spelling of JSON keys in the output
+    #[arg(long, value_enum, default_value_t = KeyStyle::Snake, env = "ADOBE1A_KEY_STYLE")]
+    key_style: KeyStyle,
+    /// Shape of the output JSON, as for `extract`
+    #[arg(long, value_enum, default_value_t = OutputFormat::Full, env = "ADOBE1A_FORMAT")]
+    format: OutputFormat,
 }
 
-fn main() -> Result<()> {
-    let args = Args::parse();
-    let outline = extract_outline(&args.input)
-        .with_context(|| format!("Failed to process {}", args.input.display()))?;
-    
-    std::fs::write(&args.output, serde_json::to_string_pretty(&outline)?)?;
-    println!("Successfully processed {}", args.input.display());
-    Ok(())
+#[derive(clap::Args)]
+struct LintArgs {
+    /// Outline JSON files, in either key style
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+    /// Report a skipped heading level (H1 followed by H3) as a warning or an error
+    #[arg(long, value_enum, default_value_t = Severity::Warning, env = "ADOBE1A_LEVEL_JUMPS")]
+    level_jumps: Severity,
+}
+
+#[derive(clap::Args)]
+struct ExtractArgs {
+    /// A JSON file of flag values, such as `{"engine": "font", "jobs": 4}`,
+    /// below the command line and environment variables in precedence
+    #[arg(long, env = "ADOBE1A_CONFIG")]
+    #[allow(dead_code)] // Read by `parse_args`, before the other flags are parsed
+    config: Option<PathBuf>,
+    /// A PDF file, or a directory whose PDFs are all processed
+    #[arg(short, long, env = "ADOBE1A_INPUT", required_unless_present = "input_list")]
+    input: Option<PathBuf>,
+    /// A file listing the PDFs to process, one path per line (`#` starts a
+    /// comment line); relative paths are taken from the list's directory
+    #[arg(long, env = "ADOBE1A_INPUT_LIST", conflicts_with = "input")]
+    input_list: Option<PathBuf>,
+    /// Read inputs as PDFs, as plain text from another converter (UTF-8,
+    /// pages split by form feeds or --page-marker lines), or as Markdown; a
+    /// directory then supplies its `.txt` or `.md` files
+    #[arg(long, value_enum, default_value_t = InputFormat::Pdf, env = "ADOBE1A_INPUT_FORMAT")]
+    input_format: InputFormat,
+    /// With --input-format text or markdown, a regex matching the lines that
+    /// start a new page, e.g. `^--- Page \d+ ---$` or `^<!-- pagebreak -->$`
+    #[arg(long, alias = "page-break-marker", env = "ADOBE1A_PAGE_MARKER")]
+    page_marker: Option<Regex>,
+    /// The output JSON file, or a directory to write `<input name>.json` into
+    #[arg(short, long, env = "ADOBE1A_OUTPUT", required_unless_present = "no_output_file")]
+    output: Option<PathBuf>,
+    /// Print the outline as an indented tree to stdout
+    #[arg(long, env = "ADOBE1A_PRINT_TREE")]
+    print_tree: bool,
+    /// Don't write output files (use with --print-tree or --summary)
+    #[arg(long, env = "ADOBE1A_NO_OUTPUT_FILE", conflicts_with = "output")]
+    no_output_file: bool,
+    /// Print per-stage timings to stderr
+    #[arg(long, env = "ADOBE1A_STATS")]
+    stats: bool,
+    /// Embed per-stage timings in the output JSON
+    #[arg(long, env = "ADOBE1A_STATS_JSON")]
+    stats_json: bool,
+    /// Always use invisible text (e.g. OCR layers), even next to visible text
+    #[arg(long, conflicts_with = "exclude_hidden_text", env = "ADOBE1A_INCLUDE_HIDDEN_TEXT")]
+    include_hidden_text: bool,
+    /// Never use invisible text
+    #[arg(long, env = "ADOBE1A_EXCLUDE_HIDDEN_TEXT")]
+    exclude_hidden_text: bool,
+    /// Fraction of the page height at the top and bottom ignored as header/footer (0 disables)
+    #[arg(long, default_value_t = 0.06, env = "ADOBE1A_MARGIN_BAND", value_parser = parse_margin_band)]
+    margin_band: f64,
+    /// Use this title, exactly as given, instead of detecting one
+    #[arg(long, conflicts_with = "no_title", env = "ADOBE1A_TITLE")]
+    title: Option<String>,
+    /// Title the outline after the file name without looking for a title
+    #[arg(long, env = "ADOBE1A_NO_TITLE")]
+    no_title: bool,
+    /// List up to five scored title candidates in the output
+    #[arg(long, env = "ADOBE1A_TITLE_CANDIDATES")]
+    title_candidates: bool,
+    /// Give each heading a `snippet`: the opening of the first paragraph
+    /// under it, cut at a sentence or word to at most N characters (300 when
+    /// N isn't given)
+    #[arg(long, value_name = "N", num_args = 0..=1, require_equals = true,
+          default_missing_value = "300", env = "ADOBE1A_WITH_SNIPPETS",
+          value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    with_snippets: Option<usize>,
+    /// Give each heading a `page_offset`, the characters before it in its
+    /// page's text, and a `run_index`, the run it starts in among its page's
+    #[arg(long, env = "ADOBE1A_WITH_OFFSETS")]
+    with_offsets: bool,
+    /// Give each heading a `y_fraction`, how far down its page it is (0 at
+    /// the top, 1 at the bottom), for a viewer to scroll to
+    #[arg(long, env = "ADOBE1A_WITH_POSITIONS")]
+    with_positions: bool,
+    /// Recase heading text: as found, in title case (keeping acronyms), in
+    /// sentence case or in capitals
+    #[arg(long, value_enum, default_value_t = CaseStyle::Preserve, env = "ADOBE1A_NORMALIZE_CASE")]
+    normalize_case: CaseStyle,
+    /// Recase the title as --normalize-case does the headings
+    #[arg(long, env = "ADOBE1A_NORMALIZE_TITLE")]
+    normalize_title: bool,
+    /// Give every heading a `number`: the one printed in front of it, or
+    /// one made up from its place in the outline, marked `synthetic`
+    #[arg(long, env = "ADOBE1A_SYNTHESIZE_NUMBERS")]
+    synthesize_numbers: bool,
+    /// When a level holds a single heading in the whole document, move the
+    /// headings under it up a level, noting it in the warnings. Limited to
+    /// the LEVELS given (e.g. =H1,H2), otherwise every level.
+    #[arg(long, value_name = "LEVELS", num_args = 0..=1, require_equals = true, value_delimiter = ',',
+          default_missing_value = "H1,H2,H3,H4", env = "ADOBE1A_COLLAPSE_SINGLETONS", value_parser = parse_level)]
+    collapse_singletons: Option<Vec<u8>>,
+    /// Keep the headings of a sparse first page with the title on it, which
+    /// are otherwise taken for a cover page's name, address and date lines
+    #[arg(long, env = "ADOBE1A_NO_COVER_DETECTION")]
+    no_cover_detection: bool,
+    /// Read documents as memos and letters: no blank lines needed around
+    /// headings, colon and keyword headings weighted up, a subject line
+    /// ("RE: ...") as the title, and no running header/footer analysis.
+    /// `auto` does for documents of at most --short-document-pages pages
+    #[arg(long, value_enum, default_value_t = ShortDocument::Auto, env = "ADOBE1A_SHORT_DOCUMENT")]
+    short_document: ShortDocument,
+    /// Most pages of a document --short-document auto reads as a memo or letter
+    #[arg(long, default_value_t = 3, env = "ADOBE1A_SHORT_DOCUMENT_PAGES")]
+    short_document_pages: usize,
+    /// Drop headings with fewer letters than this after cleaning
+    #[arg(long, default_value_t = 2, env = "ADOBE1A_MIN_HEADING_LETTERS")]
+    min_heading_letters: usize,
+    /// Keep at most this many headings (font engine) instead of a cap that grows with the page count
+    #[arg(long, env = "ADOBE1A_MAX_HEADINGS",
+          value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    max_headings: Option<usize>,
+    /// Headings allowed per page when --max-headings isn't given (never fewer than 10 in all)
+    #[arg(long, default_value_t = 1.5, env = "ADOBE1A_HEADINGS_PER_PAGE", value_parser = parse_per_page)]
+    headings_per_page: f64,
+    /// Most headings kept from one page of ordinary length (more on a fuller
+    /// page) before its weakest are dropped with a warning; 0 disables
+    #[arg(long, default_value_t = 8, env = "ADOBE1A_PAGE_HEADING_LIMIT")]
+    page_heading_limit: usize,
+    /// Drop headings whose confidence is below this (0 to 1)
+    #[arg(long, default_value_t = 0.0, env = "ADOBE1A_MIN_CONFIDENCE", value_parser = parse_share)]
+    min_confidence: f64,
+    /// Fail a file whose outline's quality score (0 to 100) is below this;
+    /// its output is still written, and a directory run fails at the end
+    #[arg(long, env = "ADOBE1A_MIN_QUALITY", value_parser = clap::value_parser!(u8).range(0..=100))]
+    min_quality: Option<u8>,
+    /// Write a report on every processed file and corpus totals to this path
+    #[arg(long, env = "ADOBE1A_SUMMARY")]
+    summary: Option<PathBuf>,
+    /// Format of the --summary report
+    #[arg(long, value_enum, default_value_t = SummaryFormat::Json, env = "ADOBE1A_SUMMARY_FORMAT")]
+    summary_format: SummaryFormat,
+    /// Score each outline against the reference outline `<input name>.json`
+    /// in this directory (level, text and page of each heading), as in a
+    /// benchmark corpus's expected outputs; scores go into the summary
+    #[arg(long, env = "ADOBE1A_EXPECTED")]
+    expected: Option<PathBuf>,
+    /// With --expected, write a JUnit XML report to this path: a test case
+    /// per file, failing when its F1 is below --min-f1 or it couldn't be processed
+    #[arg(long, env = "ADOBE1A_REPORT", requires = "expected")]
+    report: Option<PathBuf>,
+    /// The F1 score (0 to 1) a file needs to pass in the --report
+    #[arg(long, default_value_t = 0.8, env = "ADOBE1A_MIN_F1", value_parser = parse_share)]
+    min_f1: f64,
+    /// Keep a JSON file of the run's progress at this path, replaced whole
+    /// every few seconds and marked complete with the summary at the end
+    #[arg(long, env = "ADOBE1A_STATUS_FILE")]
+    status_file: Option<PathBuf>,
+    /// Process this many files of a directory or list at once
+    #[arg(long, env = "ADOBE1A_JOBS",
+          value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    jobs: Option<usize>,
+    /// Skip directory or list inputs whose output is already up to date
+    #[arg(long, env = "ADOBE1A_RESUME")]
+    resume: bool,
+    /// Overwrite an existing output in a directory, and with --resume
+    /// reprocess every file anyway
+    #[arg(long, env = "ADOBE1A_FORCE")]
+    force: bool,
+    /// Extraction engine; `auto` tries the text engine, then the font engine
+    #[arg(long, value_enum, default_value_t = Engine::Auto, env = "ADOBE1A_ENGINE")]
+    engine: Engine,
+    /// For a PDF portfolio: outline the attached PDFs in the same output,
+    /// each in an output of its own (`<output>.<attachment>.json`), or not at all
+    #[arg(long, value_enum, default_value_t = Portfolio::Combine, env = "ADOBE1A_PORTFOLIO")]
+    portfolio: Portfolio,
+    /// Outline the attached PDF whose name matches this pattern (any case,
+    /// `*` and `?` wildcards) instead of the PDF holding it
+    #[arg(long, value_name = "NAME", env = "ADOBE1A_ATTACHMENT")]
+    attachment: Option<String>,
+    /// Look for several documents concatenated into one PDF (e.g. scanned
+    /// memos) and put each one's headings under an H1 with its title
+    #[arg(long, env = "ADOBE1A_SPLIT_DOCUMENTS")]
+    split_documents: bool,
+    /// Also write each document found by --split-documents to `<output>.<n>.json`
+    #[arg(long, requires = "split_documents", env = "ADOBE1A_SPLIT_OUTPUT")]
+    split_output: bool,
+    /// Only read these pages, e.g. `1-20,450-470`; page numbers in the output stay absolute
+    #[arg(long, env = "ADOBE1A_PAGES")]
+    pages: Option<PageRanges>,
+    /// Read only the first --quick-pages pages, take the outline from the PDF's
+    /// bookmarks when it has them and prefer the metadata title; outputs that
+    /// may be missing headings are marked `partial`
+    #[arg(long, env = "ADOBE1A_QUICK")]
+    quick: bool,
+    /// How many pages --quick reads
+    #[arg(long, default_value_t = 15, requires = "quick", env = "ADOBE1A_QUICK_PAGES",
+          value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    quick_pages: usize,
+    /// Record on each heading what found it (`text`, `font` or `bookmarks`)
+    #[arg(long, env = "ADOBE1A_INCLUDE_SOURCES")]
+    include_sources: bool,
+    /// Record on each heading what found it, its score before calibration and
+    /// the level a level override replaced (`sources`, `raw_score` and
+    /// `detected_level`)
+    #[arg(long, env = "ADOBE1A_EXPLAIN")]
+    explain: bool,
+    /// JSON file of per-engine curves mapping raw scores to confidences,
+    /// e.g. `{"font": [[0.6, 0.3], [1.0, 0.95]]}`
+    #[arg(long, env = "ADOBE1A_CALIBRATION", value_parser = parse_calibration)]
+    calibration: Option<Calibration>,
+    /// JSON file of rules setting the level of headings whose text matches a
+    /// pattern, the first match winning, e.g.
+    /// `[{"pattern": "^PART \\d+", "level": "H1"}]`
+    #[arg(long, env = "ADOBE1A_LEVEL_OVERRIDES", value_parser = parse_level_overrides)]
+    level_overrides: Option<LevelOverrides>,
+    /// Megabytes a document's extraction may hold in buffers before it falls
+    /// back to quick mode, without snippets or offsets, with a warning
+    #[arg(long, value_name = "MB", env = "ADOBE1A_MAX_MEMORY",
+          value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    max_memory: Option<usize>,
+    /// Language of the documents as a BCP 47 tag, e.g. `tr-TR`, deciding how
+    /// heading text is case-folded when matched; each PDF's own `/Lang` by
+    /// default
+    #[arg(long, env = "ADOBE1A_LANGUAGE")]
+    language: Option<String>,
+    /// JSON file extending or replacing the built-in keyword lists
+    #[arg(long, env = "ADOBE1A_LEXICON")]
+    lexicon: Option<PathBuf>,
+    /// JSON file of post-processing steps applied to every outline before
+    /// it's written (drop_levels, strip_numbers, min_confidence,
+    /// rename_title_regex, limit)
+    #[arg(long, env = "ADOBE1A_POST", value_parser = parse_transform)]
+    post: Option<Transform>,
+    /// Spelling of JSON keys in outputs and the summary
+    #[arg(long, value_enum, default_value_t = KeyStyle::Snake, env = "ADOBE1A_KEY_STYLE")]
+    key_style: KeyStyle,
+    /// Order of headings in the output JSON: as in the document, most
+    /// confident first, or by level then page
+    #[arg(long, value_enum, default_value_t = SortOrder::Document, env = "ADOBE1A_SORT")]
+    sort: SortOrder,
+    /// Shape of the output JSON: everything extracted, or exactly the
+    /// Adobe Round 1A schema (title, and level, text and page per heading)
+    #[arg(long, value_enum, default_value_t = OutputFormat::Full, env = "ADOBE1A_FORMAT")]
+    format: OutputFormat,
 }
 
-fn extract_outline(pdf_path: &PathBuf) -> Result<Outline> {
-    if let Ok(outline) = try_pdf_extract(pdf_path) {
-        if !outline.outline.is_empty() {
-            return Ok(outline);
+// Every flag can also be set through an `ADOBE1A_<FLAG>` environment
+// variable, and extraction's flags in a `--config` file. A flag on the
+// command line wins over the variable, which wins over the file, which wins
+// over the default. clap reports a bad value from a variable or the file as
+// if it came from the flag, so where it came from is named in the error here.
+fn parse_args() -> Cli {
+    let mut args = with_default_subcommand(std::env::args_os().collect());
+    let config = config_path(&args);
+    let from_file = match &config {
+        Some(path) => match config_file_args(path) {
+            Ok((extra, keys)) => {
+                args.extend(extra);
+                keys
+            }
+            Err(e) => {
+                eprintln!("error: {:#}", e);
+                std::process::exit(2);
+            }
+        },
+        None => Vec::new(),
+    };
+    Cli::try_parse_from(args).unwrap_or_else(|e| {
+        let source = e.get(clap::error::ContextKind::InvalidArg).and_then(|arg| {
+            let arg = arg.to_string();
+            let long = arg.split_whitespace().next()?.trim_start_matches("--").to_string();
+            if let (true, Some(path)) = (from_file.contains(&long), &config) {
+                return Some((arg, format!("key \"{}\" in {}", long, path.display())));
+            }
+            let command = Cli::command();
+            let var = command.get_subcommands()
+                .flat_map(|subcommand| subcommand.get_arguments())
+                .find(|a| a.get_long() == Some(long.as_str()))?
+                .get_env()?
+                .to_string_lossy()
+                .into_owned();
+            (!on_command_line(&long, None) && std::env::var_os(&var).is_some())
+                .then(|| (arg, format!("environment variable {}", var)))
+        });
+        match source {
+            Some((arg, source)) => {
+                let message = e.render().to_string()
+                    .replacen(&format!("'{}'", arg), &format!("{} ('{}')", source, arg), 1);
+                eprint!("{}", message);
+                std::process::exit(2);
+            }
+            None => e.exit(),
         }
+    })
+}
+
+// Whether the flag `--<long>` (or `-<short>`) was given on the command line
+fn on_command_line(long: &str, short: Option<char>) -> bool {
+    std::env::args().any(|a| {
+        a == format!("--{}", long) || a.starts_with(&format!("--{}=", long))
+            || short.is_some_and(|short| a.starts_with(&format!("-{}", short)) && !a.starts_with("--"))
+    })
+}
+
+// The `--config` file extraction's arguments name, on the command line or
+// in its variable
+fn config_path(args: &[std::ffi::OsString]) -> Option<PathBuf> {
+    if args.get(1).is_none_or(|command| command != "extract") {
+        return None;
     }
+    let args: Vec<String> = args.iter().map(|a| a.to_string_lossy().into_owned()).collect();
+    let given = args.iter().position(|a| a == "--config").and_then(|i| args.get(i + 1).cloned())
+        .or_else(|| args.iter().find_map(|a| a.strip_prefix("--config=").map(str::to_string)));
+    given.or_else(|| std::env::var("ADOBE1A_CONFIG").ok()).map(PathBuf::from)
+}
 
-    extract_with_lopdf(pdf_path)
+// The arguments a `--config` file adds, and the flags they set.
+// The file is a JSON object keyed by long flag names, with `-` or `_`
+// between words. A value is a string or number, `true` or `false` for a
+// switch, or a list for a flag taken several times. A flag given on the
+// command line or in its variable is left alone, and so is one that
+// conflicts with a flag given there.
+fn config_file_args(path: &Path) -> Result<(Vec<std::ffi::OsString>, Vec<String>)> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read config file {}", path.display()))?;
+    let values: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&text)
+        .with_context(|| format!("Invalid config file {}: expected a JSON object of flag values", path.display()))?;
+    let mut command = Cli::command();
+    command.build();
+    let extract = command.find_subcommand("extract").expect("the extract subcommand");
+    let given = |arg: &clap::Arg| {
+        let long = arg.get_long().unwrap_or_default();
+        on_command_line(long, arg.get_short()) || arg.get_env().is_some_and(|var| std::env::var_os(var).is_some())
+    };
+
+    let (mut extra, mut keys) = (Vec::new(), Vec::new());
+    for (key, value) in values {
+        let long = key.replace('_', "-");
+        let arg = extract.get_arguments()
+            .find(|arg| arg.get_long() == Some(long.as_str()) && long != "config")
+            .with_context(|| format!("Invalid config file {}: no flag --{}", path.display(), long))?;
+        // Conflicts are declared on one of the pair
+        let conflicts = |other: &clap::Arg| {
+            extract.get_arg_conflicts_with(arg).iter().any(|a| a.get_id() == other.get_id())
+                || extract.get_arg_conflicts_with(other).iter().any(|a| a.get_id() == arg.get_id())
+        };
+        if given(arg) || extract.get_arguments().any(|other| conflicts(other) && given(other)) {
+            continue;
+        }
+        let switch = !arg.get_action().takes_values();
+        let values = match value {
+            serde_json::Value::Array(values) => values,
+            value => vec![value],
+        };
+        for value in values {
+            let value = match value {
+                serde_json::Value::String(value) => value,
+                serde_json::Value::Number(value) => value.to_string(),
+                serde_json::Value::Bool(value) if switch => match value {
+                    true => String::new(),
+                    false => continue,
+                },
+                serde_json::Value::Bool(value) => value.to_string(),
+                other => anyhow::bail!("Invalid config file {}: \"{}\" is {}, not a string, number or switch", path.display(), key, other),
+            };
+            if switch && !value.is_empty() {
+                anyhow::bail!("Invalid config file {}: --{} is a switch, so \"{}\" is true or false", path.display(), long, key);
+            }
+            extra.push(match switch {
+                true => format!("--{}", long).into(),
+                false => format!("--{}={}", long, value).into(),
+            });
+        }
+        keys.push(long);
+    }
+    Ok((extra, keys))
 }
 
-fn try_pdf_extract(pdf_path: &PathBuf) -> Result<Outline> {
-    let bytes = std::fs::read(pdf_path)?;
-    let text = pdf_extract::extract_text_from_mem(&bytes)?;
-    
-    if text.trim().is_empty() {
-        return Err(anyhow::anyhow!("No text extracted"));
+// Read while parsing arguments, so a bad spec is reported before any file
+// is processed
+fn parse_transform(path: &str) -> std::result::Result<Transform, String> {
+    Transform::load(Path::new(path)).map_err(|e| format!("{:#}", e))
+}
+
+fn parse_calibration(path: &str) -> std::result::Result<Calibration, String> {
+    Calibration::load(Path::new(path)).map_err(|e| format!("{:#}", e))
+}
+
+fn parse_level_overrides(path: &str) -> std::result::Result<LevelOverrides, String> {
+    LevelOverrides::load(Path::new(path)).map_err(|e| format!("{:#}", e))
+}
+
+// A heading level as the output writes it ("H2"), as its depth
+fn parse_level(level: &str) -> std::result::Result<u8, String> {
+    level.trim().strip_prefix(['H', 'h'])
+        .and_then(|depth| depth.parse::<u8>().ok())
+        .filter(|depth| (1..=6).contains(depth))
+        .ok_or_else(|| format!("`{}` isn't a heading level from H1 to H6", level))
+}
+
+// The ranges the library's builder checks, checked here as well so a bad
+// value is reported against its flag, variable or config key
+fn parse_share(value: &str) -> std::result::Result<f64, String> {
+    parse_within(value, |share| (0.0..=1.0).contains(&share), "from 0 to 1")
+}
+
+fn parse_margin_band(value: &str) -> std::result::Result<f64, String> {
+    parse_within(value, |band| (0.0..0.5).contains(&band), "from 0 to under 0.5")
+}
+
+fn parse_per_page(value: &str) -> std::result::Result<f64, String> {
+    parse_within(value, |rate| rate > 0.0 && rate.is_finite(), "above 0")
+}
+
+fn parse_within(value: &str, within: impl Fn(f64) -> bool, expected: &str) -> std::result::Result<f64, String> {
+    let number: f64 = value.trim().parse().map_err(|e| format!("{}", e))?;
+    match within(number) {
+        true => Ok(number),
+        false => Err(format!("{} isn't {}", number, expected)),
     }
+}
 
-    let mut title = String::new();
-    let mut headings = Vec::new();
+// Flags without a subcommand mean `extract`, as before there were subcommands
+fn with_default_subcommand(mut args: Vec<std::ffi::OsString>) -> Vec<std::ffi::OsString> {
+    let explicit = args.get(1).and_then(|a| a.to_str()).is_some_and(|first| {
+        matches!(first, "help" | "-h" | "--help")
+            || Cli::command().find_subcommand(first).is_some()
+    });
+    if !explicit {
+        args.insert(1.min(args.len()), "extract".into());
+    }
+    args
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum InputFormat {
+    Pdf,
+    Text,
+    Markdown,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SummaryFormat {
+    Json,
+    Csv,
+}
+
+impl ExtractArgs {
+    // Everything that decides how a PDF is read goes through the library's
+    // builder, which checks it the same way for both
+    fn extractor(&self) -> Result<Extractor> {
+        let mut builder = Extractor::builder()
+            .engine(self.engine)
+            .hidden_text(if self.include_hidden_text {
+                HiddenText::Include
+            } else if self.exclude_hidden_text {
+                HiddenText::Exclude
+            } else {
+                HiddenText::Auto
+            })
+            .margin_band(self.margin_band)
+            .title_candidates(self.title_candidates)
+            .min_heading_letters(self.min_heading_letters)
+            .headings_per_page(self.headings_per_page)
+            .page_heading_limit(self.page_heading_limit)
+            .min_confidence(self.min_confidence)
+            .offsets(self.with_offsets)
+            .positions(self.with_positions)
+            .heading_case(self.normalize_case)
+            .normalize_title(self.normalize_title)
+            .synthesize_numbers(self.synthesize_numbers)
+            .cover_detection(!self.no_cover_detection)
+            .short_document(self.short_document)
+            .short_document_pages(self.short_document_pages)
+            .collapse_singletons(self.collapse_singletons.clone().unwrap_or_default())
+            .split_documents(self.split_documents)
+            .portfolio(self.portfolio)
+            .include_sources(self.include_sources || self.explain)
+            .include_raw_scores(self.explain)
+            .calibration(self.calibration.clone().unwrap_or_default())
+            .level_overrides(self.level_overrides.clone().unwrap_or_default())
+            .title(match (&self.title, self.no_title) {
+                (Some(title), _) => TitleMode::Fixed(title.clone()),
+                (None, true) => TitleMode::Filename,
+                (None, false) => TitleMode::Detect,
+            });
+        if let Some(max_chars) = self.with_snippets {
+            builder = builder.snippet_chars(max_chars);
+        }
+        if let Some(max_headings) = self.max_headings {
+            builder = builder.max_headings(max_headings);
+        }
+        if let Some(pages) = &self.pages {
+            builder = builder.pages(pages.clone());
+        }
+        if self.quick {
+            builder = builder.quick(self.quick_pages);
+        }
+        if let Some(path) = &self.lexicon {
+            builder = builder.lexicon(Lexicon::load(path)?);
+        }
+        if let Some(pattern) = &self.attachment {
+            builder = builder.attachment(pattern.clone());
+        }
+        if let Some(megabytes) = self.max_memory {
+            builder = builder.max_memory(megabytes.saturating_mul(1024 * 1024));
+        }
+        if let Some(language) = &self.language {
+            builder = builder.language(language.clone());
+        }
+        Ok(builder.build()?)
+    }
+}
+
+// Exit status when the input was a PDF with nothing to outline, kept apart
+// from 1 for other failures and clap's 2 for bad arguments. A directory run
+// counts such files as `no_content` in its summary without failing.
+const EXIT_NO_CONTENT: u8 = 3;
+// Likewise when the input wasn't a PDF at all (`not_pdf`)
+const EXIT_NOT_A_PDF: u8 = 4;
+// When every file was processed but some outline scored below --min-quality
+const EXIT_LOW_QUALITY: u8 = 5;
+
+// Outlines were written, but some scored below --min-quality
+#[derive(Debug)]
+struct LowQuality(String);
+
+impl std::fmt::Display for LowQuality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for LowQuality {}
 
-    let pages: Vec<&str> = if text.contains('\x0C') {
-        text.split('\x0C').collect()
-    } else {
-        text.split("\n\n\n").collect()
+fn main() -> ExitCode {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+    let cli = parse_args();
+    let result = match cli.command {
+        Command::Extract(args) => run_extract(&args),
+        Command::Lint(args) => run_lint(&args),
+        Command::Merge(args) => run_merge(&args),
+        Command::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), "adobe1a", &mut std::io::stdout());
+            Ok(())
+        }
     };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            if e.chain().any(|cause| cause.is::<NoTextContent>()) {
+                ExitCode::from(EXIT_NO_CONTENT)
+            } else if e.chain().any(|cause| cause.is::<NotAPdf>()) {
+                ExitCode::from(EXIT_NOT_A_PDF)
+            } else if e.chain().any(|cause| cause.is::<LowQuality>()) {
+                ExitCode::from(EXIT_LOW_QUALITY)
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
+
+fn run_merge(args: &MergeArgs) -> Result<()> {
+    let extractor = Extractor::builder().engine(args.engine).build()?;
+    let volumes = args.volumes.iter()
+        .map(|volume| {
+            let is_json = volume.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+            let outline = match is_json {
+                true => output::from_json(&std::fs::read(volume)?),
+                false => extractor.extract_path(volume),
+            };
+            outline.with_context(|| format!("Failed to read volume {}", volume.display()))
+        })
+        .collect::<Result<Vec<Outline>>>()?;
 
-    for (page_num, page_text) in pages.iter().enumerate() {
-        let current_page = page_num + 1;
-        let lines: Vec<&str> = page_text.lines()
-            .map(|l| l.trim())
-            .filter(|l| !l.is_empty())
-            .collect();
+    let merged = merge::merge(volumes);
+    write_outline(&merged, Some(&args.output), args.key_style, args.format, SortOrder::Document, args.print_tree)?;
+    println!("Merged {} volumes -> {}", args.volumes.len(), args.output.display());
+    Ok(())
+}
 
-        if title.is_empty() && current_page == 1 {
-            title = functions::extract_document_title(&lines, page_text);
+// Print every finding in every file, then fail if any was an error
+fn run_lint(args: &LintArgs) -> Result<()> {
+    let options = LintOptions { level_jumps: args.level_jumps };
+    let (mut errors, mut warnings) = (0, 0);
+    for file in &args.files {
+        let json = std::fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+        for finding in lint::lint(&json, &options) {
+            println!("{}: {}", file.display(), finding);
+            match finding.severity {
+                Severity::Error => errors += 1,
+                Severity::Warning => warnings += 1,
+            }
+        }
+    }
+    println!("{} errors, {} warnings in {} files", errors, warnings, args.files.len());
+    if errors > 0 {
+        anyhow::bail!("{} errors found", errors);
+    }
+    Ok(())
+}
+
+fn run_extract(args: &ExtractArgs) -> Result<()> {
+    if args.print_tree && args.sort != SortOrder::Document {
+        // The tree nests each heading under the one before it
+        anyhow::bail!("--print-tree needs headings in document order to nest them; drop --sort or use --sort document");
+    }
+    let extractor = args.extractor()?;
+    let config = extractor.config();
+    let input = match (&args.input, &args.input_list) {
+        (_, Some(list)) => return run_batch(inputs::read_input_list(list)?, args, config),
+        (Some(dir), None) if dir.is_dir() => {
+            let inputs = match args.input_format {
+                InputFormat::Pdf => inputs::pdfs_in_dir(dir)?,
+                InputFormat::Text => inputs::text_files_in_dir(dir)?,
+                InputFormat::Markdown => inputs::markdown_files_in_dir(dir)?,
+            };
+            return run_batch(inputs, args, config);
         }
+        (Some(input), None) => input,
+        (None, None) => unreachable!("clap requires --input or --input-list"),
+    };
 
-        for (i, line) in lines.iter().enumerate() {
-            if let Some(heading) = functions::analyze_potential_heading(
-                line,
-                i,
-                &lines,
-                current_page,
-            ) {
-                if !headings.iter().any(|h: &Heading| h.text == heading.text && h.page == heading.page) {
-                    headings.push(heading);
-                }
+    let output = match &args.output {
+        Some(dir) if is_directory_target(dir) => {
+            std::fs::create_dir_all(dir)?;
+            let output = output_paths(std::slice::from_ref(input), dir).remove(0);
+            if output.exists() && !args.force {
+                anyhow::bail!("{} already exists; pass --force to overwrite it", output.display());
             }
+            Some(output)
         }
+        other => other.clone(),
+    };
+
+    let status = status_file(args, 1)?;
+    if let Some(status) = &status {
+        status.started(input);
     }
+    let start = Instant::now();
+    let result = process_file(input, output.as_deref(), args, config)
+        .with_context(|| format!("Failed to process {}", input.display()));
+    if args.summary.is_some() || args.report.is_some() || status.is_some() {
+        let file = match &result {
+            Ok((outline, stats)) => FileSummary::succeeded(input, outline, stats.clone())
+                .with_min_quality(args.min_quality)
+                .with_evaluation(evaluate(input, outline, args)),
+            Err(e) => FileSummary::failed(input, e),
+        };
+        if let Some(status) = &status {
+            status.finished(&file, start.elapsed());
+        }
+        let summary = BatchSummary::new(vec![file]);
+        if let Some(status) = &status {
+            status.complete(&summary)?;
+        }
+        if let Some(path) = &args.summary {
+            write_summary(path, &summary, args)?;
+        }
+        if let Some(path) = &args.report {
+            write_report(path, &summary, args)?;
+        }
+    }
+    let (outline, stats) = result?;
+    report_success(input, output.as_deref());
 
-    Ok(Outline {
-        title: if title.is_empty() {
-            pdf_path.file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("Untitled")
-                .to_string()
-        } else {
-            title
-        },
-        outline: functions::establish_hierarchy(headings),
-    })
+    if args.stats {
+        stats.print_table();
+    }
+    if let (Some(score), Some(min_quality)) = (outline.quality_score, args.min_quality) {
+        if score < min_quality {
+            return Err(LowQuality(format!("{} scored {}, below --min-quality {}", input.display(), score, min_quality)).into());
+        }
+    }
+    Ok(())
 }
 
-fn extract_with_lopdf(pdf_path: &PathBuf) -> Result<Outline> {
-    let doc = Document::load(pdf_path)?;
-    let mut title = String::new();
-    
-    // Use the new font-based approach
-    let heading_candidates = font_utils::extract_heading_candidates(&doc);
-    
-    // Convert font-based candidates to our Heading format and filter
-    let mut headings: Vec<Heading> = heading_candidates.into_iter()
-        .filter(|candidate| {
-            candidate.text.len() > 3 && 
-            candidate.confidence > 0.6 && // Higher confidence threshold
-            !functions::is_excluded_text(&candidate.text)
-        })
-        .map(|candidate| Heading {
-            level: candidate.level,
-            text: functions::clean_heading_text(&candidate.text),
-            page: candidate.page,
-            confidence: candidate.confidence,
-        })
-        .collect();
-
-    // Sort by confidence and take top candidates to avoid noise
-    headings.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
-    
-    // Take only top 50 headings to avoid overwhelming output
-    headings.truncate(50);
-    
-    // Sort back by page order
-    headings.sort_by(|a, b| a.page.cmp(&b.page));
-
-    // Extract title from first page if we haven't found one
-    if title.is_empty() {
-        for (page_index, (page_id, _)) in doc.page_iter().enumerate() {
-            if page_index == 0 { // First page only
-                if let Ok(text) = doc.extract_text(&[page_id]) {
-                    let lines: Vec<&str> = text.lines()
-                        .map(|l| l.trim())
-                        .filter(|l| !l.is_empty())
-                        .collect();
-                    title = functions::extract_document_title(&lines, &text);
-                    break;
+// Process every PDF of a directory or input list into `<stem>.json` in the
+// output directory, carrying on past failures, missing files included
+fn run_batch(pdfs: Vec<PathBuf>, args: &ExtractArgs, config: &ExtractorConfig) -> Result<()> {
+    let outputs: Vec<Option<PathBuf>> = match &args.output {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            output_paths(&pdfs, dir).into_iter().map(Some).collect()
+        }
+        None => vec![None; pdfs.len()],
+    };
+
+    let gate = |file: FileSummary| {
+        let file = file.with_min_quality(args.min_quality);
+        if let (FileStatus::LowQuality, Some(score)) = (file.status, file.quality_score) {
+            eprintln!("Low quality: {} scored {}", file.path, score);
+        }
+        file
+    };
+    let status = status_file(args, pdfs.len())?;
+    let batch_start = Instant::now();
+    let process_one = |pdf: &PathBuf, output: &Option<PathBuf>| {
+        if let (true, false, Some(output)) = (args.resume, args.force, output) {
+            if let Some(outline) = cached_outline(pdf, output, args.quick) {
+                println!("Skipped {} (up to date)", pdf.display());
+                return gate(FileSummary::cached(pdf, &outline).with_evaluation(evaluate(pdf, &outline, args)));
+            }
+        }
+        // --resume reprocesses stale outputs in place
+        if let (false, false, Some(output)) = (args.resume, args.force, output) {
+            if output.exists() {
+                let e = anyhow::anyhow!("{} already exists; pass --force to overwrite it", output.display());
+                eprintln!("Skipped {}: {:#}", pdf.display(), e);
+                return FileSummary::failed(pdf, &e);
+            }
+        }
+        match process_file(pdf, output.as_deref(), args, config) {
+            Ok((outline, stats)) => {
+                report_success(pdf, output.as_deref());
+                let evaluation = evaluate(pdf, &outline, args);
+                gate(FileSummary::succeeded(pdf, &outline, stats).with_evaluation(evaluation))
+            }
+            Err(e) => {
+                let summary = FileSummary::failed(pdf, &e);
+                match summary.status {
+                    FileStatus::NoContent => eprintln!("Nothing to outline in {}: {:#}", pdf.display(), e),
+                    FileStatus::NotPdf => eprintln!("Skipped {}: {:#}", pdf.display(), e),
+                    _ => eprintln!("Failed to process {}: {:#}", pdf.display(), e),
                 }
+                summary
             }
         }
+    };
+    let process = |(pdf, output): (&PathBuf, &Option<PathBuf>)| {
+        let Some(status) = &status else {
+            return process_one(pdf, output);
+        };
+        status.started(pdf);
+        let start = Instant::now();
+        let file = process_one(pdf, output);
+        status.finished(&file, start.elapsed());
+        file
+    };
+    // Either way the results come back in path order
+    let files: Vec<FileSummary> = match args.jobs {
+        Some(jobs) => rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build()?
+            .install(|| pdfs.par_iter().zip(&outputs).map(process).collect()),
+        None => pdfs.iter().zip(&outputs).map(process).collect(),
+    };
+    let mut summary = BatchSummary::new(files);
+    summary.totals.stats.record_wall(batch_start.elapsed());
+    if let Some(status) = &status {
+        status.complete(&summary)?;
     }
 
-    Ok(Outline {
-        title: if title.is_empty() {
-            pdf_path.file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("Untitled")
-                .to_string()
-        } else {
-            title
-        },
-        outline: functions::establish_hierarchy(headings),
+    if args.stats {
+        summary.totals.stats.print_table();
+    }
+    // Written before failing so the reports cover the failures too
+    if let Some(path) = &args.summary {
+        write_summary(path, &summary, args)?;
+    }
+    if let Some(path) = &args.report {
+        write_report(path, &summary, args)?;
+    }
+    if summary.totals.failed > 0 {
+        anyhow::bail!("{} of {} files failed", summary.totals.failed, pdfs.len());
+    }
+    if summary.totals.low_quality > 0 {
+        let message = format!("{} of {} files scored below --min-quality {}",
+            summary.totals.low_quality, pdfs.len(), args.min_quality.unwrap_or_default());
+        return Err(LowQuality(message).into());
+    }
+    Ok(())
+}
+
+// An existing directory, or a path written with a trailing separator
+fn is_directory_target(path: &Path) -> bool {
+    path.is_dir() || path.to_string_lossy().ends_with(['/', std::path::MAIN_SEPARATOR])
+}
+
+// `<stem>.json` inside `dir` for each input. Inputs from different
+// directories can share a stem (compared case-insensitively, for the file
+// systems that do); those get a short hash of their full path appended so
+// they don't overwrite each other.
+fn output_paths(inputs: &[PathBuf], dir: &Path) -> Vec<PathBuf> {
+    let stem = |path: &Path| path.file_stem().and_then(|s| s.to_str()).unwrap_or("output").to_string();
+    let mut stem_counts: HashMap<String, usize> = HashMap::new();
+    for input in inputs {
+        *stem_counts.entry(stem(input).to_lowercase()).or_default() += 1;
+    }
+
+    inputs.iter()
+        .map(|input| {
+            let stem = stem(input);
+            if stem_counts[&stem.to_lowercase()] > 1 {
+                let hash = ids::fnv1a(input.to_string_lossy().as_bytes()) as u32;
+                dir.join(format!("{}-{:08x}.json", stem, hash))
+            } else {
+                dir.join(format!("{}.json", stem))
+            }
+        })
+        .collect()
+}
+
+// The existing output for `input`, if it is newer than the input, parses
+// completely and was produced from the same bytes by this version. A file
+// truncated by an interrupted run fails to parse and is reprocessed, and so
+// is a partial quick-mode output unless this run is quick too.
+fn cached_outline(input: &Path, output: &Path, quick: bool) -> Option<Outline> {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    if modified(output)? <= modified(input)? {
+        return None;
+    }
+    let outline: Outline = output::from_json(&std::fs::read(output).ok()?).ok()?;
+    let bytes = read_input(input).ok()?;
+    let current = outline.document_id == ids::document_id(&bytes) && outline.extractor_version == EXTRACTOR_VERSION;
+    (current && (quick || !outline.partial)).then_some(outline)
+}
+
+// How often --status-file is rewritten while files keep finishing
+const STATUS_INTERVAL: Duration = Duration::from_secs(2);
+
+fn status_file(args: &ExtractArgs, total: usize) -> Result<Option<StatusFile>> {
+    args.status_file.as_deref()
+        .map(|path| StatusFile::create(path, total, STATUS_INTERVAL, args.key_style))
+        .transpose()
+}
+
+fn write_summary(path: &Path, summary: &BatchSummary, args: &ExtractArgs) -> Result<()> {
+    let contents = match args.summary_format {
+        SummaryFormat::Json => output::to_json(summary, args.key_style)?,
+        SummaryFormat::Csv => summary.to_csv(),
+    };
+    std::fs::write(path, contents)
+        .with_context(|| format!("Failed to write summary {}", path.display()))
+}
+
+fn write_report(path: &Path, summary: &BatchSummary, args: &ExtractArgs) -> Result<()> {
+    std::fs::write(path, summary.to_junit(args.min_f1))
+        .with_context(|| format!("Failed to write report {}", path.display()))
+}
+
+// `outline` scored against its reference in the --expected directory. A
+// missing reference leaves the file unscored; one that can't be read is
+// also reported.
+fn evaluate(input: &Path, outline: &Outline, args: &ExtractArgs) -> Option<Evaluation> {
+    let stem = input.file_stem()?.to_string_lossy();
+    let path = args.expected.as_ref()?.join(format!("{}.json", stem));
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            eprintln!("Failed to read reference outline {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    match output::from_json::<Outline>(&bytes) {
+        Ok(reference) => Some(evaluation::evaluate(&outline.outline, &reference.outline)),
+        Err(e) => {
+            eprintln!("Failed to read reference outline {}: {:#}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn report_success(input: &Path, output: Option<&Path>) {
+    match output {
+        Some(output) => println!("Successfully processed {} -> {}", input.display(), output.display()),
+        None => println!("Successfully processed {}", input.display()),
+    }
+}
+
+// `output` is None with --no-output-file
+fn process_file(input: &Path, output: Option<&Path>, args: &ExtractArgs, config: &ExtractorConfig) -> Result<(Outline, Stats)> {
+    let mut stats = Stats::new();
+    // A PDF is read once, for its outline and for any attachments split out
+    let bytes = match args.input_format {
+        InputFormat::Pdf => Some(stats.time(Stage::FileRead, || read_input(input))?),
+        _ => None,
+    };
+    let mut outline = match &bytes {
+        Some(bytes) => {
+            let name = input.file_stem().and_then(|s| s.to_str()).unwrap_or("Untitled");
+            extract_outline_from_bytes(bytes, name, config, &mut stats)?
+        }
+        None => analyze_text_file(input, args.input_format, args, config)?,
+    };
+    // A portfolio's attachments are split out of the PDF read, which an
+    // --attachment isn't
+    let mut parts = match (&bytes, config.portfolio) {
+        (Some(bytes), Portfolio::Split) if config.attachment.is_none() => {
+            let (parts, warnings) = portfolio::extract_parts(bytes, config, &mut stats)?;
+            outline.warnings.extend(warnings);
+            parts
+        }
+        _ => Vec::new(),
+    };
+    if let Some(post) = &args.post {
+        outline = post.apply(outline);
+        for part in &mut parts {
+            part.outline = post.apply(std::mem::take(&mut part.outline));
+        }
+    }
+    if args.stats_json {
+        outline.stats = Some(stats.clone());
+    }
+
+    write_outline(&outline, output, args.key_style, args.format, args.sort, args.print_tree)?;
+    for part in &parts {
+        // Attachment names are untrusted; keep them from naming other directories
+        let stem = part.name.rsplit_once('.').map_or(part.name.as_str(), |(stem, _)| stem)
+            .replace(['/', '\\'], "_");
+        let output = output.map(|output| output.with_extension(format!("{}.json", stem)));
+        write_outline(&part.outline, output.as_deref(), args.key_style, args.format, args.sort, args.print_tree)?;
+    }
+    if args.split_output {
+        for (n, document) in documents::split(&outline).iter().enumerate() {
+            let output = output.map(|output| output.with_extension(format!("{}.json", n + 1)));
+            write_outline(document, output.as_deref(), args.key_style, args.format, args.sort, args.print_tree)?;
+        }
+    }
+    Ok((outline, stats))
+}
+
+fn analyze_text_file(input: &Path, format: InputFormat, args: &ExtractArgs, config: &ExtractorConfig) -> Result<Outline> {
+    let text = std::fs::read_to_string(input)?;
+    let options = TextOptions {
+        name: input.file_stem().and_then(|s| s.to_str()).unwrap_or("Untitled").to_string(),
+        page_marker: args.page_marker.clone(),
+        config: config.clone(),
+    };
+    let text = text.trim_start_matches('\u{feff}');
+    Ok(match format {
+        InputFormat::Markdown => analyze_markdown(text, options),
+        _ => analyze_text(text, options),
     })
 }
+
+fn write_outline(outline: &Outline, output: Option<&Path>, key_style: KeyStyle, format: OutputFormat, sort: SortOrder, print_tree: bool) -> Result<()> {
+    if let Some(output) = output {
+        // Write to a temporary file first so an interrupted run never leaves a
+        // half-written output behind under the real name
+        let partial = output.with_extension("json.partial");
+        let json = match format {
+            OutputFormat::Full => output::outline_to_json(outline, key_style, sort)?,
+            OutputFormat::Adobe1a => output::challenge_json(outline, sort)?,
+        };
+        std::fs::write(&partial, json)?;
+        std::fs::rename(&partial, output)?;
+    }
+    if print_tree {
+        // One print call, so trees from parallel jobs don't interleave
+        print!("{}", output::render_tree(outline, &output::TreeStyle::for_stdout()));
+    }
+    Ok(())
+}
@@ -0,0 +1,161 @@
+//! Outlines of Markdown sources, in the same shape as a PDF's. The headings
+//! are the document's own, so no heuristics are involved: ATX (`# Title`) and
+//! Setext (underlined) headings are read with a CommonMark parser, which also
+//! keeps `#` lines inside code blocks from counting.
+
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+use crate::text::{page_range_warnings, TextOptions};
+use crate::{Heading, Outline, TitleSource};
+
+/// The outline of a Markdown document. Each heading keeps its own level,
+/// `#` to `######` giving H1 to H6, with a confidence of 1. The title is the
+/// `title` of a YAML (`---`) or TOML (`+++`) front matter block, else the
+/// first H1, else `options.name` made readable.
+///
+/// Everything is on page 1 unless `options.page_marker` is set; then each
+/// line matching it outside a code block starts a new page. `pages` and
+/// `quick` in the configuration select pages as they do for a PDF.
+///
+/// ```
+/// use adobe1a::{markdown::analyze_markdown, TextOptions, TitleSource};
+/// use regex::Regex;
+///
+/// let source = "---\ntitle: \"Deployment Guide\"\n---\n\n# Overview\n\nSetup\n=====\n\n\
+///               ```sh\n# not a heading\n<!-- pagebreak -->\n```\n\n## Requirements\n\n\
+///               <!-- pagebreak -->\n\n### Linux *and* `macOS`\n\n    # indented code\n\n###### Notes\n";
+/// let options = TextOptions {
+///     page_marker: Some(Regex::new(r"^<!-- pagebreak -->$")?),
+///     ..TextOptions::default()
+/// };
+/// let outline = analyze_markdown(source, options);
+/// assert_eq!((outline.title.as_str(), outline.title_source), ("Deployment Guide", TitleSource::Metadata));
+/// let headings: Vec<_> = outline.outline.iter().map(|h| (h.level.as_str(), h.text.as_str(), h.page)).collect();
+/// assert_eq!(headings, [
+///     ("H1", "Overview", 1), ("H1", "Setup", 1), ("H2", "Requirements", 1),
+///     ("H3", "Linux and macOS", 2), ("H6", "Notes", 2),
+/// ]);
+/// assert_eq!(outline.page_count, Some(2));
+///
+/// // Without front matter the first H1 is the title
+/// let outline = analyze_markdown("Intro text.\n\n## Scope\n\n# User Manual\n", TextOptions::default());
+/// assert_eq!((outline.title.as_str(), outline.title_source), ("User Manual", TitleSource::Content));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn analyze_markdown(markdown: &str, options: TextOptions) -> Outline {
+    let TextOptions { name, page_marker, config } = options;
+    let parser = Parser::new_ext(markdown, Options::ENABLE_YAML_STYLE_METADATA_BLOCKS | Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS);
+
+    let mut headings = Vec::new();
+    // The text under each heading, for its snippet; code, tables and images
+    // are left out
+    let mut bodies: Vec<String> = Vec::new();
+    let mut skipped_depth = 0;
+    let mut code_blocks = Vec::new();
+    let mut front_matter_title = None;
+    // The heading or front matter being read, with its text so far
+    let mut heading: Option<(usize, usize, String)> = None;
+    let mut metadata: Option<String> = None;
+    for (event, range) in parser.into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => heading = Some((level as usize, range.start, String::new())),
+            Event::End(TagEnd::Heading(_)) => {
+                headings.extend(heading.take());
+                bodies.push(String::new());
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                code_blocks.push(range);
+                skipped_depth += 1;
+            }
+            Event::Start(Tag::Table(_) | Tag::Image { .. } | Tag::HtmlBlock) => skipped_depth += 1,
+            Event::End(TagEnd::CodeBlock | TagEnd::Table | TagEnd::Image | TagEnd::HtmlBlock) => skipped_depth -= 1,
+            Event::End(TagEnd::Paragraph | TagEnd::Item | TagEnd::BlockQuote(_)) => {
+                if let Some(body) = bodies.last_mut() {
+                    body.push_str("\n\n");
+                }
+            }
+            Event::Start(Tag::MetadataBlock(_)) => metadata = Some(String::new()),
+            Event::End(TagEnd::MetadataBlock(_)) => {
+                front_matter_title = metadata.take().and_then(|block| front_matter_field(&block, "title"));
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, _, heading_text)) = &mut heading {
+                    heading_text.push_str(&text);
+                } else if let Some(block) = &mut metadata {
+                    block.push_str(&text);
+                } else if let (Some(body), 0) = (bodies.last_mut(), skipped_depth) {
+                    body.push_str(&text);
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some((_, _, heading_text)) = &mut heading {
+                    heading_text.push(' ');
+                } else if let (Some(body), 0) = (bodies.last_mut(), skipped_depth) {
+                    body.push('\n');
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let breaks = page_breaks(markdown, page_marker.as_ref(), &code_blocks);
+    let page_count = breaks.len() + 1;
+    let outline: Vec<Heading> = headings.into_iter()
+        .zip(bodies)
+        .map(|((depth, offset, text), body)| Heading {
+            level: format!("H{}", depth),
+            text: text.trim().to_string(),
+            page: 1 + breaks.iter().filter(|&&at| at < offset).count(),
+            confidence: 1.0,
+            sources: vec!["markdown".to_string()],
+            snippet: config.snippet_chars.and_then(|max_chars| crate::sections::snippet(&body, max_chars)),
+            ..Default::default()
+        })
+        .filter(|heading| config.includes_page(heading.page))
+        .collect();
+
+    let first_h1 = outline.iter().find(|heading| heading.level == "H1").map(|heading| heading.text.clone());
+    let (title, title_source) = match (front_matter_title, first_h1) {
+        (Some(title), _) => (title, TitleSource::Metadata),
+        (None, title) => crate::resolve_title(title, &name, &config.lexicon),
+    };
+    let mut outline = Outline {
+        title,
+        title_source,
+        outline,
+        page_count: Some(page_count),
+        partial: config.quick.is_some_and(|window| page_count > window),
+        warnings: page_range_warnings(&config, page_count),
+        engine: vec!["markdown".to_string()],
+        ..Outline::default()
+    };
+    crate::drop_empty_headings(&mut outline, &config);
+    crate::finish_outline(&mut outline, &name, &config, markdown.as_bytes(), &[]);
+    outline
+}
+
+// Where each page after the first starts: the offsets of the lines matching
+// `marker` that aren't inside a code block
+fn page_breaks(markdown: &str, marker: Option<&Regex>, code_blocks: &[std::ops::Range<usize>]) -> Vec<usize> {
+    let Some(marker) = marker else {
+        return Vec::new();
+    };
+    let mut breaks = Vec::new();
+    let mut offset = 0;
+    for line in markdown.split_inclusive('\n') {
+        if marker.is_match(line.trim()) && !code_blocks.iter().any(|block| block.contains(&offset)) {
+            breaks.push(offset);
+        }
+        offset += line.len();
+    }
+    breaks
+}
+
+// A top-level `key: value` (YAML) or `key = value` (TOML) line of a front
+// matter block, unquoted
+fn front_matter_field(block: &str, key: &str) -> Option<String> {
+    block.lines()
+        .find_map(|line| line.strip_prefix(key)?.trim_start().strip_prefix([':', '=']))
+        .map(|value| value.trim().trim_matches(['"', '\'']).trim().to_string())
+        .filter(|value| !value.is_empty())
+}
@@ -0,0 +1,54 @@
+// An approximate account of the memory one document's extraction holds in
+// buffers of its own: decoded content streams, the text runs read from
+// them, and page text. The parsed PDF itself isn't counted. Pages are read
+// in parallel, so the account is shared between threads.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub(crate) struct MemoryBudget {
+    limit: Option<usize>,
+    used: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl MemoryBudget {
+    // No limit still keeps count, for the peak in the stats
+    pub(crate) fn new(limit: Option<usize>) -> Self {
+        MemoryBudget { limit, used: AtomicUsize::new(0), peak: AtomicUsize::new(0) }
+    }
+
+    // `bytes` more are held
+    pub(crate) fn charge(&self, bytes: usize) {
+        let used = self.used.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.peak.fetch_max(used, Ordering::Relaxed);
+    }
+
+    // `bytes` charged earlier are let go
+    pub(crate) fn release(&self, bytes: usize) {
+        self.used.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    // Whether more than the limit was ever held at once. Once it has been,
+    // readers skip the pages they haven't started.
+    pub(crate) fn exceeded(&self) -> bool {
+        self.limit.is_some_and(|limit| self.peak() > limit)
+    }
+
+    pub(crate) fn peak(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+}
+
+// A byte count for messages, in megabytes from one up
+pub(crate) fn describe(bytes: usize) -> String {
+    match bytes >= MEGABYTE {
+        true => format!("{} MB", bytes / MEGABYTE),
+        false => format!("{} bytes", bytes),
+    }
+}
+
+pub(crate) const MEGABYTE: usize = 1024 * 1024;
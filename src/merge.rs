@@ -0,0 +1,97 @@
+//! One outline for a set of PDFs read as consecutive volumes, such as a
+//! specification published in parts.
+
+use crate::documents::SubDocument;
+use crate::sanitize::{normalize_for_match, sanitize_text};
+use crate::{ids, Heading, Outline, Warning};
+
+/// Merges the volumes' outlines in order. Each volume becomes an H1 named
+/// after its title, with its headings one level down. Pages count on through
+/// the volumes as if they were concatenated, each heading keeps its page
+/// within its volume as `volume_page`, and `documents` lists each volume's
+/// pages. Repeated headings in different volumes ("Foreword") are all kept.
+///
+/// A volume without a `page_count` (an output from before it was recorded)
+/// is taken to end at its last heading, with a warning.
+///
+/// ```
+/// use adobe1a::{merge::merge, Heading, Outline};
+///
+/// let volume = |title: &str, pages| Outline {
+///     title: title.into(),
+///     page_count: Some(pages),
+///     outline: vec![Heading { level: "H1".into(), text: "Scope".into(), page: 2, ..Default::default() }],
+///     ..Default::default()
+/// };
+/// let merged = merge(vec![volume("Part 1", 10), volume("Part 2", 5)]);
+/// let scope = &merged.outline[3];
+/// assert_eq!((scope.level.as_str(), scope.page, scope.volume_page), ("H2", 12, Some(2)));
+/// assert_eq!(merged.page_count, Some(15));
+/// ```
+pub fn merge(volumes: Vec<Outline>) -> Outline {
+    let mut merged = Outline::default();
+    let mut titles: Vec<String> = Vec::new();
+    let mut document_ids = Vec::new();
+    let mut languages: Vec<Option<String>> = Vec::new();
+    let mut offset = 0;
+
+    for (i, volume) in volumes.into_iter().enumerate() {
+        // Outline files may come from elsewhere, so their text is cleaned up
+        // as extraction would have
+        let title = match sanitize_text(&volume.title) {
+            title if title.is_empty() => format!("Volume {}", i + 1),
+            title => title,
+        };
+        let page_count = volume.page_count.unwrap_or_else(|| {
+            merged.warnings.push(Warning::new("volume_page_count_unknown", format!(
+                "{:?} doesn't record its page count; later volumes' pages assume it ends at its last heading", title)));
+            volume.outline.iter().map(|heading| heading.page).max().unwrap_or(1)
+        });
+
+        merged.outline.push(Heading {
+            level: "H1".to_string(),
+            text: title.clone(),
+            page: offset + 1,
+            confidence: 1.0,
+            volume_page: Some(1),
+            ..Default::default()
+        });
+        // The volume's title set as a heading on its first page is the H1 above
+        let lang = volume.language.as_deref();
+        let title_key = normalize_for_match(&title, lang);
+        let repeats_title = |heading: &Heading| heading.page == 1 && normalize_for_match(&heading.text, lang) == title_key;
+        let headings = volume.outline.into_iter()
+            .map(|heading| Heading { text: sanitize_text(&heading.text), ..heading })
+            .filter(|heading| !heading.text.is_empty() && !repeats_title(heading));
+        merged.outline.extend(headings.map(|heading| Heading {
+            page: offset + heading.page,
+            volume_page: Some(heading.page),
+            ..heading.demoted()
+        }));
+        merged.warnings.extend(volume.warnings.into_iter().map(|warning| Warning {
+            message: format!("{}: {}", title, warning.message),
+            ..warning
+        }));
+        merged.documents.push(SubDocument { title: title.clone(), first_page: offset + 1, last_page: offset + page_count });
+
+        if !titles.contains(&title) {
+            titles.push(title);
+        }
+        merged.add_engines(volume.engine);
+        document_ids.push(volume.document_id);
+        languages.push(volume.language);
+        offset += page_count;
+    }
+
+    merged.title = titles.join(" / ");
+    merged.page_count = Some(offset);
+    // A language only where every volume is in the same one
+    languages.dedup();
+    if let [language] = languages.as_slice() {
+        merged.language = language.clone();
+    }
+    merged.document_id = ids::document_id(document_ids.join("\n").as_bytes());
+    merged.extractor_version = crate::EXTRACTOR_VERSION.to_string();
+    ids::assign_heading_ids(&mut merged.outline);
+    merged
+}
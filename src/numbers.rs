@@ -0,0 +1,129 @@
+//! Section numbers for every heading: the number printed in front of it
+//! when it has one, otherwise one made up from its place in the outline.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::functions::{self, SEQUENCE_NUMBER};
+use crate::{Heading, APPENDIX_HEADING, SECTION_HEADING};
+
+// A letter or roman numeral in front of a heading: "IV. Results", "b) Costs"
+static ENUMERATION: Lazy<Regex> = Lazy::new(||
+    Regex::new(r"^([IVXLCDM]+|[ivxlcdm]+|[A-Za-z])[.)]\s+\S").unwrap());
+
+// A heading the numbering has reached, which later headings may nest under
+struct Open {
+    depth: u8,
+    number: Vec<String>,
+    // The number of its last child so far, and how many it has had
+    last_child: Option<Vec<String>>,
+    children: usize,
+}
+
+/// Sets every heading's `number`. A heading keeps the number printed in
+/// front of it ("3.2", "IV", "Appendix B"); any other gets a `synthetic` one
+/// from its place among the headings before it. Headings nest the way
+/// [`crate::Outline::to_tree`] nests them, each under the closest earlier
+/// heading shallower than it, so a skipped level (an H3 straight after an
+/// H1) numbers as the next level down, and headings with nothing above
+/// them, such as the H2s a document opens with, are numbered from 1 at the
+/// top. A made-up number continues its printed siblings' sequence where
+/// there is one: "2.3" after "2.2", "C" after "B", "V" after "IV".
+///
+/// ```
+/// use adobe1a::{numbers::synthesize_numbers, Heading};
+///
+/// let numbers = |headings: &[(&str, &str)]| -> Vec<(String, bool)> {
+///     let mut headings: Vec<Heading> = headings.iter()
+///         .map(|&(level, text)| Heading { level: level.into(), text: text.into(), page: 1, ..Default::default() })
+///         .collect();
+///     synthesize_numbers(&mut headings);
+///     headings.into_iter().map(|h| (h.number.unwrap(), h.synthetic)).collect()
+/// };
+/// let made_up = |numbers: &[&str]| -> Vec<(String, bool)> { numbers.iter().map(|n| (n.to_string(), true)).collect() };
+///
+/// assert_eq!(numbers(&[("H1", "Introduction"), ("H2", "Scope"), ("H2", "Terms"), ("H1", "Methods"), ("H2", "Sampling")]),
+///            made_up(&["1", "1.1", "1.2", "2", "2.1"]));
+///
+/// // A skipped level numbers as the next one down, and a return to a
+/// // shallower level closes everything deeper
+/// assert_eq!(numbers(&[("H1", "Introduction"), ("H3", "Terms"), ("H3", "Units"), ("H2", "Scope"), ("H4", "Limits"), ("H1", "Methods")]),
+///            made_up(&["1", "1.1", "1.2", "1.3", "1.3.1", "2"]));
+///
+/// // A document that opens below H1 numbers its first headings at the top
+/// assert_eq!(numbers(&[("H2", "Background"), ("H3", "History"), ("H2", "Aims"), ("H1", "Methods"), ("H2", "Sampling")]),
+///            made_up(&["1", "1.1", "2", "3", "3.1"]));
+///
+/// // Printed numbers win, and the headings between them continue their sequence
+/// let mixed = numbers(&[("H1", "1. Introduction"), ("H2", "Scope"), ("H1", "Chapter 2: Methods"), ("H2", "2.1 Sampling"),
+///                       ("H2", "Weighting"), ("H1", "Results"), ("H1", "Appendix B Tables"), ("H1", "Glossary")]);
+/// assert_eq!(mixed, [("1", false), ("1.1", true), ("2", false), ("2.1", false), ("2.2", true), ("3", true), ("B", false), ("C", true)]
+///     .map(|(number, synthetic)| (number.to_string(), synthetic)));
+///
+/// // Letters and roman numerals continue in their own scheme, and headings
+/// // under them extend their numbers
+/// let schemes = numbers(&[("H1", "IV. Results"), ("H2", "Costs"), ("H1", "Discussion"), ("H2", "b) Risks"), ("H2", "Staffing")]);
+/// assert_eq!(schemes, [("IV", false), ("IV.1", true), ("V", true), ("b", false), ("c", true)]
+///     .map(|(number, synthetic)| (number.to_string(), synthetic)));
+/// ```
+pub fn synthesize_numbers(headings: &mut [Heading]) {
+    let mut open: Vec<Open> = Vec::new();
+    let mut top = Open { depth: 0, number: Vec::new(), last_child: None, children: 0 };
+    for heading in headings {
+        let depth = heading.depth();
+        while open.last().is_some_and(|parent| parent.depth >= depth) {
+            open.pop();
+        }
+        let parent = open.last_mut().unwrap_or(&mut top);
+        parent.children += 1;
+
+        let printed = printed_number(&heading.text);
+        heading.synthetic = printed.is_none();
+        let number = printed.unwrap_or_else(|| {
+            let continued = parent.last_child.as_ref().and_then(|previous| {
+                let (last, rest) = previous.split_last()?;
+                Some([rest, &[next_in_sequence(last)?]].concat())
+            });
+            continued.unwrap_or_else(|| [&parent.number[..], &[parent.children.to_string()]].concat())
+        });
+        parent.last_child = Some(number.clone());
+        heading.number = Some(number.join("."));
+        open.push(Open { depth, number, last_child: None, children: 0 });
+    }
+}
+
+// The number printed in front of a heading's text, split at its periods
+pub(crate) fn printed_number(text: &str) -> Option<Vec<String>> {
+    let text = functions::strip_list_marker(text.trim()).0;
+    let number = if let Some(section) = SECTION_HEADING.captures(text) {
+        // Spelled-out numbers ("Chapter Three") aren't kept as printed
+        let number = &section[2];
+        let numeral = number.chars().all(|c| c.is_ascii_digit() || c == '.') || functions::roman_value(number).is_some() || number.len() == 1;
+        numeral.then(|| number.to_string())
+    } else if let Some(appendix) = APPENDIX_HEADING.captures(text) {
+        Some(appendix[1].to_string())
+    } else if let Some(decimal) = SEQUENCE_NUMBER.captures(text) {
+        Some(decimal[1].to_string())
+    } else {
+        ENUMERATION.captures(text).map(|enumeration| enumeration[1].to_string())
+    };
+    number.map(|number| number.split('.').map(str::to_string).collect())
+}
+
+// The number after `part` in its own scheme: "4" after "3", "V" after "IV",
+// "c" after "b". A lone "I" is taken for a numeral.
+pub(crate) fn next_in_sequence(part: &str) -> Option<String> {
+    if let Ok(value) = part.parse::<u32>() {
+        return Some((value + 1).to_string());
+    }
+    let upper = part.chars().all(|c| c.is_ascii_uppercase());
+    let numeral = functions::roman_value(part).filter(|_| part.len() > 1 || part.eq_ignore_ascii_case("i"));
+    if let Some(value) = numeral {
+        let next = functions::to_roman(value + 1);
+        return Some(if upper { next.to_uppercase() } else { next });
+    }
+    match part.as_bytes() {
+        [letter] if letter.is_ascii_alphabetic() && !letter.eq_ignore_ascii_case(&b'z') => Some(((letter + 1) as char).to_string()),
+        _ => None,
+    }
+}
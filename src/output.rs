@@ -0,0 +1,307 @@
+use std::io::IsTerminal;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use crate::{Heading, Outline};
+
+// How object keys are spelled in JSON output. Field names are snake_case in
+// the code; camelCase is a rewrite of the serialized keys, so every nested
+// object (headings, warnings, stats, summary records) follows along.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum KeyStyle {
+    #[default]
+    Snake,
+    Camel,
+}
+
+pub fn to_json<T: Serialize>(value: &T, style: KeyStyle) -> anyhow::Result<String> {
+    Ok(match style {
+        KeyStyle::Snake => serde_json::to_string_pretty(value)?,
+        KeyStyle::Camel => {
+            let mut value = serde_json::to_value(value)?;
+            rename_keys(&mut value, &camel_case);
+            serde_json::to_string_pretty(&value)?
+        }
+    })
+}
+
+// The order headings are written in. Outlines are built and kept in
+// document order, which nesting depends on; the others are applied only as
+// an outline is serialized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortOrder {
+    #[default]
+    Document,
+    // Most confident first, for triage
+    Confidence,
+    // H1s first, then H2s and so on, each level by page
+    Level,
+}
+
+impl SortOrder {
+    // Indices into `headings` in this order; ties go to the earlier page,
+    // then to document order
+    pub fn order(self, headings: &[Heading]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..headings.len()).collect();
+        match self {
+            SortOrder::Document => {}
+            SortOrder::Confidence => order.sort_by(|&a, &b| {
+                headings[b].confidence.total_cmp(&headings[a].confidence)
+                    .then(headings[a].page.cmp(&headings[b].page))
+                    .then(a.cmp(&b))
+            }),
+            SortOrder::Level => order.sort_by_key(|&i| (headings[i].depth(), headings[i].page, i)),
+        }
+        order
+    }
+}
+
+/// An outline as pretty-printed JSON with its headings in `sort` order.
+/// The outline itself is left in document order.
+///
+/// ```
+/// use adobe1a::output::{outline_to_json, KeyStyle, SortOrder};
+/// use adobe1a::Outline;
+///
+/// let outline = Outline::from_json_str(r#"{"title": "Guide", "outline": [
+///     {"level": "H1", "text": "1 Setup", "page": 1, "confidence": 0.7},
+///     {"level": "H2", "text": "1.1 Install", "page": 2, "confidence": 0.9},
+///     {"level": "H1", "text": "2 Usage", "page": 3, "confidence": 0.7},
+///     {"level": "H2", "text": "2.1 Running", "page": 3, "confidence": 0.5}
+/// ]}"#)?;
+/// let texts = |sort| -> anyhow::Result<Vec<String>> {
+///     let json = outline_to_json(&outline, KeyStyle::Snake, sort)?;
+///     Ok(Outline::from_json_str(&json)?.outline.into_iter().map(|h| h.text).collect())
+/// };
+/// assert_eq!(texts(SortOrder::Document)?, ["1 Setup", "1.1 Install", "2 Usage", "2.1 Running"]);
+/// // Equal confidences keep document order
+/// assert_eq!(texts(SortOrder::Confidence)?, ["1.1 Install", "1 Setup", "2 Usage", "2.1 Running"]);
+/// assert_eq!(texts(SortOrder::Level)?, ["1 Setup", "2 Usage", "1.1 Install", "2.1 Running"]);
+/// assert_eq!(outline.outline[1].text, "1.1 Install");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn outline_to_json(outline: &Outline, style: KeyStyle, sort: SortOrder) -> anyhow::Result<String> {
+    let mut value = serde_json::to_value(outline)?;
+    if let Some(Value::Array(headings)) = value.get_mut("outline") {
+        let mut document = std::mem::take(headings);
+        *headings = sort.order(&outline.outline).into_iter()
+            .map(|i| std::mem::take(&mut document[i]))
+            .collect();
+    }
+    to_json(&value, style)
+}
+
+// The shape an outline is written in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    // Everything the outline holds
+    #[default]
+    Full,
+    // The Adobe India Hackathon Round 1A schema, exactly: see `challenge_json`
+    Adobe1a,
+}
+
+#[derive(Serialize)]
+struct ChallengeOutline<'a> {
+    title: &'a str,
+    outline: Vec<ChallengeHeading<'a>>,
+}
+
+#[derive(Serialize)]
+struct ChallengeHeading<'a> {
+    level: &'static str,
+    text: &'a str,
+    page: usize,
+}
+
+/// An outline in the Round 1A challenge's schema and nothing more: the
+/// title, and for each heading its level, text and page (from 1), with its
+/// headings in `sort` order. Levels below H3 are written as H3, the deepest
+/// the schema has. Every other field is left out, whatever was extracted,
+/// and the keys are the schema's whatever the key style.
+///
+/// ```
+/// use adobe1a::output::{challenge_json, SortOrder};
+/// use adobe1a::Outline;
+///
+/// let outline = Outline::from_json_str(r#"{"title": "Guide", "outline": [
+///     {"level": "H1", "text": "1 Setup", "page": 1, "confidence": 0.7, "snippet": "Unpack the kit."},
+///     {"level": "H4", "text": "1.1.1.1 Cables", "page": 2, "confidence": 0.9, "number": "1.1.1.1"}
+/// ], "warnings": [{"code": "headings_capped", "message": "..."}], "page_count": 2}"#)?;
+/// let json: serde_json::Value = serde_json::from_str(&challenge_json(&outline, SortOrder::Document)?)?;
+/// assert_eq!(json, serde_json::json!({
+///     "title": "Guide",
+///     "outline": [
+///         {"level": "H1", "text": "1 Setup", "page": 1},
+///         {"level": "H3", "text": "1.1.1.1 Cables", "page": 2}
+///     ]
+/// }));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn challenge_json(outline: &Outline, sort: SortOrder) -> anyhow::Result<String> {
+    let challenge = ChallengeOutline {
+        title: &outline.title,
+        outline: sort.order(&outline.outline).into_iter()
+            .map(|i| &outline.outline[i])
+            .map(|heading| ChallengeHeading {
+                level: match heading.depth() {
+                    1 => "H1",
+                    2 => "H2",
+                    _ => "H3",
+                },
+                text: &heading.text,
+                page: heading.page,
+            })
+            .collect(),
+    };
+    Ok(serde_json::to_string_pretty(&challenge)?)
+}
+
+// Reads output written in either key style
+pub fn from_json<T: DeserializeOwned>(bytes: &[u8]) -> anyhow::Result<T> {
+    let mut value: Value = serde_json::from_slice(bytes)?;
+    rename_keys(&mut value, &snake_case);
+    Ok(serde_json::from_value(value)?)
+}
+
+fn rename_keys(value: &mut Value, rename: &dyn Fn(&str) -> String) {
+    match value {
+        Value::Object(map) => {
+            *map = std::mem::take(map).into_iter()
+                .map(|(key, mut value)| {
+                    rename_keys(&mut value, rename);
+                    (rename(&key), value)
+                })
+                .collect();
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| rename_keys(item, rename)),
+        _ => {}
+    }
+}
+
+// "page_label" -> "pageLabel"; keys without underscores ("H1") are kept
+fn camel_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' && !out.is_empty() {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// "pageLabel" -> "page_label"; keys that don't start lowercase ("H1") are kept
+fn snake_case(key: &str) -> String {
+    if !key.starts_with(|c: char| c.is_lowercase()) {
+        return key.to_string();
+    }
+    let mut out = String::with_capacity(key.len() + 4);
+    for c in key.chars() {
+        if c.is_uppercase() {
+            out.push('_');
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// How a terminal rendering should look
+pub struct TreeStyle {
+    // Total line width in columns
+    pub width: usize,
+    // Color heading text by confidence with ANSI escapes
+    pub color: bool,
+}
+
+impl TreeStyle {
+    // Color and the real width when stdout is a terminal (NO_COLOR turns
+    // color off); 80 plain columns otherwise
+    pub fn for_stdout() -> Self {
+        let stdout = std::io::stdout();
+        if !stdout.is_terminal() {
+            return TreeStyle { width: 80, color: false };
+        }
+        let width = terminal_size::terminal_size_of(&stdout)
+            .map(|(terminal_size::Width(w), _)| w as usize)
+            .unwrap_or(80);
+        TreeStyle { width, color: std::env::var_os("NO_COLOR").is_none() }
+    }
+}
+
+// The outline as an indented tree: the title, then one line per heading
+// nested by level, with its page number right-aligned
+//
+//   Foundation Level Extensions
+//   ├── Revision History                      p. 3
+//   └── Introduction                          p. 5
+//       └── 2.1 Intended Audience             p. 6
+pub fn render_tree(outline: &Outline, style: &TreeStyle) -> String {
+    let mut out = format!("{}\n", outline.title);
+    // A heading is drawn at most one step below the previous one, so an H2
+    // before any H1 (or an H3 straight after an H1) doesn't hang off nothing
+    let mut depths: Vec<usize> = Vec::with_capacity(outline.outline.len());
+    for heading in &outline.outline {
+        let limit = depths.last().map_or(0, |&previous| previous + 1);
+        depths.push((heading.depth() as usize - 1).min(limit));
+    }
+
+    // Whether the most recent heading at each depth has a later sibling,
+    // i.e. whether its vertical rule continues below
+    let mut open: Vec<bool> = Vec::new();
+    for (i, heading) in outline.outline.iter().enumerate() {
+        let d = depths[i];
+        let has_next = depths[i + 1..].iter()
+            .take_while(|&&later| later >= d)
+            .any(|&later| later == d);
+
+        let mut prefix = String::new();
+        for level in 0..d {
+            prefix.push_str(if open.get(level).copied().unwrap_or(false) { "│   " } else { "    " });
+        }
+        prefix.push_str(if has_next { "├── " } else { "└── " });
+        open.resize(d + 1, false);
+        open[d] = has_next;
+
+        let page = format!("p. {}", heading.page);
+        let used = prefix.chars().count() + page.chars().count() + 1;
+        let text = truncate(&heading.text, style.width.saturating_sub(used).max(8));
+        let padding = style.width.saturating_sub(used + text.chars().count());
+
+        out.push_str(&prefix);
+        if style.color {
+            out.push_str(&format!("\x1b[{}m{}\x1b[0m", confidence_color(heading.confidence), text));
+        } else {
+            out.push_str(&text);
+        }
+        out.push_str(&" ".repeat(padding + 1));
+        out.push_str(&page);
+        out.push('\n');
+    }
+    out
+}
+
+// ANSI foreground: green from 0.8, yellow from 0.6, red below
+fn confidence_color(confidence: f64) -> u8 {
+    if confidence >= 0.8 {
+        32
+    } else if confidence >= 0.6 {
+        33
+    } else {
+        31
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", truncated.trim_end())
+}
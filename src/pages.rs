@@ -0,0 +1,181 @@
+use lopdf::{Document, Object};
+
+use crate::trees::TreeWalk;
+
+// Logical page labels ("i", "ii", "A-1", ...) for every physical page, read
+// from the catalog's /PageLabels number tree. Returns None when the document
+// doesn't define labels, so callers can omit them entirely.
+pub fn page_labels(doc: &Document) -> Option<Vec<String>> {
+    page_labels_walk(doc, &mut TreeWalk::default())
+}
+
+// `page_labels`, noting in `walk` any part of the number tree it skipped
+pub fn page_labels_walk(doc: &Document, walk: &mut TreeWalk) -> Option<Vec<String>> {
+    let page_count = doc.get_pages().len();
+    let root = doc.catalog().ok()?.get(b"PageLabels").ok()?;
+
+    let mut ranges = Vec::new();
+    collect_number_tree(doc, root, 0, walk, &mut ranges);
+    if ranges.is_empty() {
+        return None;
+    }
+    ranges.sort_by_key(|(start, _)| *start);
+
+    let mut labels = Vec::with_capacity(page_count);
+    for page_index in 0..page_count {
+        // The applicable range is the last one starting at or before this page
+        let range = ranges.iter().rev().find(|(start, _)| *start <= page_index);
+        labels.push(match range {
+            Some((start, style)) => style.label(page_index - start),
+            // Pages before the first range have no label; fall back to the number
+            None => (page_index + 1).to_string(),
+        });
+    }
+    Some(labels)
+}
+
+// Walk a number tree node, collecting (first page index, label style) pairs
+fn collect_number_tree(doc: &Document, node: &Object, depth: usize, walk: &mut TreeWalk, ranges: &mut Vec<(usize, LabelStyle)>) {
+    if !walk.enter(node, depth) {
+        return;
+    }
+    let Ok((_, node)) = doc.dereference(node) else { return };
+    let Ok(dict) = node.as_dict() else { return };
+
+    if let Ok(nums) = dict.get(b"Nums").and_then(|n| doc.dereference(n)).and_then(|(_, n)| n.as_array()) {
+        for pair in nums.chunks(2) {
+            let [key, value] = pair else { continue };
+            let Ok(start) = key.as_i64() else { continue };
+            let Ok((_, value)) = doc.dereference(value) else { continue };
+            let Ok(label_dict) = value.as_dict() else { continue };
+            if start >= 0 {
+                ranges.push((start as usize, LabelStyle::from_dict(doc, label_dict)));
+            }
+        }
+    }
+
+    if let Ok(kids) = dict.get(b"Kids").and_then(|k| doc.dereference(k)).and_then(|(_, k)| k.as_array()) {
+        for kid in kids {
+            collect_number_tree(doc, kid, depth + 1, walk, ranges);
+        }
+    }
+}
+
+// The document info dictionary's /Title with a 0..1 plausibility score,
+// checked against the text of the first two pages. Junk such as "Microsoft
+// Word - report.docx" scores low rather than being dropped, so it can still
+// be listed among the title candidates.
+pub fn metadata_title(doc: &Document) -> Option<(String, f64)> {
+    let info = doc.trailer.get(b"Info").and_then(|i| doc.dereference(i)).ok()?.1.as_dict().ok()?;
+    let raw = info.get(b"Title").and_then(|t| doc.dereference(t)).ok()?.1.as_str().ok()?;
+    let title = decode_text_string(raw).trim().to_string();
+    if title.is_empty() {
+        return None;
+    }
+
+    let score = crate::titles::metadata_title_score(&title, &opening_text(doc));
+    Some((title, score))
+}
+
+// The text of the first two pages, which a metadata title is checked against
+pub(crate) fn opening_text(doc: &Document) -> String {
+    let opening: Vec<u32> = doc.get_pages().keys().take(2).copied().collect();
+    doc.extract_text(&opening).unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NumberStyle {
+    Decimal,
+    UpperRoman,
+    LowerRoman,
+    UpperAlpha,
+    LowerAlpha,
+    // No /S entry: the label is the prefix alone
+    None,
+}
+
+#[derive(Debug, Clone)]
+struct LabelStyle {
+    style: NumberStyle,
+    prefix: String,
+    start: usize,
+}
+
+impl LabelStyle {
+    fn from_dict(doc: &Document, dict: &lopdf::Dictionary) -> Self {
+        let style = match dict.get(b"S").and_then(|s| s.as_name()) {
+            Ok(b"D") => NumberStyle::Decimal,
+            Ok(b"R") => NumberStyle::UpperRoman,
+            Ok(b"r") => NumberStyle::LowerRoman,
+            Ok(b"A") => NumberStyle::UpperAlpha,
+            Ok(b"a") => NumberStyle::LowerAlpha,
+            _ => NumberStyle::None,
+        };
+        let prefix = dict.get(b"P")
+            .and_then(|p| doc.dereference(p))
+            .and_then(|(_, p)| p.as_str())
+            .map(decode_text_string)
+            .unwrap_or_default();
+        let start = dict.get(b"St")
+            .and_then(|s| s.as_i64())
+            .ok()
+            .filter(|&s| s >= 1)
+            .unwrap_or(1) as usize;
+
+        LabelStyle { style, prefix, start }
+    }
+
+    // Label for the page `offset` pages into this range
+    fn label(&self, offset: usize) -> String {
+        let value = self.start + offset;
+        let number = match self.style {
+            NumberStyle::Decimal => value.to_string(),
+            NumberStyle::UpperRoman => to_roman(value),
+            NumberStyle::LowerRoman => to_roman(value).to_lowercase(),
+            NumberStyle::UpperAlpha => to_alpha(value),
+            NumberStyle::LowerAlpha => to_alpha(value).to_lowercase(),
+            NumberStyle::None => String::new(),
+        };
+        format!("{}{}", self.prefix, number)
+    }
+}
+
+fn to_roman(mut value: usize) -> String {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "M"), (900, "CM"), (500, "D"), (400, "CD"), (100, "C"), (90, "XC"),
+        (50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+    ];
+    let mut out = String::new();
+    for (amount, numeral) in NUMERALS {
+        while value >= amount {
+            out.push_str(numeral);
+            value -= amount;
+        }
+    }
+    out
+}
+
+// The spec's letter numbering: A..Z, then AA..ZZ, then AAA..ZZZ
+fn to_alpha(value: usize) -> String {
+    if value == 0 {
+        return String::new();
+    }
+    let letter = (b'A' + ((value - 1) % 26) as u8) as char;
+    let repeat = (value - 1) / 26 + 1;
+    std::iter::repeat_n(letter, repeat).collect()
+}
+
+// PDF text strings are UTF-16BE when they start with a byte order mark and
+// PDFDocEncoding (close enough to Latin-1 for labels and titles) otherwise
+pub fn decode_text_string(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16.chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.to_string();
+    }
+    bytes.iter().map(|&b| b as char).collect()
+}
@@ -0,0 +1,211 @@
+//! PDF portfolios: a cover PDF whose real content is attached PDFs, listed
+//! in the catalog's /EmbeddedFiles name tree. A single attachment can also
+//! be picked out by name and read in place of the PDF holding it.
+
+use anyhow::Result;
+use lopdf::{Document, Object};
+use regex::Regex;
+
+use crate::config::ExtractorConfig;
+use crate::pages::decode_text_string;
+use crate::timing::{Stage, Stats};
+use crate::trees::TreeWalk;
+use crate::{Heading, Outline, Warning};
+
+/// One PDF attached to a portfolio, with its outline
+#[derive(Debug)]
+pub struct Part {
+    /// The attachment's file name, e.g. "budget.pdf"
+    pub name: String,
+    pub outline: Outline,
+}
+
+/// Whether the catalog marks `doc` as a portfolio (it has a /Collection)
+pub fn is_portfolio(doc: &Document) -> bool {
+    doc.catalog().is_ok_and(|catalog| catalog.has(b"Collection"))
+}
+
+/// The outlines of the PDFs attached to the portfolio in `bytes`, in name
+/// tree order, with a warning for each attachment that isn't a PDF or can't
+/// be read. Both are empty when `bytes` isn't a portfolio.
+pub fn extract_parts(bytes: &[u8], config: &ExtractorConfig, stats: &mut Stats) -> Result<(Vec<Part>, Vec<Warning>)> {
+    let mut doc = stats.time(Stage::LopdfLoad, || Document::load_mem(bytes))?;
+    let tree_warning = crate::trees::repair_page_tree(&mut doc);
+    let (parts, warnings) = parts_of(&doc, config, stats);
+    Ok((parts, tree_warning.into_iter().chain(warnings).collect()))
+}
+
+pub(crate) fn parts_of(doc: &Document, config: &ExtractorConfig, stats: &mut Stats) -> (Vec<Part>, Vec<Warning>) {
+    let mut parts = Vec::new();
+    let mut warnings = Vec::new();
+    if !is_portfolio(doc) {
+        return (parts, warnings);
+    }
+
+    // An attached portfolio is read for its own cover sheet only, so a
+    // portfolio attaching itself can't recurse
+    // The requested pages and title are the cover sheet's, not the attachments'
+    let config = ExtractorConfig {
+        portfolio: crate::config::Portfolio::Ignore,
+        pages: None,
+        title: crate::config::TitleMode::Detect,
+        ..config.clone()
+    };
+    let mut walk = TreeWalk::default();
+    let attachments = attachments(doc, &mut walk);
+    warnings.extend(walk.warning("attachment name tree"));
+    for (name, bytes) in attachments {
+        let Some(bytes) = bytes.filter(|bytes| crate::pdf_from_header(bytes).is_ok()) else {
+            warnings.push(Warning::new("portfolio_attachment_skipped", format!(
+                "attachment {:?} is not a PDF and was skipped", name)));
+            continue;
+        };
+        let stem = name.rsplit_once('.').map_or(name.as_str(), |(stem, _)| stem);
+        match crate::extract_outline_from_bytes(&bytes, stem, &config, stats) {
+            Ok(outline) => parts.push(Part { name, outline }),
+            Err(e) => warnings.push(Warning::new("portfolio_attachment_failed", format!(
+                "attachment {:?} could not be read: {}", name, e))),
+        }
+    }
+    (parts, warnings)
+}
+
+// An attached file picked out by name, with a warning when the name
+// matched others too
+pub(crate) struct Attachment {
+    pub(crate) name: String,
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) warning: Option<Warning>,
+}
+
+// The first attachment of the PDF in `bytes`, in name tree order, whose
+// name matches `pattern`: ignoring case, with `*` standing for any run of
+// characters and `?` for any one. Fails, listing the attachments there
+// are, when none matches, and when the one that does isn't a PDF.
+pub(crate) fn find_attachment(bytes: &[u8], pattern: &str) -> Result<Attachment> {
+    let doc = Document::load_mem(bytes)?;
+    let mut walk = TreeWalk::default();
+    let mut attachments = attachments(&doc, &mut walk);
+    let glob = glob(pattern);
+    let quoted = |names: &mut dyn Iterator<Item = &String>| names.map(|name| format!("{:?}", name)).collect::<Vec<_>>().join(", ");
+    let matching: Vec<usize> = (0..attachments.len()).filter(|&i| glob.is_match(&attachments[i].0)).collect();
+    let Some(&first) = matching.first() else {
+        match attachments.is_empty() {
+            true => anyhow::bail!("no attachment matches {:?}; the PDF has no attachments", pattern),
+            false => anyhow::bail!("no attachment matches {:?}; the PDF's attachments are {}",
+                pattern, quoted(&mut attachments.iter().map(|(name, _)| name))),
+        }
+    };
+    let warning = (matching.len() > 1).then(|| Warning::new("attachment_ambiguous", format!(
+        "{:?} also matches {}; only {:?} was read",
+        pattern, quoted(&mut matching[1..].iter().map(|&i| &attachments[i].0)), attachments[first].0)));
+
+    let (name, bytes) = attachments.swap_remove(first);
+    match bytes.filter(|bytes| crate::pdf_from_header(bytes).is_ok()) {
+        Some(bytes) => Ok(Attachment { name, bytes, warning }),
+        None => anyhow::bail!("attachment {:?} is not a PDF", name),
+    }
+}
+
+// A case-insensitive regex for a file name pattern with `*` and `?`
+fn glob(pattern: &str) -> Regex {
+    let mut regex = String::from("(?i)^");
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).expect("escaped pattern is a valid regex")
+}
+
+// Appends each part to the cover sheet's outline as an H1 named after the
+// attachment, with the part's headings one level below it. Page numbers
+// stay those of the attachment.
+pub(crate) fn combine(outline: &mut Outline, parts: Vec<Part>) {
+    // Headings now count pages within the attachments, which the cover
+    // sheet's page count doesn't bound
+    if !parts.is_empty() {
+        outline.page_count = None;
+    }
+    for part in parts {
+        outline.outline.push(Heading {
+            level: "H1".to_string(),
+            text: part.name.clone(),
+            page: 1,
+            confidence: 1.0,
+            ..Default::default()
+        });
+        outline.outline.extend(part.outline.outline.into_iter().map(|heading| Heading {
+            id: String::new(),
+            ..heading.demoted()
+        }));
+        outline.warnings.extend(part.outline.warnings.into_iter().map(|warning| Warning {
+            message: format!("{}: {}", part.name, warning.message),
+            ..warning
+        }));
+        outline.add_engines(part.outline.engine);
+    }
+}
+
+// (file name, contents) for every entry of the /EmbeddedFiles name tree;
+// contents are None when the file specification has no readable stream
+fn attachments(doc: &Document, walk: &mut TreeWalk) -> Vec<(String, Option<Vec<u8>>)> {
+    let root = doc.catalog().ok()
+        .and_then(|catalog| catalog.get(b"Names").ok())
+        .and_then(|names| doc.dereference(names).ok())
+        .and_then(|(_, names)| names.as_dict().ok())
+        .and_then(|names| names.get(b"EmbeddedFiles").ok());
+    let mut entries = Vec::new();
+    if let Some(root) = root {
+        collect_name_tree(doc, root, 0, walk, &mut entries);
+    }
+    entries.into_iter()
+        .map(|(key, spec)| file_spec(doc, spec).unwrap_or((key, None)))
+        .collect()
+}
+
+// Walk a name tree node, collecting (key, value) pairs in order
+pub(crate) fn collect_name_tree<'a>(doc: &'a Document, node: &'a Object, depth: usize, walk: &mut TreeWalk, entries: &mut Vec<(String, &'a Object)>) {
+    if !walk.enter(node, depth) {
+        return;
+    }
+    let Ok((_, node)) = doc.dereference(node) else { return };
+    let Ok(dict) = node.as_dict() else { return };
+
+    if let Ok(names) = dict.get(b"Names").and_then(|n| doc.dereference(n)).and_then(|(_, n)| n.as_array()) {
+        for pair in names.chunks(2) {
+            let [key, value] = pair else { continue };
+            let Ok(key) = doc.dereference(key).and_then(|(_, key)| key.as_str()) else { continue };
+            entries.push((decode_text_string(key), value));
+        }
+    }
+
+    if let Ok(kids) = dict.get(b"Kids").and_then(|k| doc.dereference(k)).and_then(|(_, k)| k.as_array()) {
+        for kid in kids {
+            collect_name_tree(doc, kid, depth + 1, walk, entries);
+        }
+    }
+}
+
+// A file specification's name (/UF, else /F) and embedded stream contents
+// (/EF, same preference). None when `spec` isn't a file specification.
+fn file_spec(doc: &Document, spec: &Object) -> Option<(String, Option<Vec<u8>>)> {
+    let spec = doc.dereference(spec).ok()?.1.as_dict().ok()?;
+    let name = [&b"UF"[..], b"F"].into_iter()
+        .find_map(|key| spec.get(key).and_then(|n| doc.dereference(n)).and_then(|(_, n)| n.as_str()).ok())
+        .map(decode_text_string)?;
+
+    let files = spec.get(b"EF").and_then(|ef| doc.dereference(ef)).and_then(|(_, ef)| ef.as_dict());
+    let stream = files.ok().and_then(|files| {
+        [&b"UF"[..], b"F"].into_iter()
+            .find_map(|key| files.get(key).and_then(|s| doc.dereference(s)).and_then(|(_, s)| s.as_stream()).ok())
+    });
+    let contents = stream.and_then(|stream| match stream.dict.has(b"Filter") {
+        true => stream.decompressed_content().ok(),
+        false => Some(stream.content.clone()),
+    });
+    Some((name, contents))
+}
@@ -0,0 +1,203 @@
+//! A 0–100 score of how far an extracted outline can be trusted, for
+//! triaging many outputs without opening each one, and the measurable
+//! signals it is made of.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::numbers::{next_in_sequence, printed_number};
+use crate::{functions, round_score, Heading, Outline, TitleSource};
+
+// Confidence at which a heading counts as confidently found
+pub const HIGH_CONFIDENCE: f64 = 0.8;
+
+// Pages a heading's text can recur on before it's taken for a running
+// header or footer rather than a section name used twice ("Summary")
+const RUNNING_TEXT_PAGES: usize = 3;
+
+// A table of contents entry and the heading for it may be this many pages
+// apart, as when a bookmark points at the page before a section opens
+const TOC_PAGE_SLACK: usize = 1;
+
+// Share of the score each signal carries when it applies
+const WEIGHTS: Weights = Weights { confidence: 30.0, numbering: 20.0, hierarchy: 15.0, toc_agreement: 15.0, running_text: 10.0, title: 10.0 };
+
+struct Weights {
+    confidence: f64,
+    numbering: f64,
+    hierarchy: f64,
+    toc_agreement: f64,
+    running_text: f64,
+    title: f64,
+}
+
+/// The signals behind an outline's `quality_score`, each from 0 (worst) to
+/// 1 (best), or None where the signal doesn't apply to the document
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QualityBreakdown {
+    // Share of headings at least HIGH_CONFIDENCE confident
+    pub confidence: Option<f64>,
+    // Share of the printed section numbers after the first that continue
+    // the one before them ("2.3" after "2.2", "3" or "2.2.1"); None with
+    // fewer than two
+    pub numbering: Option<f64>,
+    // Share of headings that don't skip a level on the way down, which a
+    // reader would have to repair (an H3 after an H1, or at the start)
+    pub hierarchy: Option<f64>,
+    // Share of the table of contents' entries, on the pages read, found
+    // among the headings; None without one
+    pub toc_agreement: Option<f64>,
+    // Share of headings that aren't a running header or footer let
+    // through: text recurring on RUNNING_TEXT_PAGES pages or more
+    pub running_text: Option<f64>,
+    // 1 for a title found in the document or given by the caller, 0.5 for
+    // the document info title, 0 for one made up from the file name
+    pub title: f64,
+}
+
+impl QualityBreakdown {
+    /// The weighted score, from 0 to 100. The signals weigh in as:
+    /// confidence 30, numbering 20, hierarchy 15, ToC agreement 15, running
+    /// text 10, title 10. Signals that don't apply are left out and the
+    /// others scaled up to fill the 100. An outline without headings scores 0.
+    pub fn score(&self) -> u8 {
+        if self.confidence.is_none() {
+            return 0;
+        }
+        let signals = [
+            (self.confidence, WEIGHTS.confidence),
+            (self.numbering, WEIGHTS.numbering),
+            (self.hierarchy, WEIGHTS.hierarchy),
+            (self.toc_agreement, WEIGHTS.toc_agreement),
+            (self.running_text, WEIGHTS.running_text),
+            (Some(self.title), WEIGHTS.title),
+        ];
+        let (total, weights) = signals.iter()
+            .filter_map(|&(value, weight)| Some((value? * weight, weight)))
+            .fold((0.0, 0.0), |(total, weights), (value, weight)| (total + value, weights + weight));
+        (100.0 * total / weights).round().clamp(0.0, 100.0) as u8
+    }
+}
+
+/// Measures how trustworthy `outline` looks. `toc` is the document's own
+/// table of contents, such as the PDF's bookmarks, as headings; pass an
+/// empty slice when there is none. The same outline always gets the same
+/// breakdown; values are rounded to three decimals.
+///
+/// ```
+/// use adobe1a::{quality::assess, Heading, Outline, TitleSource};
+///
+/// let heading = |level: &str, text: &str, page, confidence| Heading {
+///     level: level.into(), text: text.into(), page, confidence, ..Default::default()
+/// };
+/// let good = Outline {
+///     title: "Annual Report".into(),
+///     outline: vec![
+///         heading("H1", "1. Introduction", 1, 0.95), heading("H2", "1.1 Scope", 2, 0.9),
+///         heading("H2", "1.2 Terms", 2, 0.9), heading("H1", "2. Results", 3, 0.95), heading("H1", "3. Outlook", 5, 0.85),
+///     ],
+///     ..Default::default()
+/// };
+/// let toc = [heading("H1", "Introduction", 1, 1.0), heading("H1", "Results", 3, 1.0), heading("H1", "Outlook", 4, 1.0)];
+/// let quality = assess(&good, &toc);
+/// assert_eq!(quality.score(), 100);
+/// assert_eq!((quality.numbering, quality.toc_agreement), (Some(1.0), Some(1.0)));
+///
+/// // Doubtful headings, a running header on every page, broken numbering,
+/// // a skipped level and a title made from the file name
+/// let bad = Outline {
+///     title: "scan_0042".into(),
+///     title_source: TitleSource::Filename,
+///     outline: vec![
+///         heading("H2", "Confidential Draft", 1, 0.5), heading("H1", "1. Introduction", 1, 0.9),
+///         heading("H3", "1.7 Scope", 1, 0.45), heading("H2", "Confidential Draft", 2, 0.5),
+///         heading("H2", "Confidential Draft", 3, 0.5), heading("H1", "2. Results", 3, 0.6),
+///     ],
+///     ..Default::default()
+/// };
+/// let quality = assess(&bad, &[]);
+/// assert_eq!(quality.confidence, Some(0.167));
+/// assert_eq!(quality.numbering, Some(0.5));
+/// assert_eq!(quality.hierarchy, Some(0.667));
+/// assert_eq!(quality.running_text, Some(0.5));
+/// assert_eq!((quality.toc_agreement, quality.title), (None, 0.0));
+/// assert_eq!(quality.score(), 35);
+///
+/// // Against a table of contents, entries are matched without their
+/// // numbering, on the same page or the next
+/// let toc = [heading("H1", "Introduction", 1, 1.0), heading("H1", "Methods", 2, 1.0), heading("H1", "Discussion", 4, 1.0)];
+/// assert_eq!(assess(&bad, &toc).toc_agreement, Some(0.333));
+/// let toc = [heading("H1", "Methods", 2, 1.0), heading("H1", "Discussion", 4, 1.0)];
+/// assert_eq!((assess(&bad, &toc).toc_agreement, assess(&bad, &toc).score()), (Some(0.0), 30));
+///
+/// assert_eq!(assess(&Outline { title: "Memo".into(), ..Default::default() }, &[]).score(), 0);
+/// ```
+pub fn assess(outline: &Outline, toc: &[Heading]) -> QualityBreakdown {
+    let headings = &outline.outline;
+    let share = |count: usize, of: usize| (of > 0).then(|| round_score(count as f64 / of as f64));
+
+    let confident = headings.iter().filter(|heading| heading.confidence >= HIGH_CONFIDENCE).count();
+
+    let numbers: Vec<Vec<String>> = headings.iter().filter_map(|heading| printed_number(&heading.text)).collect();
+    let continued = numbers.windows(2).filter(|pair| continues(&pair[0], &pair[1])).count();
+
+    let mut previous_depth = 0;
+    let mut jumps = 0;
+    for heading in headings {
+        let depth = heading.depth();
+        if depth > previous_depth + 1 {
+            jumps += 1;
+        }
+        previous_depth = depth;
+    }
+
+    let lang = outline.language.as_deref();
+    let mut pages_by_key: HashMap<String, HashSet<usize>> = HashMap::new();
+    for heading in headings {
+        pages_by_key.entry(functions::dedupe_key(&heading.text, lang)).or_default().insert(heading.page);
+    }
+    let running = headings.iter()
+        .filter(|heading| pages_by_key[&functions::dedupe_key(&heading.text, lang)].len() >= RUNNING_TEXT_PAGES)
+        .count();
+
+    let found = toc.iter()
+        .filter(|entry| {
+            let key = toc_key(&entry.text, lang);
+            headings.iter().any(|heading| heading.page.abs_diff(entry.page) <= TOC_PAGE_SLACK && toc_key(&heading.text, lang) == key)
+        })
+        .count();
+
+    QualityBreakdown {
+        confidence: share(confident, headings.len()),
+        numbering: share(continued, numbers.len().saturating_sub(1)),
+        hierarchy: share(headings.len() - jumps, headings.len()),
+        toc_agreement: share(found, toc.len()),
+        running_text: share(headings.len() - running, headings.len()),
+        title: match outline.title_source {
+            TitleSource::Content | TitleSource::Override => 1.0,
+            TitleSource::Metadata => 0.5,
+            TitleSource::Filename => 0.0,
+        },
+    }
+}
+
+// Whether `next` can follow `previous` in one numbering: as its first
+// child, its next sibling or the next sibling of one of its parents
+fn continues(previous: &[String], next: &[String]) -> bool {
+    let first_child = next.len() == previous.len() + 1
+        && next.starts_with(previous)
+        && matches!(next[previous.len()].as_str(), "1" | "a" | "A" | "i" | "I");
+    first_child || (1..=previous.len()).any(|depth| {
+        next.len() == depth
+            && next[..depth - 1] == previous[..depth - 1]
+            && next_in_sequence(&previous[depth - 1]).is_some_and(|following| following == next[depth - 1])
+    })
+}
+
+// Heading text without its numbering, punctuation or case, for matching
+// table of contents entries that leave the numbers out
+fn toc_key(text: &str, lang: Option<&str>) -> String {
+    functions::dedupe_key(text, lang).chars().filter(|c| c.is_alphanumeric()).collect()
+}
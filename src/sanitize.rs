@@ -0,0 +1,387 @@
+// Text as it leaves the extractor. Decoded PDF strings carry NULs and other
+// control characters (a UTF-16 string read a byte at a time is half NULs),
+// which JSON escapes but downstream tools choke on.
+
+use crate::config::CaseStyle;
+use crate::functions::roman_value;
+use crate::lexicon::Lexicon;
+use crate::Outline;
+
+pub use crate::functions::clean_heading_text;
+
+/// `text` with control characters removed, noncharacters replaced by U+FFFD
+/// and runs of whitespace collapsed to single spaces, trimmed. Tabs and line
+/// breaks are whitespace like any other, since every field this is applied
+/// to is a single line.
+///
+/// Controls are dropped rather than turned into spaces, because they mostly
+/// sit inside words: "R\0e\0p\0o\0r\0t" is "Report". Unpaired surrogates
+/// can't occur in a Rust string; lossy UTF-16 decoding has already turned
+/// them into U+FFFD.
+///
+/// ```
+/// use adobe1a::sanitize::sanitize_text;
+///
+/// // C0 controls, DEL and C1 controls go
+/// assert_eq!(sanitize_text("R\0e\0p\0o\0r\0t"), "Report");
+/// assert_eq!(sanitize_text("\u{1}Scope\u{1f}\u{7f}"), "Scope");
+/// assert_eq!(sanitize_text("Re\u{80}sults\u{9f}"), "Results");
+/// assert_eq!(sanitize_text("\u{1b}[1mBold"), "[1mBold");
+/// // Tabs, line breaks and other whitespace separate words
+/// assert_eq!(sanitize_text("\tGoals\r\nand\u{b}\u{c}Scope\n"), "Goals and Scope");
+/// assert_eq!(sanitize_text("Annex\u{a0}\u{2003}A\u{85}Terms"), "Annex A Terms");
+/// // Noncharacters become the replacement character
+/// assert_eq!(sanitize_text("Caf\u{fffe}"), "Caf\u{fffd}");
+/// assert_eq!(sanitize_text("\u{ffff}\u{fdd0}\u{1fffe}"), "\u{fffd}\u{fffd}\u{fffd}");
+/// // Everything else is kept
+/// assert_eq!(sanitize_text("Résumé – 第1章 \u{fffd}"), "Résumé – 第1章 \u{fffd}");
+/// assert_eq!(sanitize_text("\0\u{1}\t"), "");
+/// assert_eq!(sanitize_text(""), "");
+/// ```
+///
+/// Extraction applies it to the title, headings, page labels, snippets,
+/// title candidates, document titles and metadata:
+///
+/// ```
+/// use adobe1a::{config::ExtractorConfig, extract_outline_from_bytes, output, timing::Stats};
+///
+/// // The headings, bookmarks and info title are littered with NULs
+/// let bytes = include_bytes!("../fixtures/nul-heading.pdf");
+/// for config in [ExtractorConfig::default(), ExtractorConfig { quick: Some(15), ..ExtractorConfig::default() }] {
+///     let outline = extract_outline_from_bytes(bytes, "fixture", &config, &mut Stats::new())?;
+///     let json = output::to_json(&outline, Default::default())?;
+///     assert!(!json.contains("\\u0000") && !json.contains('\0'));
+///     assert!(outline.outline.iter().any(|heading| heading.text.ends_with("Introduction")));
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn sanitize_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut pending_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            pending_space = !out.is_empty();
+            continue;
+        }
+        if c.is_control() {
+            continue;
+        }
+        if pending_space {
+            out.push(' ');
+            pending_space = false;
+        }
+        out.push(if is_noncharacter(c) { char::REPLACEMENT_CHARACTER } else { c });
+    }
+    out
+}
+
+// U+FDD0..U+FDEF and the last two code points of every plane, which Unicode
+// reserves for internal use and never assigns
+fn is_noncharacter(c: char) -> bool {
+    let c = c as u32;
+    (0xFDD0..=0xFDEF).contains(&c) || c & 0xFFFE == 0xFFFE
+}
+
+/// Text reduced to what decides whether two headings say the same thing:
+/// whitespace collapsed to single spaces and letters case-folded, so that
+/// "Straße" and "STRASSE" match. Every comparison of heading text in the
+/// crate goes through this: deduplication, [`crate::Outline::find`],
+/// quality scoring, lint, merging and reliability tables.
+///
+/// `lang` is the document's language as a BCP 47 tag ("tr-TR"). Turkish
+/// and Azerbaijani fold capital "I" to dotless "ı", as their lowercase
+/// does. In every language, dotted capital "İ" folds to a plain "i" rather
+/// than to an "i" with a combining dot, so a Turkish heading still matches
+/// when the document's language isn't known.
+///
+/// ```
+/// use adobe1a::sanitize::normalize_for_match;
+///
+/// let same = |a: &str, b: &str, lang| normalize_for_match(a, lang) == normalize_for_match(b, lang);
+/// // Turkish dotted and dotless I
+/// assert!(same("İÇİNDEKİLER", "içindekiler", None));
+/// assert!(same("İÇİNDEKİLER", "içindekiler", Some("tr")));
+/// assert!(same("KILAVUZ", "kılavuz", Some("tr-TR")));
+/// assert!(same("KILAVUZ", "kılavuz", Some("az")));
+/// assert!(!same("KILAVUZ", "kılavuz", None));
+/// assert!(!same("KILAVUZ", "kilavuz", Some("tr")));
+/// assert_eq!(normalize_for_match("BAŞLIK", Some("tr")), "başlık");
+/// assert_eq!(normalize_for_match("I\u{307}ZMIR", Some("tr")), "izmır");
+/// // German sharp s, in either case, and other letters that fold to two
+/// assert!(same("Straße", "STRASSE", None));
+/// assert!(same("GROẞE", "große", None));
+/// assert!(same("Oﬃce", "office", None));
+/// assert_eq!(normalize_for_match("ΣΚΟΠΟΣ", None), normalize_for_match("σκοπος", None));
+/// // Whitespace and case, as anywhere else
+/// assert_eq!(normalize_for_match("  Project\tSCOPE \n", None), "project scope");
+/// ```
+pub fn normalize_for_match(text: &str, lang: Option<&str>) -> String {
+    let turkic = lang
+        .and_then(|tag| tag.split(['-', '_']).next())
+        .is_some_and(|primary| primary.eq_ignore_ascii_case("tr") || primary.eq_ignore_ascii_case("az"));
+    let mut out = String::with_capacity(text.len());
+    for word in text.split_whitespace() {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        let mut chars = word.chars().peekable();
+        while let Some(c) = chars.next() {
+            // "I" with a combining dot above is "İ" decomposed
+            let dotted = matches!(c, 'I' | 'i') && chars.next_if_eq(&'\u{307}').is_some();
+            match c {
+                'I' if turkic && !dotted => out.push('ı'),
+                'İ' => out.push('i'),
+                'ß' | 'ẞ' => out.push_str("ss"),
+                'ς' => out.push('σ'),
+                'ſ' => out.push('s'),
+                'ﬀ' => out.push_str("ff"),
+                'ﬁ' => out.push_str("fi"),
+                'ﬂ' => out.push_str("fl"),
+                'ﬃ' => out.push_str("ffi"),
+                'ﬄ' => out.push_str("ffl"),
+                'ﬅ' | 'ﬆ' => out.push_str("st"),
+                _ => out.extend(c.to_lowercase()),
+            }
+        }
+    }
+    out
+}
+
+// Letters in the longest word of a mixed-case heading still kept in
+// capitals as an acronym ("NASA", "GDPR"); a longer one is shouting
+const MAX_ACRONYM_LETTERS: usize = 5;
+
+/// `text` recased in `style`, with `stopwords` (lowercase) kept lowercase in
+/// title case. See [`title_case`] and [`sentence_case`]; upper case is
+/// Unicode's.
+pub fn normalize_case(text: &str, style: CaseStyle, stopwords: &[String]) -> String {
+    match style {
+        CaseStyle::Preserve => text.to_string(),
+        CaseStyle::Title => title_case(text, stopwords),
+        CaseStyle::Sentence => sentence_case(text),
+        CaseStyle::Upper => text.to_uppercase(),
+    }
+}
+
+/// `text` in title case: every word capitalized except `stopwords`
+/// (lowercase), which are lowercased unless they're the first or last word
+/// or open a subtitle after a colon. Each part of a hyphenated word is
+/// treated as a word, except after a single letter ("E-mail").
+///
+/// Words that read as acronyms or numerals keep their capitals. In text
+/// with lowercase letters, those are words of up to five letters all in
+/// capitals, and words with a capital inside ("iPhone", "McKinsey") are
+/// left alone too. Text set all in capitals says nothing about any word, so
+/// only words without vowels ("PDF", "HTML") stay. Roman numerals and a
+/// single letter labelling a list item ("A.", "B)") stay in either.
+///
+/// ```
+/// use adobe1a::lexicon::Lexicon;
+/// use adobe1a::sanitize::title_case;
+///
+/// let stopwords = Lexicon::default().stopwords;
+/// for (text, expected) in [
+///     // From capitals, sentence case and title case alike
+///     ("RISK MANAGEMENT FOR SMALL TEAMS", "Risk Management for Small Teams"),
+///     ("Risk management for small teams", "Risk Management for Small Teams"),
+///     ("Risk Management for Small Teams", "Risk Management for Small Teams"),
+///     ("risk management for small teams", "Risk Management for Small Teams"),
+///     // Unicode letters, and French articles among the stopwords
+///     ("ÉVALUATION DES RISQUES", "Évaluation des Risques"),
+///     ("ÄNDERUNGSÜBERSICHT", "Änderungsübersicht"),
+///     ("ΕΙΣΑΓΩΓΗ ΣΤΙΣ ΜΕΘΟΔΟΥΣ", "Εισαγωγη Στις Μεθοδους"),
+///     ("ОБЗОР РЫНКА", "Обзор Рынка"),
+///     ("第1章 概要", "第1章 概要"),
+///     // First and last words, and the first after a colon, are capitalized
+///     ("THE END OF THE LINE", "The End of the Line"),
+///     ("WHAT TO LOOK FOR", "What to Look For"),
+///     ("APPENDIX A: THE DATA WE USED", "Appendix A: The Data We Used"),
+///     ("a guide to the galaxy", "A Guide to the Galaxy"),
+///     // Hyphenated words
+///     ("STATE-OF-THE-ART METHODS", "State-of-the-Art Methods"),
+///     ("long-term planning", "Long-Term Planning"),
+///     ("E-MAIL AND X-RAY POLICY", "E-mail and X-ray Policy"),
+///     ("UP-TO-DATE RECORDS", "Up-to-Date Records"),
+///     // Acronyms in mixed-case text
+///     ("Guidelines from the WHO and NASA", "Guidelines from the WHO and NASA"),
+///     ("Using the GDPR in practice", "Using the GDPR in Practice"),
+///     ("IMPORTANT notice to members", "Important Notice to Members"),
+///     ("Rolling out iPhone support at McKinsey", "Rolling Out iPhone Support at McKinsey"),
+///     // Acronyms in text all in capitals
+///     ("EXPORTING TO PDF AND HTML", "Exporting to PDF and HTML"),
+///     ("3D PRINTING", "3D Printing"),
+///     // which can't tell an acronym with a vowel from a word
+///     ("THE API GATEWAY", "The Api Gateway"),
+///     // Numbers, numerals and list labels
+///     ("1.2 SCOPE OF WORK", "1.2 Scope of Work"),
+///     ("PART IV: RESULTS", "Part IV: Results"),
+///     ("CHAPTER XII: THE RETURN", "Chapter XII: The Return"),
+///     ("A. BACKGROUND", "A. Background"),
+///     ("B) a note on terms", "B) A Note on Terms"),
+///     ("THE 2ND QUARTER", "The 2nd Quarter"),
+///     // Punctuation and apostrophes
+///     ("\"QUOTED\" HEADINGS (DRAFT)", "\"Quoted\" Headings (Draft)"),
+///     ("D'ÉVALUATION ET L'AVENIR", "D'évaluation et L'avenir"),
+///     ("WHAT'S NEW?", "What's New?"),
+///     ("OVERVIEW — GOALS AND SCOPE", "Overview — Goals and Scope"),
+///     ("", ""),
+///     ("2024", "2024"),
+/// ] {
+///     assert_eq!(title_case(text, &stopwords), expected, "{:?}", text);
+/// }
+/// ```
+pub fn title_case(text: &str, stopwords: &[String]) -> String {
+    recase(text, |word| if word.starts || word.last || !stopwords.contains(&word.bare.to_lowercase()) {
+        Case::Capitalized
+    } else {
+        Case::Lower
+    })
+}
+
+/// `text` in sentence case: the first word capitalized, and the first after
+/// a colon, and every other word lowercased. Acronyms, numerals and list
+/// labels keep their capitals as in [`title_case`]. Proper nouns can't be
+/// told apart, so they're lowercased too.
+///
+/// ```
+/// use adobe1a::sanitize::sentence_case;
+///
+/// for (text, expected) in [
+///     ("RISK MANAGEMENT FOR SMALL TEAMS", "Risk management for small teams"),
+///     ("Risk Management for Small Teams", "Risk management for small teams"),
+///     ("risk management for small teams", "Risk management for small teams"),
+///     ("ÉVALUATION DES RISQUES", "Évaluation des risques"),
+///     ("ΕΙΣΑΓΩΓΗ ΣΤΙΣ ΜΕΘΟΔΟΥΣ", "Εισαγωγη στις μεθοδους"),
+///     ("APPENDIX A: THE DATA WE USED", "Appendix A: The data we used"),
+///     ("STATE-OF-THE-ART METHODS", "State-of-the-art methods"),
+///     ("Guidelines From The WHO And NASA", "Guidelines from the WHO and NASA"),
+///     ("Rolling Out iPhone Support", "Rolling out iPhone support"),
+///     ("EXPORTING TO PDF", "Exporting to PDF"),
+///     ("1.2 SCOPE OF WORK", "1.2 Scope of work"),
+///     ("PART IV RESULTS", "Part IV results"),
+///     ("A. BACKGROUND", "A. Background"),
+///     ("", ""),
+/// ] {
+///     assert_eq!(sentence_case(text), expected, "{:?}", text);
+/// }
+/// ```
+pub fn sentence_case(text: &str) -> String {
+    recase(text, |word| if word.starts { Case::Capitalized } else { Case::Lower })
+}
+
+enum Case {
+    Capitalized,
+    Lower,
+}
+
+// A word, or a part of a hyphenated one, as the casing rules see it
+struct Word<'a> {
+    // Its letters and digits, without punctuation around them
+    bare: &'a str,
+    // The first word, or the first after a colon
+    starts: bool,
+    // The last word with letters
+    last: bool,
+}
+
+// `text` with each word cased as `case` says, except for words whose
+// capitals carry meaning
+fn recase(text: &str, case: impl Fn(&Word) -> Case) -> String {
+    // Text set all in capitals, where capitals don't mark acronyms
+    let shouting = !text.chars().any(char::is_lowercase);
+    let words: Vec<&str> = text.split(' ').collect();
+    let last = words.iter().rposition(|word| word.chars().any(char::is_alphabetic));
+    let mut starts = true;
+    let mut out = Vec::with_capacity(words.len());
+    for (i, &word) in words.iter().enumerate() {
+        if !word.chars().any(char::is_alphabetic) {
+            out.push(word.to_string());
+            continue;
+        }
+        let parts: Vec<&str> = word.split('-').collect();
+        let cased: Vec<String> = parts.iter().enumerate()
+            .map(|(j, &part)| {
+                let bare = part.trim_matches(|c: char| !c.is_alphanumeric());
+                if keeps_capitals(part, bare, shouting) {
+                    return part.to_string();
+                }
+                let word = Word { bare, starts: starts && j == 0, last: Some(i) == last && j + 1 == parts.len() };
+                // "E-mail", "X-ray"
+                let after_letter = j > 0 && parts[j - 1].chars().filter(|c| c.is_alphabetic()).count() == 1;
+                match case(&word) {
+                    Case::Capitalized if !after_letter => capitalize(part),
+                    _ => part.to_lowercase(),
+                }
+            })
+            .collect();
+        out.push(cased.join("-"));
+        // A subtitle, or the text after a list label, starts afresh
+        starts = word.ends_with(':') || is_label(word);
+    }
+    out.join(" ")
+}
+
+// A single letter labelling a list item or appendix ("A.", "B)", "C:")
+fn is_label(word: &str) -> bool {
+    let mut chars = word.chars();
+    matches!((chars.next(), chars.next(), chars.next()), (Some(letter), Some('.' | ')' | ':'), None) if letter.is_alphabetic())
+}
+
+// Whether a word's capitals are kept as they are
+fn keeps_capitals(part: &str, bare: &str, shouting: bool) -> bool {
+    let letters: Vec<char> = bare.chars().filter(|c| c.is_alphabetic()).collect();
+    let capitals = letters.iter().all(|c| c.is_uppercase());
+    if !capitals {
+        // A capital after the first letter: a name like "iPhone"
+        return !shouting && letters.iter().skip(1).any(|c| c.is_uppercase());
+    }
+    // Ordinals ("2ND") are lowercase whatever the text around them
+    let suffix = bare.trim_start_matches(|c: char| c.is_ascii_digit());
+    if suffix.len() < bare.len() && ["ST", "ND", "RD", "TH"].contains(&suffix) {
+        return false;
+    }
+    if is_label(part) || (letters.len() == bare.chars().count() && roman_value(bare).is_some()) {
+        return true;
+    }
+    letters.len() <= MAX_ACRONYM_LETTERS && match shouting {
+        // Only a word that can't be a word ("PDF") reads as an acronym
+        true => letters.iter().all(|c| c.is_ascii_alphabetic() && !"AEIOUY".contains(*c)),
+        false => letters.len() > 1,
+    }
+}
+
+// The first letter in capitals and the rest in lowercase; a word led by a
+// digit ("2nd") is all lowercase
+fn capitalize(word: &str) -> String {
+    let Some(first) = word.find(char::is_alphanumeric).filter(|&i| !word[i..].starts_with(|c: char| c.is_numeric())) else {
+        return word.to_lowercase();
+    };
+    let letter = word[first..].chars().next().unwrap();
+    let rest = &word[first + letter.len_utf8()..];
+    format!("{}{}{}", &word[..first], letter.to_uppercase(), rest.to_lowercase())
+}
+
+// Every piece of text in `outline` that ends up in the output. A title with
+// nothing left falls back to one made from `name`, as when none is found.
+pub(crate) fn sanitize_outline(outline: &mut Outline, name: &str, lexicon: &Lexicon) {
+    outline.title = sanitize_text(&outline.title);
+    if outline.title.is_empty() {
+        (outline.title, outline.title_source) = crate::resolve_title(None, name, lexicon);
+    }
+    for heading in &mut outline.outline {
+        heading.text = sanitize_text(&heading.text);
+        heading.page_label = heading.page_label.as_deref().map(sanitize_text);
+        heading.snippet = heading.snippet.as_deref().map(sanitize_text).filter(|snippet| !snippet.is_empty());
+    }
+    for candidate in &mut outline.title_candidates {
+        candidate.text = sanitize_text(&candidate.text);
+    }
+    for document in &mut outline.documents {
+        document.title = sanitize_text(&document.title);
+    }
+    let metadata = &mut outline.metadata;
+    metadata.title = metadata.title.as_deref().map(sanitize_text).filter(|title| !title.is_empty());
+    for list in [&mut metadata.authors, &mut metadata.keywords] {
+        *list = list.iter().map(|item| sanitize_text(item)).filter(|item| !item.is_empty()).collect();
+    }
+}
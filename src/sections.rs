@@ -0,0 +1,438 @@
+//! The text under each heading, from the end of the heading's line to the
+//! start of the next heading's, and the preview snippets taken from it; and
+//! where each heading starts in its page's text and runs, and how far down
+//! its page.
+
+use anyhow::Result;
+use lopdf::Document;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use crate::config::ExtractorConfig;
+use crate::font_utils::{self, TextRun};
+use crate::functions;
+use crate::memory::MemoryBudget;
+use crate::Heading;
+
+/// One page's text as an engine read it: pdf-extract's, or the page rebuilt
+/// from its runs. A document's pages come as a list from page 1, in order,
+/// with the pages that weren't read left empty, so the text engine's
+/// heuristics and the sections under headings read either engine's text
+/// the same way.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageText {
+    // From 1
+    pub page: usize,
+    pub text: String,
+}
+
+/// Characters in a snippet when `--with-snippets` doesn't give a number
+pub const DEFAULT_SNIPPET_CHARS: usize = 300;
+
+// Fewest words in a paragraph of prose, and the share of its characters
+// that must be letters
+const MIN_PROSE_WORDS: usize = 6;
+const MIN_PROSE_LETTERS: f64 = 0.6;
+
+// Lines of a table's cells are short; a paragraph of three or more lines
+// averaging fewer characters than this is taken for one
+const MIN_PROSE_LINE_CHARS: usize = 25;
+
+// A sentence end further in than this share of the limit is cut at; an
+// earlier one would waste most of the snippet, so a word boundary is used
+const MIN_SENTENCE_SHARE: f64 = 0.5;
+
+// The caption or label of a figure or table ("Figure 3:", "Table 2.1 –",
+// "Fig. 4"), or a note on its source
+static CAPTION: Lazy<Regex> = Lazy::new(|| Regex::new(
+    r"(?i)^(fig\.?|figure|table|chart|exhibit|diagram|image|plate)\s*[\dIVXA-Z][\d.]*\b|^(source|note)s?:"
+).unwrap());
+
+// Lines a heading can be wrapped over, and the letters and digits its line
+// can hold beyond its own
+const MAX_HEADING_LINES: usize = 3;
+// Runs a heading can be drawn in, which can be a word or a letter each
+const MAX_HEADING_RUNS: usize = 40;
+const MAX_EXTRA_CHARS: usize = 4;
+
+// Words whose period doesn't end a sentence, or a heading
+pub(crate) const ABBREVIATIONS: &[&str] = &[
+    "e.g", "i.e", "cf", "vs", "fig", "figs", "no", "nos", "vol", "pp", "approx", "dr", "mr", "mrs", "ms", "st", "al",
+    "etc", "inc", "ltd", "co", "corp", "jr", "sr", "dept", "rs",
+];
+
+/// The opening of a section's text, for a preview: its first paragraph of
+/// prose, with words hyphenated across lines joined and whitespace
+/// collapsed. Paragraphs are separated by blank lines. Figure and table
+/// captions, blocks of table cells and lines too short to be prose are
+/// skipped. A paragraph longer than `max_chars` is cut after the last
+/// sentence that fits, or at a word with an ellipsis when that would leave
+/// less than half of it. None when the section holds no prose.
+///
+/// ```
+/// use adobe1a::sections::snippet;
+///
+/// let section = "Figure 3: Regional sales, 2023\n\n\
+///                North   12.4   13.1\nSouth   9.8   10.2\nEast   7.1   7.7\n\n\
+///                Sales grew in every region. The north led for the third year run-\n\
+///                ning, helped by two new stores.\n\nA second paragraph.";
+/// assert_eq!(snippet(section, 300).as_deref(),
+///            Some("Sales grew in every region. The north led for the third year running, helped by two new stores."));
+///
+/// // Cut after the last whole sentence that fits
+/// assert_eq!(snippet(section, 50).as_deref(), Some("Sales grew in every region."));
+///
+/// // A first sentence too long to fit is cut at a word
+/// let section = "The board approved the budget for the coming year after a long discussion of costs.";
+/// assert_eq!(snippet(section, 40).as_deref(), Some("The board approved the budget for the…"));
+///
+/// // Abbreviations don't end sentences
+/// let section = "Costs rose, e.g. for energy and rent. Staff numbers held steady through the year.";
+/// assert_eq!(snippet(section, 60).as_deref(), Some("Costs rose, e.g. for energy and rent."));
+///
+/// assert_eq!(snippet("Table 1. Figures by year\n\n2021  2022  2023", 300), None);
+/// ```
+///
+/// With `snippet_chars` set, extraction gives each heading the snippet of
+/// the text between it and the next heading, which can run onto the next
+/// page:
+///
+/// ```
+/// use adobe1a::{analyze_text, config::ExtractorConfig, extract_outline, timing::Stats, TextOptions};
+///
+/// let config = ExtractorConfig { snippet_chars: Some(300), ..ExtractorConfig::default() };
+/// let outline = extract_outline("fixtures/agenda.pdf".as_ref(), &config, &mut Stats::new())?;
+/// let heading = outline.outline.iter().find(|h| h.text == "3. Any Other Business").unwrap();
+/// assert_eq!(heading.snippet.as_deref(), Some("Members may raise further items with the chair before the meeting closes."));
+///
+/// let text = "Annual Report 2024\n\n1. Introduction\nThis report covers the year's work in three parts.\n\
+///             \x0CTable 1: Staff\nNorth  12\nSouth  9\nEast  7\n\nNumbers held steady, with two new posts in the south.\n\
+///             \x0C2. Results\nRevenue grew by a fifth over the previous year, as planned.\n";
+/// let outline = analyze_text(text, TextOptions { config, ..TextOptions::default() });
+/// let snippets: Vec<_> = outline.outline.iter().map(|h| h.snippet.as_deref()).collect();
+/// assert_eq!(snippets, [
+///     Some("This report covers the year's work in three parts."),
+///     Some("Revenue grew by a fifth over the previous year, as planned."),
+/// ]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn snippet(section: &str, max_chars: usize) -> Option<String> {
+    let words = functions::standalone_words(section.lines());
+    let paragraph = paragraphs(section).into_iter()
+        .find(|lines| is_prose(lines))?;
+    let joined = functions::dehyphenate(&paragraph, &words).join(" ");
+    let (joined, _) = functions::strip_list_marker(joined.trim());
+    let text = joined.split_whitespace().collect::<Vec<_>>().join(" ");
+    Some(cut(&text, max_chars))
+}
+
+// Sets the `snippet` of each heading found in `pages`
+pub(crate) fn attach_snippets(headings: &mut [Heading], pages: &[PageText], max_chars: usize) {
+    let sections = section_texts(pages, headings);
+    for (heading, section) in headings.iter_mut().zip(sections) {
+        heading.snippet = snippet(&section, max_chars);
+    }
+}
+
+// The text under each heading, paragraphs separated by blank lines, with a
+// page break counting as one. Each heading is looked for on its own page,
+// after the one before it; one that can't be found gets no text, and its
+// text stays with the heading before it.
+pub(crate) fn section_texts(pages: &[PageText], headings: &[Heading]) -> Vec<String> {
+    let lines = PageLines::new(pages);
+    let found = lines.locate(headings);
+    (0..headings.len())
+        .map(|i| {
+            let Some((_, start)) = found[i] else {
+                return String::new();
+            };
+            let end = found[i + 1..].iter().flatten().map(|&(line, _)| line).next().unwrap_or(lines.lines.len());
+            lines.lines[start..end.max(start)].join("\n")
+        })
+        .collect()
+}
+
+/// The text of each page of a PDF from page 1, as `--with-snippets` and
+/// `--with-offsets` read it: pdf-extract's text where it has some, or the
+/// page rebuilt from its runs. Pages past the last one `config` reads are
+/// left out, and pages before it that it skips are empty unless
+/// pdf-extract read them.
+///
+/// A heading's `page_offset` counts the characters of its page's text here
+/// before it, and its `run_index` counts the runs before it on its page in
+/// [`crate::layout::extract_runs`]:
+///
+/// ```
+/// use adobe1a::{config::Engine, layout, sections, Extractor};
+///
+/// let bytes = std::fs::read("fixtures/agenda.pdf")?;
+/// for engine in [Engine::Text, Engine::Font].into_iter().filter(|engine| engine.is_built()) {
+///     let extractor = Extractor::builder().engine(engine).offsets(true).build()?;
+///     let outline = extractor.extract_bytes(&bytes)?;
+///     let pages = sections::page_texts(&bytes, extractor.config())?;
+///     let runs = layout::extract_runs_from_bytes(&bytes)?;
+///     assert!(!outline.outline.is_empty());
+///     for heading in &outline.outline {
+///         let offset = heading.page_offset.expect("every heading is found in its page's text");
+///         let page = &pages[heading.page - 1];
+///         assert_eq!(page.page, heading.page);
+///         let text: String = page.text.chars().skip(offset).collect();
+///         let words = heading.text.split_whitespace();
+///         assert!(text.split_whitespace().take(words.clone().count()).eq(words), "{:?} at {}", heading.text, offset);
+///
+///         // A run can hold a bullet or number in front of the heading
+///         let run = runs.iter().filter(|run| run.page == heading.page).nth(heading.run_index.unwrap()).unwrap();
+///         assert!(run.text.contains(heading.text.split_whitespace().next().unwrap()), "{:?} in {:?}", heading.text, run.text);
+///     }
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn page_texts(bytes: &[u8], config: &ExtractorConfig) -> Result<Vec<PageText>> {
+    let bytes = crate::pdf_from_header(bytes)?;
+    let mut doc = Document::load_mem(bytes)?;
+    // Read from the repaired page tree, as extraction does
+    let repaired = match crate::trees::repair_page_tree(&mut doc) {
+        Some(_) => {
+            let mut out = Vec::new();
+            doc.save_to(&mut out)?;
+            Some(out)
+        }
+        None => None,
+    };
+    Ok(crate::page_texts(repaired.as_deref().unwrap_or(bytes), &doc, config, &MemoryBudget::new(None)))
+}
+
+// Sets each heading's `page_offset` in `pages` and its `run_index` among its
+// page's runs in `doc`. Headings are looked for as for their sections; one
+// that can't be found gets neither.
+pub(crate) fn attach_offsets(headings: &mut [Heading], pages: &[PageText], doc: &Document) {
+    let lines = PageLines::new(pages);
+    let found = lines.locate(headings);
+    for (heading, location) in headings.iter_mut().zip(found) {
+        heading.page_offset = location.map(|(start, end)| lines.char_offset(start, end, &heading.text));
+    }
+
+    let found = heading_runs(headings, doc);
+    for (heading, run) in headings.iter_mut().zip(found) {
+        heading.run_index = run.map(|(index, _)| index);
+    }
+}
+
+// Sets each heading's `y_fraction` from the top of the run it starts in,
+// unless it has one already, from a bookmark
+pub(crate) fn attach_positions(headings: &mut [Heading], doc: &Document) {
+    let found = heading_runs(headings, doc);
+    for (heading, run) in headings.iter_mut().zip(found) {
+        heading.y_fraction = heading.y_fraction.or(run.map(|(_, top)| top));
+    }
+}
+
+// For each heading in `doc`, the index among its page's runs of the run it
+// starts in, and the top of that run's text as a fraction of the page's
+// height from the top. A page's headings are looked for in order down its
+// runs, so a heading repeating another's text finds the next run.
+fn heading_runs(headings: &[Heading], doc: &Document) -> Vec<Option<(usize, f64)>> {
+    let page_ids = doc.get_pages();
+    let mut page = 0;
+    let (mut runs, mut page_box, mut cursor) = (Vec::new(), [0.0; 4], 0);
+    let mut found = Vec::with_capacity(headings.len());
+    for heading in headings {
+        if heading.page != page {
+            let Some(&page_id) = page_ids.get(&(heading.page as u32)) else {
+                found.push(None);
+                continue;
+            };
+            page = heading.page;
+            runs = font_utils::page_runs(doc, page_id, page);
+            page_box = font_utils::page_box(doc, page_id);
+            cursor = 0;
+        }
+        let texts: Vec<&str> = runs.iter().map(|run| if run.visible { run.text.as_str() } else { "" }).collect();
+        let span = find_span(&texts, cursor, texts.len(), &heading.text, MAX_HEADING_RUNS);
+        found.push(span.map(|(start, end)| {
+            cursor = end;
+            let run = &runs[start];
+            // The baseline plus the font size is about where capitals reach
+            (start, font_utils::y_from_top(run.y + run.size, &page_box))
+        }));
+    }
+    found
+}
+
+// The trimmed lines of every page, with a blank line after each page
+struct PageLines<'a> {
+    lines: Vec<&'a str>,
+    // Where each page's lines start, and the end of the last page's
+    page_starts: Vec<usize>,
+    // Each line's page text, and the byte offset there of its trimmed start
+    sources: Vec<(&'a str, usize)>,
+}
+
+impl<'a> PageLines<'a> {
+    fn new(pages: &'a [PageText]) -> Self {
+        let mut lines = PageLines { lines: Vec::new(), page_starts: Vec::with_capacity(pages.len() + 1), sources: Vec::new() };
+        for page in pages.iter().map(|page| page.text.as_str()) {
+            lines.page_starts.push(lines.lines.len());
+            let mut offset = 0;
+            for line in page.split_inclusive('\n') {
+                let indent = line.len() - line.trim_start().len();
+                lines.lines.push(line.trim());
+                lines.sources.push((page, offset + indent));
+                offset += line.len();
+            }
+            lines.lines.push("");
+            lines.sources.push((page, page.len()));
+        }
+        lines.page_starts.push(lines.lines.len());
+        lines
+    }
+
+    // Where each heading's line starts and where its text starts. Each
+    // heading is looked for on its own page, after the one before it.
+    fn locate(&self, headings: &[Heading]) -> Vec<Option<(usize, usize)>> {
+        let mut cursor = 0;
+        headings.iter()
+            .map(|heading| {
+                let (Some(&page_start), Some(&page_end)) = (self.page_starts.get(heading.page.wrapping_sub(1)), self.page_starts.get(heading.page)) else {
+                    return None;
+                };
+                let location = find_heading(&self.lines, cursor.max(page_start), page_end, &heading.text);
+                if let Some((_, end)) = location {
+                    cursor = end;
+                }
+                location
+            })
+            .collect()
+    }
+
+    // Characters into its page's text where `text`, found on lines `start`
+    // to `end`, begins: past any list marker or number its line opens with
+    fn char_offset(&self, start: usize, end: usize, text: &str) -> usize {
+        let (page, from) = self.sources[start];
+        let (_, last) = self.sources[end - 1];
+        let to = last + self.lines[end - 1].len();
+        let target = key(text);
+        let begin = page[from..to].char_indices()
+            .map(|(i, _)| from + i)
+            .find(|&i| page[i..].starts_with(char::is_alphanumeric) && key(&page[i..to]).starts_with(&target))
+            .unwrap_or(from);
+        page[..begin].chars().count()
+    }
+}
+
+// The first line from `from` (before `to`) that `text` starts on, and the
+// line after it ends. A heading can be wrapped over a few lines, and its
+// line can hold a little more than its cleaned-up text: a list marker or
+// number in front, a page number behind.
+pub(crate) fn find_heading(lines: &[&str], from: usize, to: usize, text: &str) -> Option<(usize, usize)> {
+    find_span(lines, from, to, text, MAX_HEADING_LINES)
+}
+
+// As `find_heading`, for `text` spread over up to `max_pieces` lines or runs
+fn find_span(pieces: &[&str], from: usize, to: usize, text: &str, max_pieces: usize) -> Option<(usize, usize)> {
+    let target = key(text);
+    if target.is_empty() {
+        return None;
+    }
+    let to = to.min(pieces.len());
+    (from..to).find_map(|start| {
+        let mut joined = String::new();
+        for end in start + 1..=(start + max_pieces).min(to) {
+            joined.push_str(&key(pieces[end - 1]));
+            let extra = joined.len().saturating_sub(target.len());
+            if extra <= MAX_EXTRA_CHARS && (joined.ends_with(&target) || joined.starts_with(&target)) {
+                return Some((start, end));
+            }
+            if joined.is_empty() || !target.starts_with(&joined) {
+                return None;
+            }
+        }
+        None
+    })
+}
+
+// Letters and digits only, lowercased, for matching a heading's cleaned-up
+// text against the line it came from
+fn key(text: &str) -> String {
+    text.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+// The lines of each paragraph of `text`
+fn paragraphs(text: &str) -> Vec<Vec<&str>> {
+    let mut paragraphs = vec![Vec::new()];
+    for line in text.lines().map(str::trim) {
+        match (line.is_empty(), paragraphs.last_mut()) {
+            (true, Some(last)) if !last.is_empty() => paragraphs.push(Vec::new()),
+            (false, Some(last)) => last.push(line),
+            _ => {}
+        }
+    }
+    paragraphs.retain(|lines| !lines.is_empty());
+    paragraphs
+}
+
+// Whether a paragraph reads as running text rather than a caption, a block
+// of table cells or a stray label
+fn is_prose(lines: &[&str]) -> bool {
+    let text = lines.join(" ");
+    let chars = text.chars().filter(|c| !c.is_whitespace()).count();
+    let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+    let words = text.split_whitespace().count();
+    let line_chars = lines.iter().map(|line| line.chars().count()).sum::<usize>() / lines.len();
+    words >= MIN_PROSE_WORDS
+        && letters as f64 >= chars as f64 * MIN_PROSE_LETTERS
+        && !CAPTION.is_match(&text)
+        && (lines.len() < 3 || line_chars >= MIN_PROSE_LINE_CHARS)
+}
+
+// `text` cut to at most `max_chars` characters: after the last sentence that
+// fits, or at the last word that fits with an ellipsis
+fn cut(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let limit = text.char_indices().nth(max_chars).map_or(text.len(), |(i, _)| i);
+    let sentence_end = text[..limit].char_indices()
+        .filter(|&(i, c)| matches!(c, '.' | '!' | '?') && text[i + 1..].starts_with(char::is_whitespace))
+        .filter(|&(i, c)| c != '.' || !is_abbreviation(&text[..i]))
+        .map(|(i, _)| i + 1)
+        .next_back();
+    if let Some(end) = sentence_end.filter(|&end| text[..end].chars().count() as f64 >= max_chars as f64 * MIN_SENTENCE_SHARE) {
+        return text[..end].to_string();
+    }
+    // Room for the ellipsis
+    let limit = text.char_indices().nth(max_chars.saturating_sub(1)).map_or(text.len(), |(i, _)| i);
+    let head = match text[limit..].starts_with(char::is_whitespace) {
+        true => &text[..limit],
+        false => text[..limit].rsplit_once(char::is_whitespace).map_or(&text[..limit], |(head, _)| head),
+    };
+    format!("{}…", head.trim_end_matches(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | ':' | '-' | '–')))
+}
+
+// Whether the period after `before` belongs to an abbreviation or an initial
+fn is_abbreviation(before: &str) -> bool {
+    let word = before.rsplit(char::is_whitespace).next().unwrap_or("").trim_start_matches(['(', '"', '\'']);
+    let is_initial = word.chars().count() == 1 && word.chars().all(char::is_uppercase);
+    is_initial || ABBREVIATIONS.contains(&word.to_lowercase().as_str())
+}
+
+// A page's text rebuilt from its runs: a line per baseline, and a blank line
+// where the gap to the next line is well over the text size
+pub(crate) fn page_text(runs: &[TextRun]) -> String {
+    let mut text = String::new();
+    let mut previous: Option<&TextRun> = None;
+    for run in runs.iter().filter(|run| run.visible && !run.text.trim().is_empty()) {
+        if let Some(last) = previous {
+            let gap = (last.y - run.y).abs();
+            if gap > run.size.max(last.size) * 1.5 {
+                text.push_str("\n\n");
+            } else if gap > run.size.min(last.size) * 0.5 {
+                text.push('\n');
+            } else if run.x > last.x + last.width * 0.9 {
+                text.push(' ');
+            }
+        }
+        text.push_str(run.text.trim());
+        previous = Some(run);
+    }
+    text
+}
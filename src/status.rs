@@ -0,0 +1,189 @@
+//! A JSON status file for monitoring a batch run from outside the process.
+//! It's rewritten as files start and finish, at most once per interval,
+//! and replaced by renaming a finished temporary file over it, so a reader
+//! never sees it half-written.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::output::{self, KeyStyle};
+use crate::summary::{BatchSummary, FileStatus, FileSummary};
+
+/// Progress of a batch run, written to a file. Calls may come from several
+/// threads at once, as files of a batch are processed in parallel; each
+/// holds a lock while it records its change and, when a write is due,
+/// while the file is written.
+///
+/// ```
+/// use std::time::Duration;
+/// use adobe1a::output::KeyStyle;
+/// use adobe1a::status::StatusFile;
+/// use adobe1a::summary::{BatchSummary, FileSummary};
+///
+/// let path = std::env::temp_dir().join(format!("adobe1a-status-doc-{}.json", std::process::id()));
+/// let status = StatusFile::create(&path, 1, Duration::ZERO, KeyStyle::Snake)?;
+/// let read = || -> serde_json::Value { serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap() };
+/// assert_eq!(read()["state"], "running");
+/// assert_eq!(read()["files_total"], 1);
+///
+/// status.started("input/memo.pdf".as_ref());
+/// assert_eq!(read()["current"][0], "input/memo.pdf");
+///
+/// let error = anyhow::anyhow!("Failed to process input/memo.pdf");
+/// let file = FileSummary::failed("input/memo.pdf".as_ref(), &error);
+/// status.finished(&file, Duration::from_millis(40));
+/// assert_eq!(read()["files_completed"], 1);
+/// assert_eq!(read()["failures"][0]["error"], "Failed to process input/memo.pdf");
+///
+/// status.complete(&BatchSummary::new(vec![file]))?;
+/// assert_eq!(read()["state"], "complete");
+/// assert_eq!(read()["summary"]["totals"]["failed"], 1);
+/// # std::fs::remove_file(&path)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct StatusFile {
+    path: PathBuf,
+    interval: Duration,
+    key_style: KeyStyle,
+    started: Instant,
+    progress: Mutex<Progress>,
+}
+
+#[derive(Default)]
+struct Progress {
+    total: usize,
+    // Files being processed now, in the order they started
+    current: Vec<String>,
+    files: Vec<FileTime>,
+    failures: Vec<Failure>,
+    last_write: Option<Instant>,
+}
+
+#[derive(Clone, Serialize)]
+struct FileTime {
+    path: String,
+    status: FileStatus,
+    duration_ms: f64,
+}
+
+#[derive(Clone, Serialize)]
+struct Failure {
+    path: String,
+    error: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum State {
+    Running,
+    Complete,
+}
+
+#[derive(Serialize)]
+struct Status<'a> {
+    state: State,
+    files_total: usize,
+    files_completed: usize,
+    current: &'a [String],
+    elapsed_ms: f64,
+    // None until a file has finished to estimate from
+    eta_ms: Option<f64>,
+    files: &'a [FileTime],
+    failures: &'a [Failure],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<&'a BatchSummary>,
+}
+
+impl StatusFile {
+    /// Starts a run of `total` files, writing its first status to `path`
+    /// straight away so that a path that can't be written fails the run
+    /// before any file is processed. Later writes come at most once per
+    /// `interval`.
+    pub fn create(path: &Path, total: usize, interval: Duration, key_style: KeyStyle) -> Result<StatusFile> {
+        let status = StatusFile {
+            path: path.to_path_buf(),
+            interval,
+            key_style,
+            started: Instant::now(),
+            progress: Mutex::new(Progress { total, ..Progress::default() }),
+        };
+        let mut progress = status.progress.lock().unwrap();
+        status.write(&mut progress, None)?;
+        drop(progress);
+        Ok(status)
+    }
+
+    /// `file` is being processed
+    pub fn started(&self, file: &Path) {
+        let mut progress = self.progress.lock().unwrap();
+        progress.current.push(file.display().to_string());
+        self.write_due(&mut progress);
+    }
+
+    /// `file` is done, after `duration` of wall-clock time
+    pub fn finished(&self, file: &FileSummary, duration: Duration) {
+        let mut progress = self.progress.lock().unwrap();
+        if let Some(i) = progress.current.iter().position(|path| *path == file.path) {
+            progress.current.remove(i);
+        }
+        progress.files.push(FileTime {
+            path: file.path.clone(),
+            status: file.status,
+            duration_ms: duration.as_secs_f64() * 1000.0,
+        });
+        if file.status == FileStatus::Failed {
+            progress.failures.push(Failure { path: file.path.clone(), error: file.error.clone().unwrap_or_default() });
+        }
+        self.write_due(&mut progress);
+    }
+
+    /// Marks the run complete, with its summary
+    pub fn complete(&self, summary: &BatchSummary) -> Result<()> {
+        let mut progress = self.progress.lock().unwrap();
+        progress.current.clear();
+        self.write(&mut progress, Some(summary))
+    }
+
+    // A failed write in the middle of a run is only logged; the run itself
+    // goes on, and the next write may succeed
+    fn write_due(&self, progress: &mut Progress) {
+        if progress.last_write.is_some_and(|last| last.elapsed() < self.interval) {
+            return;
+        }
+        if let Err(e) = self.write(progress, None) {
+            log::warn!("{:#}", e);
+        }
+    }
+
+    fn write(&self, progress: &mut Progress, summary: Option<&BatchSummary>) -> Result<()> {
+        let elapsed = self.started.elapsed();
+        let completed = progress.files.len();
+        // Wall-clock time per file so far, which allows for parallel jobs
+        let eta = (completed > 0 && summary.is_none()).then(|| {
+            elapsed.as_secs_f64() * 1000.0 / completed as f64 * progress.total.saturating_sub(completed) as f64
+        });
+        let status = Status {
+            state: if summary.is_some() { State::Complete } else { State::Running },
+            files_total: progress.total,
+            files_completed: completed,
+            current: &progress.current,
+            elapsed_ms: elapsed.as_secs_f64() * 1000.0,
+            eta_ms: eta.or(summary.map(|_| 0.0)),
+            files: &progress.files,
+            failures: &progress.failures,
+            summary,
+        };
+        let contents = output::to_json(&status, self.key_style)?;
+        let mut partial = self.path.clone().into_os_string();
+        partial.push(".partial");
+        std::fs::write(&partial, contents)
+            .and_then(|_| std::fs::rename(&partial, &self.path))
+            .with_context(|| format!("Failed to write status file {}", self.path.display()))?;
+        progress.last_write = Some(Instant::now());
+        Ok(())
+    }
+}
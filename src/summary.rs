@@ -0,0 +1,271 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use crate::evaluation::Evaluation;
+use crate::junit::{self, Outcome, TestCase};
+use crate::timing::Stats;
+use crate::{NoTextContent, NotAPdf, Outline, Warning};
+
+// One record per input of a directory run, plus corpus totals
+#[derive(Debug, Serialize)]
+pub struct BatchSummary {
+    pub files: Vec<FileSummary>,
+    pub totals: Totals,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    Ok,
+    // Processed without error but no headings were found
+    Empty,
+    // A valid PDF with nothing to outline (no pages, or only a header)
+    NoContent,
+    // Not a PDF at all, such as a renamed DOCX
+    NotPdf,
+    Failed,
+    // Skipped by --resume because the existing output was up to date
+    Cached,
+    // Processed, but the outline scored below --min-quality
+    LowQuality,
+}
+
+impl FileStatus {
+    fn name(self) -> &'static str {
+        match self {
+            FileStatus::Ok => "ok",
+            FileStatus::Empty => "empty",
+            FileStatus::NoContent => "no_content",
+            FileStatus::NotPdf => "not_pdf",
+            FileStatus::Failed => "failed",
+            FileStatus::Cached => "cached",
+            FileStatus::LowQuality => "low_quality",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileSummary {
+    pub path: String,
+    pub status: FileStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    // What produced the outline, and which version, as the outline records it
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub engine: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extractor_version: Option<String>,
+    // The outline's quality score, 0 to 100; None for a file that failed or
+    // an output from before outlines were scored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality_score: Option<u8>,
+    // Heading count per level ("H1", "H2", ...)
+    pub headings: BTreeMap<String, usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<Warning>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    // The outline scored against its reference, with --expected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evaluation: Option<Evaluation>,
+    pub stats: Stats,
+}
+
+impl FileSummary {
+    pub fn succeeded(path: &Path, outline: &Outline, stats: Stats) -> Self {
+        let mut headings = BTreeMap::new();
+        for heading in &outline.outline {
+            *headings.entry(heading.level.clone()).or_default() += 1;
+        }
+        FileSummary {
+            path: path.display().to_string(),
+            status: if outline.outline.is_empty() { FileStatus::Empty } else { FileStatus::Ok },
+            title: Some(outline.title.clone()),
+            engine: outline.engine.clone(),
+            extractor_version: Some(outline.extractor_version.clone()).filter(|version| !version.is_empty()),
+            quality_score: outline.quality_score,
+            headings,
+            warnings: outline.warnings.clone(),
+            error: None,
+            evaluation: None,
+            stats,
+        }
+    }
+
+    // From an output left by an earlier run; no timings were spent on it
+    pub fn cached(path: &Path, outline: &Outline) -> Self {
+        FileSummary {
+            status: FileStatus::Cached,
+            ..FileSummary::succeeded(path, outline, Stats::default())
+        }
+    }
+
+    pub fn failed(path: &Path, error: &anyhow::Error) -> Self {
+        FileSummary {
+            path: path.display().to_string(),
+            status: if error.chain().any(|cause| cause.is::<NoTextContent>()) {
+                FileStatus::NoContent
+            } else if error.chain().any(|cause| cause.is::<NotAPdf>()) {
+                FileStatus::NotPdf
+            } else {
+                FileStatus::Failed
+            },
+            title: None,
+            engine: Vec::new(),
+            extractor_version: None,
+            quality_score: None,
+            headings: BTreeMap::new(),
+            warnings: Vec::new(),
+            error: Some(format!("{:#}", error)),
+            evaluation: None,
+            stats: Stats::default(),
+        }
+    }
+
+    // Marks an outline scoring below `min_quality` as `low_quality`. Empty
+    // outlines keep their own status.
+    pub fn with_min_quality(mut self, min_quality: Option<u8>) -> Self {
+        let below = self.quality_score.zip(min_quality).is_some_and(|(score, min)| score < min);
+        if below && matches!(self.status, FileStatus::Ok | FileStatus::Cached) {
+            self.status = FileStatus::LowQuality;
+        }
+        self
+    }
+
+    pub fn with_evaluation(mut self, evaluation: Option<Evaluation>) -> Self {
+        self.evaluation = evaluation;
+        self
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Totals {
+    pub files: usize,
+    pub ok: usize,
+    pub empty: usize,
+    pub no_content: usize,
+    pub not_pdf: usize,
+    pub failed: usize,
+    pub cached: usize,
+    pub low_quality: usize,
+    pub headings: BTreeMap<String, usize>,
+    pub warnings: usize,
+    pub stats: Stats,
+}
+
+impl BatchSummary {
+    pub fn new(files: Vec<FileSummary>) -> Self {
+        let mut totals = Totals { files: files.len(), ..Totals::default() };
+        for file in &files {
+            match file.status {
+                FileStatus::Ok => totals.ok += 1,
+                FileStatus::Empty => totals.empty += 1,
+                FileStatus::NoContent => totals.no_content += 1,
+                FileStatus::NotPdf => totals.not_pdf += 1,
+                FileStatus::Failed => totals.failed += 1,
+                FileStatus::Cached => totals.cached += 1,
+                FileStatus::LowQuality => totals.low_quality += 1,
+            }
+            for (level, count) in &file.headings {
+                *totals.headings.entry(level.clone()).or_default() += count;
+            }
+            totals.warnings += file.warnings.len();
+            totals.stats.merge(&file.stats);
+        }
+        BatchSummary { files, totals }
+    }
+
+    // One row per file, then a totals row with the status `total`, an empty
+    // path, and the heading and warning counts and stage times summed.
+    // Heading columns cover H1-H3; any other levels are counted under
+    // `other_headings`. Several engines are joined with `+`.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("path,status,title,engine,extractor_version,quality_score,h1,h2,h3,other_headings,warnings,total_ms,error\n");
+        for file in &self.files {
+            let fields = [
+                csv_field(&file.path),
+                file.status.name().to_string(),
+                csv_field(file.title.as_deref().unwrap_or("")),
+                csv_field(&file.engine.join("+")),
+                csv_field(file.extractor_version.as_deref().unwrap_or("")),
+                file.quality_score.map_or(String::new(), |score| score.to_string()),
+            ];
+            let error = csv_field(file.error.as_deref().unwrap_or(""));
+            csv_row(&mut out, &fields, &file.headings, file.warnings.len(), &file.stats, &error);
+        }
+        let totals = &self.totals;
+        let fields = [String::new(), "total".to_string(), String::new(), String::new(), String::new(), String::new()];
+        csv_row(&mut out, &fields, &totals.headings, totals.warnings, &totals.stats, "");
+        out
+    }
+
+    // A JUnit report with a test case per file: one whose outline scored an
+    // F1 below `min_f1` against its reference fails, one that couldn't be
+    // processed is an error, and one without a reference is skipped
+    pub fn to_junit(&self, min_f1: f64) -> String {
+        let cases: Vec<TestCase> = self.files.iter()
+            .map(|file| TestCase {
+                name: file.path.clone(),
+                time: file.stats.total(),
+                outcome: match (&file.error, &file.evaluation) {
+                    (Some(error), _) => Outcome::Error { message: error.clone() },
+                    (None, None) => Outcome::Skipped { message: "no reference outline".to_string() },
+                    (None, Some(evaluation)) if evaluation.f1 >= min_f1 => Outcome::Passed,
+                    (None, Some(evaluation)) => Outcome::Failed {
+                        message: format!("F1 {:.3} is below {} (precision {:.3}, recall {:.3})",
+                            evaluation.f1, min_f1, evaluation.precision, evaluation.recall),
+                        details: mismatch_details(evaluation),
+                    },
+                },
+            })
+            .collect();
+        junit::report("adobe1a", &cases)
+    }
+}
+
+// Mismatched headings listed in a failing test case's details
+const MISMATCHES_SHOWN: usize = 5;
+
+// The first few mismatched headings, missed ones before unexpected ones
+fn mismatch_details(evaluation: &Evaluation) -> String {
+    let mismatches: Vec<String> = evaluation.missed.iter().map(|heading| format!("missed: {}", heading))
+        .chain(evaluation.unexpected.iter().map(|heading| format!("unexpected: {}", heading)))
+        .collect();
+    let mut details = mismatches.iter().take(MISMATCHES_SHOWN).cloned().collect::<Vec<_>>().join("\n");
+    if mismatches.len() > MISMATCHES_SHOWN {
+        let _ = write!(details, "\n... and {} more", mismatches.len() - MISMATCHES_SHOWN);
+    }
+    details
+}
+
+// Quote a field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// The fields from `path` to `quality_score`, already quoted, then the
+// heading, warning and timing columns and the error
+fn csv_row(out: &mut String, fields: &[String], headings: &BTreeMap<String, usize>, warnings: usize, stats: &Stats, error: &str) {
+    let count = |level: &str| headings.get(level).copied().unwrap_or(0);
+    let other: usize = headings.iter()
+        .filter(|(level, _)| !["H1", "H2", "H3"].contains(&level.as_str()))
+        .map(|(_, count)| count)
+        .sum();
+    let _ = writeln!(
+        out,
+        "{},{},{},{},{},{},{:.2},{}",
+        fields.join(","),
+        count("H1"),
+        count("H2"),
+        count("H3"),
+        other,
+        warnings,
+        stats.total().as_secs_f64() * 1000.0,
+        error,
+    );
+}
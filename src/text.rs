@@ -0,0 +1,269 @@
+//! Heading detection on plain text. The text engine runs the same line
+//! heuristics on what pdf-extract reads from a PDF; [`analyze_text`] runs them
+//! on text from anywhere else.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use regex::Regex;
+use crate::config::ExtractorConfig;
+use crate::functions;
+use crate::sanitize::normalize_for_match;
+use crate::sections::PageText;
+use crate::timing::{Stage, Stats};
+use crate::validation::CandidateFilter;
+use crate::{heuristic_title_candidates, resolve_title, Heading, Outline, Warning};
+
+/// How [`analyze_text`] reads its input
+#[derive(Debug, Clone)]
+pub struct TextOptions {
+    /// Turned into the title when none is found in the text, as a PDF's file
+    /// name is
+    pub name: String,
+    /// A line matching this starts a new page and is itself dropped, e.g.
+    /// `^-+ Page \d+ -+$`. Without one, form feeds separate the pages, and
+    /// text with neither is a single page.
+    pub page_marker: Option<Regex>,
+    pub config: ExtractorConfig,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        TextOptions { name: "Untitled".to_string(), page_marker: None, config: ExtractorConfig::default() }
+    }
+}
+
+/// The outline of plain text, e.g. from another converter, found with the
+/// text engine's heuristics. Pages are numbered from 1 in the order they
+/// come; the numbers in page markers aren't read. `pages` and `quick` in the
+/// configuration select pages as they do for a PDF, and settings that only
+/// concern PDFs are ignored.
+///
+/// ```
+/// use adobe1a::{analyze_text, TextOptions};
+/// use regex::Regex;
+///
+/// let text = "Annual Report 2024\n\n1. Introduction\nThis report covers the year's work in three parts.\n\
+///             \x0C2. Results\nRevenue grew by a fifth over the previous year, as planned.\n";
+/// let outline = analyze_text(text, TextOptions::default());
+/// assert_eq!(outline.title, "Annual Report 2024");
+/// let headings: Vec<_> = outline.outline.iter().map(|h| (h.text.as_str(), h.page)).collect();
+/// assert_eq!(headings, [("1. Introduction", 1), ("2. Results", 2)]);
+/// assert_eq!(outline.page_count, Some(2));
+///
+/// // Another converter's page markers instead of form feeds
+/// let text = "=== Page 1 ===\nMinutes of the Board Meeting\n\n1. Overview\nThe plan is set out in full below.\n\
+///             === Page 2 ===\n2. Background\nIt follows the review completed last spring.\n";
+/// let options = TextOptions {
+///     page_marker: Some(Regex::new(r"^=== Page \d+ ===$")?),
+///     ..TextOptions::default()
+/// };
+/// let outline = analyze_text(text, options);
+/// assert_eq!(outline.title, "Minutes of the Board Meeting");
+/// let headings: Vec<_> = outline.outline.iter().map(|h| (h.text.as_str(), h.page)).collect();
+/// assert_eq!(headings, [("1. Overview", 1), ("2. Background", 2)]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn analyze_text(text: &str, options: TextOptions) -> Outline {
+    let TextOptions { name, page_marker, config } = options;
+    let pages: Vec<PageText> = split_pages(text, page_marker.as_ref()).into_iter()
+        .enumerate()
+        .map(|(i, page)| PageText { page: i + 1, text: page.to_string() })
+        .collect();
+    let outline = outline_from_pages(&pages, pages.len(), &name, &config, &mut Stats::new());
+    let mut outline = crate::attributed(outline, "text", &config);
+    outline.page_count = Some(pages.len());
+    outline.warnings.extend(page_range_warnings(&config, pages.len()));
+    outline.partial = config.quick.is_some_and(|window| pages.len() > window);
+    crate::drop_empty_headings(&mut outline, &config);
+    if let Some(max_chars) = config.snippet_chars {
+        crate::sections::attach_snippets(&mut outline.outline, &pages, max_chars);
+    }
+    if !config.title_candidates {
+        outline.title_candidates.clear();
+    }
+    crate::finish_outline(&mut outline, &name, &config, text.as_bytes(), &[]);
+    outline
+}
+
+// With no PDF to fail on, asking only for pages past the end is a warning too
+pub(crate) fn page_range_warnings(config: &ExtractorConfig, page_count: usize) -> Vec<Warning> {
+    crate::check_page_ranges(config, Some(page_count))
+        .unwrap_or_else(|e| vec![Warning::new("pages_out_of_range", e.to_string())])
+}
+
+// The text of each page, split at marker lines when there's a marker and at
+// form feeds otherwise. Text before the first marker is only a page of its
+// own when it holds something.
+fn split_pages<'a>(text: &'a str, marker: Option<&Regex>) -> Vec<&'a str> {
+    let Some(marker) = marker else {
+        return text.split('\x0C').collect();
+    };
+    let mut pages = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if marker.is_match(line.trim()) {
+            pages.push(&text[start..offset]);
+            start = offset + line.len();
+        }
+        offset += line.len();
+    }
+    pages.push(&text[start..]);
+    if pages.len() > 1 && pages[0].trim().is_empty() {
+        pages.remove(0);
+    }
+    pages
+}
+
+// Lines this close to the top or bottom of a page are where running
+// headers, footers and page numbers are looked for
+const PAGE_EDGE_LINES: usize = 3;
+
+// Running headers and footers: the letters of lines near the edges of a
+// page that recur near the edges of another, whatever their numbers
+fn running_lines(pages: &[PageText]) -> HashSet<String> {
+    let mut pages_with: HashMap<String, usize> = HashMap::new();
+    for page in pages {
+        let lines: Vec<&str> = page.text.lines().filter(|l| !l.trim().is_empty()).collect();
+        let edges: HashSet<String> = lines.iter().take(PAGE_EDGE_LINES)
+            .chain(lines.iter().rev().take(PAGE_EDGE_LINES))
+            .map(|line| letters(line))
+            .filter(|key| !key.is_empty())
+            .collect();
+        for key in edges {
+            *pages_with.entry(key).or_default() += 1;
+        }
+    }
+    pages_with.into_iter().filter(|(_, count)| *count > 1).map(|(key, _)| key).collect()
+}
+
+// A running header or footer, or a line without letters such as a page number
+fn is_furniture(line: &str, running: &HashSet<String>) -> bool {
+    let key = letters(line);
+    key.is_empty() || running.contains(&key)
+}
+
+fn letters(line: &str) -> String {
+    line.chars().filter(|c| c.is_alphabetic()).flat_map(char::to_lowercase).collect()
+}
+
+// The text engine's pass over `pages`, of a document of `page_count`:
+// title scoring on page 1, then every line of the selected pages weighed as
+// a heading, and the hierarchy built from what's found
+pub(crate) fn outline_from_pages(pages: &[PageText], page_count: usize, name: &str, config: &ExtractorConfig, stats: &mut Stats) -> Outline {
+    let mut title_scores = Vec::new();
+    let mut headings: Vec<Heading> = Vec::new();
+    let mut seen: HashSet<(String, usize)> = HashSet::new();
+    // Leading columns of each heading's line, which pdf-extract sometimes
+    // keeps from the page's layout
+    let mut indents = Vec::new();
+    let mut page_chars: BTreeMap<usize, usize> = BTreeMap::new();
+
+    // All of it, for the document-wide statistics
+    let text = pages.iter().map(|page| page.text.as_str()).collect::<Vec<_>>().join("\x0C");
+    let filter = CandidateFilter::new(config);
+    let lang = config.language.as_deref();
+    let all_caps = functions::uppercase_ratio(&text) > config.all_caps_ratio;
+    if all_caps {
+        log::debug!("{} is set in capitals; capitalization won't mark headings", name);
+    }
+    let short = config.is_short_document(page_count);
+    let mut state = functions::HeadingState::default();
+    state.all_caps_document = all_caps;
+    state.short_document = short;
+    // The previous block's last line and page, to catch a line pdf-extract
+    // repeats on both sides of a page break
+    let mut previous_last_line: Option<(String, usize)> = None;
+    let words = functions::standalone_words(text.lines());
+    // A short document has too few pages for its running lines to repeat
+    let running = if short { HashSet::new() } else { running_lines(pages) };
+    // A heading left as a bare marker ("CHAPTER 4") at the foot of a page,
+    // by index and page, to take its title from the top of the next
+    let mut pending_marker: Option<(usize, usize)> = None;
+    stats.time(Stage::LineHeuristics, || {
+        for PageText { page: current_page, text: page_text } in pages {
+            let current_page = *current_page;
+            if !config.includes_page(current_page) {
+                continue;
+            }
+            *page_chars.entry(current_page).or_default() += page_text.chars().filter(|c| !c.is_whitespace()).count();
+            let untrimmed: Vec<&str> = page_text.lines()
+                .filter(|l| !l.trim().is_empty())
+                .collect();
+            let raw_lines: Vec<&str> = untrimmed.iter().map(|l| l.trim()).collect();
+            let joined = functions::dehyphenate_indexed(&raw_lines, &words);
+            let lines: Vec<&str> = joined.iter().map(|(_, line)| line.as_str()).collect();
+
+            if title_scores.is_empty() && current_page == 1 && config.detects_title() {
+                title_scores = functions::extract_document_title(&lines, &config.lexicon, all_caps);
+                if short {
+                    functions::short_document_titles(&mut title_scores, &lines, &config.lexicon);
+                }
+            }
+
+            // The line that opens the page finishes a marker the last page
+            // ended with, rather than being weighed on its own
+            let mut title_line = None;
+            if let Some((index, _)) = pending_marker.take().filter(|&(_, page)| page + 1 == current_page) {
+                let first = lines.iter().position(|line| !is_furniture(line, &running));
+                if let Some(j) = first.filter(|&j| functions::completes_marker(lines[j])) {
+                    let heading = &mut headings[index];
+                    heading.text = format!("{} {}", heading.text, functions::clean_heading_text(lines[j], &config.lexicon));
+                    title_line = Some(j);
+                }
+            }
+
+            // A memo's or letter's address block, down to its subject line,
+            // holds names and dates rather than headings
+            let address_block = match short && current_page == 1 {
+                true => functions::subject_line(&lines, &config.lexicon).map_or(0, |(i, _)| i + 1),
+                false => 0,
+            };
+            for (i, line) in lines.iter().enumerate().skip(address_block) {
+                if title_line == Some(i) {
+                    continue;
+                }
+                if let Some(heading) = functions::analyze_potential_heading(
+                    line,
+                    i,
+                    &lines,
+                    current_page,
+                    &filter,
+                    &config.lexicon,
+                    &mut state,
+                ) {
+                    let key = normalize_for_match(&heading.text, lang);
+                    let repeats_page_break = i == 0 && previous_last_line.as_ref().is_some_and(|(last, page)| {
+                        *page + 1 == current_page && *last == normalize_for_match(line, lang)
+                    });
+                    if !repeats_page_break && seen.insert((key, heading.page)) {
+                        if functions::is_bare_marker(&heading.text) && lines[i + 1..].iter().all(|line| is_furniture(line, &running)) {
+                            pending_marker = Some((headings.len(), current_page));
+                        }
+                        headings.push(heading);
+                        indents.push(functions::leading_columns(untrimmed[joined[i].0]));
+                    }
+                }
+            }
+            if let Some(last) = lines.last() {
+                previous_last_line = Some((normalize_for_match(last, lang), current_page));
+            }
+        }
+        functions::apply_indentation(&mut headings, &indents);
+    });
+    let (drops, warnings) = functions::crowded_page_drops(&headings, &page_chars, config);
+    let headings: Vec<Heading> = headings.into_iter()
+        .enumerate()
+        .filter(|(i, _)| !drops.contains(i))
+        .map(|(_, heading)| heading)
+        .collect();
+
+    let (title, title_source) = resolve_title(title_scores.first().map(|(t, _)| t.clone()), name, &config.lexicon);
+    Outline {
+        title,
+        title_source,
+        title_candidates: heuristic_title_candidates(&title_scores),
+        outline: stats.time(Stage::Hierarchy, || functions::establish_hierarchy(headings, lang)),
+        warnings,
+        ..Outline::default()
+    }
+}
@@ -0,0 +1,145 @@
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::time::{Duration, Instant};
+
+// Pipeline stages that get their own wall-clock timer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    FileRead,
+    TextExtraction,
+    LineHeuristics,
+    LopdfLoad,
+    RunExtraction,
+    CandidateClassification,
+    Hierarchy,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 7] = [
+        Stage::FileRead,
+        Stage::TextExtraction,
+        Stage::LineHeuristics,
+        Stage::LopdfLoad,
+        Stage::RunExtraction,
+        Stage::CandidateClassification,
+        Stage::Hierarchy,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::FileRead => "file_read",
+            Stage::TextExtraction => "text_extraction",
+            Stage::LineHeuristics => "line_heuristics",
+            Stage::LopdfLoad => "lopdf_load",
+            Stage::RunExtraction => "run_extraction",
+            Stage::CandidateClassification => "candidate_classification",
+            Stage::Hierarchy => "hierarchy",
+        }
+    }
+
+    fn index(self) -> usize {
+        Stage::ALL.iter().position(|&s| s == self).unwrap()
+    }
+}
+
+// Accumulated time per stage. Run extraction and classification happen per
+// page on the rayon pool, so those two are summed across pages rather than
+// measured end to end.
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    durations: [Duration; 7],
+    files: usize,
+    // Most bytes extraction held in its own buffers at once, as counted
+    // against `max_memory`; across a batch, the most for any one file
+    peak_memory: usize,
+    // How long a whole batch took. Files run in parallel under --jobs, so
+    // this can be far less than the stage times summed across them.
+    wall: Option<Duration>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Stats { files: 1, ..Default::default() }
+    }
+
+    pub fn time<T>(&mut self, stage: Stage, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.add(stage, start.elapsed());
+        result
+    }
+
+    pub fn add(&mut self, stage: Stage, elapsed: Duration) {
+        self.durations[stage.index()] += elapsed;
+    }
+
+    pub fn record_memory(&mut self, bytes: usize) {
+        self.peak_memory = self.peak_memory.max(bytes);
+    }
+
+    pub fn peak_memory(&self) -> usize {
+        self.peak_memory
+    }
+
+    pub fn record_wall(&mut self, elapsed: Duration) {
+        self.wall = Some(elapsed);
+    }
+
+    pub fn wall(&self) -> Option<Duration> {
+        self.wall
+    }
+
+    // Fold another file's stats into a batch total
+    pub fn merge(&mut self, other: &Stats) {
+        for (total, d) in self.durations.iter_mut().zip(other.durations.iter()) {
+            *total += *d;
+        }
+        self.files += other.files;
+        self.record_memory(other.peak_memory);
+    }
+
+    // The stage times summed: CPU time spent in the pipeline, rather than
+    // time elapsed, for a batch whose files ran in parallel
+    pub fn total(&self) -> Duration {
+        self.durations.iter().sum()
+    }
+
+    pub fn print_table(&self) {
+        if self.files > 1 {
+            eprintln!("Stage timings across {} files", self.files);
+        }
+        eprintln!("{:<26} {:>12}", "stage", "time (ms)");
+        for stage in Stage::ALL {
+            eprintln!("{:<26} {:>12.2}", stage.name(), millis(self.durations[stage.index()]));
+        }
+        match self.wall {
+            Some(wall) => {
+                eprintln!("{:<26} {:>12.2}", "stage time (summed)", millis(self.total()));
+                eprintln!("{:<26} {:>12.2}", "wall clock", millis(wall));
+            }
+            None => eprintln!("{:<26} {:>12.2}", "total", millis(self.total())),
+        }
+        eprintln!("{:<26} {:>12.2}", "peak buffers (MB)", self.peak_memory as f64 / (1024.0 * 1024.0));
+    }
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+// Serialized as `{ "<stage>_ms": <millis>, ... }` in pipeline order, their
+// sum as `total_ms`, a batch's elapsed time as `wall_ms`, then the peak
+// memory
+impl Serialize for Stats {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(Stage::ALL.len() + 2 + usize::from(self.wall.is_some())))?;
+        for stage in Stage::ALL {
+            map.serialize_entry(&format!("{}_ms", stage.name()), &millis(self.durations[stage.index()]))?;
+        }
+        map.serialize_entry("total_ms", &millis(self.total()))?;
+        if let Some(wall) = self.wall {
+            map.serialize_entry("wall_ms", &millis(wall))?;
+        }
+        map.serialize_entry("peak_memory_bytes", &self.peak_memory)?;
+        map.end()
+    }
+}
@@ -0,0 +1,85 @@
+// Whether a PDF's document info /Title can be trusted. Generators fill it
+// with whatever they had to hand: "Untitled", the source file's name, the
+// name of the template the document started from.
+
+use std::collections::HashSet;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+pub use crate::functions::{extract_document_title, title_from_filename};
+
+// Scores for a metadata title that is junk, that is plausible but not found
+// on the opening pages, and that the opening pages confirm. Titles scoring
+// at least PLAUSIBLE are used; the rest only appear as title candidates.
+const JUNK: f64 = 0.2;
+const UNCONFIRMED_WORD: f64 = 0.3;
+pub const PLAUSIBLE: f64 = 0.5;
+const UNCONFIRMED: f64 = 0.6;
+const CONFIRMED: f64 = 0.9;
+
+// Share of a title's words that must appear in the opening pages' text for
+// it to count as found there
+const MIN_WORDS_FOUND: f64 = 0.8;
+
+// Placeholders left by a generator or an empty template, compared after
+// lowercasing and dropping a trailing number ("Document1", "Slide 3")
+const PLACEHOLDERS: &[&str] = &[
+    "untitled", "untitled document", "no title", "title", "document", "new document", "doc",
+    "presentation", "powerpoint presentation", "slide", "slides", "layout", "page", "blank",
+    "template", "default", "draft", "scan", "scanned document", "image", "img", "pdf",
+    "unknown", "none", "n/a", "na", "null", "cover", "front page", "microsoft word", "word document",
+];
+
+// "Microsoft Word - report.docx", "Untitled-1", a file name or path, or an
+// identifier with no letters between its separators ("IMG_0042", "2023-11-04")
+static GENERATED: Lazy<Regex> = Lazy::new(|| Regex::new(concat!(
+    r"(?i)^microsoft\s|^untitled\b|[/\\]",
+    r"|\.(docx?|odt|rtf|pdf|pptx?|xlsx?|indd|tex|cdr|ai|psd|pub|txt|html?|jpe?g|png|tiff?)$",
+    r"|^[\w-]*_[\w-]*$|^[\d\s._-]+$|^img[\s_-]?\d+$",
+)).unwrap());
+
+static TRAILING_NUMBER: Lazy<Regex> = Lazy::new(|| Regex::new(r"[\s_-]*\d+$").unwrap());
+
+/// How far a document info title can be trusted, from 0 to 1. Titles scoring
+/// at least [`PLAUSIBLE`] are used as the document title when the first page
+/// isn't read, and before it in quick mode. `opening_text` is the text of the
+/// first two pages; a title whose words appear there scores highest.
+///
+/// ```
+/// use adobe1a::titles::{metadata_title_score, PLAUSIBLE};
+///
+/// let opening = "ANNUAL REPORT\n2023–24\nPrepared for the Board of Directors\nContents";
+/// assert!(metadata_title_score("Annual Report 2023–24", opening) >= PLAUSIBLE);
+/// assert!(metadata_title_score("Microsoft Word - Annual Report.docx", opening) < PLAUSIBLE);
+/// assert!(metadata_title_score("Document1", opening) < PLAUSIBLE);
+/// ```
+///
+/// A title that fails the check gives way to the first page's, and is still
+/// listed among the title candidates.
+pub fn metadata_title_score(title: &str, opening_text: &str) -> f64 {
+    let title = title.trim();
+    let bare = TRAILING_NUMBER.replace(&title.to_lowercase(), "").to_string();
+    if !title.chars().any(char::is_alphabetic) || PLACEHOLDERS.contains(&bare.as_str()) || GENERATED.is_match(title) {
+        return JUNK;
+    }
+    let words = words(title);
+    let opening = words_of(opening_text);
+    let found = words.iter().filter(|word| opening.contains(*word)).count();
+    match (found as f64 >= words.len() as f64 * MIN_WORDS_FOUND, words.len()) {
+        (true, _) => CONFIRMED,
+        (false, 1) => UNCONFIRMED_WORD,
+        (false, _) => UNCONFIRMED,
+    }
+}
+
+// The lowercased words of `text`, split at anything but letters and digits
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn words_of(text: &str) -> HashSet<String> {
+    words(text).into_iter().collect()
+}
@@ -0,0 +1,233 @@
+//! Post-processing of a finished outline, described in a small JSON file so
+//! the same clean-up can be applied to every output (`--post`). A spec is a
+//! list of steps applied in order:
+//!
+//! ```json
+//! [
+//!   { "op": "drop_levels", "levels": ["H4"] },
+//!   { "op": "strip_numbers" },
+//!   { "op": "min_confidence", "value": 0.8 },
+//!   { "op": "rename_title_regex", "pattern": "^Microsoft Word - (.*)\\.docx$", "replacement": "$1" },
+//!   { "op": "limit", "count": 50 }
+//! ]
+//! ```
+//!
+//! Heading ids are kept as extraction assigned them, so they still match
+//! those of an untransformed outline.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{Outline, SECTION_HEADING};
+
+// "1.2 ", "3) ", "B. ", "iv. ": numbering in front of a heading's text. A
+// bare number needs to be short, so "2024 Annual Report" keeps its year.
+static NUMBER_PREFIX: Lazy<Regex> = Lazy::new(||
+    Regex::new(r"^\s*(?:\d+(?:\.\d+)+\.?|\d{1,2}[.)]?|\d+[.)]|[A-Za-z][.)]|(?i:[IVXLCDM]+)[.)])\s+").unwrap());
+
+/// One step of a [`Transform`]
+#[derive(Debug, Clone)]
+pub enum Operation {
+    /// Drops the headings at these levels. Headings below them keep their
+    /// own levels rather than moving up.
+    ///
+    /// ```
+    /// # use adobe1a::{transform::Transform, Heading, Outline};
+    /// # let heading = |level: &str, text: &str| Heading { level: level.into(), text: text.into(), page: 1, confidence: 0.9, ..Default::default() };
+    /// let outline = Outline { outline: vec![heading("H1", "Scope"), heading("H4", "Note"), heading("H2", "Terms")], ..Outline::default() };
+    /// let transform = Transform::parse(r#"[{ "op": "drop_levels", "levels": ["H3", "H4"] }]"#)?;
+    /// let texts: Vec<_> = transform.apply(outline).outline.into_iter().map(|h| h.text).collect();
+    /// assert_eq!(texts, ["Scope", "Terms"]);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    DropLevels(Vec<String>),
+    /// Removes numbering from the start of each heading: "1.2", "3)", "B.",
+    /// "iv." and "Chapter 3:" before a title. A heading that is nothing but
+    /// its number is left alone.
+    ///
+    /// ```
+    /// # use adobe1a::{transform::Transform, Heading, Outline};
+    /// # let heading = |text: &str| Heading { level: "H1".into(), text: text.into(), page: 1, confidence: 0.9, ..Default::default() };
+    /// let texts = ["1.2 Scope", "3) Goals", "B. Budget", "iv. Terms", "Chapter 3: Methods", "2024 Annual Report", "Appendix A", "7"];
+    /// let outline = Outline { outline: texts.iter().map(|t| heading(t)).collect(), ..Outline::default() };
+    /// let transform = Transform::parse(r#"[{ "op": "strip_numbers" }]"#)?;
+    /// let texts: Vec<_> = transform.apply(outline).outline.into_iter().map(|h| h.text).collect();
+    /// assert_eq!(texts, ["Scope", "Goals", "Budget", "Terms", "Methods", "2024 Annual Report", "Appendix A", "7"]);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    StripNumbers,
+    /// Drops headings detected with less confidence than this (0 to 1)
+    ///
+    /// ```
+    /// # use adobe1a::{transform::Transform, Heading, Outline};
+    /// # let heading = |text: &str, confidence| Heading { level: "H1".into(), text: text.into(), page: 1, confidence, ..Default::default() };
+    /// let outline = Outline { outline: vec![heading("Scope", 0.95), heading("Maybe", 0.65), heading("Terms", 0.8)], ..Outline::default() };
+    /// let transform = Transform::parse(r#"[{ "op": "min_confidence", "value": 0.8 }]"#)?;
+    /// let texts: Vec<_> = transform.apply(outline).outline.into_iter().map(|h| h.text).collect();
+    /// assert_eq!(texts, ["Scope", "Terms"]);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    MinConfidence(f64),
+    /// Rewrites the title's first match of `pattern` with `replacement`,
+    /// which may refer to groups as `$1` or `${name}`. A title the pattern
+    /// doesn't match is kept.
+    ///
+    /// ```
+    /// # use adobe1a::{transform::Transform, Outline};
+    /// let transform = Transform::parse(r#"[{ "op": "rename_title_regex", "pattern": "^Microsoft Word - (.*)\\.docx$", "replacement": "$1" }]"#)?;
+    /// let outline = Outline { title: "Microsoft Word - Annual Report.docx".into(), ..Outline::default() };
+    /// assert_eq!(transform.apply(outline).title, "Annual Report");
+    /// let outline = Outline { title: "Annual Report".into(), ..Outline::default() };
+    /// assert_eq!(transform.apply(outline).title, "Annual Report");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    RenameTitle { pattern: Regex, replacement: String },
+    /// Keeps only the first this many headings
+    ///
+    /// ```
+    /// # use adobe1a::{transform::Transform, Heading, Outline};
+    /// # let heading = |text: &str| Heading { level: "H1".into(), text: text.into(), page: 1, confidence: 0.9, ..Default::default() };
+    /// let outline = Outline { outline: vec![heading("Scope"), heading("Goals"), heading("Terms")], ..Outline::default() };
+    /// let transform = Transform::parse(r#"[{ "op": "limit", "count": 2 }]"#)?;
+    /// assert_eq!(transform.apply(outline).outline.len(), 2);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    Limit(usize),
+}
+
+// A step as written in the spec, before it's checked
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case", deny_unknown_fields)]
+enum Step {
+    DropLevels { levels: Vec<String> },
+    StripNumbers,
+    MinConfidence { value: f64 },
+    RenameTitleRegex { pattern: String, replacement: String },
+    Limit { count: usize },
+}
+
+impl Step {
+    fn name(&self) -> &'static str {
+        match self {
+            Step::DropLevels { .. } => "drop_levels",
+            Step::StripNumbers => "strip_numbers",
+            Step::MinConfidence { .. } => "min_confidence",
+            Step::RenameTitleRegex { .. } => "rename_title_regex",
+            Step::Limit { .. } => "limit",
+        }
+    }
+
+    fn check(self) -> std::result::Result<Operation, String> {
+        Ok(match self {
+            Step::DropLevels { levels } => {
+                if levels.is_empty() {
+                    return Err("`levels` is empty; list levels such as \"H4\"".into());
+                }
+                if let Some(level) = levels.iter().find(|level| !is_level(level)) {
+                    return Err(format!("{:?} isn't a heading level such as \"H4\"", level));
+                }
+                Operation::DropLevels(levels)
+            }
+            Step::StripNumbers => Operation::StripNumbers,
+            Step::MinConfidence { value } => {
+                if !(0.0..=1.0).contains(&value) {
+                    return Err(format!("`value` is {}, but confidences run from 0 to 1", value));
+                }
+                Operation::MinConfidence(value)
+            }
+            Step::RenameTitleRegex { pattern, replacement } => {
+                let pattern = Regex::new(&pattern).map_err(|e| format!("`pattern` isn't a valid regex: {}", e))?;
+                Operation::RenameTitle { pattern, replacement }
+            }
+            Step::Limit { count: 0 } => return Err("`count` is 0, which would drop every heading".into()),
+            Step::Limit { count } => Operation::Limit(count),
+        })
+    }
+}
+
+// "H1", "H2", ...
+fn is_level(level: &str) -> bool {
+    level.strip_prefix('H').and_then(|n| n.parse::<u8>().ok()).is_some_and(|n| n > 0)
+}
+
+/// A list of [`Operation`]s, applied in order
+#[derive(Debug, Clone, Default)]
+pub struct Transform {
+    operations: Vec<Operation>,
+}
+
+impl Transform {
+    /// Reads and checks a spec file
+    pub fn load(path: &Path) -> Result<Transform> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read transform {}", path.display()))?;
+        Transform::parse(&text).with_context(|| format!("Invalid transform {}", path.display()))
+    }
+
+    /// Checks a spec given as JSON text. Errors name the step, counted from 1.
+    ///
+    /// ```
+    /// use adobe1a::transform::Transform;
+    ///
+    /// let error = |spec: &str| Transform::parse(spec).unwrap_err().to_string();
+    /// assert!(error(r#"[{ "op": "drop_level", "levels": ["H4"] }]"#).contains("unknown variant `drop_level`"));
+    /// assert!(error(r#"[{ "op": "limit", "count": 5, "from": 2 }]"#).contains("unknown field `from`"));
+    /// assert_eq!(error(r#"[{ "op": "strip_numbers" }, { "op": "min_confidence", "value": 80 }]"#),
+    ///            "step 2 (min_confidence): `value` is 80, but confidences run from 0 to 1");
+    /// assert_eq!(error(r#"[{ "op": "drop_levels", "levels": ["h4"] }]"#),
+    ///            r#"step 1 (drop_levels): "h4" isn't a heading level such as "H4""#);
+    /// assert!(error(r#"[{ "op": "rename_title_regex", "pattern": "(", "replacement": "" }]"#)
+    ///     .starts_with("step 1 (rename_title_regex): `pattern` isn't a valid regex"));
+    /// assert!(error(r#"{ "op": "strip_numbers" }"#).contains("expected a sequence"));
+    /// ```
+    pub fn parse(json: &str) -> Result<Transform> {
+        let steps: Vec<Step> = serde_json::from_str(json)?;
+        let operations = steps.into_iter().enumerate()
+            .map(|(i, step)| {
+                let name = step.name();
+                step.check().map_err(|e| anyhow::anyhow!("step {} ({}): {}", i + 1, name, e))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Transform { operations })
+    }
+
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// `outline` with every operation applied in turn
+    pub fn apply(&self, mut outline: Outline) -> Outline {
+        for operation in &self.operations {
+            match operation {
+                Operation::DropLevels(levels) => outline.outline.retain(|heading| !levels.contains(&heading.level)),
+                Operation::StripNumbers => {
+                    for heading in &mut outline.outline {
+                        heading.text = strip_number(&heading.text);
+                    }
+                }
+                Operation::MinConfidence(min) => outline.outline.retain(|heading| heading.confidence >= *min),
+                Operation::RenameTitle { pattern, replacement } => {
+                    outline.title = pattern.replace(&outline.title, replacement.as_str()).into_owned();
+                }
+                Operation::Limit(count) => outline.outline.truncate(*count),
+            }
+        }
+        outline
+    }
+}
+
+// `text` without its leading numbering, unless nothing would be left
+fn strip_number(text: &str) -> String {
+    let stripped = match SECTION_HEADING.captures(text).and_then(|captures| captures.get(3)) {
+        Some(title) => title.as_str(),
+        None => NUMBER_PREFIX.find(text).map_or(text, |prefix| &text[prefix.end()..]),
+    };
+    match stripped.trim() {
+        "" => text.to_string(),
+        stripped => stripped.to_string(),
+    }
+}
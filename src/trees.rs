@@ -0,0 +1,89 @@
+// Guards for walking the PDF's trees: the page tree, bookmarks, and name and
+// number trees. Malformed and fuzzed files link nodes back to their
+// ancestors or nest them absurdly deep; a walk skips such nodes, notes it
+// in a `tree_cycle` or `tree_too_deep` warning, and carries on with the
+// rest instead of hanging or exhausting the stack.
+
+use std::collections::HashSet;
+
+use lopdf::{Document, Object, ObjectId};
+
+use crate::Warning;
+
+// No real tree nests anywhere near this deep
+pub const MAX_TREE_DEPTH: usize = 64;
+
+// What one walk has seen. Node identity is the object number, so only
+// indirect nodes can be caught looping; direct ones can't refer back.
+#[derive(Default)]
+pub struct TreeWalk {
+    visited: HashSet<ObjectId>,
+    cycle: bool,
+    too_deep: bool,
+}
+
+impl TreeWalk {
+    // Whether to enter `node` at `depth` (the root being 0). A node reached a
+    // second time or past MAX_TREE_DEPTH is skipped.
+    pub fn enter(&mut self, node: &Object, depth: usize) -> bool {
+        if depth > MAX_TREE_DEPTH {
+            self.too_deep = true;
+            return false;
+        }
+        if let Ok(id) = node.as_reference() {
+            if !self.visited.insert(id) {
+                self.cycle = true;
+                return false;
+            }
+        }
+        true
+    }
+
+    // A warning naming `tree` when the walk skipped anything
+    pub fn warning(&self, tree: &str) -> Option<Warning> {
+        if self.cycle {
+            Some(Warning::new("tree_cycle", format!(
+                "the {} reaches some of its nodes more than once, as in a loop; each was read once", tree)))
+        } else if self.too_deep {
+            Some(Warning::new("tree_too_deep", format!(
+                "the {} nests more than {} levels deep; the deeper levels were skipped", tree, MAX_TREE_DEPTH)))
+        } else {
+            None
+        }
+    }
+}
+
+// Drops the /Kids entries leading back to a node already visited or past the
+// depth limit, so that `get_pages`, and everything built on it, sees each
+// page once. lopdf's own page iterator stops on a loop but returns its pages
+// over and over until then.
+pub fn repair_page_tree(doc: &mut Document) -> Option<Warning> {
+    let root = doc.catalog().ok()?.get(b"Pages").ok()?.clone();
+    let mut walk = TreeWalk::default();
+    walk.enter(&root, 0);
+    let mut repairs = Vec::new();
+    collect_page_tree_repairs(doc, &root, 0, &mut walk, &mut repairs);
+    for (id, kids) in repairs {
+        if let Ok(node) = doc.get_dictionary_mut(id) {
+            node.set("Kids", kids);
+        }
+    }
+    walk.warning("page tree")
+}
+
+fn collect_page_tree_repairs(doc: &Document, node: &Object, depth: usize, walk: &mut TreeWalk, repairs: &mut Vec<(ObjectId, Vec<Object>)>) {
+    let Ok(id) = node.as_reference() else { return };
+    let Ok(kids) = doc.get_dictionary(id).and_then(|node| node.get(b"Kids")).and_then(Object::as_array) else {
+        return;
+    };
+    let mut kept = Vec::with_capacity(kids.len());
+    for kid in kids {
+        if walk.enter(kid, depth + 1) {
+            kept.push(kid.clone());
+            collect_page_tree_repairs(doc, kid, depth + 1, walk, repairs);
+        }
+    }
+    if kept.len() < kids.len() {
+        repairs.push((id, kept));
+    }
+}
@@ -0,0 +1,137 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::config::ExtractorConfig;
+use crate::functions::{is_canonical_section, is_title_case, reads_like_sentence, roman_value, section_heading_level, LineInfo};
+use crate::lexicon::mentions;
+
+// Length bounds, in bytes, for a line to be considered as a heading at all
+pub const MIN_HEADING_LEN: usize = 3;
+pub const MAX_HEADING_LEN: usize = 150;
+
+const STARTS_LOWERCASE: &str = "starts lowercase";
+
+// A table row flattened into one line of text: cells split by tabs or wide
+// runs of spaces, or a label followed by two or more figures ("Total
+// Revenue 790.1 1,678.3"). The font engine finds tables by position instead.
+static TABLE_ROW: Lazy<Regex> = Lazy::new(||
+    Regex::new(r"\t|\S {3,}\S.* {3,}\S|(?:\s+\(?[-+]?[$€£]?\d[\d,.]*%?\)?){2,}$").unwrap());
+
+// The text after a lowercase roman numeral or letter labelling the line:
+// "iv. Supporting Details" starts with a capital once past "iv."
+fn after_lowercase_label(line: &str) -> &str {
+    let Some((token, rest)) = line.split_once(' ') else {
+        return line;
+    };
+    let is_label = token.strip_suffix(['.', ')']).is_some_and(|body| {
+        !body.is_empty() && body.chars().all(|c| c.is_ascii_lowercase()) && (body.len() == 1 || roman_value(body).is_some())
+    });
+    if is_label { rest.trim_start() } else { line }
+}
+
+// The one set of rules deciding whether a line may become a heading, used by
+// both engines. Where the engines' old checks conflicted:
+// - length: the text engine's 3..=150 bytes wins over the font engine's 4..=100
+// - trailing connectives are compared as whole words, so "Sponsor" and
+//   "Director" no longer look like they end in "or"
+// - the font engine's sentence and word-count limits now apply to both, but
+//   its 6-word cap on lines starting with a digit is gone: it dropped real
+//   numbered headings like "1. Introduction to the Foundation Level Extensions"
+pub struct CandidateFilter<'a> {
+    config: &'a ExtractorConfig,
+}
+
+impl<'a> CandidateFilter<'a> {
+    pub fn new(config: &'a ExtractorConfig) -> Self {
+        CandidateFilter { config }
+    }
+
+    pub fn accepts(&self, info: &LineInfo) -> bool {
+        self.rejection(info).is_none()
+    }
+
+    // Like `accepts`, but a line starting in lowercase passes when its style
+    // or position already marks it as a heading; some designs set every
+    // heading in lowercase ("what we heard")
+    pub fn accepts_styled(&self, info: &LineInfo, stands_out: bool) -> bool {
+        match self.rejection(info) {
+            None => true,
+            Some(STARTS_LOWERCASE) => stands_out,
+            Some(_) => false,
+        }
+    }
+
+    // Why the line can't be a heading, or None when it can
+    pub fn rejection(&self, info: &LineInfo) -> Option<&'static str> {
+        let line = info.text.trim();
+        let lower = info.lower.as_str();
+        let word_count = info.words.len();
+
+        if !(MIN_HEADING_LEN..=MAX_HEADING_LEN).contains(&line.len()) {
+            return Some("length out of range");
+        }
+        let lexicon = &self.config.lexicon;
+        if mentions(lower, &lexicon.excluded_substrings) && !is_canonical_section(line, lexicon) {
+            return Some("boilerplate");
+        }
+        if info.char_count > 0 && info.non_letter_count as f64 / info.char_count as f64 > 0.7 {
+            return Some("mostly non-letters");
+        }
+        if line.len() < 20 && (
+            lower.starts_with("page ") ||
+            (lower.contains("chapter ") && section_heading_level(line).is_none()) ||
+            info.numeric_count > line.len() / 3
+        ) {
+            return Some("page or chapter reference");
+        }
+        if (line.contains('$') || line.contains('€') || line.contains('£')) && info.numeric_count > 2 {
+            return Some("amount");
+        }
+        if TABLE_ROW.is_match(line) {
+            return Some("table row");
+        }
+        if mentions(lower, &lexicon.prose_phrases) {
+            return Some("prose phrase");
+        }
+        if line.ends_with(',') {
+            return Some("ends mid-sentence");
+        }
+        if info.words.last().is_some_and(|w| lexicon.trailing_connectives.contains(&w.to_lowercase())) {
+            return Some("ends mid-sentence");
+        }
+        if line.ends_with('.') && word_count > self.config.max_sentence_words {
+            return Some("sentence");
+        }
+        if word_count > self.config.max_heading_words {
+            return Some("too many words");
+        }
+        // A bulleted line is a list item unless it reads as a title: "• Risk
+        // Register", but not "• 4 credits of Math" or a sentence
+        if info.bulleted && (reads_like_sentence(line) || !is_title_case(line)) {
+            return Some("list item");
+        }
+        // Checked last so that `accepts_styled` only overrules this rule
+        if after_lowercase_label(line).chars().next().is_some_and(|c| c.is_lowercase()) {
+            return Some(STARTS_LOWERCASE);
+        }
+        None
+    }
+
+    // Final check on a heading's cleaned text
+    pub fn text_rejection(&self, text: &str) -> Option<&'static str> {
+        if text.trim().is_empty() {
+            return Some("empty after cleaning");
+        }
+        let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+        if letters == 0 {
+            return Some("only punctuation or digits");
+        }
+        if letters < self.config.min_heading_letters {
+            return Some("too few letters");
+        }
+        if text.len() > MAX_HEADING_LEN {
+            return Some("too long");
+        }
+        None
+    }
+}
@@ -0,0 +1,412 @@
+//! Document metadata: the XMP packet a PDF's catalog can carry as its
+//! /Metadata stream, which tools tend to keep better up to date than the
+//! older document info dictionary, with the info dictionary filling any gaps.
+//!
+//! Only the few fields used here are read, with a small XML reader of its own
+//! rather than a full XML parser.
+
+use std::collections::HashMap;
+use anyhow::{bail, Result};
+use lopdf::Document;
+use serde::{Deserialize, Serialize};
+
+use crate::pages::decode_text_string;
+
+const DC: &str = "http://purl.org/dc/elements/1.1/";
+const PDF: &str = "http://ns.adobe.com/pdf/1.3/";
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const XML: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// A document's title, authors and keywords as its metadata records them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    // Not from XMP: the name of the attached file the outline was read
+    // from, when one was picked out of the PDF holding it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment: Option<String>,
+}
+
+impl Metadata {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.authors.is_empty() && self.keywords.is_empty() && self.attachment.is_none()
+    }
+}
+
+/// Reads `dc:title`, `dc:creator`, `dc:subject` and `pdf:Keywords` from an
+/// XMP packet. A title given in several languages (an `rdf:Alt`) is the
+/// `x-default` one, or the first when none is marked default. Keywords are
+/// split at commas and semicolons. XML that isn't well-formed is an error.
+///
+/// ```
+/// use adobe1a::xmp::parse;
+///
+/// let packet = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+/// <x:xmpmeta xmlns:x="adobe:ns:meta/">
+///   <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+///     <rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/"
+///         xmlns:pdf="http://ns.adobe.com/pdf/1.3/" pdf:Keywords="budget; forecast, 2024">
+///       <dc:title><rdf:Alt>
+///         <rdf:li xml:lang="de-DE">Jahresbericht</rdf:li>
+///         <rdf:li xml:lang="x-default">Annual Report &amp; Accounts</rdf:li>
+///       </rdf:Alt></dc:title>
+///       <dc:creator><rdf:Seq><rdf:li>Ana Ruiz</rdf:li><rdf:li>Li Wei</rdf:li></rdf:Seq></dc:creator>
+///       <dc:subject><rdf:Bag><rdf:li>finance</rdf:li><rdf:li>budget</rdf:li></rdf:Bag></dc:subject>
+///     </rdf:Description>
+///   </rdf:RDF>
+/// </x:xmpmeta>
+/// <?xpacket end="w"?>"#;
+/// let metadata = parse(packet)?;
+/// assert_eq!(metadata.title.as_deref(), Some("Annual Report & Accounts"));
+/// assert_eq!(metadata.authors, ["Ana Ruiz", "Li Wei"]);
+/// assert_eq!(metadata.keywords, ["budget", "forecast", "2024", "finance"]);
+///
+/// // Without an x-default, the first language given
+/// let packet = packet.replace(r#"xml:lang="x-default""#, r#"xml:lang="en-GB""#);
+/// assert_eq!(parse(&packet)?.title.as_deref(), Some("Jahresbericht"));
+///
+/// assert!(parse("<x:xmpmeta><rdf:RDF></x:xmpmeta>").is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Extraction reports what a PDF records as the output's `metadata`, and
+/// lists the XMP title among the title candidates. A packet that can't be
+/// read is a warning.
+pub fn parse(xml: &str) -> Result<Metadata> {
+    let root = read_tree(xml)?;
+    let mut metadata = Metadata::default();
+    for element in root.descendants() {
+        match (element.namespace.as_str(), element.local.as_str()) {
+            (DC, "title") if metadata.title.is_none() => metadata.title = preferred_language(element),
+            (DC, "creator") => metadata.authors.extend(items(element)),
+            (DC, "subject") => subjects(&mut metadata.keywords, items(element)),
+            (PDF, "Keywords") => subjects(&mut metadata.keywords, [element.text()]),
+            _ => {}
+        }
+        // Simple properties are often written as attributes of their description
+        for (namespace, local, value) in &element.attributes {
+            if (namespace.as_str(), local.as_str()) == (PDF, "Keywords") {
+                subjects(&mut metadata.keywords, [value.clone()]);
+            }
+        }
+    }
+    Ok(metadata)
+}
+
+// The metadata of `doc`: its XMP packet's, with the document info
+// dictionary's title, author and keywords filling any gaps. An XMP packet
+// that can't be read is skipped with a warning.
+pub(crate) fn document_metadata(doc: &Document) -> (Metadata, Option<crate::Warning>) {
+    let (mut metadata, warning) = match packet(doc).map(|xml| parse(&xml)) {
+        Some(Ok(metadata)) => (metadata, None),
+        Some(Err(e)) => {
+            let warning = crate::Warning::new("malformed_xmp", format!("the XMP metadata couldn't be read: {}", e));
+            (Metadata::default(), Some(warning))
+        }
+        None => (Metadata::default(), None),
+    };
+
+    let info = |key: &[u8]| -> Option<String> {
+        let info = doc.trailer.get(b"Info").and_then(|i| doc.dereference(i)).ok()?.1.as_dict().ok()?;
+        let raw = info.get(key).and_then(|v| doc.dereference(v)).ok()?.1.as_str().ok()?;
+        Some(decode_text_string(raw).trim().to_string()).filter(|value| !value.is_empty())
+    };
+    if metadata.title.is_none() {
+        metadata.title = info(b"Title");
+    }
+    if metadata.authors.is_empty() {
+        metadata.authors.extend(info(b"Author"));
+    }
+    if metadata.keywords.is_empty() {
+        subjects(&mut metadata.keywords, info(b"Keywords"));
+    }
+    (metadata, warning)
+}
+
+// The XMP title of `doc`, when it has a readable packet with one, scored as
+// the info dictionary's title is
+pub(crate) fn scored_title(doc: &Document) -> Option<(String, f64)> {
+    let title = parse(&packet(doc)?).ok()?.title?;
+    let score = crate::titles::metadata_title_score(&title, &crate::pages::opening_text(doc));
+    Some((title, score))
+}
+
+// The text of the catalog's /Metadata stream, decompressed
+fn packet(doc: &Document) -> Option<String> {
+    let reference = doc.catalog().ok()?.get(b"Metadata").ok()?;
+    let stream = doc.dereference(reference).ok()?.1.as_stream().ok()?;
+    let bytes = stream.decompressed_content().unwrap_or_else(|_| stream.content.clone());
+    Some(match bytes.as_slice() {
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        _ => String::from_utf8_lossy(&bytes).into_owned(),
+    })
+}
+
+fn utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]])).collect();
+    String::from_utf16_lossy(&units)
+}
+
+// The value in the language marked `x-default` of an `rdf:Alt`, else the
+// first one, else the element's own text
+fn preferred_language(element: &Element) -> Option<String> {
+    let alternatives: Vec<&Element> = element.descendants().filter(|e| e.is(RDF, "li")).collect();
+    alternatives.iter()
+        .find(|li| li.attribute(XML, "lang") == Some("x-default"))
+        .or(alternatives.first())
+        .map(|li| li.text())
+        .or_else(|| Some(element.text()))
+        .filter(|text| !text.is_empty())
+}
+
+// The items of an `rdf:Seq` or `rdf:Bag`, or the element's text when it's
+// written without one
+fn items(element: &Element) -> Vec<String> {
+    let items: Vec<String> = element.descendants()
+        .filter(|e| e.is(RDF, "li"))
+        .map(Element::text)
+        .filter(|text| !text.is_empty())
+        .collect();
+    match items.is_empty() {
+        true => Some(element.text()).filter(|text| !text.is_empty()).into_iter().collect(),
+        false => items,
+    }
+}
+
+// Adds each comma- or semicolon-separated keyword not already listed
+fn subjects(keywords: &mut Vec<String>, lists: impl IntoIterator<Item = String>) {
+    for list in lists {
+        for keyword in list.split([',', ';']).map(str::trim).filter(|k| !k.is_empty()) {
+            if !keywords.iter().any(|known| known.eq_ignore_ascii_case(keyword)) {
+                keywords.push(keyword.to_string());
+            }
+        }
+    }
+}
+
+// An element of the packet, its name resolved to a namespace
+#[derive(Debug, Default)]
+struct Element {
+    namespace: String,
+    local: String,
+    attributes: Vec<(String, String, String)>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn is(&self, namespace: &str, local: &str) -> bool {
+        self.namespace == namespace && self.local == local
+    }
+
+    fn attribute(&self, namespace: &str, local: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|(n, l, _)| n == namespace && l == local)
+            .map(|(_, _, value)| value.as_str())
+    }
+
+    // All text inside, whitespace collapsed
+    fn text(&self) -> String {
+        let mut text = String::new();
+        self.collect_text(&mut text);
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn collect_text(&self, out: &mut String) {
+        for child in &self.children {
+            match child {
+                Node::Text(text) => out.push_str(text),
+                Node::Element(element) => {
+                    out.push(' ');
+                    element.collect_text(out);
+                }
+            }
+        }
+    }
+
+    // This element and every element inside it, in document order
+    fn descendants(&self) -> Box<dyn Iterator<Item = &Element> + '_> {
+        let children = self.children.iter().filter_map(|child| match child {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        });
+        Box::new(std::iter::once(self).chain(children.flat_map(Element::descendants)))
+    }
+}
+
+// Reads `xml` into a tree under an unnamed root. Processing instructions,
+// comments and doctypes are skipped. Namespace prefixes are resolved with
+// the declarations in scope; an undeclared prefix keeps the prefix itself as
+// its namespace.
+fn read_tree(xml: &str) -> Result<Element> {
+    // Open elements, each with its raw prefixed name and the prefixes it declares
+    let mut stack: Vec<(Element, String, HashMap<String, String>)> = vec![(Element::default(), String::new(), HashMap::new())];
+    let mut rest = xml;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            push_text(&mut stack, rest);
+            break;
+        };
+        push_text(&mut stack, &rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let Some(end) = after.find("]]>") else { bail!("unterminated CDATA section") };
+            stack.last_mut().unwrap().0.children.push(Node::Text(after[..end].to_string()));
+            rest = &after[end + 3..];
+            continue;
+        }
+        let skipped = [("<?", "?>"), ("<!--", "-->"), ("<!", ">")].into_iter()
+            .find(|(open, _)| rest.starts_with(open));
+        if let Some((open, close)) = skipped {
+            let Some(end) = rest[open.len()..].find(close) else { bail!("unterminated {}", open) };
+            rest = &rest[open.len() + end + close.len()..];
+            continue;
+        }
+
+        let Some(end) = tag_end(rest) else { bail!("unterminated tag") };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if let Some(name) = tag.strip_prefix('/') {
+            let (element, open_name, _) = stack.pop().unwrap();
+            if stack.is_empty() || open_name != name.trim() {
+                bail!("</{}> doesn't close <{}>", name.trim(), open_name);
+            }
+            stack.last_mut().unwrap().0.children.push(Node::Element(element));
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let name = &tag[..name_end];
+        if name.is_empty() {
+            bail!("a tag without a name");
+        }
+        let raw_attributes = attributes(&tag[name_end..])?;
+        let declared: HashMap<String, String> = raw_attributes.iter()
+            .filter_map(|(key, value)| key.strip_prefix("xmlns:").map(|prefix| (prefix.to_string(), value.clone())))
+            .collect();
+        stack.push((Element::default(), name.to_string(), declared));
+        let resolve = |stack: &[(Element, String, HashMap<String, String>)], name: &str| -> (String, String) {
+            let (prefix, local) = name.split_once(':').unwrap_or(("", name));
+            let namespace = match prefix {
+                "xml" => XML.to_string(),
+                _ => stack.iter().rev()
+                    .find_map(|(_, _, declared)| declared.get(prefix))
+                    .cloned()
+                    .unwrap_or_else(|| prefix.to_string()),
+            };
+            (namespace, local.to_string())
+        };
+        let (namespace, local) = resolve(&stack, name);
+        let attributes = raw_attributes.iter()
+            .filter(|(key, _)| !key.starts_with("xmlns"))
+            .map(|(key, value)| {
+                let (namespace, local) = resolve(&stack, key);
+                (namespace, local, value.clone())
+            })
+            .collect();
+        let element = &mut stack.last_mut().unwrap().0;
+        (element.namespace, element.local, element.attributes) = (namespace, local, attributes);
+        if self_closing {
+            let (element, _, _) = stack.pop().unwrap();
+            stack.last_mut().unwrap().0.children.push(Node::Element(element));
+        }
+    }
+    if stack.len() > 1 {
+        bail!("<{}> is never closed", stack.last().unwrap().1);
+    }
+    Ok(stack.pop().unwrap().0)
+}
+
+fn push_text(stack: &mut [(Element, String, HashMap<String, String>)], text: &str) {
+    if !text.is_empty() {
+        stack.last_mut().unwrap().0.children.push(Node::Text(unescape(text)));
+    }
+}
+
+// The offset of the `>` ending the tag `rest` starts with, skipping any
+// inside quoted attribute values
+fn tag_end(rest: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in rest.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+// The `name="value"` pairs of a start tag, values unescaped
+fn attributes(mut text: &str) -> Result<Vec<(String, String)>> {
+    let mut attributes = Vec::new();
+    loop {
+        text = text.trim_start();
+        if text.is_empty() {
+            return Ok(attributes);
+        }
+        let Some(eq) = text.find('=') else { bail!("an attribute without a value") };
+        let name = text[..eq].trim();
+        let value = text[eq + 1..].trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            bail!("the value of {} isn't quoted", name);
+        };
+        let Some(end) = value[1..].find(quote) else { bail!("the value of {} isn't closed", name) };
+        attributes.push((name.to_string(), unescape(&value[1..1 + end])));
+        text = &value[end + 2..];
+    }
+}
+
+// `text` with XML's character and entity references replaced
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';').filter(|&end| end <= 10) else {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => entity.strip_prefix('#').and_then(|n| n.parse().ok()).and_then(char::from_u32),
+            },
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,77 @@
+// `--format adobe1a` writes the Round 1A challenge's schema and nothing
+// else: a title string and an outline of {level, text, page}, with levels
+// H1 to H3. Every fixture is extracted with all the optional fields turned
+// on, so any that leaked through would show.
+
+mod common;
+
+use std::process::Command;
+use adobe1a::output::{challenge_json, SortOrder};
+use adobe1a::Extractor;
+use serde_json::Value;
+
+// Checks `json` against the schema, naming `name` in failures
+fn validate(name: &str, json: &str) {
+    let value: Value = serde_json::from_str(json).unwrap();
+    let object = value.as_object().unwrap_or_else(|| panic!("{}: not an object", name));
+    let mut keys: Vec<&str> = object.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, ["outline", "title"], "{}", name);
+    assert!(object["title"].is_string(), "{}: the title isn't a string", name);
+    for heading in object["outline"].as_array().unwrap_or_else(|| panic!("{}: the outline isn't an array", name)) {
+        let heading = heading.as_object().unwrap();
+        let mut keys: Vec<&str> = heading.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["level", "page", "text"], "{}", name);
+        assert!(matches!(heading["level"].as_str(), Some("H1" | "H2" | "H3")), "{}: level {}", name, heading["level"]);
+        assert!(heading["text"].is_string(), "{}: text {}", name, heading["text"]);
+        assert!(heading["page"].as_u64().is_some_and(|page| page >= 1), "{}: page {}", name, heading["page"]);
+    }
+}
+
+#[test]
+fn fixtures_match_the_schema_whatever_is_extracted() {
+    let extractor = Extractor::builder()
+        .include_sources(true)
+        .include_raw_scores(true)
+        .title_candidates(true)
+        .offsets(true)
+        .snippet_chars(80)
+        .synthesize_numbers(true)
+        .build()
+        .unwrap();
+    for (name, pdf) in common::fixtures() {
+        let outline = extractor.extract_bytes(&pdf).unwrap();
+        let json = challenge_json(&outline, SortOrder::Document).unwrap();
+        validate(&name, &json);
+
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["title"], outline.title.as_str(), "{}", name);
+        assert_eq!(value["outline"].as_array().unwrap().len(), outline.outline.len(), "{}", name);
+    }
+}
+
+#[test]
+fn the_cli_writes_the_schema_with_format_adobe1a() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-format-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("report.pdf");
+    std::fs::write(&input, common::numbered_report()).unwrap();
+    let run = |format: &str, output: &str| {
+        let status = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+            .arg("--input").arg(&input)
+            .arg("--output").arg(dir.join(output))
+            .args(["--format", format, "--include-sources"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        std::fs::read_to_string(dir.join(output)).unwrap()
+    };
+
+    let challenge = run("adobe1a", "challenge.json");
+    validate("report", &challenge);
+    // The default is unchanged, enrichments and all
+    let full: Value = serde_json::from_str(&run("full", "full.json")).unwrap();
+    assert!(full["outline"][0].get("sources").is_some());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,51 @@
+// In a document set entirely in capitals, as old typewritten and OCRed files
+// are, capitalization says nothing about headings. The all-caps and
+// title-case rules only take lines with a keyword there, so the outline
+// comes from numbering and keywords.
+
+use adobe1a::config::ExtractorConfig;
+use adobe1a::{analyze_text, Outline, TextOptions};
+
+// A typewritten report: isolated short lines in capitals that aren't
+// headings, around numbered sections and a keyword heading
+const REPORT: &str = "\
+DEPARTMENT OF HARBOURS AND FERRIES
+REPORT ON THE STATE OF THE NORTH QUAY
+PREPARED FOR THE HARBOUR BOARD BY THE OFFICE OF THE CHIEF ENGINEER.
+BACKGROUND TO THE SURVEY
+THE BOARD ASKED FOR A SURVEY OF THE NORTH QUAY AFTER THE WINTER STORMS DAMAGED ITS WALLS.
+RECEIVED BY THE CLERK
+THE SURVEY TEAM WALKED THE FULL LENGTH OF THE QUAY AT LOW WATER IN EARLY MARCH.
+1. CONDITION OF THE WALLS
+THE SURVEY FOUND CRACKS ALONG MOST OF THE WALL, WORST AT ITS SEAWARD END, AND LOOSE STONES.
+FOR INFORMATION ONLY
+THE CRACKS HAVE WIDENED SINCE THE LAST SURVEY, WHICH WAS CARRIED OUT FIVE YEARS AGO.
+2. COST OF REPAIRS
+THE ENGINEER PUT THE COST OF REPAIRS AT TWICE THE SUM SET ASIDE IN THIS YEAR'S BUDGET.
+SEE ATTACHED SHEETS
+THE SHEETS LIST EACH SECTION OF WALL WITH THE WORK IT NEEDS AND WHAT THAT WORK WILL COST.
+3. RECOMMENDATIONS
+THE BOARD SHOULD CLOSE THE SEAWARD END UNTIL THE WORK IS DONE AND SEEK A GRANT FOR IT.
+";
+fn analyze(text: &str, all_caps_ratio: f64) -> Outline {
+    let config = ExtractorConfig { all_caps_ratio, page_heading_limit: 0, ..ExtractorConfig::default() };
+    analyze_text(text, TextOptions { config, ..TextOptions::default() })
+}
+
+fn texts(outline: &Outline) -> Vec<&str> {
+    outline.outline.iter().map(|heading| heading.text.as_str()).collect()
+}
+
+#[test]
+fn only_numbered_and_keyword_headings_are_kept() {
+    let outline = analyze(REPORT, ExtractorConfig::default().all_caps_ratio);
+    assert_eq!(texts(&outline), ["BACKGROUND TO THE SURVEY", "1. CONDITION OF THE WALLS", "2. COST OF REPAIRS", "3. RECOMMENDATIONS"]);
+}
+
+#[test]
+fn capitals_still_count_below_the_ratio() {
+    // Taken as a document with ordinary capitalization, the stray notes are headings too
+    let outline = analyze(REPORT, 1.0);
+    let texts = texts(&outline);
+    assert!(texts.contains(&"RECEIVED BY THE CLERK") && texts.contains(&"SEE ATTACHED SHEETS"), "{:?}", texts);
+}
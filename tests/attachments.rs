@@ -0,0 +1,61 @@
+// `attachment` reads one PDF attached to another, picked out by name, as if
+// it had been the input; the PDF holding it and its other attachments aren't
+// outlined.
+
+mod common;
+
+use adobe1a::Extractor;
+
+fn extract(pattern: Option<&str>, pdf: &[u8]) -> anyhow::Result<adobe1a::Outline> {
+    let builder = Extractor::builder();
+    let builder = match pattern {
+        Some(pattern) => builder.attachment(pattern),
+        None => builder,
+    };
+    builder.build().unwrap().extract_bytes(pdf)
+}
+
+fn codes(outline: &adobe1a::Outline) -> Vec<&str> {
+    outline.warnings.iter().map(|w| w.code.as_str()).collect()
+}
+
+fn texts(outline: &adobe1a::Outline) -> Vec<&str> {
+    outline.outline.iter().map(|h| h.text.as_str()).collect()
+}
+
+#[test]
+fn the_named_attachment_is_read_in_place_of_its_container() {
+    let container = common::attachments();
+    let report = extract(None, &common::numbered_report()).unwrap();
+    for pattern in ["Specification.PDF", "specification.pdf", "spec*", "SPECIFICATION.???"] {
+        let outline = extract(Some(pattern), &container).unwrap();
+        assert_eq!(texts(&outline), texts(&report), "{}", pattern);
+        assert_eq!(outline.title, report.title);
+        assert_eq!(outline.page_count, report.page_count);
+        assert_eq!(outline.metadata.attachment.as_deref(), Some("Specification.PDF"));
+        assert_eq!(codes(&outline), codes(&report));
+    }
+
+    // Without a name, the cover is outlined, and nothing of its attachments
+    let cover = extract(None, &container).unwrap();
+    assert!(cover.metadata.attachment.is_none());
+    assert!(!texts(&cover).iter().any(|text| texts(&report).contains(text)), "{:?}", texts(&cover));
+}
+
+#[test]
+fn a_pattern_matching_several_attachments_reads_the_first() {
+    let outline = extract(Some("*.pdf"), &common::attachments()).unwrap();
+    assert_eq!(outline.metadata.attachment.as_deref(), Some("Specification.PDF"));
+    let ambiguous: Vec<&str> = outline.warnings.iter().filter(|w| w.code == "attachment_ambiguous").map(|w| w.message.as_str()).collect();
+    assert_eq!(ambiguous.len(), 1, "{:?}", outline.warnings);
+    assert!(ambiguous[0].contains("\"drawings.pdf\""), "{}", ambiguous[0]);
+}
+
+#[test]
+fn no_match_lists_the_attachments() {
+    let error = extract(Some("budget.pdf"), &common::attachments()).unwrap_err();
+    assert_eq!(format!("{:#}", error),
+        r#"no attachment matches "budget.pdf"; the PDF's attachments are "Specification.PDF", "drawings.pdf""#);
+    let error = extract(Some("*"), &common::numbered_report()).unwrap_err();
+    assert_eq!(format!("{:#}", error), r#"no attachment matches "*"; the PDF has no attachments"#);
+}
@@ -0,0 +1,111 @@
+// The summary of a directory run: it lists every file in path order with
+// its status and heading counts, even when some fail, its totals give the
+// stage times summed across files alongside the time the whole batch took,
+// and the CSV form ends with a totals row.
+
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+use std::time::Instant;
+use adobe1a::summary::{BatchSummary, FileSummary};
+use adobe1a::timing::Stats;
+use adobe1a::{Heading, Outline};
+use common::pdf_builder::{Page, PdfBuilder};
+
+#[test]
+fn totals_give_the_wall_clock_time_beside_the_summed_stages() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-batch-summary-{}", std::process::id()));
+    let (input, output) = (dir.join("input"), dir.join("output"));
+    std::fs::create_dir_all(&input).unwrap();
+    for (name, pdf) in common::generated() {
+        std::fs::write(input.join(format!("{}.pdf", name)), pdf).unwrap();
+    }
+    let summary = dir.join("summary.json");
+    let start = Instant::now();
+    let status = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+        .arg("--input").arg(&input)
+        .arg("--output").arg(&output)
+        .arg("--summary").arg(&summary)
+        .args(["--jobs", "4"])
+        .status()
+        .unwrap();
+    let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+    assert!(status.success());
+
+    let summary: serde_json::Value = serde_json::from_slice(&std::fs::read(&summary).unwrap()).unwrap();
+    let stats = &summary["totals"]["stats"];
+    let (wall, total) = (stats["wall_ms"].as_f64().unwrap(), stats["total_ms"].as_f64().unwrap());
+    assert!(wall > 0.0 && wall <= elapsed, "{} of {}", wall, elapsed);
+    assert!(total > 0.0);
+    // A single file's stats have no wall-clock time of their own
+    assert!(summary["files"][0]["stats"].get("wall_ms").is_none());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn every_file_is_listed_in_path_order_when_some_fail() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-batch-statuses-{}", std::process::id()));
+    let (input, output) = (dir.join("input"), dir.join("output"));
+    std::fs::create_dir_all(&input).unwrap();
+    for (name, pdf) in common::generated() {
+        std::fs::write(input.join(format!("{}.pdf", name)), pdf).unwrap();
+    }
+    std::fs::write(input.join("broken.pdf"), b"%PDF-1.7\n1 0 obj <<").unwrap();
+    std::fs::write(input.join("blank.pdf"), PdfBuilder::new().page(Page::new().body(3)).build()).unwrap();
+    let summary = dir.join("summary.json");
+    let status = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+        .arg("--input").arg(&input)
+        .arg("--output").arg(&output)
+        .arg("--summary").arg(&summary)
+        .args(["--jobs", "4"])
+        .status()
+        .unwrap();
+    assert!(!status.success());
+
+    let summary: serde_json::Value = serde_json::from_slice(&std::fs::read(&summary).unwrap()).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let files = summary["files"].as_array().unwrap();
+    let paths: Vec<&str> = files.iter().map(|file| file["path"].as_str().unwrap()).collect();
+    let mut sorted = paths.clone();
+    sorted.sort_unstable();
+    assert_eq!(paths, sorted);
+    assert_eq!(files.len(), common::generated().len() + 2);
+
+    let status = |name: &str| {
+        let file = files.iter().find(|file| file["path"].as_str().unwrap().ends_with(name)).unwrap();
+        file["status"].as_str().unwrap().to_string()
+    };
+    assert_eq!(status("broken.pdf"), "failed");
+    assert_eq!(status("blank.pdf"), "empty");
+    assert_eq!(status("numbered-report.pdf"), "ok");
+
+    // The totals add up the files
+    let totals = &summary["totals"];
+    assert_eq!(totals["files"], files.len());
+    assert_eq!(totals["failed"], 1);
+    for (level, count) in totals["headings"].as_object().unwrap() {
+        let sum: u64 = files.iter().filter_map(|file| file["headings"][level].as_u64()).sum();
+        assert_eq!(count.as_u64(), Some(sum), "{}", level);
+    }
+}
+
+#[test]
+fn the_csv_ends_with_the_batch_totals() {
+    let heading = |level: &str| Heading { level: level.into(), text: "Scope".into(), page: 1, ..Default::default() };
+    let outline = |levels: &[&str]| Outline {
+        title: "Report, final".into(),
+        outline: levels.iter().map(|level| heading(level)).collect(),
+        ..Default::default()
+    };
+    let files = vec![
+        FileSummary::succeeded(Path::new("a.pdf"), &outline(&["H1", "H2", "H2"]), Stats::default()),
+        FileSummary::succeeded(Path::new("b.pdf"), &outline(&["H1", "H4"]), Stats::default()),
+        FileSummary::failed(Path::new("c.pdf"), &anyhow::anyhow!("broken")),
+    ];
+    let csv = BatchSummary::new(files).to_csv();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 5);
+    assert!(rows[1].starts_with("a.pdf,ok,\"Report, final\","), "{}", rows[1]);
+    assert_eq!(rows[4], ",total,,,,,2,2,0,1,0,0.00,");
+}
@@ -0,0 +1,122 @@
+// Every setting of `Extractor::builder()` changes the outline. Each is tried
+// on its own, on a generated report, against the font engine with the other
+// settings at their defaults.
+
+mod common;
+
+use adobe1a::calibration::{Calibration, Curve};
+use adobe1a::config::{CaseStyle, Engine, HiddenText, Portfolio, ShortDocument, TitleMode};
+use adobe1a::levels::{LevelOverride, LevelOverrides};
+use adobe1a::lexicon::Lexicon;
+use adobe1a::{Extractor, ExtractorBuilder, Outline};
+use common::pdf_builder::{Font, Page, PdfBuilder, Style};
+use serde_json::Value;
+
+fn report() -> PdfBuilder {
+    let bold = |size| Style::new(Font::HelveticaBold, size);
+    let regular = |size| Style::new(Font::Helvetica, size);
+    PdfBuilder::new()
+        // A running header, the title and a subject line, a blue heading, an
+        // underlined one in lowercase, and an invisible one among visible text
+        .page(Page::new()
+            .text_at(bold(11.0), 72.0, 770.0, "Quarterly Review")
+            .title("Annual Report")
+            .line(Style::BODY, "RE: Regional Figures")
+            .color(0.0, 0.0, 0.8).styled_heading(regular(13.0), "1. Introduction").color(0.0, 0.0, 0.0)
+            .body(8)
+            .underlined(bold(12.0), "results by region")
+            .body(10)
+            .invisible().styled_heading(bold(14.0), "Hidden Summary").body(8).visible())
+        // A heading at the top of a page, after a page full of text
+        .page(Page::new().styled_heading(regular(13.0), "2. Methods").body(20))
+        // A memo with a letterhead of its own, starting on a new page
+        .page(Page::new()
+            .text_at(bold(11.0), 72.0, 770.0, "Facilities Office")
+            .indented(bold(18.0), "Site Visit Memo", 164.0)
+            .body(20))
+        // Text only in an invisible render mode, as an OCR layer is
+        .page(Page::new().invisible().styled_heading(bold(14.0), "Appendix Tables").body(20).visible())
+}
+
+fn font() -> ExtractorBuilder {
+    Extractor::builder().engine(Engine::Font)
+}
+
+fn outline(builder: ExtractorBuilder, pdf: &[u8]) -> Value {
+    serde_json::to_value(builder.build().unwrap().extract_bytes(pdf).unwrap()).unwrap()
+}
+
+#[test]
+fn every_setting_changes_the_outline() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let report = report().build();
+    let changes = |setting: fn(ExtractorBuilder) -> ExtractorBuilder| {
+        outline(setting(font()), &report) != outline(font(), &report)
+    };
+    if Engine::Text.is_built() {
+        assert!(changes(|b| b.engine(Engine::Text)));
+    }
+    assert!(changes(|b| b.hidden_text(HiddenText::Include)));
+    assert!(changes(|b| b.hidden_text(HiddenText::Exclude)));
+    assert!(changes(|b| b.margin_band(0.0)));
+    assert!(changes(|b| b.top_of_page_bonus(0.0)));
+    assert!(changes(|b| b.underline_bonus(0.0)));
+    assert!(changes(|b| b.color_bonus(0.0)));
+    assert!(changes(|b| b.lowercase_penalty(0.0)));
+    // Capitals say nothing about the title in a document set all in capitals
+    let candidates = || font().title_candidates(true);
+    assert_ne!(outline(candidates().all_caps_ratio(0.0), &report), outline(candidates(), &report));
+    assert!(changes(|b| b.title_candidates(true)));
+    assert!(changes(|b| b.min_heading_letters(10)));
+    assert!(changes(|b| b.max_heading_words(1, 1)));
+    assert!(changes(|b| b.max_headings(1)));
+    assert!(changes(|b| b.page_heading_limit(1)));
+    assert!(changes(|b| b.min_confidence(0.8)));
+    assert!(changes(|b| b.split_documents(true)));
+    assert!(changes(|b| b.pages("2".parse().unwrap())));
+    assert!(changes(|b| b.quick(1)));
+    assert!(changes(|b| b.include_sources(true)));
+    assert!(changes(|b| b.include_raw_scores(true)));
+    assert!(changes(|b| b.calibration(Calibration { font: Curve::new(vec![(0.0, 0.5)]).unwrap(), ..Calibration::default() })));
+    assert!(changes(|b| b.level_overrides(LevelOverrides::new(vec![LevelOverride::new(r"^\d+\.", "H4").unwrap()]))));
+    assert!(changes(|b| b.lexicon(Lexicon { excluded_substrings: vec!["methods".to_string()], ..Lexicon::default() })));
+    assert!(changes(|b| b.language("tr")));
+    assert!(changes(|b| b.title(TitleMode::Filename)));
+    assert!(changes(|b| b.snippet_chars(40)));
+    assert!(changes(|b| b.offsets(true)));
+    assert!(changes(|b| b.positions(true)));
+    assert!(changes(|b| b.max_memory(1)));
+    assert!(changes(|b| b.heading_case(CaseStyle::Upper)));
+    assert!(changes(|b| b.synthesize_numbers(true)));
+    assert!(changes(|b| b.short_document(ShortDocument::Always)));
+    assert!(changes(|b| b.short_document_pages(4)));
+    assert!(changes(|b| b.post_process(|outline| Outline { outline: Vec::new(), ..outline })));
+}
+
+#[test]
+fn the_heading_cap_grows_with_the_page_count() {
+    // From 10 headings, so only a long document shows it
+    assert_eq!(Extractor::builder().build().unwrap().config().heading_cap(200), 300);
+    assert_eq!(Extractor::builder().headings_per_page(0.1).build().unwrap().config().heading_cap(200), 20);
+}
+
+#[test]
+fn attachment_and_portfolio_read_a_portfolio() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let report = report().build();
+    // A portfolio with the report attached
+    let cover = PdfBuilder::new()
+        .page(Page::new().styled_heading(Style::new(Font::HelveticaBold, 18.0), "Cover Sheet").body(10))
+        .attachment("report.pdf", report.clone())
+        .portfolio()
+        .build();
+    let attached = outline(font().attachment("REPORT.*"), &cover);
+    assert_eq!(attached["outline"], outline(font(), &report)["outline"]);
+    assert_eq!(attached["metadata"]["attachment"], "report.pdf");
+    let portfolio = |portfolio| outline(font().portfolio(portfolio), &cover);
+    assert_ne!(portfolio(Portfolio::Combine), portfolio(Portfolio::Ignore));
+}
@@ -0,0 +1,76 @@
+// Font-engine candidates are numbered in document order, page by page, so
+// headings of equal confidence come out in the order they were laid out,
+// and the same on every run, whether the document is read whole or
+// streamed a page at a time.
+
+#![cfg(feature = "engine-font")]
+
+mod common;
+
+use std::ops::ControlFlow;
+use std::thread;
+use adobe1a::config::Engine;
+use adobe1a::{ExtractEvent, Extractor};
+use common::pdf_builder::{Page, PdfBuilder};
+
+const SECTIONS: [[&str; 4]; 3] = [
+    ["Quay Walls", "Channel Dredging", "Ferry Terminal", "Slipway Repairs"],
+    ["Crane Rails", "Fuel Berth", "Lock Gates", "Tide Gauges"],
+    ["Harbour Lights", "Fish Market", "Boat Yard", "Sea Wall"],
+];
+
+// Runs on separate threads, each with its own hasher seeds
+const RUNS: usize = 4;
+
+// Three pages of equally styled headings
+fn plan() -> Vec<u8> {
+    SECTIONS.iter().enumerate()
+        .fold(PdfBuilder::new(), |builder, (i, sections)| {
+            let first = if i == 0 { Page::new().title("Harbour Works Plan").body(1) } else { Page::new() };
+            builder.page(sections.iter().fold(first, |page, section| page.heading(2, section).body(2)))
+        })
+        .build()
+}
+
+// The title line is a heading too, at the top of page 1
+fn expected() -> Vec<(String, usize)> {
+    let title = ("Harbour Works Plan".to_string(), 1);
+    std::iter::once(title).chain(SECTIONS.iter().enumerate()
+        .flat_map(|(i, sections)| sections.iter().map(move |section| (section.to_string(), i + 1))))
+        .collect()
+}
+
+fn whole(pdf: &[u8]) -> Vec<(String, usize)> {
+    let extractor = Extractor::builder().engine(Engine::Font).max_headings(50).build().unwrap();
+    let outline = extractor.extract_bytes(pdf).unwrap();
+    outline.outline.into_iter().map(|heading| (heading.text, heading.page)).collect()
+}
+
+fn streamed(path: &std::path::Path) -> Vec<(String, usize)> {
+    let mut sent = Vec::new();
+    Extractor::default().extract_pages(path, |event| {
+        if let ExtractEvent::Page(page) = event {
+            sent.extend(page.headings.into_iter().map(|heading| (heading.text, heading.page)));
+        }
+        ControlFlow::Continue(())
+    }).unwrap();
+    sent
+}
+
+#[test]
+fn headings_come_out_in_layout_order_on_every_run() {
+    let pdf = plan();
+    let path = std::env::temp_dir().join(format!("adobe1a-candidate-order-{}.pdf", std::process::id()));
+    std::fs::write(&path, &pdf).unwrap();
+
+    let runs: Vec<_> = thread::scope(|scope| {
+        let runs: Vec<_> = (0..RUNS).map(|_| scope.spawn(|| (whole(&pdf), streamed(&path)))).collect();
+        runs.into_iter().map(|run| run.join().unwrap()).collect()
+    });
+    std::fs::remove_file(&path).unwrap();
+
+    for (whole, streamed) in runs {
+        assert_eq!(whole, expected());
+        assert_eq!(streamed, expected());
+    }
+}
@@ -0,0 +1,81 @@
+// "Executive Summary", "Abstract", "Summary" and "Foreword" are H1s on the
+// first ten pages however they're set, sentence case in body type included,
+// as long as body text follows them. A contents list, where the next line
+// is another short entry, doesn't count.
+
+mod common;
+
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::lexicon::Lexicon;
+use adobe1a::{analyze_text, extract_outline_from_bytes, timing::Stats, Outline, TextOptions};
+use common::pdf_builder::{Page, PdfBuilder, Style};
+
+const BODY: &str = "The harbour board met to review the year's work on the quay walls and the channel.";
+
+fn analyze(pages: &[&[&str]], lexicon: Lexicon) -> Outline {
+    let text: Vec<String> = pages.iter().map(|lines| lines.join("\n")).collect();
+    let config = ExtractorConfig { lexicon, page_heading_limit: 0, ..ExtractorConfig::default() };
+    analyze_text(&text.join("\n\u{c}"), TextOptions { config, ..TextOptions::default() })
+}
+
+fn headings(outline: &Outline) -> Vec<(&str, &str, usize)> {
+    outline.outline.iter().map(|heading| (heading.level.as_str(), heading.text.as_str(), heading.page)).collect()
+}
+
+#[test]
+fn canonical_sections_are_found_in_any_case() {
+    for (line, text) in [
+        ("Executive summary", "Executive summary"),
+        ("EXECUTIVE SUMMARY", "EXECUTIVE SUMMARY"),
+        ("executive summary:", "executive summary"),
+        ("Abstract", "Abstract"),
+        ("Foreword", "Foreword"),
+    ] {
+        let outline = analyze(&[&["Harbour Works Plan 2025", BODY, line, BODY, BODY]], Lexicon::default());
+        assert_eq!(headings(&outline), [("H1", text, 1)], "{:?}", line);
+        assert!(outline.outline[0].confidence >= 0.85);
+    }
+}
+
+#[test]
+fn a_contents_entry_is_not_the_section() {
+    let outline = analyze(&[
+        &["Harbour Works Plan 2025", "Contents", "Executive summary", "Quay walls", "Channel dredging"],
+        &["Executive summary", BODY, BODY],
+    ], Lexicon::default());
+    let summaries: Vec<_> = outline.outline.iter().filter(|heading| heading.text == "Executive summary").collect();
+    assert_eq!(summaries.len(), 1);
+    assert_eq!((summaries[0].level.as_str(), summaries[0].page), ("H1", 2));
+}
+
+#[test]
+fn only_the_first_ten_pages_are_searched() {
+    let filler = [BODY, BODY, BODY];
+    let mut pages: Vec<&[&str]> = vec![&["Harbour Works Plan 2025", BODY]];
+    pages.extend(std::iter::repeat_n(&filler[..], 10));
+    pages.push(&["Summary", BODY, BODY]);
+    let outline = analyze(&pages, Lexicon::default());
+    assert!(outline.outline.iter().all(|heading| heading.text != "Summary"), "{:?}", headings(&outline));
+}
+
+#[test]
+fn the_names_come_from_the_lexicon() {
+    let lexicon = Lexicon { canonical_sections: vec!["preface".to_string()], ..Lexicon::default() };
+    let outline = analyze(&[&["Harbour Works Plan 2025", BODY, "Preface", BODY, BODY, "Executive summary", BODY, BODY]], lexicon);
+    assert_eq!(headings(&outline), [("H1", "Preface", 1)]);
+}
+
+#[test]
+fn the_font_engine_finds_a_canonical_section_in_body_type() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let pdf = PdfBuilder::new()
+        .page(Page::new().title("Harbour Works Plan 2025").body(2).line(Style::BODY, "Executive summary").body(4).heading(1, "1. Quay Walls").body(3))
+        .build();
+    let config = ExtractorConfig { engine: Engine::Font, ..ExtractorConfig::default() };
+    let outline = extract_outline_from_bytes(&pdf, "plan", &config, &mut Stats::new()).unwrap();
+    let summary = outline.outline.iter().find(|heading| heading.text == "Executive summary").expect("the summary");
+    assert_eq!(summary.level, "H1");
+    assert!(summary.confidence >= 0.85);
+}
@@ -0,0 +1,66 @@
+// A line ending in a colon is a heading when it stands apart, as "Scope of
+// Services:" does over its paragraph. A block of short labelled fields on a
+// form ("Date of submission:" over its value) gives no headings.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::{analyze_text, Extractor, TextOptions};
+use common::pdf_builder::{Page, PdfBuilder, Style};
+
+const FIELDS: [(&str, &str); 10] = [
+    ("RFP Number:", "HA-2024-017"),
+    ("Issued By:", "Harbour Authority"),
+    ("Date of Issue:", "4 March 2024"),
+    ("Date of Submission:", "12 April 2024"),
+    ("Prepared For:", "Port Users Board"),
+    ("Contact Officer:", "R. Alvarez"),
+    ("Contact Email:", "procurement"),
+    ("Estimated Value:", "2.4 million"),
+    ("Contract Term:", "36 months"),
+    ("Site Visit:", "Optional"),
+];
+const HEADING: &str = "Scope of Services:";
+const PARAGRAPH: [&str; 2] = [
+    "The authority seeks a contractor to dredge the inner channel and repair the quay walls.",
+    "Work is expected to start in the summer and finish before the winter storms arrive.",
+];
+
+fn cover_sheet_text() -> String {
+    let mut text = String::from("Request for Proposals\n\n");
+    for (label, value) in FIELDS {
+        text.push_str(&format!("{}\n{}\n", label, value));
+    }
+    text.push_str(&format!("\n{}\n\n{}\n\n", HEADING, PARAGRAPH.join("\n")));
+    text
+}
+
+#[test]
+fn only_the_real_colon_heading_is_found_in_text() {
+    // Blank lines are gone by the time lines are classified, so the heading
+    // follows straight on from the last field's value
+    let outline = analyze_text(&cover_sheet_text(), TextOptions::default());
+    let texts: Vec<&str> = outline.outline.iter().map(|heading| heading.text.as_str()).collect();
+    assert_eq!(texts, ["Scope of Services"]);
+}
+
+#[test]
+fn only_the_real_colon_heading_is_found_in_a_pdf() {
+    // The labels are all in body type, which leaves the font engine nothing
+    // to go on; the text engine reads them by their colons
+    if !Engine::Text.is_built() {
+        return;
+    }
+    let page = FIELDS.iter()
+        .fold(Page::new().title("Request for Proposals"), |page, (label, value)| {
+            page.line(Style::BODY, label).line(Style::BODY, value)
+        })
+        .line(Style::BODY, "")
+        .line(Style::BODY, HEADING)
+        .line(Style::BODY, "");
+    let page = PARAGRAPH.iter().fold(page, |page, line| page.line(Style::BODY, line));
+    let pdf = PdfBuilder::new().page(page).build();
+    let outline = Extractor::builder().engine(Engine::Text).build().unwrap().extract_bytes(&pdf).unwrap();
+    let texts: Vec<&str> = outline.outline.iter().map(|heading| heading.text.as_str()).collect();
+    assert_eq!(texts, ["Scope of Services"]);
+}
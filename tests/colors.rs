@@ -0,0 +1,53 @@
+// Headings set in a brand colour and half a point larger than the black
+// body text are told apart by the colour bonus. Colour alone never makes a
+// heading: a coloured link at body size is read as body text.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::{Extractor, ExtractorBuilder, Outline};
+use common::pdf_builder::{Font, Page, PdfBuilder, Style};
+
+const HEADINGS: [&str; 3] = ["Project Background", "Delivery Approach", "Funding Model"];
+const LINK: &str = "Read the Full Plan Online";
+
+fn brochure() -> Vec<u8> {
+    let heading = Style::new(Font::Helvetica, 10.5);
+    let link = Style::new(Font::Helvetica, 10.0);
+    let page = HEADINGS.iter().fold(Page::new(), |page, text| {
+        page.color(0.1, 0.3, 0.7).styled_heading(heading, text).color(0.0, 0.0, 0.0).body(5)
+    });
+    let page = page.color(0.1, 0.3, 0.7).styled_heading(link, LINK).color(0.0, 0.0, 0.0).body(3);
+    PdfBuilder::new().page(page).build()
+}
+
+fn extract(builder: ExtractorBuilder) -> Outline {
+    builder.engine(Engine::Font).include_raw_scores(true).build().unwrap().extract_bytes(&brochure()).unwrap()
+}
+
+fn confidence(outline: &Outline, text: &str) -> Option<f64> {
+    outline.outline.iter().find(|heading| heading.text == text).map(|heading| heading.confidence)
+}
+
+#[test]
+fn coloured_headings_gain_the_bonus() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let (with, without) = (extract(Extractor::builder()), extract(Extractor::builder().color_bonus(0.0)));
+    for heading in HEADINGS {
+        let with = confidence(&with, heading).unwrap_or_else(|| panic!("{} missing: {:?}", heading, with.outline));
+        let without = confidence(&without, heading).unwrap_or(0.0);
+        assert!(with > without, "{}: {} without the bonus, {} with it", heading, without, with);
+    }
+}
+
+#[test]
+fn colour_alone_is_not_a_heading() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let (with, without) = (extract(Extractor::builder()), extract(Extractor::builder().color_bonus(0.0)));
+    assert_eq!(confidence(&with, LINK), None, "{:?}", with.outline);
+    assert_eq!(confidence(&with, LINK), confidence(&without, LINK));
+}
@@ -0,0 +1,367 @@
+// Fixtures shared by the golden tests and the benchmarks: the PDFs
+// committed under fixtures/, and PDFs generated with `pdf_builder`
+#![allow(dead_code)]
+
+pub mod pdf_builder;
+
+use pdf_builder::{Font, Page, PdfBuilder, Style, BODY_SIZE, PAGE_HEIGHT, PAGE_WIDTH};
+
+// Committed fixtures that extract to an outline; the others test errors
+const COMMITTED: &[&str] = &[
+    "agenda", "hybrid-fonts", "italic-headings", "memo", "nul-heading", "rfp-cover", "table-page",
+];
+
+/// Every fixture by name, committed ones first
+pub fn fixtures() -> Vec<(String, Vec<u8>)> {
+    let committed = COMMITTED.iter().map(|name| {
+        let path = format!("{}/fixtures/{}.pdf", env!("CARGO_MANIFEST_DIR"), name);
+        (name.to_string(), std::fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path, e)))
+    });
+    committed.chain(generated()).collect()
+}
+
+/// The generated fixtures by name
+pub fn generated() -> Vec<(String, Vec<u8>)> {
+    [
+        ("numbered-report", numbered_report()),
+        ("two-columns", two_columns()),
+        ("toc-and-bookmarks", toc_and_bookmarks()),
+        ("serif-headings", serif_headings()),
+        ("unnumbered-sections", unnumbered_sections()),
+        ("sparse-pages", sparse_pages()),
+        ("continued-sections", continued_sections()),
+        ("split-chapter", split_chapter()),
+        ("two-page-memo", two_page_memo()),
+        ("letter", letter()),
+        ("drop-caps", magazine(true)),
+    ]
+    .into_iter()
+    .map(|(name, pdf)| (name.to_string(), pdf))
+    .collect()
+}
+
+// A running header and a page number, as most reports have
+fn furniture(page: Page, number: usize) -> Page {
+    let small = Style::new(Font::Helvetica, 8.0);
+    page.text_at(small, 72.0, PAGE_HEIGHT - 40.0, "Northwind Annual Review 2024")
+        .rect(72.0, PAGE_HEIGHT - 46.0, PAGE_WIDTH - 144.0, 0.5)
+        .text_at(small, PAGE_WIDTH / 2.0, 36.0, &number.to_string())
+}
+
+// Numbered headings three levels deep over several pages
+pub fn numbered_report() -> Vec<u8> {
+    PdfBuilder::new()
+        .page(furniture(Page::new()
+            .title("Northwind Annual Review")
+            .body(6)
+            .heading(1, "1. Introduction")
+            .body(10)
+            .heading(2, "1.1 Background")
+            .body(12), 1))
+        .page(furniture(Page::new()
+            .heading(1, "2. Methods")
+            .body(8)
+            .heading(2, "2.1 Data Collection")
+            .body(6)
+            .heading(3, "2.1.1 Regional Surveys")
+            .body(8)
+            .heading(3, "2.1.2 Interviews")
+            .body(8), 2))
+        .page(furniture(Page::new()
+            .heading(1, "3. Results")
+            .body(10)
+            .heading(2, "3.1 Revenue")
+            .body(8)
+            .heading(2, "3.2 Staffing")
+            .body(10), 3))
+        .page(furniture(Page::new()
+            .heading(1, "4. Recommendations")
+            .body(12)
+            .heading(1, "Appendix A: Survey Questions")
+            .body(8), 4))
+        .build()
+}
+
+// Headings in both columns of a two-column layout
+pub fn two_columns() -> Vec<u8> {
+    PdfBuilder::new()
+        .page(Page::new()
+            .title("Field Notes on Coastal Erosion")
+            .columns(2)
+            .heading(1, "1. Study Area")
+            .body(20)
+            .heading(2, "1.1 Survey Sites")
+            .body(14)
+            .column_break()
+            .heading(1, "2. Observations")
+            .body(18)
+            .heading(2, "2.1 Winter Storms")
+            .body(16))
+        .page(Page::new()
+            .columns(2)
+            .heading(1, "3. Discussion")
+            .body(40)
+            .heading(1, "4. Conclusions")
+            .body(12))
+        .build()
+}
+
+// A contents page and bookmarks that agree with the headings
+pub fn toc_and_bookmarks() -> Vec<u8> {
+    let entries = [(1, "1. Scope", 2), (2, "1.1 Terms Used", 2), (1, "2. Requirements", 3), (2, "2.1 Security", 3), (1, "3. Delivery", 4)];
+    PdfBuilder::new()
+        .page(Page::new().title("Service Specification").body(4).toc(&entries))
+        .page(Page::new().heading(1, "1. Scope").body(12).heading(2, "1.1 Terms Used").body(16))
+        .page(Page::new().heading(1, "2. Requirements").body(10).heading(2, "2.1 Security").body(18))
+        .page(Page::new().heading(1, "3. Delivery").body(24))
+        .bookmark(1, "1. Scope", 2)
+        .bookmark(2, "1.1 Terms Used", 2)
+        .bookmark(1, "2. Requirements", 3)
+        .bookmark(2, "2.1 Security", 3)
+        .bookmark(1, "3. Delivery", 4)
+        .info_title("Service Specification")
+        .build()
+}
+
+// Times headings over Helvetica body text, the lowest level in italics
+pub fn serif_headings() -> Vec<u8> {
+    let h1 = Style::new(Font::TimesBold, 15.0);
+    let h2 = Style::new(Font::TimesItalic, 12.0);
+    PdfBuilder::new()
+        .page(Page::new()
+            .line(Style::new(Font::TimesBold, 24.0), "A History of the Harbour")
+            .body(6)
+            .styled_heading(h1, "Early Settlement")
+            .body(10)
+            .styled_heading(h2, "The First Quay")
+            .body(10)
+            .styled_heading(h2, "Trade with the Islands")
+            .body(8))
+        .page(Page::new()
+            .styled_heading(h1, "The Railway Years")
+            .body(14)
+            .styled_heading(h2, "Decline and Renewal")
+            .body(14))
+        .build()
+}
+
+// Standard section names without numbers
+pub fn unnumbered_sections() -> Vec<u8> {
+    PdfBuilder::new()
+        .page(Page::new()
+            .title("Library Services Review")
+            .heading(1, "Executive Summary")
+            .body(14)
+            .heading(1, "Background")
+            .body(14))
+        .page(Page::new()
+            .heading(1, "Findings")
+            .body(10)
+            .heading(2, "Opening Hours")
+            .body(8)
+            .heading(2, "Digital Lending")
+            .body(8)
+            .heading(1, "Recommendations")
+            .body(8))
+        .build()
+}
+
+// Short pages with wide gaps in them: a cover line far below the title, a
+// page holding one section, and a heading alone at the foot of a page. Read
+// as one string, the text has no page breaks, and its blank-line gaps fall
+// nowhere near them.
+pub fn sparse_pages() -> Vec<u8> {
+    let small = Style::new(Font::Helvetica, 9.0);
+    PdfBuilder::new()
+        .page(Page::new()
+            .title("Harbour Works Programme")
+            .text_at(small, 72.0, 120.0, "Prepared for the Port Authority Board")
+            .text_at(small, 72.0, 100.0, "March 2024"))
+        .page(Page::new()
+            .heading(1, "1. Scope")
+            .body(4)
+            .text_at(Style::heading(1), 72.0, 110.0, "2. Schedule"))
+        .page(Page::new()
+            .body(6)
+            .heading(2, "2.1 Dredging")
+            .body(3))
+        .page(Page::new()
+            .heading(1, "3. Budget")
+            .body(5)
+            .heading(2, "3.1 Contingency")
+            .body(2))
+        .build()
+}
+
+// Sections that run over a page break with their heading repeated and
+// marked as continued, in the two styles reports use
+pub fn continued_sections() -> Vec<u8> {
+    PdfBuilder::new()
+        .page(Page::new()
+            .title("Depot Upgrade Plan")
+            .heading(1, "Scope")
+            .body(6)
+            .heading(1, "Budget")
+            .body(30))
+        .page(Page::new()
+            .heading(1, "Budget (continued)")
+            .body(8)
+            .heading(1, "Staffing")
+            .body(6)
+            .heading(1, "Scope - continued")
+            .body(5))
+        .build()
+}
+
+// A chapter whose number falls at the foot of one page and whose title
+// opens the next, as happens when a chapter starts low on a page
+pub fn split_chapter() -> Vec<u8> {
+    PdfBuilder::new()
+        .page(furniture(Page::new()
+            .title("Warehouse Migration Handbook")
+            .heading(1, "CHAPTER 3: PLANNING")
+            .body(34)
+            .text_at(Style::heading(1), 72.0, 80.0, "CHAPTER 4"), 1))
+        .page(furniture(Page::new()
+            .heading(1, "IMPLEMENTATION")
+            .body(10)
+            .heading(2, "Cutover Weekend")
+            .body(6)
+            .heading(1, "CHAPTER 5: REVIEW")
+            .body(4), 2))
+        .build()
+}
+
+// A memo over two pages: an address block under "MEMORANDUM" whose subject
+// line names it, and headings in bold body text set tight against the
+// paragraphs around them
+pub fn two_page_memo() -> Vec<u8> {
+    let bold = Style::new(Font::HelveticaBold, BODY_SIZE);
+    PdfBuilder::new()
+        .page(Page::new()
+            .title("MEMORANDUM")
+            .line(Style::BODY, "TO: All Department Heads")
+            .line(Style::BODY, "FROM: Office of the City Manager")
+            .line(Style::BODY, "DATE: March 3, 2025")
+            .line(Style::BODY, "RE: Contract Amendment No. 4")
+            .paragraph("The council approved the fourth amendment to the facilities maintenance contract at its \
+                        February meeting. This memo sets out what changes and what each department needs to do.")
+            .line(bold, "Summary of Changes:")
+            .paragraph("The maintenance term is extended by two years at the current rates. Emergency call-outs \
+                        are now billed by the hour rather than as a fixed fee.")
+            .line(bold, "Budget Impact")
+            .paragraph("Departments should expect call-out costs to rise by about a tenth. Finance will adjust \
+                        the quarterly allocations in April."))
+        .page(Page::new()
+            .line(bold, "Next Steps:")
+            .paragraph("Please review the revised schedule with your facilities leads before the end of the month \
+                        and send any concerns to the contracts team.")
+            .line(bold, "Questions")
+            .paragraph("Contact the contracts team at extension 4410 with any questions about the amendment."))
+        .build()
+}
+
+// A one-page letter: letterhead, date, the recipient's address, a subject
+// line and one heading
+pub fn letter() -> Vec<u8> {
+    let bold = Style::new(Font::HelveticaBold, BODY_SIZE);
+    PdfBuilder::new()
+        .page(Page::new()
+            .line(Style::new(Font::HelveticaBold, 14.0), "Harbourside Planning Consultants")
+            .line(Style::BODY, "14 Quay Street, Bristol BS1 4DJ")
+            .line(Style::BODY, "12 May 2025")
+            .line(Style::BODY, "Ms. Anita Patel")
+            .line(Style::BODY, "Planning Department, Bristol City Council")
+            .line(Style::BODY, "Dear Ms. Patel,")
+            .line(bold, "Re: Application for Planning Permission at 22 Mill Lane")
+            .paragraph("We write on behalf of our client to submit the enclosed application for the change of use \
+                        of the former mill building to six residential units.")
+            .line(bold, "Supporting Documents")
+            .paragraph("The application is accompanied by a heritage statement, a flood risk assessment and the \
+                        full set of drawings listed in the schedule. We would be grateful if you could confirm \
+                        receipt and let us know which case officer has been assigned.")
+            .line(Style::BODY, "Yours sincerely,")
+            .line(Style::BODY, "Daniel Okafor"))
+        .build()
+}
+
+// A magazine-style report whose sections open on a drop cap, an initial
+// letter three lines tall; or the same pages without them
+pub fn magazine(drop_caps: bool) -> Vec<u8> {
+    let opening = |page: Page, letter: &str| match drop_caps {
+        true => page.drop_cap(letter, 6),
+        false => page.body(6),
+    };
+    PdfBuilder::new()
+        .page(furniture(opening(Page::new().title("Harbour Quarterly").heading(1, "Dredging the Inner Basin"), "T")
+            .body(8)
+            .heading(2, "Silt Surveys")
+            .body(10), 1))
+        .page(furniture(opening(Page::new().heading(1, "A New Ferry Terminal"), "A")
+            .body(10)
+            .heading(2, "Passenger Numbers")
+            .body(8), 2))
+        .page(furniture(opening(Page::new().heading(1, "Wildlife on the Estuary"), "W")
+            .body(12), 3))
+        .build()
+}
+
+// A report long enough that quick mode leaves pages unread: twenty pages,
+// each opening a numbered section with a topic of its own
+pub fn long_report() -> Vec<u8> {
+    const TOPICS: [&str; 20] = [
+        "Introduction", "Governance", "Markets", "Customers", "Products", "Operations", "Suppliers",
+        "Logistics", "Workforce", "Safety", "Environment", "Technology", "Security", "Finance",
+        "Revenue", "Costs", "Investments", "Risks", "Outlook", "Appendices",
+    ];
+    let mut builder = PdfBuilder::new();
+    for (i, topic) in TOPICS.iter().enumerate() {
+        let page = match i {
+            0 => Page::new().title("Northwind Group Report"),
+            _ => Page::new(),
+        };
+        builder = builder.page(furniture(page.heading(1, &format!("{}. {}", i + 1, topic)).body(12), i + 1));
+    }
+    builder.build()
+}
+
+// A cover PDF with two PDFs attached: the specification, which is the
+// numbered report, and a set of drawings, which is the split chapter
+pub fn attachments() -> Vec<u8> {
+    PdfBuilder::new()
+        .page(Page::new().title("Tender Submission").body(6))
+        .attachment("Specification.PDF", numbered_report())
+        .attachment("drawings.pdf", split_chapter())
+        .build()
+}
+
+// A made-up name for `n`, spelled in syllables, so each number gets a name
+// of its own: 0 is "Ba", 1 "Ke", 12 "Keke"
+fn name(mut n: usize) -> String {
+    const SYLLABLES: [&str; 10] = ["ba", "ke", "ri", "to", "mu", "sa", "ne", "lo", "vi", "du"];
+    let mut name = String::new();
+    loop {
+        name.insert_str(0, SYLLABLES[n % SYLLABLES.len()]);
+        n /= SYLLABLES.len();
+        if n == 0 {
+            break;
+        }
+    }
+    name[..1].to_uppercase() + &name[1..]
+}
+
+// Plain text, as the text engine reads it, of `pages` pages separated by
+// form feeds with `per_page` numbered headings each, every one with a name
+// of its own and a line of body text after it
+pub fn many_headings(pages: usize, per_page: usize) -> String {
+    let body = "The committee reviewed the figures for each region in turn and agreed on the next steps.";
+    (0..pages)
+        .map(|page| {
+            (0..per_page)
+                .map(|i| page * per_page + i)
+                .map(|n| format!("{}. {} Holdings\n{}\n", n + 1, name(n), body))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\x0C")
+}
@@ -0,0 +1,594 @@
+//! Small PDFs built from code, so a layout that trips the heuristics can be
+//! written down as a test case instead of committed as a binary file.
+//!
+//! Pages are laid out top down from a cursor, in one or more columns:
+//!
+//! ```ignore
+//! let pdf = PdfBuilder::new()
+//!     .page(Page::new().title("Annual Report").heading(1, "1. Introduction").body(12))
+//!     .page(Page::new().columns(2).heading(2, "1.1 Scope").body(60))
+//!     .bookmark(1, "Introduction", 1)
+//!     .build();
+//! ```
+//!
+//! Text is drawn with the standard 14 fonts, which every reader knows the
+//! widths of, so both engines can read it. It should be ASCII: strings are
+//! written as their bytes, without an encoding.
+#![allow(dead_code)]
+
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream};
+
+pub const PAGE_WIDTH: f64 = 612.0;
+pub const PAGE_HEIGHT: f64 = 792.0;
+pub const MARGIN: f64 = 72.0;
+const COLUMN_GAP: f64 = 24.0;
+
+// Body text size, and line spacing as a multiple of the size
+pub const BODY_SIZE: f64 = 10.0;
+const LEADING: f64 = 1.4;
+// A drop cap spans this many body lines, from the first's cap height to the
+// last's baseline
+const DROP_CAP_LINES: usize = 3;
+const DROP_CAP_SIZE: f64 = 44.0;
+
+// Prose that body lines are cut from, long enough not to repeat on a page
+const PROSE: &str = "The committee reviewed the figures for each region in turn and agreed that the \
+    targets set last year were met in most of them. Where a target was missed, the regional lead \
+    explained the causes and the steps already taken to recover. Spending stayed within the budget \
+    approved in the spring, although the cost of materials rose faster than expected over the \
+    summer. The board asked for a fuller account of staffing before the next meeting, with the \
+    vacancies in each office listed against the plan. Members also noted that the new reporting \
+    system has reduced the time needed to close each month by several days.";
+
+/// One of the standard 14 fonts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Helvetica,
+    HelveticaBold,
+    HelveticaOblique,
+    TimesRoman,
+    TimesBold,
+    TimesItalic,
+    Courier,
+    CourierBold,
+}
+
+impl Font {
+    pub fn base_font(self) -> &'static str {
+        match self {
+            Font::Helvetica => "Helvetica",
+            Font::HelveticaBold => "Helvetica-Bold",
+            Font::HelveticaOblique => "Helvetica-Oblique",
+            Font::TimesRoman => "Times-Roman",
+            Font::TimesBold => "Times-Bold",
+            Font::TimesItalic => "Times-Italic",
+            Font::Courier => "Courier",
+            Font::CourierBold => "Courier-Bold",
+        }
+    }
+}
+
+/// A font at a size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    pub font: Font,
+    pub size: f64,
+}
+
+impl Style {
+    pub const fn new(font: Font, size: f64) -> Self {
+        Style { font, size }
+    }
+
+    pub const BODY: Style = Style::new(Font::Helvetica, BODY_SIZE);
+    pub const TITLE: Style = Style::new(Font::HelveticaBold, 22.0);
+
+    /// The style [`Page::heading`] uses for a level from 1: bold, from 16pt
+    /// down to body size
+    pub fn heading(level: u8) -> Self {
+        let size = match level {
+            1 => 16.0,
+            2 => 13.0,
+            3 => 11.5,
+            _ => BODY_SIZE,
+        };
+        Style::new(Font::HelveticaBold, size)
+    }
+}
+
+enum Item {
+    // A line of text at the cursor, indented from the column's left edge,
+    // with this much space above it
+    Line { style: Style, text: String, indent: f64, space_before: f64 },
+    // Text at a fixed position, leaving the cursor alone
+    At { style: Style, x: f64, y: f64, text: String },
+    // A large initial letter, level with the top of the next line and
+    // reaching down to the baseline of the one after next
+    DropCap { style: Style, text: String },
+    // The text render mode for what follows: 0 fills, 3 draws nothing
+    RenderMode(i64),
+    // The fill colour for what follows, as red, green and blue from 0 to 1
+    Color(f64, f64, f64),
+    // A rule under the last line, as wide as its text
+    Underline,
+    // A filled rectangle, for rules and underlines
+    Rect { x: f64, y: f64, width: f64, height: f64 },
+    // Move on to the top of the next column
+    ColumnBreak,
+}
+
+/// A page's content, laid out when the PDF is built
+pub struct Page {
+    items: Vec<Item>,
+    columns: usize,
+    // Where the next run of body lines starts in `PROSE`
+    prose_at: usize,
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Page { items: Vec::new(), columns: 1, prose_at: 0 }
+    }
+}
+
+impl Page {
+    pub fn new() -> Self {
+        Page::default()
+    }
+
+    /// Lay out the lines after this in `columns` columns of equal width
+    pub fn columns(mut self, columns: usize) -> Self {
+        assert!(columns > 0, "a page needs at least one column");
+        self.columns = columns;
+        self
+    }
+
+    /// A line of text in `style`
+    pub fn line(self, style: Style, text: &str) -> Self {
+        self.indented(style, text, 0.0)
+    }
+
+    /// A line of text in `style`, indented by `indent` points
+    pub fn indented(mut self, style: Style, text: &str, indent: f64) -> Self {
+        self.items.push(Item::Line { style, text: text.to_string(), indent, space_before: 0.0 });
+        self
+    }
+
+    /// The document title, large and bold
+    pub fn title(self, text: &str) -> Self {
+        self.spaced(Style::TITLE, text, 0.0, Style::TITLE.size)
+    }
+
+    /// A heading at `level` from 1, in [`Style::heading`], with space above it
+    pub fn heading(self, level: u8, text: &str) -> Self {
+        self.styled_heading(Style::heading(level), text)
+    }
+
+    /// A heading in a style of its own, with space above it
+    pub fn styled_heading(self, style: Style, text: &str) -> Self {
+        self.spaced(style, text, 0.0, style.size)
+    }
+
+    fn spaced(mut self, style: Style, text: &str, indent: f64, space_before: f64) -> Self {
+        self.items.push(Item::Line { style, text: text.to_string(), indent, space_before });
+        self
+    }
+
+    /// `lines` lines of prose in body text, wrapped to the column width.
+    /// Each call continues the prose where the last one stopped.
+    pub fn body(mut self, lines: usize) -> Self {
+        let words: Vec<&str> = PROSE.split_whitespace().collect();
+        let width = self.column_width();
+        for _ in 0..lines {
+            let mut line = String::new();
+            loop {
+                let word = words[self.prose_at % words.len()];
+                if !line.is_empty() && text_width(&format!("{} {}", line, word), BODY_SIZE) > width {
+                    break;
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+                self.prose_at += 1;
+            }
+            self = self.line(Style::BODY, &line);
+        }
+        self
+    }
+
+    /// `lines` lines of prose, as [`Page::body`] gives them, opening with
+    /// `letter` as a drop cap: an initial letter three lines tall, in a
+    /// serif, with the first three lines indented beside it
+    pub fn drop_cap(mut self, letter: &str, lines: usize) -> Self {
+        let style = Style::new(Font::TimesBold, DROP_CAP_SIZE);
+        self.items.push(Item::DropCap { style, text: letter.to_string() });
+        let first = self.items.len();
+        self = self.body(lines);
+        for item in self.items[first..].iter_mut().take(DROP_CAP_LINES) {
+            if let Item::Line { indent, .. } = item {
+                *indent = text_width(letter, DROP_CAP_SIZE) + 4.0;
+            }
+        }
+        self
+    }
+
+    /// A paragraph of `text` in body text, wrapped to the column width
+    pub fn paragraph(mut self, text: &str) -> Self {
+        let width = self.column_width();
+        let mut line = String::new();
+        for word in text.split_whitespace() {
+            if !line.is_empty() && text_width(&format!("{} {}", line, word), BODY_SIZE) > width {
+                self = self.line(Style::BODY, &std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        if !line.is_empty() {
+            self = self.line(Style::BODY, &line);
+        }
+        self
+    }
+
+    /// A table of contents: a "Contents" heading, then a line per entry of
+    /// `(level, text, page)` with dot leaders to its page number, indented
+    /// by level
+    pub fn toc(mut self, entries: &[(u8, &str, usize)]) -> Self {
+        self = self.heading(1, "Contents");
+        let width = self.column_width();
+        for &(level, text, page) in entries {
+            let indent = 18.0 * (level.max(1) - 1) as f64;
+            let number = page.to_string();
+            let room = width - indent - text_width(text, BODY_SIZE) - text_width(&number, BODY_SIZE);
+            let dots = ".".repeat((room / text_width(".", BODY_SIZE)).max(3.0) as usize - 2);
+            self = self.indented(Style::BODY, &format!("{} {} {}", text, dots, number), indent);
+        }
+        self
+    }
+
+    /// `text` at a fixed position, such as a running header or a page
+    /// number, outside the flow of lines
+    pub fn text_at(mut self, style: Style, x: f64, y: f64, text: &str) -> Self {
+        self.items.push(Item::At { style, x, y, text: text.to_string() });
+        self
+    }
+
+    /// Draw the text after this invisibly, in render mode 3, as an OCR
+    /// layer over a scan is drawn, until [`Page::visible`]
+    pub fn invisible(mut self) -> Self {
+        self.items.push(Item::RenderMode(3));
+        self
+    }
+
+    /// Draw the text after this normally again
+    pub fn visible(mut self) -> Self {
+        self.items.push(Item::RenderMode(0));
+        self
+    }
+
+    /// Draw what follows in a colour, as red, green and blue from 0 to 1;
+    /// black is (0, 0, 0)
+    pub fn color(mut self, red: f64, green: f64, blue: f64) -> Self {
+        self.items.push(Item::Color(red, green, blue));
+        self
+    }
+
+    /// A heading in `style`, with space above it and a rule under it
+    pub fn underlined(mut self, style: Style, text: &str) -> Self {
+        self = self.styled_heading(style, text);
+        self.items.push(Item::Underline);
+        self
+    }
+
+    /// A filled rectangle, such as a rule across the page
+    pub fn rect(mut self, x: f64, y: f64, width: f64, height: f64) -> Self {
+        self.items.push(Item::Rect { x, y, width, height });
+        self
+    }
+
+    /// Continue at the top of the next column
+    pub fn column_break(mut self) -> Self {
+        self.items.push(Item::ColumnBreak);
+        self
+    }
+
+    fn column_width(&self) -> f64 {
+        (PAGE_WIDTH - 2.0 * MARGIN - COLUMN_GAP * (self.columns - 1) as f64) / self.columns as f64
+    }
+
+    // The page's content stream, using `fonts` for resource names
+    fn content(&self, fonts: &[Font]) -> Vec<u8> {
+        let resource = |font: Font| format!("F{}", fonts.iter().position(|&f| f == font).unwrap() + 1);
+        let show = |operations: &mut Vec<Operation>, style: Style, x: f64, y: f64, text: &str| {
+            operations.push(Operation::new("BT", vec![]));
+            operations.push(Operation::new("Tf", vec![Object::Name(resource(style.font).into_bytes()), style.size.into()]));
+            operations.push(Operation::new("Td", vec![x.into(), y.into()]));
+            operations.push(Operation::new("Tj", vec![Object::string_literal(text)]));
+            operations.push(Operation::new("ET", vec![]));
+        };
+
+        let mut operations = Vec::new();
+        let top = PAGE_HEIGHT - MARGIN;
+        let (mut column, mut y, mut first) = (0, top, true);
+        // Where the last line started, and how wide it was
+        let mut last_line = (0.0, 0.0, 0.0);
+        for item in &self.items {
+            match item {
+                Item::Line { style, text, indent, space_before } => {
+                    let before = if first { 0.0 } else { space_before + style.size * LEADING };
+                    if y - before < MARGIN {
+                        column += 1;
+                        y = top;
+                    } else {
+                        y -= before;
+                    }
+                    assert!(column < self.columns, "the page overflows its {} column(s) at {:?}", self.columns, text);
+                    let x = MARGIN + column as f64 * (self.column_width() + COLUMN_GAP) + indent;
+                    show(&mut operations, *style, x, y, text);
+                    last_line = (x, y, text_width(text, style.size));
+                    first = false;
+                }
+                Item::At { style, x, y, text } => show(&mut operations, *style, *x, *y, text),
+                Item::DropCap { style, text } => {
+                    let line = BODY_SIZE * LEADING;
+                    let first_line = if first { y } else { y - line };
+                    let x = MARGIN + column as f64 * (self.column_width() + COLUMN_GAP);
+                    show(&mut operations, *style, x, first_line - line * (DROP_CAP_LINES - 1) as f64, text);
+                }
+                Item::RenderMode(mode) => operations.push(Operation::new("Tr", vec![(*mode).into()])),
+                Item::Color(red, green, blue) => operations.push(Operation::new("rg", vec![(*red).into(), (*green).into(), (*blue).into()])),
+                Item::Underline => {
+                    let (x, y, width) = last_line;
+                    operations.push(Operation::new("re", vec![x.into(), (y - 4.0).into(), width.into(), 1.into()]));
+                    operations.push(Operation::new("f", vec![]));
+                }
+                Item::Rect { x, y, width, height } => {
+                    operations.push(Operation::new("re", vec![(*x).into(), (*y).into(), (*width).into(), (*height).into()]));
+                    operations.push(Operation::new("f", vec![]));
+                }
+                Item::ColumnBreak => {
+                    column += 1;
+                    (y, first) = (top, true);
+                }
+            }
+        }
+        Content { operations }.encode().unwrap()
+    }
+
+    fn fonts(&self) -> impl Iterator<Item = Font> + '_ {
+        self.items.iter().filter_map(|item| match item {
+            Item::Line { style, .. } | Item::At { style, .. } | Item::DropCap { style, .. } => Some(style.font),
+            _ => None,
+        })
+    }
+}
+
+/// A whole document: pages, bookmarks, info entries, an XMP packet and
+/// attached files
+#[derive(Default)]
+pub struct PdfBuilder {
+    pages: Vec<Page>,
+    // (level from 1, title, page from 1)
+    bookmarks: Vec<(u8, String, usize)>,
+    info_title: Option<String>,
+    info_author: Option<String>,
+    xmp: Option<String>,
+    // Whether the attachments make a portfolio, with a /Collection
+    portfolio: bool,
+    // Page label ranges: the first page from 1, and its label dictionary
+    labels: Vec<(usize, Dictionary)>,
+    // (file name, contents)
+    attachments: Vec<(String, Vec<u8>)>,
+}
+
+impl PdfBuilder {
+    pub fn new() -> Self {
+        PdfBuilder::default()
+    }
+
+    pub fn page(mut self, page: Page) -> Self {
+        self.pages.push(page);
+        self
+    }
+
+    /// A bookmark at `level` from 1 pointing at `page` from 1. Bookmarks
+    /// nest under the last one a level above them.
+    pub fn bookmark(mut self, level: u8, title: &str, page: usize) -> Self {
+        self.bookmarks.push((level.max(1), title.to_string(), page));
+        self
+    }
+
+    /// The document info /Title
+    pub fn info_title(mut self, title: &str) -> Self {
+        self.info_title = Some(title.to_string());
+        self
+    }
+
+    /// The document info /Author
+    pub fn info_author(mut self, author: &str) -> Self {
+        self.info_author = Some(author.to_string());
+        self
+    }
+
+    /// An XMP packet as the catalog's /Metadata stream, compressed
+    pub fn xmp(mut self, packet: &str) -> Self {
+        self.xmp = Some(packet.to_string());
+        self
+    }
+
+    /// A file attached under `name` in the /EmbeddedFiles name tree, which
+    /// lists attachments in the order they're added
+    pub fn attachment(mut self, name: &str, contents: Vec<u8>) -> Self {
+        self.attachments.push((name.to_string(), contents));
+        self
+    }
+
+    /// Label the pages from `page` (from 1) on in `style` ("D", "r", "R",
+    /// "a" or "A", or none for the prefix alone), after `prefix` and
+    /// counting from `start`, in the catalog's /PageLabels
+    pub fn page_labels(mut self, page: usize, style: Option<&str>, prefix: Option<&str>, start: Option<i64>) -> Self {
+        let mut label = Dictionary::new();
+        if let Some(style) = style {
+            label.set("S", style);
+        }
+        if let Some(prefix) = prefix {
+            label.set("P", Object::string_literal(prefix));
+        }
+        if let Some(start) = start {
+            label.set("St", start);
+        }
+        self.labels.push((page, label));
+        self
+    }
+
+    /// Mark the document a portfolio, whose attachments are its content
+    pub fn portfolio(mut self) -> Self {
+        self.portfolio = true;
+        self
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        save(&mut self.document())
+    }
+
+    /// The document before it's saved, for a test to damage or add to
+    pub fn document(&self) -> Document {
+        let mut fonts: Vec<Font> = Vec::new();
+        for font in self.pages.iter().flat_map(Page::fonts) {
+            if !fonts.contains(&font) {
+                fonts.push(font);
+            }
+        }
+
+        let mut doc = Document::with_version("1.5");
+        let parent = doc.new_object_id();
+        let mut font_resources = Dictionary::new();
+        for (i, font) in fonts.iter().enumerate() {
+            let id = doc.add_object(dictionary! {
+                "Type" => "Font", "Subtype" => "Type1", "BaseFont" => font.base_font(),
+            });
+            font_resources.set(format!("F{}", i + 1), id);
+        }
+        let resources = doc.add_object(dictionary! { "Font" => font_resources });
+        let page_ids: Vec<ObjectId> = self.pages.iter()
+            .map(|page| {
+                let content = doc.add_object(Stream::new(dictionary! {}, page.content(&fonts)));
+                doc.add_object(dictionary! {
+                    "Type" => "Page", "Parent" => parent, "Contents" => content, "Resources" => resources,
+                    "MediaBox" => vec![0.into(), 0.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()],
+                })
+            })
+            .collect();
+        doc.objects.insert(parent, dictionary! {
+            "Type" => "Pages",
+            "Count" => page_ids.len() as i64,
+            "Kids" => page_ids.iter().map(|&id| id.into()).collect::<Vec<Object>>(),
+        }.into());
+
+        let mut catalog = dictionary! { "Type" => "Catalog", "Pages" => parent };
+        if !self.bookmarks.is_empty() {
+            catalog.set("Outlines", self.add_bookmarks(&mut doc, &page_ids));
+            catalog.set("PageMode", "UseOutlines");
+        }
+        if !self.attachments.is_empty() {
+            let mut names = Vec::new();
+            for (name, contents) in &self.attachments {
+                let file = doc.add_object(Stream::new(dictionary! { "Type" => "EmbeddedFile" }, contents.clone()));
+                let spec = doc.add_object(dictionary! {
+                    "Type" => "Filespec", "F" => Object::string_literal(name.as_str()), "EF" => dictionary! { "F" => file },
+                });
+                names.extend([Object::string_literal(name.as_str()), spec.into()]);
+            }
+            catalog.set("Names", dictionary! { "EmbeddedFiles" => dictionary! { "Names" => names } });
+        }
+        if self.portfolio {
+            catalog.set("Collection", Dictionary::new());
+        }
+        if !self.labels.is_empty() {
+            let mut nums = Vec::new();
+            for (page, label) in &self.labels {
+                nums.extend([(*page as i64 - 1).into(), label.clone().into()]);
+            }
+            catalog.set("PageLabels", dictionary! { "Nums" => nums });
+        }
+        if let Some(packet) = &self.xmp {
+            let mut metadata = Stream::new(dictionary! { "Type" => "Metadata", "Subtype" => "XML" }, packet.as_bytes().to_vec());
+            metadata.compress().unwrap();
+            catalog.set("Metadata", doc.add_object(metadata));
+        }
+        let catalog = doc.add_object(catalog);
+        doc.trailer.set("Root", catalog);
+        let mut info = Dictionary::new();
+        for (key, value) in [("Title", &self.info_title), ("Author", &self.info_author)] {
+            if let Some(value) = value {
+                info.set(key, Object::string_literal(value.as_str()));
+            }
+        }
+        if !info.is_empty() {
+            let info = doc.add_object(info);
+            doc.trailer.set("Info", info);
+        }
+        doc
+    }
+
+    // The outline tree of the bookmarks, returning its root
+    fn add_bookmarks(&self, doc: &mut Document, page_ids: &[ObjectId]) -> ObjectId {
+        let root = doc.new_object_id();
+        let ids: Vec<ObjectId> = self.bookmarks.iter().map(|_| doc.new_object_id()).collect();
+        // Each bookmark's parent: the last bookmark before it a level up
+        let parents: Vec<Option<usize>> = (0..self.bookmarks.len())
+            .map(|i| (0..i).rev().find(|&j| self.bookmarks[j].0 < self.bookmarks[i].0))
+            .collect();
+        let children = |parent: Option<usize>| -> Vec<usize> {
+            (0..self.bookmarks.len()).filter(|&i| parents[i] == parent).collect()
+        };
+
+        for (i, (_, title, page)) in self.bookmarks.iter().enumerate() {
+            let page_id = *page_ids.get(page - 1).expect("a bookmark points past the last page");
+            let mut item = dictionary! {
+                "Title" => Object::string_literal(title.as_str()),
+                "Parent" => parents[i].map_or(root, |p| ids[p]),
+                "Dest" => vec![page_id.into(), "Fit".into()],
+            };
+            let siblings = children(parents[i]);
+            let at = siblings.iter().position(|&s| s == i).unwrap();
+            if at > 0 {
+                item.set("Prev", ids[siblings[at - 1]]);
+            }
+            if let Some(&next) = siblings.get(at + 1) {
+                item.set("Next", ids[next]);
+            }
+            let kids = children(Some(i));
+            if let (Some(&first), Some(&last)) = (kids.first(), kids.last()) {
+                item.set("First", ids[first]);
+                item.set("Last", ids[last]);
+                item.set("Count", kids.len() as i64);
+            }
+            doc.objects.insert(ids[i], item.into());
+        }
+        let top = children(None);
+        doc.objects.insert(root, dictionary! {
+            "Type" => "Outlines",
+            "First" => ids[top[0]],
+            "Last" => ids[*top.last().unwrap()],
+            "Count" => top.len() as i64,
+        }.into());
+        root
+    }
+}
+
+/// The bytes of `doc` as a PDF file
+pub fn save(doc: &mut Document) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes).unwrap();
+    bytes
+}
+
+// Width of `text` at `size`, from an average Helvetica glyph of about half
+// an em; close enough for wrapping lines
+fn text_width(text: &str, size: f64) -> f64 {
+    text.chars().count() as f64 * size * 0.5
+}
@@ -0,0 +1,63 @@
+// `adobe1a completions <shell>` prints a completion script for the extract
+// flags and their value sets. Bare flags with no subcommand still extract.
+
+mod common;
+
+use std::process::Command;
+use adobe1a::config::Engine;
+
+fn completions(shell: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_adobe1a")).args(["completions", shell]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn every_shell_gets_a_script() {
+    for shell in ["bash", "zsh", "fish", "powershell"] {
+        let script = completions(shell);
+        assert!(script.contains("min-confidence"), "{} script lacks --min-confidence", shell);
+    }
+    let unknown = Command::new(env!("CARGO_BIN_EXE_adobe1a")).args(["completions", "tcsh"]).output().unwrap();
+    assert!(!unknown.status.success());
+}
+
+#[test]
+fn value_sets_complete() {
+    let script = completions("fish");
+    // Each flag's values follow its line, up to the next `complete` line
+    let values = |flag: &str| {
+        let start = script.find(&format!("extract\" -l {} ", flag)).unwrap_or_else(|| panic!("no --{}", flag));
+        let rest = &script[start..];
+        rest[..rest[1..].find("\ncomplete ").map_or(rest.len(), |end| end + 1)].to_string()
+    };
+    // Only the engines built can be chosen
+    let engines: Vec<&str> = [("auto", Engine::Auto), ("text", Engine::Text), ("font", Engine::Font)].into_iter()
+        .filter(|(_, engine)| engine.is_built())
+        .map(|(name, _)| name)
+        .collect();
+    for (flag, expected) in [
+        ("engine", &engines[..]),
+        ("format", &["full", "adobe1a"]),
+        ("sort", &["document", "confidence", "level"]),
+        ("key-style", &["snake", "camel"]),
+    ] {
+        let values = values(flag);
+        for value in expected {
+            assert!(values.contains(&format!("{}\\t", value)), "--{} lacks {}: {}", flag, value, values);
+        }
+    }
+}
+
+#[test]
+fn bare_flags_still_extract() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-bare-flags-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (input, output) = (dir.join("report.pdf"), dir.join("report.json"));
+    std::fs::write(&input, common::numbered_report()).unwrap();
+    let status = Command::new(env!("CARGO_BIN_EXE_adobe1a")).arg("-i").arg(&input).arg("-o").arg(&output).status().unwrap();
+    assert!(status.success());
+    let outline: serde_json::Value = serde_json::from_slice(&std::fs::read(&output).unwrap()).unwrap();
+    assert!(!outline["outline"].as_array().unwrap().is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
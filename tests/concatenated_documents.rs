@@ -0,0 +1,124 @@
+// With `split_documents`, a PDF of memos scanned in one batch is outlined
+// as one document per memo, each under an H1 with its subject line. A page
+// starts a new document only when two signals agree: here a new letterhead
+// and a large centred opening after a page that ended short.
+
+mod common;
+
+use std::process::Command;
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::documents::SubDocument;
+use adobe1a::{extract_outline_from_bytes, timing::Stats, Outline};
+use common::pdf_builder::{Font, Page, PdfBuilder, Style};
+
+// Letterheads sit in the header band, above the 6% margin
+const LETTERHEAD_Y: f64 = 765.0;
+const OPENING_Y: f64 = 735.0;
+
+// The first page of a memo: a letterhead, "MEMORANDUM" centred in large
+// type, then the subject line and a numbered section
+fn memo(letterhead: &str, subject: &str, section: &str) -> Page {
+    Page::new()
+        .text_at(Style::new(Font::Helvetica, 9.0), 72.0, LETTERHEAD_Y, letterhead)
+        // 20pt, so about 100pt wide: centred on the 612pt page
+        .text_at(Style::new(Font::HelveticaBold, 20.0), 256.0, OPENING_Y, "MEMORANDUM")
+        .line(Style::BODY, &format!("Subject: {}", subject))
+        .body(1)
+        .heading(1, section)
+        .body(3)
+}
+
+// Three memos from three offices, the second running onto a second page
+fn batch(letterheads: [&str; 3]) -> Vec<u8> {
+    PdfBuilder::new()
+        .page(memo(letterheads[0], "Berth Allocation", "1. Current Berths"))
+        .page(memo(letterheads[1], "Dredging Schedule", "1. Channel Depths"))
+        .page(Page::new()
+            .text_at(Style::new(Font::Helvetica, 9.0), 72.0, LETTERHEAD_Y, letterheads[1])
+            .heading(1, "2. Dredging Windows")
+            .body(2))
+        .page(memo(letterheads[2], "Ferry Timetable", "1. Winter Sailings"))
+        .build()
+}
+
+fn extract(pdf: &[u8], split_documents: bool) -> Outline {
+    let config = ExtractorConfig { split_documents, ..ExtractorConfig::default() };
+    extract_outline_from_bytes(pdf, "batch", &config, &mut Stats::new()).unwrap()
+}
+
+fn document(title: &str, first_page: usize, last_page: usize) -> SubDocument {
+    SubDocument { title: title.to_string(), first_page, last_page }
+}
+
+fn headings(outline: &Outline) -> Vec<(&str, &str, usize)> {
+    outline.outline.iter().map(|heading| (heading.level.as_str(), heading.text.as_str(), heading.page)).collect()
+}
+
+const OFFICES: [&str; 3] = ["Northwind Ports Ltd", "Harbour Board", "City Ferries"];
+
+#[test]
+fn three_memos_are_three_documents() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let outline = extract(&batch(OFFICES), true);
+    assert_eq!(outline.documents, [
+        document("Berth Allocation", 1, 1),
+        document("Dredging Schedule", 2, 3),
+        document("Ferry Timetable", 4, 4),
+    ]);
+    assert_eq!(headings(&outline), [
+        ("H1", "Berth Allocation", 1),
+        ("H2", "1. Current Berths", 1),
+        ("H1", "Dredging Schedule", 2),
+        ("H2", "1. Channel Depths", 2),
+        ("H2", "2. Dredging Windows", 3),
+        ("H1", "Ferry Timetable", 4),
+        ("H2", "1. Winter Sailings", 4),
+    ]);
+
+    // Nothing is looked for unless asked
+    let whole = extract(&batch(OFFICES), false);
+    assert!(whole.documents.is_empty());
+    assert!(whole.outline.iter().all(|heading| !heading.text.ends_with("Allocation")));
+}
+
+#[test]
+fn one_signal_is_not_enough() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    // Every memo opens like a cover, but under the same letterhead
+    let outline = extract(&batch(["Harbour Board"; 3]), true);
+    assert!(outline.documents.is_empty(), "{:?}", outline.documents);
+    assert_eq!(headings(&outline), headings(&extract(&batch(["Harbour Board"; 3]), false)));
+}
+
+#[test]
+fn each_document_can_be_written_on_its_own() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let dir = std::env::temp_dir().join(format!("adobe1a-concatenated-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("batch.pdf");
+    std::fs::write(&input, batch(OFFICES)).unwrap();
+    let run = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+        .arg("--input").arg(&input)
+        .arg("--output").arg(dir.join("out.json"))
+        .args(["--split-documents", "--split-output"])
+        .output()
+        .unwrap();
+    assert!(run.status.success(), "{}", String::from_utf8_lossy(&run.stderr));
+
+    let read = |name: &str| Outline::from_json_str(&std::fs::read_to_string(dir.join(name)).unwrap()).unwrap();
+    assert_eq!(read("out.json").documents.len(), 3);
+    let second = read("out.2.json");
+    assert_eq!(second.title, "Dredging Schedule");
+    assert_eq!(second.documents, [document("Dredging Schedule", 2, 3)]);
+    assert_eq!(second.page_count, Some(2));
+    // Pages count from the memo's first page, and its headings are back at the top level
+    assert_eq!(headings(&second), [("H1", "1. Channel Depths", 1), ("H1", "2. Dredging Windows", 2)]);
+    assert!(!dir.join("out.4.json").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,142 @@
+// Where extraction's settings come from: a flag on the command line wins
+// over its `ADOBE1A_*` variable, which wins over a `--config` file, which
+// wins over the default. A bad value names the variable or file it came
+// from.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+struct Run {
+    dir: PathBuf,
+}
+
+impl Run {
+    fn new(name: &str) -> Run {
+        let dir = std::env::temp_dir().join(format!("adobe1a-configuration-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("report.pdf"), common::numbered_report()).unwrap();
+        Run { dir }
+    }
+
+    fn config(&self, json: &str) -> PathBuf {
+        let path = self.dir.join("adobe1a.json");
+        std::fs::write(&path, json).unwrap();
+        path
+    }
+
+    // Extracts the report with `args` and the variables `env`, none of the
+    // others being set
+    fn extract(&self, args: &[&str], env: &[(&str, &str)]) -> Output {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_adobe1a"));
+        for (var, _) in std::env::vars().filter(|(var, _)| var.starts_with("ADOBE1A_")) {
+            command.env_remove(var);
+        }
+        command.arg("--input").arg(self.dir.join("report.pdf"))
+            .arg("--output").arg(self.dir.join("report.json"))
+            .arg("--force")
+            .args(args)
+            .envs(env.iter().copied())
+            .output()
+            .unwrap()
+    }
+
+    fn outline(&self, args: &[&str], env: &[(&str, &str)]) -> serde_json::Value {
+        let output = self.extract(args, env);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        serde_json::from_slice(&std::fs::read(self.dir.join("report.json")).unwrap()).unwrap()
+    }
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn path(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
+
+#[test]
+fn the_command_line_wins_over_variables_over_the_config_file() {
+    let run = Run::new("precedence");
+    let detected = run.outline(&[], &[])["title"].clone();
+    let config = run.config(r#"{"title": "From File", "stats_json": true}"#);
+    let config = path(&config);
+
+    let outline = run.outline(&["--config", config], &[]);
+    assert_eq!(outline["title"], "From File");
+    // A switch, with its key spelled with underscores
+    assert!(outline["stats"].is_object());
+    let env = [("ADOBE1A_TITLE", "From Env")];
+    assert_eq!(run.outline(&["--config", config], &env)["title"], "From Env");
+    assert_eq!(run.outline(&["--config", config, "--title", "From Cli"], &env)["title"], "From Cli");
+    // The file can be named by its variable too
+    assert_eq!(run.outline(&[], &[("ADOBE1A_CONFIG", config)])["title"], "From File");
+    assert_eq!(run.outline(&[], &[])["title"], detected);
+}
+
+#[test]
+fn a_file_value_gives_way_to_a_flag_it_conflicts_with() {
+    let run = Run::new("conflicts");
+    let config = run.config(r#"{"no-title": true}"#);
+    assert_eq!(run.outline(&["--config", path(&config)], &[])["title_source"], "filename");
+    let outline = run.outline(&["--config", path(&config), "--title", "Given"], &[]);
+    assert_eq!(outline["title"], "Given");
+}
+
+#[test]
+fn bad_values_name_their_source() {
+    let run = Run::new("errors");
+    let stderr = |output: Output| {
+        assert_eq!(output.status.code(), Some(2));
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    let error = stderr(run.extract(&[], &[("ADOBE1A_MIN_CONFIDENCE", "high")]));
+    assert!(error.contains("environment variable ADOBE1A_MIN_CONFIDENCE"), "{}", error);
+    let config = run.config(r#"{"min-confidence": "high"}"#);
+    let error = stderr(run.extract(&["--config", path(&config)], &[]));
+    assert!(error.contains(&format!("key \"min-confidence\" in {}", path(&config))), "{}", error);
+    // The flag's own value is checked, not the file's
+    assert!(run.extract(&["--config", path(&config), "--min-confidence", "0.5"], &[]).status.success());
+
+    let config = run.config(r#"{"colour": "red"}"#);
+    assert!(stderr(run.extract(&["--config", path(&config)], &[])).contains("no flag --colour"));
+    let config = run.config(r#"{"stats-json": "yes"}"#);
+    assert!(stderr(run.extract(&["--config", path(&config)], &[])).contains("--stats-json is a switch"));
+    let config = run.config("[]");
+    assert!(stderr(run.extract(&["--config", path(&config)], &[])).contains("expected a JSON object of flag values"));
+}
+
+#[test]
+fn out_of_range_values_name_their_source() {
+    let run = Run::new("ranges");
+    let stderr = |output: Output| {
+        assert_eq!(output.status.code(), Some(2));
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    for (var, value) in [
+        ("ADOBE1A_MIN_CONFIDENCE", "7"),
+        ("ADOBE1A_MARGIN_BAND", "0.9"),
+        ("ADOBE1A_HEADINGS_PER_PAGE", "0"),
+        ("ADOBE1A_MIN_F1", "-0.1"),
+        ("ADOBE1A_JOBS", "0"),
+        ("ADOBE1A_MAX_HEADINGS", "0"),
+    ] {
+        let error = stderr(run.extract(&[], &[(var, value)]));
+        assert!(error.contains(&format!("environment variable {}", var)), "{}", error);
+    }
+    let error = stderr(run.extract(&["--margin-band", "0.5"], &[]));
+    assert!(error.contains("--margin-band") && error.contains("under 0.5"), "{}", error);
+    let error = stderr(run.extract(&["--jobs", "0"], &[]));
+    assert!(error.contains("--jobs"), "{}", error);
+    let config = run.config(r#"{"min-confidence": 1.5}"#);
+    let error = stderr(run.extract(&["--config", path(&config)], &[]));
+    assert!(error.contains(&format!("key \"min-confidence\" in {}", path(&config))), "{}", error);
+    // The ends of each range are allowed
+    assert!(run.extract(&["--min-confidence", "1", "--margin-band", "0", "--jobs", "1"], &[]).status.success());
+}
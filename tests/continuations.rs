@@ -0,0 +1,37 @@
+// A heading repeated on a later page with a continuation marker ("Budget
+// (continued)") is merged into its first occurrence, however short it is,
+// rather than listed twice; one whose first occurrence isn't read is kept
+// without the marker and flagged as `continued`.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::Extractor;
+
+#[test]
+fn continued_headings_merge_into_their_first_occurrence() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let pdf = common::continued_sections();
+    let outline = Extractor::builder().engine(Engine::Font).build().unwrap().extract_bytes(&pdf).unwrap().outline;
+    let headings: Vec<(&str, usize, bool)> = outline.iter().map(|h| (h.text.as_str(), h.page, h.continued)).collect();
+    assert_eq!(headings, [
+        ("Depot Upgrade Plan", 1, false),
+        ("Scope", 1, false),
+        ("Budget", 1, false),
+        ("Staffing", 2, false),
+    ]);
+}
+
+#[test]
+fn a_continuation_without_its_first_occurrence_is_kept() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let pdf = common::continued_sections();
+    let extractor = Extractor::builder().engine(Engine::Font).pages("2".parse().unwrap()).build().unwrap();
+    let outline = extractor.extract_bytes(&pdf).unwrap().outline;
+    let headings: Vec<(&str, bool)> = outline.iter().map(|h| (h.text.as_str(), h.continued)).collect();
+    assert_eq!(headings, [("Budget", true), ("Staffing", false), ("Scope", true)]);
+}
@@ -0,0 +1,32 @@
+// A cover page's headings are dropped: a first page with the title on it
+// and little other text, ahead of the rest of the document. A numbered
+// heading or standard section name on it starts the body, and it and the
+// headings after it stay. A single-page document has no cover.
+
+use adobe1a::config::Engine;
+use adobe1a::Extractor;
+
+fn page_one(extractor: &Extractor, fixture: &str) -> (String, Vec<String>) {
+    let path = format!("{}/fixtures/{}", env!("CARGO_MANIFEST_DIR"), fixture);
+    let outline = extractor.extract_path(path.as_ref()).unwrap();
+    (outline.title, outline.outline.into_iter().filter(|h| h.page == 1).map(|h| h.text).collect())
+}
+
+// The client, address, "Submitted By" and date lines of an RFP's cover
+#[test]
+fn an_rfp_cover_has_no_headings() {
+    let (title, headings) = page_one(&Extractor::default(), "rfp-cover.pdf");
+    assert_eq!((title.as_str(), headings.len()), ("Request for Proposal", 0), "{:?}", headings);
+    let kept = Extractor::builder().cover_detection(false).build().unwrap();
+    assert!(!page_one(&kept, "rfp-cover.pdf").1.is_empty());
+}
+
+// A one-page memo is all there is, however little text it holds
+#[test]
+fn a_one_page_memo_has_no_cover() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let (_, headings) = page_one(&Extractor::default(), "memo.pdf");
+    assert!(["Parking Changes", "Temporary Spaces", "Questions"].iter().all(|text| headings.iter().any(|h| h == text)), "{:?}", headings);
+}
@@ -0,0 +1,62 @@
+// A page that yields far more headings than a page should, as OCR damage or
+// a busy design can make one do, keeps only its most confident headings and
+// is named in a `crowded_page` warning. Headings that continue the
+// document's numbering are always kept.
+
+mod common;
+
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::{analyze_text, extract_outline_from_bytes, timing::Stats, Outline, TextOptions};
+use common::pdf_builder::{Font, Page, PdfBuilder, Style};
+
+// Figures that read as numbered headings, twelve of them on one page
+const FIGURES: [&str; 12] = [
+    "47 Gross Margin", "12 Net Sales", "88 Unit Costs", "31 Field Notes", "64 Store Count", "9 Staff Hours",
+    "73 Market Share", "25 Cash Flow", "56 Price Index", "18 Stock Levels", "92 Order Volume", "37 Site Visits",
+];
+const NUMBERED: [&str; 4] = ["1. Introduction", "2. Results", "2.1 Regional Results", "3. Outlook"];
+
+// Three pages, the middle one crowded with figures
+fn review() -> Vec<u8> {
+    let figure = Style::new(Font::HelveticaBold, 12.0);
+    let crowded = FIGURES.iter().fold(Page::new().heading(1, "2. Results").body(1), |page, text| page.line(figure, text));
+    PdfBuilder::new()
+        .page(Page::new().heading(1, "1. Introduction").body(2))
+        .page(crowded.styled_heading(Style::new(Font::HelveticaBold, 14.0), "2.1 Regional Results").body(1))
+        .page(Page::new().heading(1, "3. Outlook").body(2))
+        .build()
+}
+
+fn check(outline: Outline) {
+    let crowded: Vec<_> = outline.warnings.iter().filter(|w| w.code == "crowded_page").collect();
+    assert_eq!(crowded.len(), 1);
+    assert!(crowded[0].message.starts_with("page 2 "), "{}", crowded[0].message);
+
+    let texts: Vec<_> = outline.outline.iter().map(|heading| heading.text.as_str()).collect();
+    for numbered in NUMBERED {
+        assert!(texts.contains(&numbered), "{:?}", texts);
+    }
+    // The 8 strongest figures, and the numbered headings around them
+    assert_eq!(outline.headings_on_page(2).count(), 10);
+}
+
+#[test]
+fn the_font_engine_keeps_a_crowded_pages_strongest_headings() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let config = ExtractorConfig { engine: Engine::Font, max_headings: Some(50), ..ExtractorConfig::default() };
+    check(extract_outline_from_bytes(&review(), "review", &config, &mut Stats::new()).unwrap());
+}
+
+#[test]
+fn the_text_engine_keeps_a_crowded_pages_strongest_headings() {
+    // The same pages as plain text, one line each, as the text engine reads them
+    let body = "The committee met to review the plan and agreed on the steps.";
+    let pages = [
+        format!("1. Introduction\n{body}\n{body}\n"),
+        format!("2. Results\n{body}\n{}\n2.1 Regional Results\n{body}\n", FIGURES.join("\n")),
+        format!("3. Outlook\n{body}\n{body}\n"),
+    ];
+    check(analyze_text(&pages.join("\x0C"), TextOptions::default()));
+}
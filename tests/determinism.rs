@@ -0,0 +1,84 @@
+// The same PDF gives byte-identical JSON however the process's hash maps
+// are seeded. Each thread draws its own random `RandomState` keys, so an
+// outline that depended on a HashMap's iteration order would come out
+// differently from one thread to the next. Headings of equal confidence are
+// ordered by page and then by their place in the document, never by chance.
+
+mod common;
+
+use std::thread;
+use adobe1a::config::Engine;
+use adobe1a::output::SortOrder;
+use adobe1a::{Extractor, Outline};
+
+// Threads per fixture, each with its own hasher seeds
+const RUNS: usize = 4;
+
+// Extractions of one document in a row on the same thread
+const REPEATS: usize = 20;
+
+#[test]
+fn text_engine_output_is_independent_of_hasher_seeds() {
+    check(Engine::Text);
+}
+
+#[test]
+fn font_engine_output_is_independent_of_hasher_seeds() {
+    check(Engine::Font);
+}
+
+fn check(engine: Engine) {
+    if !engine.is_built() {
+        return;
+    }
+    for (name, pdf) in common::fixtures() {
+        let outputs: Vec<String> = thread::scope(|scope| {
+            let runs: Vec<_> = (0..RUNS).map(|_| scope.spawn(|| extract(engine, &pdf))).collect();
+            runs.into_iter().map(|run| run.join().unwrap()).collect()
+        });
+        assert!(outputs.windows(2).all(|pair| pair[0] == pair[1]),
+            "{}: the outline changed between runs with different hasher seeds", name);
+    }
+}
+
+#[test]
+fn repeated_extractions_are_byte_identical() {
+    for engine in [Engine::Text, Engine::Font] {
+        if !engine.is_built() {
+            continue;
+        }
+        let pdf = common::numbered_report();
+        let first = extract(engine, &pdf);
+        for run in 1..REPEATS {
+            assert_eq!(extract(engine, &pdf), first, "{:?}: run {} differed from the first", engine, run + 1);
+        }
+    }
+}
+
+#[test]
+fn tied_confidences_sort_by_page_then_document_order() {
+    let outline = Outline::from_json_str(r#"{"title": "Guide", "outline": [
+        {"level": "H1", "text": "Late", "page": 4, "confidence": 0.8},
+        {"level": "H2", "text": "Weak", "page": 1, "confidence": 0.4},
+        {"level": "H1", "text": "First on 2", "page": 2, "confidence": 0.8},
+        {"level": "H2", "text": "Second on 2", "page": 2, "confidence": 0.8},
+        {"level": "H1", "text": "Strong", "page": 5, "confidence": 0.9}
+    ]}"#).unwrap();
+    let texts: Vec<&str> = SortOrder::Confidence.order(&outline.outline).into_iter()
+        .map(|i| outline.outline[i].text.as_str())
+        .collect();
+    assert_eq!(texts, ["Strong", "First on 2", "Second on 2", "Late", "Weak"]);
+}
+
+// With every optional score and list written out, so they're compared too
+fn extract(engine: Engine, pdf: &[u8]) -> String {
+    let extractor = Extractor::builder()
+        .engine(engine)
+        .include_sources(true)
+        .include_raw_scores(true)
+        .title_candidates(true)
+        .offsets(true)
+        .build()
+        .unwrap();
+    extractor.extract_bytes(pdf).unwrap().to_json_string(true).unwrap()
+}
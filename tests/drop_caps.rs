@@ -0,0 +1,36 @@
+// A drop cap, an initial letter three lines tall opening a paragraph, is
+// left out of the font engine's reading: a report with drop caps on every
+// page extracts as it does without them, scores and body size included.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::{Extractor, Outline};
+
+fn extract(pdf: &[u8]) -> Outline {
+    Extractor::builder()
+        .engine(Engine::Font)
+        .include_raw_scores(true)
+        .title_candidates(true)
+        .build()
+        .unwrap()
+        .extract_bytes(pdf)
+        .unwrap()
+}
+
+#[test]
+fn drop_caps_leave_the_outline_as_it_is_without_them() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let (with, without) = (extract(&common::magazine(true)), extract(&common::magazine(false)));
+    assert!(!without.outline.is_empty());
+    assert_eq!(with.outline, without.outline);
+    assert_eq!(with.title, without.title);
+
+    // The largest text on the first page is the masthead, not the letter
+    for outline in [&with, &without] {
+        let largest = outline.title_candidates.iter().find(|candidate| candidate.source == "font_size").unwrap();
+        assert_eq!(largest.text, "Harbour Quarterly");
+    }
+}
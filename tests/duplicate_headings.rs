@@ -0,0 +1,38 @@
+// A heading repeated later in a document, as running section titles and
+// continued tables repeat theirs, is kept once. Repeats are found by a hash
+// lookup of each heading's number-stripped key, so thousands of headings
+// take no longer to check than a handful.
+
+mod common;
+
+use std::time::{Duration, Instant};
+use adobe1a::config::ExtractorConfig;
+use adobe1a::{analyze_text, Outline, TextOptions};
+
+// With nothing capped, so every heading found is in the outline
+fn analyze(text: &str) -> Outline {
+    let config = ExtractorConfig { max_headings: Some(usize::MAX), page_heading_limit: 0, ..ExtractorConfig::default() };
+    analyze_text(text, TextOptions { config, ..TextOptions::default() })
+}
+
+#[test]
+fn a_repeated_heading_is_kept_once() {
+    let body = "The committee reviewed the figures for each region in turn.";
+    let text = format!("1. Regional Figures\n{body}\n\x0C2. Staffing Plans\n{body}\n\x0C1. Regional Figures\n{body}\n\x0C3. Outlook\n{body}\n");
+    let outline = analyze(&text);
+    let headings: Vec<_> = outline.outline.iter().map(|h| (h.text.as_str(), h.page)).collect();
+    assert_eq!(headings, [("1. Regional Figures", 1), ("2. Staffing Plans", 2), ("3. Outlook", 4)]);
+}
+
+#[test]
+fn five_thousand_headings_are_deduplicated_quickly() {
+    let text = common::many_headings(500, 10);
+    let doubled = format!("{}\x0C{}", text, text);
+    let start = Instant::now();
+    let outline = analyze(&doubled);
+    // Seconds when every heading was compared with every other, even
+    // unoptimized
+    assert!(start.elapsed() < Duration::from_secs(10), "took {:?}", start.elapsed());
+    assert_eq!(outline.outline.len(), 5000);
+    assert!(outline.outline.iter().all(|heading| heading.page <= 500));
+}
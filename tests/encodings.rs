@@ -0,0 +1,115 @@
+// Strings in simple fonts are read through the font's encoding: a base
+// encoding, /Differences by glyph name and a ToUnicode map. The PDFs are
+// built here rather than with the fixtures' builder, which draws ASCII in
+// the standard fonts without an /Encoding.
+
+use adobe1a::config::Engine;
+use adobe1a::{layout, Extractor};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Dictionary, Document, Object, Stream, StringFormat};
+
+// One page showing `lines` in the font, the first at 24pt so the font
+// engine has a heading to find, and then a line of Helvetica. The font's
+// /ToUnicode map, if any, is a stream of `to_unicode`.
+fn pdf(mut font: Dictionary, to_unicode: Option<&[u8]>, lines: &[&[u8]]) -> Vec<u8> {
+    let mut doc = Document::with_version("1.5");
+    let pages = doc.new_object_id();
+    if let Some(cmap) = to_unicode {
+        font.set("ToUnicode", doc.add_object(Stream::new(dictionary! {}, cmap.to_vec())));
+    }
+    let font = doc.add_object(font);
+    let body = doc.add_object(dictionary! { "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "Helvetica" });
+    let mut operations = Vec::new();
+    let lines = lines.iter().copied().chain([HELVETICA.as_bytes()]);
+    for (i, line) in lines.enumerate() {
+        let (resource, size) = match (i, line == HELVETICA.as_bytes()) {
+            (_, true) => ("F2", 10),
+            (0, _) => ("F1", 24),
+            _ => ("F1", 10),
+        };
+        operations.extend([
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec![Object::Name(resource.into()), size.into()]),
+            Operation::new("Td", vec![72.into(), (700 - 30 * i as i64).into()]),
+            Operation::new("Tj", vec![Object::String(line.to_vec(), StringFormat::Literal)]),
+            Operation::new("ET", vec![]),
+        ]);
+    }
+    let content = doc.add_object(Stream::new(dictionary! {}, Content { operations }.encode().unwrap()));
+    let page = doc.add_object(dictionary! {
+        "Type" => "Page", "Parent" => pages, "Contents" => content,
+        "Resources" => dictionary! { "Font" => dictionary! { "F1" => font, "F2" => body } },
+        "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+    });
+    doc.objects.insert(pages, dictionary! { "Type" => "Pages", "Count" => 1, "Kids" => vec![page.into()] }.into());
+    let catalog = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages });
+    doc.trailer.set("Root", catalog);
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes).unwrap();
+    bytes
+}
+
+const HELVETICA: &str = "The rest of the page is in Helvetica.";
+
+// The runs' text, but for the line of Helvetica
+fn texts(pdf: &[u8]) -> Vec<String> {
+    layout::extract_runs_from_bytes(pdf).unwrap().into_iter().map(|run| run.text).filter(|text| text != HELVETICA).collect()
+}
+
+#[test]
+fn differences_override_the_base_encoding() {
+    let font = dictionary! {
+        "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "ABCDEF+Minion-Bold",
+        "Encoding" => dictionary! {
+            "BaseEncoding" => "WinAnsiEncoding",
+            "Differences" => vec![31.into(), "fi".into(), 149.into(), "bullet".into(), "g42".into(), 200.into(), "ffl".into()],
+        },
+    };
+    let pdf = pdf(font, None, &[b"2010\x962015 Strategic Plan", b"\x1Dnal \x95 \x93Scope\x94 \xC8e", b"Caf\xE9"]);
+    assert_eq!(texts(&pdf), [
+        "2010\u{2013}2015 Strategic Plan",
+        // 0x1D isn't in WinAnsi or the Differences, so it's left out; the
+        // unknown glyph g42 at 150 leaves WinAnsi's en dash alone
+        "nal \u{2022} \u{201C}Scope\u{201D} ffle",
+        "Caf\u{E9}",
+    ]);
+}
+
+#[test]
+fn mac_roman_and_standard_encodings() {
+    let mac = dictionary! { "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "Palatino", "Encoding" => "MacRomanEncoding" };
+    assert_eq!(texts(&pdf(mac, None, &[b"R\x8Esum\x8E \xD0 \xA5 Notes"]))[0], "R\u{E9}sum\u{E9} \u{2013} \u{2022} Notes");
+    // No /Encoding: a Type 1 font's own, Standard, with its curly quotes
+    let standard = dictionary! { "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "Times-Roman" };
+    assert_eq!(texts(&pdf(standard, None, &[b"`Quoted' \xB1 \xAE"]))[0], "\u{2018}Quoted\u{2019} \u{2013} fi");
+}
+
+#[test]
+fn to_unicode_names_codes_the_encoding_leaves_open() {
+    let cmap = b"/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+        1 begincodespacerange <00> <FF> endcodespacerange\n\
+        2 beginbfchar\n<01> <0041>\n<02> <FB01>\nendbfchar\n\
+        1 beginbfrange\n<10> <12> <0031>\nendbfrange\n\
+        endcmap\nend\nend\n";
+    let font = dictionary! {
+        "Type" => "Font", "Subtype" => "TrueType", "BaseFont" => "QWERTY+Calibri",
+        "Encoding" => dictionary! { "Differences" => vec![3.into(), "g42".into()] },
+        "FontDescriptor" => dictionary! { "Flags" => 4 },
+    };
+    assert_eq!(texts(&pdf(font, Some(cmap), &[b"\x10\x11\x12 \x01\x02nal"]))[0], "123 Afinal");
+}
+
+#[test]
+fn unmapped_symbolic_fonts_are_skipped() {
+    let font = dictionary! { "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "ZapfDingbats" };
+    let pdf = pdf(font, None, &[b"4n4"]);
+    assert!(texts(&pdf).is_empty());
+    assert_eq!(layout::extract_runs_from_bytes(&pdf).unwrap().len(), 1);
+
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let outline = Extractor::builder().engine(Engine::Font).build().unwrap().extract_bytes(&pdf).unwrap();
+    let warning = outline.warnings.iter().find(|w| w.code == "unmapped_font").expect("an unmapped_font warning");
+    assert_eq!(warning.message, "text in the symbolic font ZapfDingbats was skipped on page 1, as nothing maps its codes to characters");
+}
@@ -0,0 +1,67 @@
+// The engines are cargo features. Whichever are built, Auto extracts with
+// them; an engine left out is refused by the builder and by `--engine`, and
+// an outline names only engines that ran.
+
+mod common;
+
+use std::process::Command;
+use adobe1a::config::Engine;
+use adobe1a::{BuildError, Extractor};
+
+#[test]
+fn is_built_follows_the_features() {
+    assert_eq!(Engine::Text.is_built(), cfg!(feature = "engine-text"));
+    assert_eq!(Engine::Font.is_built(), cfg!(feature = "engine-font"));
+    assert!(Engine::Auto.is_built());
+}
+
+#[test]
+fn the_builder_refuses_an_engine_left_out() {
+    for engine in [Engine::Auto, Engine::Text, Engine::Font] {
+        match Extractor::builder().engine(engine).build() {
+            Ok(_) => assert!(engine.is_built(), "{:?}", engine),
+            Err(error) => {
+                assert_eq!(error, BuildError::EngineNotBuilt(engine));
+                assert!(error.to_string().contains("cargo feature engine-"), "{}", error);
+            }
+        }
+    }
+}
+
+#[test]
+fn auto_uses_the_engines_built() {
+    let outline = Extractor::builder().build().unwrap().extract_bytes(&common::numbered_report()).unwrap();
+    assert!(!outline.outline.is_empty());
+    assert!(!outline.engine.is_empty());
+    for engine in &outline.engine {
+        let built = match engine.as_str() {
+            "text" => Engine::Text.is_built(),
+            "font" => Engine::Font.is_built(),
+            // Bookmarks need neither engine
+            _ => true,
+        };
+        assert!(built, "{} ran but isn't built", engine);
+    }
+}
+
+#[test]
+fn the_cli_refuses_an_engine_left_out() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-engine-features-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("report.pdf");
+    std::fs::write(&input, common::numbered_report()).unwrap();
+    for (name, engine) in [("text", Engine::Text), ("font", Engine::Font)] {
+        let output = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+            .arg("--input").arg(&input)
+            .args(["--no-output-file", "--engine", name])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.success(), engine.is_built(), "--engine {}", name);
+        // The flag only offers the engines built
+        if !engine.is_built() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(stderr.contains(&format!("invalid value '{}' for '--engine", name)), "{}", stderr);
+        }
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,61 @@
+// Inputs with nothing to outline fail with an error of their own, found
+// with `downcast_ref`, and the command line exits with a status of its own
+// for each: 3 for a PDF without content, 4 for a file that isn't a PDF.
+
+use adobe1a::config::ExtractorConfig;
+use adobe1a::{extract_outline_from_bytes, timing::Stats, NoTextContent, NotAPdf, Outline};
+use std::process::Command;
+
+fn extract(bytes: &[u8]) -> anyhow::Result<Outline> {
+    extract_outline_from_bytes(bytes, "fixture", &ExtractorConfig::default(), &mut Stats::new())
+}
+
+fn exit_code(fixture: &str) -> Option<i32> {
+    let input = format!("{}/fixtures/{}", env!("CARGO_MANIFEST_DIR"), fixture);
+    let output = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+        .args(["--input", &input, "--no-output-file"])
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .unwrap();
+    output.status.code()
+}
+
+#[test]
+fn pdfs_without_content() {
+    let error = extract(include_bytes!("../fixtures/zero-pages.pdf")).unwrap_err();
+    assert_eq!(error.downcast_ref(), Some(&NoTextContent::NoPages));
+    let error = extract(include_bytes!("../fixtures/header-only.pdf")).unwrap_err();
+    assert_eq!(error.downcast_ref(), Some(&NoTextContent::HeaderOnly));
+    assert_eq!(exit_code("zero-pages.pdf"), Some(3));
+    assert_eq!(exit_code("header-only.pdf"), Some(3));
+}
+
+// Pages without text aren't an error: the outline is empty, with a warning
+#[test]
+fn blank_pages_are_not_an_error() {
+    let outline = extract(include_bytes!("../fixtures/blank-page.pdf")).unwrap();
+    assert!(outline.outline.is_empty());
+    assert_eq!(outline.warnings[0].code, "no_text");
+    assert_eq!(exit_code("blank-page.pdf"), Some(0));
+}
+
+#[test]
+fn files_that_are_not_pdfs() {
+    let error = extract(include_bytes!("../fixtures/renamed-docx.pdf")).unwrap_err();
+    assert_eq!(error.downcast_ref(), Some(&NotAPdf::Zip));
+    assert!(error.to_string().contains("ZIP archive"));
+    let error = extract(include_bytes!("../fixtures/renamed-text.pdf")).unwrap_err();
+    assert_eq!(error.downcast_ref(), Some(&NotAPdf::Unknown));
+    let error = extract(b"<!DOCTYPE html><html><body>Report</body></html>").unwrap_err();
+    assert_eq!(error.downcast_ref(), Some(&NotAPdf::Html));
+    assert_eq!(exit_code("renamed-docx.pdf"), Some(4));
+    assert_eq!(exit_code("renamed-text.pdf"), Some(4));
+}
+
+// Junk before the header is skipped, as readers do
+#[test]
+fn junk_before_the_header() {
+    let mut bytes = vec![b'x'; 500];
+    bytes.extend(include_bytes!("../fixtures/blank-page.pdf"));
+    assert!(extract(&bytes).is_ok());
+}
@@ -0,0 +1,43 @@
+// A document with no title on its pages or in its metadata is titled from
+// its file name, cleaned of separators, version and copy markers, with years
+// and acronyms kept (title_fallback.rs covers the fallback itself). The
+// lexicon's stopwords stay lowercase.
+
+mod common;
+
+use adobe1a::config::TitleMode;
+use adobe1a::lexicon::Lexicon;
+use adobe1a::titles::title_from_filename;
+use adobe1a::{extract_outline_from_bytes, timing::Stats, Extractor};
+
+#[test]
+fn file_names_clean_up_into_titles() {
+    for (stem, title) in [
+        ("final_RFP_v3_CLEAN_(2)", "RFP"),
+        ("annual-report_2023_FINAL", "Annual Report 2023"),
+        ("Copy of budget_draft", "Budget"),
+        ("meeting.minutes.v1.2", "Meeting Minutes"),
+        ("Q3_results (1)", "Q3 Results"),
+        ("STEMPathwaysFlyer", "STEM Pathways Flyer"),
+        ("terms_and_conditions-rev2", "Terms and Conditions"),
+        ("   lease__agreement   ", "Lease Agreement"),
+        ("HR-policy-handbook_v12", "HR Policy Handbook"),
+        ("ISO-9001-audit_copy", "ISO 9001 Audit"),
+        // Nothing but noise keeps the name as it was
+        ("final_v2_(3)", "final_v2_(3)"),
+    ] {
+        assert_eq!(title_from_filename(stem, &Lexicon::default().stopwords), title, "{:?}", stem);
+    }
+}
+
+#[test]
+fn the_configured_lexicon_decides_the_small_words() {
+    let title = |lexicon: Lexicon| {
+        let extractor = Extractor::builder().title(TitleMode::Filename).lexicon(lexicon).build().unwrap();
+        let pdf = common::numbered_report();
+        extract_outline_from_bytes(&pdf, "terms_and_conditions_of_sale", extractor.config(), &mut Stats::new()).unwrap().title
+    };
+    assert_eq!(title(Lexicon::default()), "Terms and Conditions of Sale");
+    let lexicon = Lexicon { stopwords: vec!["of".to_string()], ..Lexicon::default() };
+    assert_eq!(title(lexicon), "Terms And Conditions of Sale");
+}
@@ -0,0 +1,67 @@
+// Golden outlines: each fixture's outline from each engine, compared with
+// the JSON kept in tests/golden/, so a heuristic change that moves, drops
+// or adds a heading anywhere shows up as a diff to review. When the change
+// is intended, rewrite the files and commit them with it:
+//
+//   UPDATE_GOLDEN=1 cargo test --test golden
+
+mod common;
+
+use std::path::PathBuf;
+use adobe1a::config::Engine;
+use adobe1a::Extractor;
+
+// Lines of context shown around the first difference in a failing file
+const CONTEXT_LINES: usize = 3;
+
+#[test]
+fn text_engine_matches_golden_outlines() {
+    check(Engine::Text, "text");
+}
+
+#[test]
+fn font_engine_matches_golden_outlines() {
+    check(Engine::Font, "font");
+}
+
+fn check(engine: Engine, suffix: &str) {
+    if !engine.is_built() {
+        return;
+    }
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let extractor = Extractor::builder().engine(engine).build().unwrap();
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let mut failures = Vec::new();
+    for (name, pdf) in common::fixtures() {
+        let mut outline = extractor.extract_bytes(&pdf).unwrap_or_else(|e| panic!("{}: {:#}", name, e));
+        // Changes with every release without the outline changing
+        outline.extractor_version.clear();
+        let actual = outline.to_json_string(true).unwrap() + "\n";
+
+        let path = dir.join(format!("{}.{}.json", name, suffix));
+        if update {
+            std::fs::write(&path, &actual).unwrap();
+            continue;
+        }
+        match std::fs::read_to_string(&path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => failures.push(format!("{}\n{}", path.display(), first_difference(&expected, &actual))),
+            Err(_) => failures.push(format!("{} is missing", path.display())),
+        }
+    }
+    assert!(failures.is_empty(), "{} outline(s) differ from their golden files; if the change is intended, \
+            rerun with UPDATE_GOLDEN=1 and commit the result\n\n{}", failures.len(), failures.join("\n\n"));
+}
+
+// The lines around the first that differs, marked - for the golden file
+// and + for the new outline
+fn first_difference(expected: &str, actual: &str) -> String {
+    let (expected, actual): (Vec<&str>, Vec<&str>) = (expected.lines().collect(), actual.lines().collect());
+    let at = expected.iter().zip(&actual).position(|(a, b)| a != b).unwrap_or(expected.len().min(actual.len()));
+    let from = at.saturating_sub(CONTEXT_LINES);
+    let mut out = Vec::new();
+    out.extend(expected[from..at].iter().map(|line| format!("  {}", line)));
+    out.extend(expected.iter().skip(at).take(CONTEXT_LINES).map(|line| format!("- {}", line)));
+    out.extend(actual.iter().skip(at).take(CONTEXT_LINES).map(|line| format!("+ {}", line)));
+    format!("line {}:\n{}", at + 1, out.join("\n"))
+}
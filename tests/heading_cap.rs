@@ -0,0 +1,109 @@
+// The font engine keeps at most `headings_per_page` times the page count
+// headings (never fewer than MIN_HEADING_CAP) unless `max_headings` is set,
+// and a `headings_capped` warning says how many were dropped and where the
+// confidence cutoff fell.
+
+mod common;
+
+use adobe1a::config::{Engine, ExtractorConfig, MIN_HEADING_CAP};
+use adobe1a::{extract_outline_from_bytes, timing::Stats, Outline};
+use common::pdf_builder::{Font, Page, PdfBuilder, Style};
+
+const REGIONS: [&str; 6] = ["Northern", "Southern", "Eastern", "Western", "Coastal", "Inland"];
+const FIGURES: [&str; 12] = [
+    "Margin", "Sales", "Costs", "Notes", "Stores", "Hours", "Share", "Cash", "Prices", "Stock", "Orders", "Visits",
+];
+const PLACES: [&str; 10] = ["Harbours", "Rivers", "Forests", "Uplands", "Marshes", "Islands", "Valleys", "Moors", "Lakes", "Plains"];
+
+fn extract(pdf: &[u8], max_headings: Option<usize>) -> Outline {
+    let config = ExtractorConfig { engine: Engine::Font, page_heading_limit: 0, max_headings, ..ExtractorConfig::default() };
+    extract_outline_from_bytes(pdf, "capped", &config, &mut Stats::new()).unwrap()
+}
+
+fn capped(outline: &Outline) -> Option<&str> {
+    outline.warnings.iter().find(|w| w.code == "headings_capped").map(|w| w.message.as_str())
+}
+
+// A two-page memo with a dozen bold figure captions on each page
+fn memo() -> Vec<u8> {
+    let caption = Style::new(Font::HelveticaBold, 11.0);
+    let page = |region: &str| {
+        FIGURES.iter().fold(Page::new().heading(1, &format!("{} Results", region)).body(1), |page, figure| {
+            page.line(caption, &format!("{} {}", region, figure)).body(1)
+        })
+    };
+    PdfBuilder::new().page(page(REGIONS[0])).page(page(REGIONS[1])).build()
+}
+
+// Sixty chapters, each page with two weaker captions
+fn standard() -> Vec<u8> {
+    let caption = Style::new(Font::HelveticaBold, 11.0);
+    (0..60)
+        .fold(PdfBuilder::new(), |builder, chapter| {
+            let (region, place) = (REGIONS[chapter % 6], PLACES[chapter / 6]);
+            builder.page(
+                Page::new()
+                    .heading(1, &format!("The {} {}", region, place))
+                    .body(2)
+                    .line(caption, &format!("{} {} {}", region, place, FIGURES[chapter % 12]))
+                    .body(2)
+                    .line(caption, &format!("{} {} {}", region, place, FIGURES[(chapter + 5) % 12]))
+                    .body(2),
+            )
+        })
+        .build()
+}
+
+#[test]
+fn the_cap_grows_with_the_page_count() {
+    let config = ExtractorConfig::default();
+    assert_eq!(config.heading_cap(1), MIN_HEADING_CAP);
+    assert_eq!(config.heading_cap(4), MIN_HEADING_CAP);
+    assert_eq!(config.heading_cap(60), 90);
+    assert_eq!(config.heading_cap(900), 1350);
+    assert_eq!(ExtractorConfig { headings_per_page: 0.5, ..config.clone() }.heading_cap(101), 51);
+    assert_eq!(ExtractorConfig { max_headings: Some(50), ..config }.heading_cap(900), 50);
+}
+
+#[test]
+fn a_short_document_with_many_candidates_is_capped_tightly() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let outline = extract(&memo(), None);
+    assert_eq!(outline.outline.len(), MIN_HEADING_CAP);
+    let texts: Vec<_> = outline.outline.iter().map(|heading| heading.text.as_str()).collect();
+    assert!(texts.contains(&"Northern Results") && texts.contains(&"Southern Results"), "{:?}", texts);
+
+    let message = capped(&outline).expect("a headings_capped warning");
+    assert!(message.starts_with("16 of 26 heading candidates were dropped by the cap of 10; "), "{}", message);
+    let cutoff = outline.outline.iter().map(|heading| heading.confidence).fold(f64::MAX, f64::min);
+    assert!(message.ends_with(&format!("the lowest confidence kept is {:.2}", cutoff)), "{}", message);
+}
+
+#[test]
+fn an_absolute_cap_overrides_the_adaptive_one() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let outline = extract(&memo(), Some(50));
+    assert_eq!(outline.outline.len(), 26);
+    assert_eq!(capped(&outline), None);
+}
+
+#[test]
+fn a_long_document_keeps_its_late_chapters() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let outline = extract(&standard(), None);
+    let chapters: Vec<_> = outline.outline.iter().filter(|heading| heading.text.starts_with("The ")).collect();
+    assert_eq!(chapters.len(), 60);
+    assert_eq!((chapters[59].text.as_str(), chapters[59].page), ("The Inland Plains", 60));
+    assert_eq!(outline.outline.len(), 90);
+    assert!(capped(&outline).unwrap().starts_with("90 of 180 "), "{:?}", outline.warnings);
+
+    // Under the old fixed cap of 50 the last ten chapters were lost
+    let fixed = extract(&standard(), Some(50));
+    assert!(fixed.outline.iter().all(|heading| heading.text != "The Inland Plains"));
+}
@@ -0,0 +1,21 @@
+// `heading_case` recases heading text with the lexicon's stopwords, and the
+// title too when `normalize_title` is set.
+
+use adobe1a::config::CaseStyle;
+use adobe1a::{Extractor, Outline};
+
+fn outline(case: CaseStyle, normalize_title: bool) -> Outline {
+    let bytes = include_bytes!("../fixtures/rfp-cover.pdf");
+    let extractor = Extractor::builder().heading_case(case).normalize_title(normalize_title).build().unwrap();
+    extractor.extract_bytes(bytes).unwrap()
+}
+
+#[test]
+fn headings_are_recased_and_the_title_only_when_asked() {
+    let preserved = outline(CaseStyle::Preserve, true);
+    let upper = outline(CaseStyle::Upper, false);
+    assert!(!upper.outline.is_empty());
+    assert!(upper.outline.iter().zip(&preserved.outline).all(|(a, b)| a.text == b.text.to_uppercase()));
+    assert_eq!(upper.title, preserved.title);
+    assert_eq!(outline(CaseStyle::Upper, true).title, preserved.title.to_uppercase());
+}
@@ -0,0 +1,35 @@
+// With `include_sources` set, each heading names what found it: `text` or
+// `font` for an engine, or `bookmarks` for a quick-mode outline taken from
+// the PDF's bookmarks. It's left out unless asked for.
+
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::{extract_outline_from_bytes, timing::Stats};
+
+const NUL_HEADING: &[u8] = include_bytes!("../fixtures/nul-heading.pdf");
+
+fn sources(config: ExtractorConfig) -> Vec<Vec<String>> {
+    let outline = extract_outline_from_bytes(NUL_HEADING, "fixture", &config, &mut Stats::new()).unwrap();
+    outline.outline.into_iter().map(|heading| heading.sources).collect()
+}
+
+#[test]
+fn each_engine_names_itself() {
+    let config = ExtractorConfig { include_sources: true, ..ExtractorConfig::default() };
+    for (engine, name) in [(Engine::Text, "text"), (Engine::Font, "font")] {
+        if engine.is_built() {
+            let found = sources(ExtractorConfig { engine, ..config.clone() });
+            assert!(!found.is_empty() && found.iter().all(|sources| sources == &[name]), "{:?}", found);
+        }
+    }
+}
+
+#[test]
+fn quick_mode_names_the_bookmarks() {
+    let found = sources(ExtractorConfig { include_sources: true, quick: Some(15), ..ExtractorConfig::default() });
+    assert!(!found.is_empty() && found.iter().all(|sources| sources == &["bookmarks"]), "{:?}", found);
+}
+
+#[test]
+fn sources_are_left_out_unless_asked_for() {
+    assert!(sources(ExtractorConfig::default()).iter().all(Vec::is_empty));
+}
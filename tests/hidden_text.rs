@@ -0,0 +1,79 @@
+// Invisible text (render mode 3): an OCR layer over a scan is read when the
+// page has nothing visible, and a hidden duplicate beside visible text is
+// left out, unless the policy says otherwise. Only the font engine sees
+// render modes, so a forced policy is the font engine's.
+
+mod common;
+
+use adobe1a::config::{Engine, HiddenText};
+use adobe1a::{BuildError, Extractor, Outline};
+use common::pdf_builder::{Page, PdfBuilder};
+
+fn extract(hidden_text: HiddenText, pdf: &[u8]) -> Outline {
+    Extractor::builder().engine(Engine::Font).hidden_text(hidden_text).build().unwrap().extract_bytes(pdf).unwrap()
+}
+
+fn texts(outline: &Outline) -> Vec<&str> {
+    outline.outline.iter().map(|heading| heading.text.as_str()).collect()
+}
+
+// Two scanned pages whose only text is their OCR layer
+fn ocr_scan() -> Vec<u8> {
+    PdfBuilder::new()
+        .page(Page::new().invisible().title("Survey of Harbour Walls").heading(1, "1. Condition").body(20))
+        .page(Page::new().invisible().heading(1, "2. Repairs").body(20))
+        .build()
+}
+
+// Visible headings, with tagging notes drawn invisibly beside them, as some
+// accessibility tools leave them
+fn redundant_hidden() -> Vec<u8> {
+    PdfBuilder::new()
+        .page(Page::new()
+            .title("Library Opening Hours")
+            .heading(1, "1. Weekdays").body(10)
+            .invisible().heading(1, "Reading Order Note").visible()
+            .heading(1, "2. Weekends").body(10))
+        .build()
+}
+
+#[test]
+fn an_ocr_layer_is_read_when_nothing_is_visible() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let pdf = ocr_scan();
+    for policy in [HiddenText::Auto, HiddenText::Include] {
+        let outline = extract(policy, &pdf);
+        assert_eq!(texts(&outline), ["1. Condition", "2. Repairs"], "{:?}", policy);
+    }
+    assert!(extract(HiddenText::Exclude, &pdf).outline.is_empty());
+}
+
+#[test]
+fn hidden_text_beside_visible_text_is_left_out() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let pdf = redundant_hidden();
+    for policy in [HiddenText::Auto, HiddenText::Exclude] {
+        let outline = extract(policy, &pdf);
+        assert!(texts(&outline).ends_with(&["1. Weekdays", "2. Weekends"]), "{:?}: {:?}", policy, texts(&outline));
+        assert!(!texts(&outline).contains(&"Reading Order Note"));
+    }
+    assert!(texts(&extract(HiddenText::Include, &pdf)).contains(&"Reading Order Note"));
+}
+
+#[test]
+fn a_forced_policy_needs_the_font_engine() {
+    let pdf = redundant_hidden();
+    if Engine::Font.is_built() {
+        let auto = Extractor::builder().hidden_text(HiddenText::Exclude).build().unwrap().extract_bytes(&pdf).unwrap();
+        assert_eq!(auto.engine, ["font"]);
+        assert!(!texts(&auto).contains(&"Reading Order Note"));
+    }
+    if Engine::Text.is_built() {
+        let text = Extractor::builder().engine(Engine::Text).hidden_text(HiddenText::Include).build();
+        assert_eq!(text.err(), Some(BuildError::FontEngineOnly { knob: "hidden_text" }));
+    }
+}
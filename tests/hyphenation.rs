@@ -0,0 +1,66 @@
+// A word hyphenated across a line break is joined back up before headings
+// are found and snippets are cut. The hyphen stays in a compound: a word
+// that already has one, or one whose first part is a word on its own
+// elsewhere in the document.
+
+mod common;
+
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::{analyze_text, extract_outline_from_bytes, sections, timing::Stats, Outline, TextOptions};
+use common::pdf_builder::{Page, PdfBuilder, Style};
+
+const BODY: &str = "The harbour board met to review the year's work on the quay walls and the channel.";
+
+fn texts(outline: &Outline) -> Vec<&str> {
+    outline.outline.iter().map(|heading| heading.text.as_str()).collect()
+}
+
+#[test]
+fn headings_are_joined_in_the_text_engine() {
+    let text = [
+        "Harbour Works Plan", BODY,
+        "1. Implementa-", "tion Plan", BODY, BODY,
+        "2. State-of-", "the-art Dredging", BODY, BODY,
+        "3. Co-", "operation Agreements", BODY, BODY,
+    ].join("\n");
+    let config = ExtractorConfig { page_heading_limit: 0, ..ExtractorConfig::default() };
+    let outline = analyze_text(&text, TextOptions { config, ..TextOptions::default() });
+    assert_eq!(texts(&outline), ["1. Implementation Plan", "2. State-of-the-art Dredging", "3. Cooperation Agreements"]);
+}
+
+#[test]
+fn headings_are_joined_in_the_font_engine() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let h1 = Style::heading(1);
+    let pdf = PdfBuilder::new()
+        .page(Page::new()
+            .title("Harbour Works Plan")
+            .body(1)
+            .line(h1, "1. Implementa-").line(h1, "tion Plan").body(2)
+            .line(h1, "2. State-of-").line(h1, "the-art Dredging").body(2)
+            .line(h1, "3. Co-").line(h1, "operation Agreements").body(2))
+        .build();
+    let config = ExtractorConfig { engine: Engine::Font, ..ExtractorConfig::default() };
+    let outline = extract_outline_from_bytes(&pdf, "plan", &config, &mut Stats::new()).unwrap();
+    let texts = texts(&outline);
+    for heading in ["1. Implementation Plan", "2. State-of-the-art Dredging", "3. Cooperation Agreements"] {
+        assert!(texts.contains(&heading), "{:?}", texts);
+    }
+}
+
+#[test]
+fn snippets_are_joined() {
+    let section = "The new terminal needs close co-\noperation with the ferry operators.\n\
+                   Its state-of-\nthe-art cranes need decision-\nmaking by the board, as does every decision.";
+    assert_eq!(
+        sections::snippet(section, 300).as_deref(),
+        Some("The new terminal needs close cooperation with the ferry operators. \
+              Its state-of-the-art cranes need decision-making by the board, as does every decision."),
+    );
+
+    // Without "decision" on its own elsewhere, the hyphen is taken for a break
+    let section = "The cranes need decision-\nmaking by the board.";
+    assert_eq!(sections::snippet(section, 300).as_deref(), Some("The cranes need decisionmaking by the board."));
+}
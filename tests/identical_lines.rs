@@ -0,0 +1,69 @@
+// Two lines on a page with the same text are classified separately: a 20pt
+// "Overview" heading keeps its large-font level whether a body-size
+// "Overview" cross-reference comes before or after it.
+
+mod common;
+
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::{extract_outline_from_bytes, timing::Stats, Outline};
+use common::pdf_builder::{Font, Page, PdfBuilder, Style};
+
+fn extract(page: Page) -> Outline {
+    let pdf = PdfBuilder::new()
+        .page(page)
+        .page(Page::new().heading(1, "Funding").body(3))
+        .build();
+    let config = ExtractorConfig { engine: Engine::Font, ..ExtractorConfig::default() };
+    extract_outline_from_bytes(&pdf, "collision", &config, &mut Stats::new()).unwrap()
+}
+
+fn check(outline: Outline) {
+    let overview: Vec<_> = outline.outline.iter().filter(|heading| heading.text == "Overview").collect();
+    assert_eq!(overview.len(), 1, "{:?}", outline.outline);
+    assert_eq!((overview[0].level.as_str(), overview[0].page), ("H1", 1));
+
+    let funding = outline.outline.iter().find(|heading| heading.text == "Funding").unwrap();
+    assert!(overview[0].confidence >= funding.confidence, "{} < {}", overview[0].confidence, funding.confidence);
+}
+
+#[test]
+fn the_large_heading_survives_a_later_cross_reference() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    check(extract(Page::new()
+        .title("Harbour Plan")
+        .body(1)
+        .styled_heading(Style::new(Font::HelveticaBold, 20.0), "Overview")
+        .body(3)
+        .line(Style::new(Font::Helvetica, 11.0), "Overview")
+        .body(2)));
+}
+
+#[test]
+fn the_large_heading_survives_an_earlier_cross_reference() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    check(extract(Page::new()
+        .title("Harbour Plan")
+        .body(1)
+        .body(2)
+        .line(Style::new(Font::Helvetica, 11.0), "Overview")
+        .body(2)
+        .styled_heading(Style::new(Font::HelveticaBold, 20.0), "Overview")
+        .body(3)));
+}
+
+#[test]
+fn the_cross_reference_alone_is_not_a_heading() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let outline = extract(Page::new()
+        .title("Harbour Plan")
+        .body(1)
+        .line(Style::new(Font::Helvetica, 11.0), "Overview")
+        .body(2));
+    assert!(outline.outline.iter().all(|heading| heading.text != "Overview"), "{:?}", outline.outline);
+}
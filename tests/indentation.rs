@@ -0,0 +1,56 @@
+// When the text engine's lines keep the page's indentation as leading
+// spaces, and the headings' indents form a consistent outline, it sets the
+// levels of headings whose numbering doesn't say how deep they are.
+
+mod common;
+
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::{extract_outline_from_bytes, timing::Stats};
+use common::pdf_builder::{Font, Page, PdfBuilder, Style};
+
+// The levels the text engine gives one page of 10pt Courier lines, drawn
+// with their leading spaces
+fn levels(lines: &[&str]) -> Vec<String> {
+    let page = lines.iter().fold(Page::new(), |page, line| page.line(Style::new(Font::Courier, 10.0), line));
+    let pdf = PdfBuilder::new().page(page).build();
+    let config = ExtractorConfig { engine: Engine::Text, ..ExtractorConfig::default() };
+    let outline = extract_outline_from_bytes(&pdf, "outline", &config, &mut Stats::new()).unwrap();
+    outline.outline.into_iter().map(|heading| heading.level).collect()
+}
+
+#[test]
+fn indentation_nests_unnumbered_levels() {
+    if !Engine::Text.is_built() {
+        return;
+    }
+    let handbook = [
+        "1. Introduction", "    1. Purpose of the Handbook", "    2. Intended Audience",
+        "2. Planning", "    1. Resource Allocation", "        A. Staffing Plans", "        B. Budget Lines",
+        "    2. Delivery Milestones", "3. Execution", "    1. Status Reporting",
+    ];
+    assert_eq!(levels(&handbook), ["H1", "H2", "H2", "H1", "H2", "H3", "H3", "H2", "H1", "H2"]);
+}
+
+#[test]
+fn a_multi_level_number_keeps_its_own_depth() {
+    if !Engine::Text.is_built() {
+        return;
+    }
+    let numbered = ["1. Scope", "   1.1 Terms Used", "   1.2 Related Work", "2. Design", "   2.1 Data Model", "   3. Testing"];
+    assert_eq!(levels(&numbered), ["H1", "H2", "H2", "H1", "H2", "H2"]);
+    // Indentation that contradicts a number isn't trusted at all
+    let crossed = ["1. Scope", "   1.1 Terms Used", "   1.2 Related Work", "2. Design", "2.1 Data Model", "   3. Testing"];
+    assert_eq!(levels(&crossed), ["H1", "H2", "H2", "H1", "H2", "H1"]);
+}
+
+#[test]
+fn indents_that_dont_nest_are_layout_noise() {
+    if !Engine::Text.is_built() {
+        return;
+    }
+    // Indents that don't nest, or that only one heading uses
+    let ragged = ["  1. Introduction", "2. Planning", "  3. Execution", "4. Review"];
+    assert_eq!(levels(&ragged), ["H1", "H1", "H1", "H1"]);
+    let stray = ["1. Introduction", "2. Planning", "      3. Execution", "4. Review"];
+    assert_eq!(levels(&stray), ["H1", "H1", "H1", "H1"]);
+}
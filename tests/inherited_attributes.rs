@@ -0,0 +1,100 @@
+// /Resources, /MediaBox and /Rotate set on a Pages node apply to the pages
+// under it that don't set their own, as Word exports often write them.
+// Fonts are still named and bold text still found, and positions use the
+// inherited page size and orientation.
+
+mod common;
+
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::layout::{extract_runs_from_bytes, TextRun};
+use adobe1a::{extract_outline_from_bytes, timing::Stats, Extractor};
+use common::pdf_builder::{self, Page, PdfBuilder};
+use lopdf::{Document, Object, ObjectId};
+
+fn report() -> Document {
+    PdfBuilder::new()
+        .page(Page::new().title("Harbour Works Plan").body(1).heading(1, "1. Quay Walls").body(3))
+        .page(Page::new().heading(1, "2. Channel Dredging").body(3))
+        .document()
+}
+
+fn tree(doc: &Document) -> (ObjectId, Vec<ObjectId>) {
+    let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    let parent = doc.get_dictionary(pages[0]).unwrap().get(b"Parent").unwrap().as_reference().unwrap();
+    (parent, pages)
+}
+
+// Moves `key` from every page to their Pages node, written inline there
+fn hoist(doc: &mut Document, key: &[u8]) {
+    let (parent, pages) = tree(doc);
+    let value = doc.get_dictionary(pages[0]).unwrap().get(key).unwrap().clone();
+    let value = match value {
+        Object::Reference(id) => doc.get_object(id).unwrap().clone(),
+        value => value,
+    };
+    for page in pages {
+        doc.get_dictionary_mut(page).unwrap().remove(key);
+    }
+    doc.get_dictionary_mut(parent).unwrap().set(key, value);
+}
+
+fn run<'a>(runs: &'a [TextRun], text: &str) -> &'a TextRun {
+    runs.iter().find(|run| run.text == text).unwrap_or_else(|| panic!("no run {:?} in {:?}", text, runs))
+}
+
+#[test]
+fn fonts_come_from_inherited_resources() {
+    let mut doc = report();
+    hoist(&mut doc, b"Resources");
+    let pdf = pdf_builder::save(&mut doc);
+
+    let runs = extract_runs_from_bytes(&pdf).unwrap();
+    let heading = run(&runs, "2. Channel Dredging");
+    assert_eq!((heading.font.as_str(), heading.is_bold), ("Helvetica-Bold", true));
+
+    if Engine::Font.is_built() {
+        let config = ExtractorConfig { engine: Engine::Font, ..ExtractorConfig::default() };
+        let outline = extract_outline_from_bytes(&pdf, "plan", &config, &mut Stats::new()).unwrap();
+        let headings: Vec<_> = outline.outline.iter().map(|heading| (heading.text.as_str(), heading.level.as_str())).collect();
+        assert!(headings.contains(&("1. Quay Walls", "H1")) && headings.contains(&("2. Channel Dredging", "H1")), "{:?}", headings);
+    }
+}
+
+#[test]
+fn a_page_size_is_inherited() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let place = |doc: &mut Document| {
+        let outline = Extractor::builder().engine(Engine::Font).positions(true).build().unwrap()
+            .extract_bytes(&pdf_builder::save(doc)).unwrap();
+        outline.outline.iter().find(|heading| heading.text == "2. Channel Dredging").unwrap().y_fraction.unwrap()
+    };
+    let letter = place(&mut report());
+
+    // A legal-size page, 216pt taller, with the same content at its foot
+    let mut doc = report();
+    let (parent, _) = tree(&doc);
+    hoist(&mut doc, b"MediaBox");
+    doc.get_dictionary_mut(parent).unwrap().set("MediaBox", vec![0.into(), 0.into(), 612.into(), 1008.into()]);
+    let legal = place(&mut doc);
+    assert!((legal * 1008.0 - (letter * 792.0 + 216.0)).abs() < 1.0, "{} {}", letter, legal);
+}
+
+#[test]
+fn a_rotation_is_inherited_unless_a_page_sets_its_own() {
+    let upright = extract_runs_from_bytes(&pdf_builder::save(&mut report())).unwrap();
+
+    let mut doc = report();
+    let (parent, pages) = tree(&doc);
+    doc.get_dictionary_mut(parent).unwrap().set("Rotate", 90);
+    doc.get_dictionary_mut(pages[1]).unwrap().set("Rotate", 0);
+    let runs = extract_runs_from_bytes(&pdf_builder::save(&mut doc)).unwrap();
+
+    // Page 1 is turned a quarter clockwise: its left edge is displayed at the top
+    let (before, after) = (run(&upright, "1. Quay Walls"), run(&runs, "1. Quay Walls"));
+    assert!((after.x - before.y).abs() < 1e-6 && (after.y - (612.0 - before.x)).abs() < 1e-6, "{:?} {:?}", before, after);
+    // Page 2 keeps its own /Rotate 0
+    let (before, after) = (run(&upright, "2. Channel Dredging"), run(&runs, "2. Channel Dredging"));
+    assert_eq!((after.x, after.y), (before.x, before.y));
+}
@@ -0,0 +1,39 @@
+// Some publishers set their lowest headings in italics at body size. When
+// a document does that throughout, on lines of their own in front of roman
+// paragraphs, the font engine keeps them a level below its other headings.
+// Italics inside a sentence, quotations, and a handful of italic lines in a
+// document that doesn't work that way stay body text.
+
+use adobe1a::config::Engine;
+use adobe1a::Extractor;
+
+const ITALIC_HEADINGS: &[u8] = include_bytes!("../fixtures/italic-headings.pdf");
+
+fn headings(pages: &str) -> Vec<(String, String)> {
+    let extractor = Extractor::builder().engine(Engine::Font).pages(pages.parse().unwrap()).build().unwrap();
+    let outline = extractor.extract_bytes(ITALIC_HEADINGS).unwrap();
+    outline.outline.into_iter().map(|heading| (heading.level, heading.text)).collect()
+}
+
+#[test]
+fn italic_run_in_headings_sit_a_level_down() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let headings = headings("1-2");
+    let italic: Vec<&str> = headings.iter()
+        .filter(|(level, _)| level == "H3")
+        .map(|(_, text)| text.as_str())
+        .collect();
+    assert_eq!(italic, ["Sampling frame", "Recruitment of observers", "Equipment and calibration", "Data cleaning", "Weighting by effort"]);
+    assert!(!headings.iter().any(|(_, text)| text.contains("in situ") || text.contains("Bibby") || text.starts_with("Every count")));
+}
+
+#[test]
+fn a_few_italic_lines_are_not_a_habit() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    // Two italic lines on the second page alone
+    assert!(!headings("2").iter().any(|(level, _)| level == "H3"));
+}
@@ -0,0 +1,140 @@
+// `--report` writes a JUnit XML test case per file of an `--expected` run:
+// passing, failing on a low F1 with the mismatched headings, an error when
+// the file couldn't be processed, or skipped without a reference. Heading
+// text reaches the XML escaped.
+
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+use adobe1a::evaluation::evaluate;
+use adobe1a::summary::{BatchSummary, FileSummary};
+use adobe1a::timing::Stats;
+use adobe1a::{Heading, Outline};
+
+// Checks `xml` is well formed as far as a report needs: every `&` starts an
+// entity or character reference, no character XML forbids appears, and the
+// elements nest as JUnit's schema has them, each test case with a time.
+// Returns the elements in document order, with their attributes' source.
+fn elements(xml: &str) -> Vec<(String, String)> {
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+    assert!(!xml.chars().any(|c| c < ' ' && !['\t', '\n', '\r'].contains(&c)), "a control character in {:?}", xml);
+    for (i, _) in xml.match_indices('&') {
+        let entity = &xml[i..xml[i..].find(';').map_or(xml.len(), |end| i + end + 1)];
+        assert!(["&amp;", "&lt;", "&gt;", "&quot;", "&apos;", "&#10;", "&#13;", "&#9;"].contains(&entity), "{:?}", entity);
+    }
+    let parent = |name: &str| match name {
+        "testsuites" => None,
+        "testsuite" => Some("testsuites"),
+        "testcase" => Some("testsuite"),
+        "failure" | "error" | "skipped" => Some("testcase"),
+        other => panic!("unexpected element <{}>", other),
+    };
+    let body = &xml[xml.find("?>").unwrap() + 2..];
+    let mut open: Vec<String> = Vec::new();
+    let mut found = Vec::new();
+    for tag in body.split('<').skip(1) {
+        let tag = &tag[..tag.find('>').expect("an unterminated tag")];
+        assert!(!tag.contains('<'), "{:?}", tag);
+        if let Some(name) = tag.strip_prefix('/') {
+            assert_eq!(open.pop().as_deref(), Some(name));
+            continue;
+        }
+        let (name, attributes) = tag.split_once(' ').unwrap_or((tag, ""));
+        assert_eq!(open.last().map(String::as_str), parent(name), "<{}> inside {:?}", name, open.last());
+        if matches!(name, "testsuites" | "testsuite" | "testcase") {
+            assert!(attributes.contains(" time=\""), "<{}> without a time", name);
+        }
+        // Quotes in values are escaped, so they pair up
+        assert_eq!(attributes.matches('"').count() % 2, 0, "{:?}", attributes);
+        found.push((name.to_string(), attributes.to_string()));
+        if !tag.ends_with('/') {
+            open.push(name.to_string());
+        }
+    }
+    assert!(open.is_empty(), "unclosed {:?}", open);
+    found
+}
+
+fn heading(level: &str, text: &str, page: usize) -> Heading {
+    Heading { level: level.into(), text: text.into(), page, ..Default::default() }
+}
+
+#[test]
+fn heading_text_is_escaped() {
+    let outline = Outline {
+        title: "Terms".into(),
+        outline: vec![heading("H1", "Terms & \"Conditions\" <Draft>", 1), heading("H2", "O'Brien\u{1}\u{7} Memo\tNotes", 2)],
+        ..Default::default()
+    };
+    let reference = [heading("H1", "Q&A: <Scope> \"v2\"", 1)];
+    let file = FileSummary::succeeded(Path::new("input/a&b <1>.pdf"), &outline, Stats::default())
+        .with_evaluation(Some(evaluate(&outline.outline, &reference)));
+    let xml = BatchSummary::new(vec![file]).to_junit(0.8);
+
+    let found = elements(&xml);
+    let case = found.iter().find(|(name, _)| name == "testcase").unwrap();
+    assert!(case.1.starts_with("name=\"input/a&amp;b &lt;1&gt;.pdf\""), "{}", case.1);
+    assert!(found.iter().any(|(name, _)| name == "failure"));
+    assert!(xml.contains("missed: H1 &quot;Q&amp;A: &lt;Scope&gt; &quot;v2&quot;&quot; (page 1)\n"), "{}", xml);
+    assert!(xml.contains("unexpected: H1 &quot;Terms &amp; &quot;Conditions&quot; &lt;Draft&gt;&quot; (page 1)\n"), "{}", xml);
+    // The control characters are dropped; the tab is text
+    assert!(xml.contains("unexpected: H2 &quot;O&apos;Brien Memo\tNotes&quot; (page 2)</failure>"), "{}", xml);
+}
+
+#[test]
+fn a_run_with_expected_outlines_writes_a_report() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-junit-{}", std::process::id()));
+    let (input, output, expected) = (dir.join("input"), dir.join("output"), dir.join("expected"));
+    for dir in [&input, &output, &expected] {
+        std::fs::create_dir_all(dir).unwrap();
+    }
+    std::fs::write(input.join("good.pdf"), common::numbered_report()).unwrap();
+    std::fs::write(input.join("wrong.pdf"), common::numbered_report()).unwrap();
+    std::fs::write(input.join("unscored.pdf"), common::numbered_report()).unwrap();
+    std::fs::write(input.join("broken.pdf"), b"%PDF-1.4 truncated").unwrap();
+    let run = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+            .arg("--input").arg(&input)
+            .arg("--output").arg(&output)
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+
+    // The first run's outline is good's reference, so it scores 1
+    run(&[]);
+    std::fs::copy(output.join("good.json"), expected.join("good.json")).unwrap();
+    std::fs::write(expected.join("wrong.json"), r#"{"title": "Report", "outline": [
+        {"level": "H1", "text": "Summary & <Scope>", "page": 1}
+    ]}"#).unwrap();
+
+    let report = dir.join("junit.xml");
+    let arguments = ["--expected", expected.to_str().unwrap(), "--report", report.to_str().unwrap(), "--force"];
+    run(&arguments);
+    let xml = std::fs::read_to_string(&report).unwrap();
+    let found = elements(&xml);
+
+    let suite = &found.iter().find(|(name, _)| name == "testsuite").unwrap().1;
+    assert!(suite.contains("tests=\"4\" failures=\"1\" errors=\"1\" skipped=\"1\""), "{}", suite);
+    // Test cases come in path order, each followed by any outcome element
+    let outcome = |file: &str| {
+        let i = found.iter().position(|(name, attributes)| name == "testcase" && attributes.contains(file)).unwrap();
+        found.get(i + 1).filter(|(name, _)| name != "testcase").map(|(name, attributes)| (name.as_str(), attributes.as_str()))
+    };
+    assert!(outcome("broken.pdf").is_some_and(|(name, _)| name == "error"));
+    assert_eq!(outcome("good.pdf"), None);
+    assert_eq!(outcome("unscored.pdf"), Some(("skipped", "message=\"no reference outline\"/")));
+    let (name, failure) = outcome("wrong.pdf").unwrap();
+    assert_eq!(name, "failure");
+    assert!(failure.starts_with("message=\"F1 0.000 is below 0.8 (precision 0.000, recall 0.000)\""), "{}", failure);
+    assert!(xml.contains("missed: H1 &quot;Summary &amp; &lt;Scope&gt;&quot; (page 1)\nunexpected: "), "{}", xml);
+
+    // The scores go into the summary too
+    let summary = dir.join("summary.json");
+    run(&[&arguments[..], &["--summary", summary.to_str().unwrap()]].concat());
+    let summary: serde_json::Value = serde_json::from_slice(&std::fs::read(&summary).unwrap()).unwrap();
+    let good = summary["files"].as_array().unwrap().iter().find(|file| file["path"].as_str().unwrap().ends_with("good.pdf")).unwrap();
+    assert_eq!(good["evaluation"]["f1"], 1.0);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
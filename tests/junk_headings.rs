@@ -0,0 +1,68 @@
+// Lines that clean down to nothing, or to nothing but punctuation and
+// digits, never become headings, in either engine: "...... 12", "5.",
+// rules of dashes. A heading needs `min_heading_letters` letters.
+
+mod common;
+
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::{analyze_text, Extractor, Outline, TextOptions};
+use common::pdf_builder::{Page, PdfBuilder, Style};
+
+const HEADINGS: [&str; 3] = ["1. Introduction", "2. Methods", "3. Results"];
+const JUNK: [&str; 7] = ["...... 12", "5.", "— — —", "12.3", "§ 4", "A.", "* * *"];
+
+// Each heading with a paragraph under it, and the junk between them
+fn lines() -> Vec<(bool, &'static str)> {
+    HEADINGS.iter().zip(JUNK.chunks(3))
+        .flat_map(|(heading, junk)| {
+            std::iter::once((true, *heading)).chain(junk.iter().map(|line| (false, *line)))
+        })
+        .collect()
+}
+
+fn assert_no_junk(outline: &Outline, min_letters: usize) {
+    for heading in &outline.outline {
+        let letters = heading.text.chars().filter(|c| c.is_alphabetic()).count();
+        assert!(letters >= min_letters, "{:?} in {:?}", heading.text, outline.outline);
+    }
+}
+
+fn texts(outline: &Outline) -> Vec<&str> {
+    outline.outline.iter().map(|heading| heading.text.as_str()).collect()
+}
+
+#[test]
+fn the_text_engine_drops_junk() {
+    let body = "The harbour board met to review the year's work on the quay walls and the channel.";
+    let text: String = lines().iter().map(|(_, line)| format!("{}\n{}\n", line, body)).collect();
+    for min_heading_letters in [2, 10] {
+        let config = ExtractorConfig { min_heading_letters, ..ExtractorConfig::default() };
+        let outline = analyze_text(&text, TextOptions { config, ..TextOptions::default() });
+        assert_no_junk(&outline, min_heading_letters);
+        let expected: &[&str] = if min_heading_letters == 2 { &HEADINGS } else { &["1. Introduction"] };
+        assert_eq!(texts(&outline), expected);
+    }
+}
+
+#[test]
+fn the_font_engine_drops_junk() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    // The junk is set as large and bold as the headings
+    let page = lines().iter().fold(Page::new(), |page, &(_, line)| page.styled_heading(Style::heading(1), line).body(2));
+    let pdf = PdfBuilder::new().page(page).build();
+    for min_heading_letters in [2, 10] {
+        let outline = Extractor::builder()
+            .engine(Engine::Font)
+            .min_heading_letters(min_heading_letters)
+            .build()
+            .unwrap()
+            .extract_bytes(&pdf)
+            .unwrap();
+        assert_no_junk(&outline, min_heading_letters);
+        let found = texts(&outline);
+        let expected: &[&str] = if min_heading_letters == 2 { &HEADINGS } else { &["1. Introduction"] };
+        assert!(expected.iter().all(|heading| found.contains(heading)), "{:?}", found);
+    }
+}
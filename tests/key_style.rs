@@ -0,0 +1,87 @@
+// `--key-style camel` respells every key in the output, however deeply it
+// is nested: in headings, warnings, metadata, stats, the documents split
+// out of a PDF and the batch summary. Values are left alone, and either
+// style reads back.
+
+mod common;
+
+use std::process::Command;
+use adobe1a::config::ExtractorConfig;
+use adobe1a::output::{from_json, to_json, KeyStyle};
+use adobe1a::documents::SubDocument;
+use adobe1a::{extract_outline_from_bytes, timing::Stats, Outline};
+use common::pdf_builder::{Page, PdfBuilder};
+use serde_json::Value;
+
+fn outline() -> Outline {
+    let pdf = PdfBuilder::new()
+        .page(Page::new().title("Harbour Development Plan").heading(1, "1. Introduction").body(3))
+        .page(Page::new().heading(2, "1.1 Scope").body(3))
+        .page_labels(1, Some("r"), None, None)
+        .xmp("<rdf:RDF><dc:title>")
+        .build();
+    let config = ExtractorConfig { title_candidates: true, ..ExtractorConfig::default() };
+    let mut stats = Stats::new();
+    let mut outline = extract_outline_from_bytes(&pdf, "plan", &config, &mut stats).unwrap();
+    outline.stats = Some(stats);
+    outline.documents = vec![SubDocument { title: "Harbour Development Plan".into(), first_page: 1, last_page: 2 }];
+    outline
+}
+
+// Every key in `value`, at any depth
+fn keys(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => map.iter().for_each(|(key, value)| {
+            out.push(key.clone());
+            keys(value, out);
+        }),
+        Value::Array(items) => items.iter().for_each(|item| keys(item, out)),
+        _ => {}
+    }
+}
+
+fn assert_camel(json: &str, expected: &[&str]) {
+    let mut found = Vec::new();
+    keys(&serde_json::from_str(json).unwrap(), &mut found);
+    assert!(found.iter().all(|key| !key.contains('_')), "snake_case keys left in {:?}", found);
+    for key in expected {
+        assert!(found.iter().any(|found| found == key), "no {} in {:?}", key, found);
+    }
+}
+
+#[test]
+fn nested_keys_are_camel_case() {
+    let outline = outline();
+    assert!(outline.outline[0].page_label.is_some() && !outline.warnings.is_empty());
+    let json = to_json(&outline, KeyStyle::Camel).unwrap();
+    assert_camel(&json, &[
+        "pageLabel", "documentId", "extractorVersion", "titleCandidates", "totalMs", "firstPage",
+    ]);
+    // Warning codes are values, not keys
+    assert!(json.contains("\"malformed_xmp\""));
+    // Stats are written but never read back
+    let read: Outline = from_json(json.as_bytes()).unwrap();
+    let written = Outline { stats: None, ..outline };
+    assert_eq!(to_json(&read, KeyStyle::Snake).unwrap(), to_json(&written, KeyStyle::Snake).unwrap());
+}
+
+#[test]
+fn the_batch_summary_is_camel_case() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-key-style-{}", std::process::id()));
+    let (input, output) = (dir.join("input"), dir.join("output"));
+    std::fs::create_dir_all(&input).unwrap();
+    std::fs::write(input.join("report.pdf"), common::numbered_report()).unwrap();
+    std::fs::write(input.join("broken.pdf"), b"%PDF-1.7\n1 0 obj <<").unwrap();
+    let summary = dir.join("summary.json");
+    Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+        .arg("--input").arg(&input)
+        .arg("--output").arg(&output)
+        .arg("--summary").arg(&summary)
+        .args(["--key-style", "camel"])
+        .output()
+        .unwrap();
+
+    assert_camel(&std::fs::read_to_string(&summary).unwrap(), &["noContent", "totalMs"]);
+    assert_camel(&std::fs::read_to_string(output.join("report.json")).unwrap(), &["documentId", "qualityBreakdown", "tocAgreement"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,49 @@
+// A line opening with a letter and a period is a lettered heading only when
+// the letter continues a sequence or introduces title-case text, and never
+// when the rest reads as a sentence: initials, "In." and list markers
+// inside prose stay body text.
+
+use adobe1a::config::ExtractorConfig;
+use adobe1a::{analyze_text, TextOptions};
+
+const BODY: &str = "The harbour board met to review the year's work on the quay walls and the channel.";
+
+fn headings(lines: &[&str]) -> Vec<String> {
+    let mut text = format!("Harbour Development Plan\n\n{}\n\n", BODY);
+    for line in lines {
+        text.push_str(&format!("{}\n\n{}\n\n", line, BODY));
+    }
+    let config = ExtractorConfig { page_heading_limit: 0, ..ExtractorConfig::default() };
+    analyze_text(&text, TextOptions { config, ..TextOptions::default() }).outline.into_iter()
+        .map(|heading| heading.text)
+        .collect()
+}
+
+#[test]
+fn prose_opening_with_a_letter_is_not_a_heading() {
+    for line in [
+        "J. Smith reported that the dredging had finished two weeks ahead of the plan.",
+        "J. Smith reported on the dredging",
+        "In. summary, the works stayed within the budget the board approved.",
+        "Be. advised that the quay will close for repairs in the spring.",
+        "e) the pilot station, which the board agreed to rebuild next year",
+        "A. few of the buoys were replaced after the winter storms damaged them.",
+        "M. K. Jones and the harbour master inspected the channel in March.",
+    ] {
+        assert_eq!(headings(&[line]), Vec::<String>::new(), "{:?}", line);
+    }
+}
+
+#[test]
+fn lettered_headings_are_kept() {
+    for (lines, expected) in [
+        // Title-case text after the letter
+        (&["A. Scope of Work"][..], &["A. Scope of Work"][..]),
+        (&["B) Funding Model"], &["B) Funding Model"]),
+        // A sequence carries letters whose text alone wouldn't do
+        (&["a. Quay Walls", "b. Channel dredging", "c. Pilot station"], &["a. Quay Walls", "b. Channel dredging", "c. Pilot station"]),
+        (&["A. Scope of Work", "B. Costs and timing"], &["A. Scope of Work", "B. Costs and timing"]),
+    ] {
+        assert_eq!(headings(lines), expected, "{:?}", lines);
+    }
+}
@@ -0,0 +1,80 @@
+// Level overrides turn the numbered report's hierarchy upside down: its
+// subsections become H1s and its sections H2s, while headings no rule
+// matches keep the levels detection gave them.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::levels::LevelOverrides;
+use adobe1a::Extractor;
+
+const INVERTED: &str = r#"[
+    { "pattern": "^\\d+\\.\\d+ ", "level": "H1" },
+    { "pattern": "^\\d+\\. ", "level": "H2" },
+    { "pattern": "^\\d", "level": "H4" }
+]"#;
+
+// Each numbered heading's level once overridden; the rest keep theirs
+const EXPECTED: &[(&str, &str)] = &[
+    ("1. Introduction", "H2"),
+    ("1.1 Background", "H1"),
+    ("2. Methods", "H2"),
+    ("2.1 Data Collection", "H1"),
+    // Neither of the first two rules matches, so the last one does
+    ("2.1.1 Regional Surveys", "H4"),
+    ("2.1.2 Interviews", "H4"),
+    ("3. Results", "H2"),
+    ("3.1 Revenue", "H1"),
+    ("3.2 Staffing", "H1"),
+    ("4. Recommendations", "H2"),
+];
+
+#[test]
+fn overrides_invert_the_detected_levels() {
+    let path = std::env::temp_dir().join(format!("adobe1a-level-overrides-{}.json", std::process::id()));
+    std::fs::write(&path, INVERTED).unwrap();
+    let overrides = LevelOverrides::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let pdf = common::numbered_report();
+    for engine in [Engine::Text, Engine::Font] {
+        if !engine.is_built() {
+            continue;
+        }
+        let extract = |overrides: LevelOverrides| {
+            Extractor::builder().engine(engine).include_raw_scores(true).level_overrides(overrides)
+                .build().unwrap().extract_bytes(&pdf).unwrap().outline
+        };
+        let detected = extract(LevelOverrides::default());
+        let inverted = extract(overrides.clone());
+        assert_eq!(detected.len(), inverted.len());
+        for (before, after) in detected.iter().zip(&inverted) {
+            let expected = match EXPECTED.iter().find(|(text, _)| *text == before.text) {
+                Some((_, level)) => level,
+                None => before.level.as_str(),
+            };
+            assert_eq!((after.text.as_str(), after.level.as_str()), (before.text.as_str(), expected), "{:?}", engine);
+            // Where a rule matched, the level detection gave is kept
+            let overridden = before.text.starts_with(|c: char| c.is_ascii_digit());
+            assert_eq!(after.detected_level.as_deref(), overridden.then_some(before.level.as_str()));
+        }
+        assert!(inverted.iter().any(|h| h.text == "1.1 Background"));
+    }
+}
+
+#[test]
+fn invalid_rules_are_reported_when_loaded() {
+    let path = std::env::temp_dir().join(format!("adobe1a-bad-level-overrides-{}.json", std::process::id()));
+    let load = |rules: &str| {
+        std::fs::write(&path, rules).unwrap();
+        let error = LevelOverrides::load(&path).map(|_| ()).map_err(|e| format!("{:#}", e));
+        std::fs::remove_file(&path).unwrap();
+        error.unwrap_err()
+    };
+    let error = load(r#"[{ "pattern": "^PART", "level": "H1" }, { "pattern": "^(\\d+", "level": "H2" }]"#);
+    assert!(error.contains("rule 2: invalid pattern `^(\\d+`"), "{}", error);
+    let error = load(r#"[{ "pattern": "^PART", "level": "H7" }]"#);
+    assert!(error.ends_with("rule 1: `H7` isn't a heading level from H1 to H4"), "{}", error);
+    let error = load(r#"[{ "pattern": "^PART", "depth": 1 }]"#);
+    assert!(error.contains("unknown field `depth`"), "{}", error);
+}
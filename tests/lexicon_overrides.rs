@@ -0,0 +1,88 @@
+// A lexicon file changes the lists the heuristics match against: a word
+// added to `h1_indicators` makes the headings containing it H1s, and
+// `replace` drops the built-in entries.
+
+mod common;
+
+use std::path::PathBuf;
+use std::process::Command;
+use adobe1a::config::ExtractorConfig;
+use adobe1a::lexicon::Lexicon;
+use adobe1a::{analyze_text, Outline, TextOptions};
+
+const TEXT: &str = "\
+Harbour Development Plan
+
+Prepared by the harbour board for the annual meeting of the port users.
+
+Project Background
+
+The harbour board met to review the year's work on the quay walls and the channel.
+
+Project Charter
+
+The board agreed the scope, the budget and the people responsible for each part.
+";
+
+fn lexicon(name: &str, overrides: &str) -> Lexicon {
+    let path: PathBuf = std::env::temp_dir().join(format!("adobe1a-lexicon-{}-{}.json", name, std::process::id()));
+    std::fs::write(&path, overrides).unwrap();
+    let lexicon = Lexicon::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    lexicon
+}
+
+fn levels(lexicon: Lexicon) -> Vec<(String, String)> {
+    let config = ExtractorConfig { lexicon, ..ExtractorConfig::default() };
+    let outline: Outline = analyze_text(TEXT, TextOptions { config, ..TextOptions::default() });
+    outline.outline.into_iter().map(|heading| (heading.text, heading.level)).collect()
+}
+
+fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+    expected.iter().map(|&(text, level)| (text.to_string(), level.to_string())).collect()
+}
+
+#[test]
+fn an_extra_h1_indicator_raises_its_headings() {
+    assert_eq!(levels(Lexicon::default()), pairs(&[("Project Background", "H1"), ("Project Charter", "H2")]));
+    let extended = lexicon("extend", r#"{ "h1_indicators": { "extend": ["Charter"] } }"#);
+    assert!(extended.h1_indicators.contains(&"charter".to_string()));
+    assert_eq!(levels(extended), pairs(&[("Project Background", "H1"), ("Project Charter", "H1")]));
+}
+
+#[test]
+fn replace_drops_the_built_in_entries() {
+    let replaced = lexicon("replace", r#"{ "h1_indicators": { "replace": ["charter"] } }"#);
+    assert_eq!(replaced.h1_indicators, ["charter"]);
+    assert_eq!(levels(replaced), pairs(&[("Project Background", "H2"), ("Project Charter", "H1")]));
+}
+
+#[test]
+fn the_cli_reads_a_lexicon_file() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-lexicon-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (input, lexicon, output) = (dir.join("plan.txt"), dir.join("lexicon.json"), dir.join("plan.json"));
+    std::fs::write(&input, TEXT).unwrap();
+    std::fs::write(&lexicon, r#"{ "h1_indicators": { "extend": ["charter"] } }"#).unwrap();
+    let status = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+        .arg("--input").arg(&input)
+        .arg("--output").arg(&output)
+        .arg("--lexicon").arg(&lexicon)
+        .args(["--input-format", "text"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let outline: serde_json::Value = serde_json::from_slice(&std::fs::read(&output).unwrap()).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let levels: Vec<&str> = outline["outline"].as_array().unwrap().iter().map(|h| h["level"].as_str().unwrap()).collect();
+    assert_eq!(levels, ["H1", "H1"]);
+}
+
+#[test]
+fn an_unknown_list_is_an_error() {
+    let path = std::env::temp_dir().join(format!("adobe1a-lexicon-unknown-{}.json", std::process::id()));
+    std::fs::write(&path, r#"{ "h5_indicators": { "extend": ["annex"] } }"#).unwrap();
+    let error = Lexicon::load(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert!(format!("{:#}", error).contains("h5_indicators"), "{:#}", error);
+}
@@ -0,0 +1,67 @@
+// A heading that an internal link or a bookmark lands on is marked
+// `referenced` and gains `link_bonus` confidence. A link to a position lands
+// on the first heading at or a little below it; a link to a whole page lands
+// on the page's first heading.
+
+mod common;
+
+use adobe1a::{config::ExtractorConfig, extract_outline_from_bytes, timing::Stats, Outline};
+use common::pdf_builder::{self, Page, PdfBuilder};
+use lopdf::{dictionary, Dictionary, Object};
+
+// Three pages of numbered sections, the first holding links into the others.
+// "2. Methods" is at the top of page 2 and "2.1 Sampling" about 480pt up it.
+fn handbook() -> Vec<u8> {
+    let mut doc = PdfBuilder::new()
+        .page(Page::new().title("Project Handbook").heading(1, "1. Introduction").body(2))
+        .page(Page::new().heading(1, "2. Methods").body(15).heading(2, "2.1 Sampling").body(10))
+        .page(Page::new().heading(1, "3. Results").body(2))
+        .document();
+    let pages: Vec<_> = doc.get_pages().into_values().collect();
+
+    let link = |dest: Object| dictionary! {
+        "Type" => "Annot", "Subtype" => "Link", "Rect" => vec![72.into(), 560.into(), 300.into(), 572.into()], "Dest" => dest,
+    };
+    let links: Vec<Dictionary> = vec![
+        // Just above "2. Methods", and to the foot of page 2, below any heading
+        link(vec![pages[1].into(), "XYZ".into(), 0.into(), 742.into(), 0.into()].into()),
+        link(vec![pages[1].into(), "XYZ".into(), 0.into(), 120.into(), Object::Null].into()),
+        // Page 3 as a whole
+        link(vec![pages[2].into(), "Fit".into()].into()),
+        // Through a GoTo action and a named destination
+        dictionary! {
+            "Type" => "Annot", "Subtype" => "Link", "Rect" => vec![72.into(), 540.into(), 300.into(), 552.into()],
+            "A" => dictionary! { "S" => "GoTo", "D" => Object::string_literal("sampling") },
+        },
+    ];
+    let annots: Vec<Object> = links.into_iter().map(|link| doc.add_object(link).into()).collect();
+    doc.get_dictionary_mut(pages[0]).unwrap().set("Annots", annots);
+    let root = doc.trailer.get(b"Root").and_then(Object::as_reference).unwrap();
+    let dests = dictionary! { "sampling" => vec![pages[1].into(), "XYZ".into(), 0.into(), 495.into(), 0.into()] };
+    doc.get_dictionary_mut(root).unwrap().set("Dests", dests);
+    pdf_builder::save(&mut doc)
+}
+
+fn extract(pdf: &[u8], link_bonus: f64) -> Outline {
+    let config = ExtractorConfig { link_bonus, ..ExtractorConfig::default() };
+    extract_outline_from_bytes(pdf, "handbook", &config, &mut Stats::new()).unwrap()
+}
+
+#[test]
+fn linked_headings_are_marked_referenced() {
+    let outline = extract(&handbook(), 0.1);
+    let referenced: Vec<_> = outline.outline.iter().filter(|h| h.referenced).map(|h| h.text.as_str()).collect();
+    assert_eq!(referenced, ["2. Methods", "2.1 Sampling", "3. Results"]);
+    assert!(outline.outline.iter().any(|h| h.text == "1. Introduction"));
+}
+
+#[test]
+fn linked_headings_gain_the_link_bonus() {
+    let pdf = handbook();
+    let (outline, unboosted) = (extract(&pdf, 0.1), extract(&pdf, 0.0));
+    assert_eq!(outline.outline.len(), unboosted.outline.len());
+    for (heading, unboosted) in outline.outline.iter().zip(&unboosted.outline) {
+        let bonus = if heading.referenced { (unboosted.confidence + 0.1).min(1.0) } else { unboosted.confidence };
+        assert_eq!(heading.confidence, bonus, "{}", heading.text);
+    }
+}
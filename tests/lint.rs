@@ -0,0 +1,103 @@
+// `lint` on hand-written outline files, one broken in each way a rule
+// checks for, and the `lint` command's exit status on them.
+
+use std::process::Command;
+use adobe1a::lint::{lint, LintOptions, Severity};
+
+// The (severity, rule, heading) of each finding in `json`, an outline whose
+// headings are given as JSON objects
+fn findings(headings: &str, options: &LintOptions) -> Vec<(Severity, &'static str, Option<usize>)> {
+    let json = format!(r#"{{"title": "Plan", "page_count": 4, "outline": [{}]}}"#, headings);
+    lint(json.as_bytes(), options).into_iter().map(|f| (f.severity, f.rule, f.heading)).collect()
+}
+
+fn default_findings(headings: &str) -> Vec<(Severity, &'static str, Option<usize>)> {
+    findings(headings, &LintOptions::default())
+}
+
+const SCOPE: &str = r#"{"level": "H1", "text": "Scope", "page": 1}"#;
+
+#[test]
+fn a_sound_outline_has_no_findings() {
+    let headings = format!(r#"{}, {{"level": "H2", "text": "Budget", "page": 2, "confidence": 0.7}},
+        {{"level": "H1", "text": "Timeline", "page": 4}}"#, SCOPE);
+    assert_eq!(default_findings(&headings), []);
+}
+
+#[test]
+fn schema() {
+    for json in [&b"{\"title\": \"Plan\", \"outline\": [{\"level\": \"H1\"}]}"[..], b"{\"title\": \"Plan\"", b"[]"] {
+        let found = lint(json, &LintOptions::default());
+        assert_eq!(found.len(), 1, "{:?}", found);
+        assert_eq!((found[0].severity, found[0].rule, found[0].heading), (Severity::Error, "schema", None));
+    }
+}
+
+#[test]
+fn page_order() {
+    let headings = format!(r#"{{"level": "H1", "text": "Budget", "page": 3}}, {}"#, SCOPE);
+    assert_eq!(default_findings(&headings), [(Severity::Warning, "page_order", Some(2))]);
+}
+
+#[test]
+fn empty_text() {
+    let headings = format!(r#"{}, {{"level": "H1", "text": " \t", "page": 1}}"#, SCOPE);
+    assert_eq!(default_findings(&headings), [(Severity::Error, "empty_text", Some(2))]);
+}
+
+#[test]
+fn level_jump() {
+    let headings = format!(r#"{}, {{"level": "H3", "text": "Costs", "page": 1}}"#, SCOPE);
+    assert_eq!(default_findings(&headings), [(Severity::Warning, "level_jump", Some(2))]);
+    let strict = LintOptions { level_jumps: Severity::Error };
+    assert_eq!(findings(&headings, &strict), [(Severity::Error, "level_jump", Some(2))]);
+    // An outline can't open below H1 either
+    assert_eq!(default_findings(r#"{"level": "H2", "text": "Costs", "page": 1}"#), [(Severity::Warning, "level_jump", Some(1))]);
+}
+
+#[test]
+fn level() {
+    let headings = format!(r#"{}, {{"level": "Heading", "text": "Costs", "page": 1}}, {{"level": "H0", "text": "Risks", "page": 1}}"#, SCOPE);
+    assert_eq!(default_findings(&headings), [(Severity::Error, "level", Some(2)), (Severity::Error, "level", Some(3))]);
+}
+
+#[test]
+fn duplicate() {
+    // Case and spacing don't count, but the page does
+    let headings = format!(r#"{}, {{"level": "H1", "text": "  SCOPE", "page": 1}}, {{"level": "H1", "text": "Scope", "page": 2}}"#, SCOPE);
+    assert_eq!(default_findings(&headings), [(Severity::Warning, "duplicate", Some(2))]);
+}
+
+#[test]
+fn page_range() {
+    let headings = format!(r#"{{"level": "H1", "text": "Cover", "page": 0}}, {}, {{"level": "H1", "text": "Index", "page": 5}}"#, SCOPE);
+    assert_eq!(default_findings(&headings), [(Severity::Error, "page_range", Some(1)), (Severity::Error, "page_range", Some(3))]);
+    // Without a page count, only page 0 is out of range
+    let json = format!(r#"{{"title": "Plan", "outline": [{}, {{"level": "H1", "text": "Index", "page": 500}}]}}"#, SCOPE);
+    assert_eq!(lint(json.as_bytes(), &LintOptions::default()), []);
+}
+
+#[test]
+fn confidence() {
+    let headings = format!(r#"{}, {{"level": "H1", "text": "Budget", "page": 1, "confidence": 1.5}},
+        {{"level": "H1", "text": "Timeline", "page": 1, "confidence": -0.1}}"#, SCOPE);
+    assert_eq!(default_findings(&headings), [(Severity::Error, "confidence", Some(2)), (Severity::Error, "confidence", Some(3))]);
+}
+
+#[test]
+fn the_command_fails_only_on_errors() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-lint-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (warned, broken) = (dir.join("warned.json"), dir.join("broken.json"));
+    std::fs::write(&warned, format!(r#"{{"title": "Plan", "outline": [{{"level": "H1", "text": "Budget", "page": 3}}, {}]}}"#, SCOPE)).unwrap();
+    std::fs::write(&broken, format!(r#"{{"title": "Plan", "outline": [{}, {{"level": "H1", "text": "", "page": 1}}]}}"#, SCOPE)).unwrap();
+    let lint = |args: &[&std::path::Path]| Command::new(env!("CARGO_BIN_EXE_adobe1a")).arg("lint").args(args).output().unwrap();
+
+    let output = lint(&[&warned]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("warned.json: warning[page_order]: heading 2: page 1 comes after a heading on page 3"));
+    let output = lint(&[&warned, &broken]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("1 errors, 1 warnings in 2 files"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,24 @@
+// The line heuristics read each line once into a summary that every check
+// shares. 50,000 lines of synthetic text, half of them numbered headings,
+// come out as every heading on its page, in order.
+
+mod common;
+
+use adobe1a::config::ExtractorConfig;
+use adobe1a::{analyze_text, TextOptions};
+
+#[test]
+fn fifty_thousand_lines_give_every_heading() {
+    let text = common::many_headings(1000, 25);
+    assert_eq!(text.lines().count(), 50_000);
+    let config = ExtractorConfig { max_headings: Some(usize::MAX), page_heading_limit: 0, ..ExtractorConfig::default() };
+    let outline = analyze_text(&text, TextOptions { config, ..TextOptions::default() });
+
+    assert_eq!(outline.page_count, Some(1000));
+    assert_eq!(outline.outline.len(), 25_000);
+    for (i, heading) in outline.outline.iter().enumerate() {
+        assert!(heading.text.starts_with(&format!("{}. ", i + 1)), "{}", heading.text);
+        assert!(heading.text.ends_with(" Holdings"), "{}", heading.text);
+        assert_eq!((heading.level.as_str(), heading.page), ("H1", i / 25 + 1));
+    }
+}
@@ -0,0 +1,61 @@
+// A design that sets every heading in lowercase still gets an outline when
+// the headings stand out by size and weight, at a small penalty against the
+// same headings capitalized. A lowercase line at body size and weight is
+// still body text.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::{Extractor, Outline};
+use common::pdf_builder::{Font, Page, PdfBuilder, Style};
+
+const HEADINGS: [&str; 4] = ["introduction", "what we heard", "next steps", "who we met"];
+
+fn report() -> Vec<u8> {
+    let heading = Style::new(Font::HelveticaBold, 18.0);
+    HEADINGS.chunks(2)
+        .fold(PdfBuilder::new(), |report, headings| {
+            let page = headings.iter().fold(Page::new(), |page, text| page.styled_heading(heading, text).body(6));
+            report.page(page.line(Style::BODY, "see the appendix for the full list of sessions").body(3))
+        })
+        .build()
+}
+
+fn extract(engine: Engine) -> Outline {
+    Extractor::builder().engine(engine).build().unwrap().extract_bytes(&report()).unwrap()
+}
+
+#[test]
+fn standout_lowercase_headings_are_found() {
+    // Only their style sets them apart, so `auto` finds them with the font engine
+    if !Engine::Font.is_built() {
+        return;
+    }
+    for engine in [Engine::Auto, Engine::Font] {
+        let outline = extract(engine);
+        let texts: Vec<&str> = outline.outline.iter().map(|heading| heading.text.as_str()).collect();
+        assert_eq!(texts, HEADINGS, "{:?}", engine);
+        assert_eq!(outline.outline.iter().map(|heading| heading.page).collect::<Vec<_>>(), [1, 1, 2, 2]);
+    }
+}
+
+#[test]
+fn lowercase_headings_score_below_capitalized_ones() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let capitalized = |text: &str| {
+        let mut chars = text.chars();
+        chars.next().map(|first| first.to_uppercase().chain(chars).collect::<String>()).unwrap()
+    };
+    let heading = Style::new(Font::HelveticaBold, 18.0);
+    let pdf = PdfBuilder::new()
+        .page(HEADINGS.iter().fold(Page::new(), |page, text| page.styled_heading(heading, &capitalized(text)).body(6)))
+        .build();
+    let upper = Extractor::builder().engine(Engine::Font).build().unwrap().extract_bytes(&pdf).unwrap();
+    let lower = extract(Engine::Font);
+    for (upper, lower) in upper.outline.iter().zip(&lower.outline) {
+        assert_eq!(upper.text, capitalized(&lower.text));
+        assert!(lower.confidence < upper.confidence, "{} {} vs {}", lower.text, lower.confidence, upper.confidence);
+    }
+}
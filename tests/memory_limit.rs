@@ -0,0 +1,96 @@
+// A document over its memory limit is read again in quick mode, its first
+// pages only and without the text under its headings, and the outline says
+// so in a warning. Limits of a few bytes stand in for a document too large
+// for the machine.
+
+mod common;
+
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::timing::Stats;
+use adobe1a::{extract_outline_from_bytes, Extractor, Outline};
+use common::pdf_builder::{Page, PdfBuilder};
+
+fn extract(engine: Engine, max_memory: Option<usize>, pdf: &[u8]) -> Outline {
+    let builder = Extractor::builder().engine(engine).snippet_chars(40);
+    let builder = match max_memory {
+        Some(bytes) => builder.max_memory(bytes),
+        None => builder,
+    };
+    builder.build().unwrap().extract_bytes(pdf).unwrap()
+}
+
+fn headings(outline: &Outline) -> Vec<(&str, usize)> {
+    outline.outline.iter().map(|h| (h.text.as_str(), h.page)).collect()
+}
+
+fn memory_warnings(outline: &Outline) -> usize {
+    outline.warnings.iter().filter(|w| w.code == "memory_limit").count()
+}
+
+#[test]
+fn a_document_over_its_limit_keeps_the_headings_of_its_first_pages() {
+    let pdf = common::long_report();
+    for engine in [Engine::Text, Engine::Font] {
+        if !engine.is_built() {
+            continue;
+        }
+        let full = extract(engine, None, &pdf);
+        assert!(full.outline.iter().any(|h| h.text == "20. Appendices"), "{:?}: {:?}", engine, headings(&full));
+        assert!(full.outline.iter().any(|h| h.snippet.is_some()));
+
+        let limited = extract(engine, Some(1), &pdf);
+        let first_pages: Vec<(&str, usize)> = headings(&full).into_iter().filter(|&(_, page)| page <= 15).collect();
+        assert_eq!(headings(&limited), first_pages, "{:?}", engine);
+        assert!(limited.partial);
+        assert!(limited.outline.iter().all(|h| h.snippet.is_none()));
+        assert_eq!(memory_warnings(&limited), 1, "{:?}", limited.warnings);
+        assert_eq!(memory_warnings(&full), 0);
+    }
+}
+
+#[test]
+fn a_generous_limit_changes_nothing() {
+    let pdf = common::numbered_report();
+    for engine in [Engine::Text, Engine::Font] {
+        if !engine.is_built() {
+            continue;
+        }
+        let full = serde_json::to_value(extract(engine, None, &pdf)).unwrap();
+        let limited = serde_json::to_value(extract(engine, Some(1 << 30), &pdf)).unwrap();
+        assert_eq!(full, limited, "{:?}", engine);
+    }
+}
+
+#[test]
+fn the_peak_is_recorded_in_the_stats() {
+    let pdf = common::numbered_report();
+    let mut stats = Stats::new();
+    extract_outline_from_bytes(&pdf, "report", &ExtractorConfig::default(), &mut stats).unwrap();
+    assert!(stats.peak_memory() > 0);
+    assert_eq!(serde_json::to_value(&stats).unwrap()["peak_memory_bytes"], stats.peak_memory());
+}
+
+// Pages are read one after another here, so the peak is one page's buffers:
+// a document of many pages of the same length peaks no higher than one of
+// them
+#[test]
+fn the_peak_follows_the_largest_page_not_the_document() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let pages = |count: usize| {
+        (1..=count).fold(PdfBuilder::new(), |builder, page| {
+            builder.page(Page::new().heading(1, &format!("{:03}. Section", page)).body(40))
+        }).build()
+    };
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    let config = ExtractorConfig { engine: Engine::Font, ..ExtractorConfig::default() };
+    let peak = |pdf: &[u8]| pool.install(|| {
+        let mut stats = Stats::new();
+        extract_outline_from_bytes(pdf, "pages", &config, &mut stats).unwrap();
+        stats.peak_memory()
+    });
+    let (one, many) = (peak(&pages(1)), peak(&pages(200)));
+    assert!(one > 0);
+    assert_eq!(many, one);
+}
@@ -0,0 +1,108 @@
+// `merge` outlines volumes read in order as one document: each volume is an
+// H1 named after its title with its own headings a level down, pages count
+// on through the volumes, and a heading every volume repeats ("Foreword")
+// is kept in each. `--format` shapes the output as for `extract`.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use adobe1a::{Extractor, Outline};
+use common::pdf_builder::{Page, PdfBuilder};
+use serde_json::Value;
+
+fn part_one() -> Vec<u8> {
+    PdfBuilder::new()
+        .page(Page::new().title("Harbour Works Specification Part One").body(2).heading(1, "Foreword").body(4))
+        .page(Page::new().heading(1, "1. Scope of the Works").body(6))
+        .page(Page::new().heading(2, "1.1 Materials and Supply").body(6))
+        .build()
+}
+
+fn part_two() -> Vec<u8> {
+    PdfBuilder::new()
+        .page(Page::new().title("Harbour Works Specification Part Two").body(2).heading(1, "Foreword").body(4))
+        .page(Page::new().heading(1, "2. Testing and Acceptance").body(6))
+        .build()
+}
+
+struct Volumes {
+    dir: PathBuf,
+}
+
+impl Volumes {
+    fn new(name: &str) -> Volumes {
+        let dir = std::env::temp_dir().join(format!("adobe1a-merge-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("part-one.pdf"), part_one()).unwrap();
+        std::fs::write(dir.join("part-two.pdf"), part_two()).unwrap();
+        Volumes { dir }
+    }
+
+    // Merges the two volumes with `args`, returning the written JSON
+    fn merge(&self, args: &[&str]) -> Value {
+        let output = self.dir.join("merged.json");
+        let run = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+            .arg("merge")
+            .arg(&output)
+            .arg(self.dir.join("part-one.pdf"))
+            .arg(self.dir.join("part-two.pdf"))
+            .args(args)
+            .env_remove("ADOBE1A_FORMAT")
+            .output()
+            .unwrap();
+        assert!(run.status.success(), "{}", String::from_utf8_lossy(&run.stderr));
+        serde_json::from_slice(&std::fs::read(&output).unwrap()).unwrap()
+    }
+}
+
+impl Drop for Volumes {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn extract(path: &Path) -> Outline {
+    Extractor::default().extract_path(path).unwrap()
+}
+
+#[test]
+fn volumes_nest_under_their_titles_with_pages_counting_on() {
+    let volumes = Volumes::new("full");
+    let merged = Outline::from_json_str(&volumes.merge(&[]).to_string()).unwrap();
+    let (one, two) = (extract(&volumes.dir.join("part-one.pdf")), extract(&volumes.dir.join("part-two.pdf")));
+    assert_eq!(merged.page_count, Some(5));
+
+    // Each volume's headings, one level down, on pages after the volumes before it
+    let mut expected = Vec::new();
+    for (volume, offset) in [(&one, 0), (&two, 3)] {
+        expected.push(("H1".to_string(), volume.title.clone(), 1 + offset, Some(1)));
+        // A volume's title line is its H1, not listed again under it
+        for heading in volume.outline.iter().filter(|heading| heading.text != volume.title) {
+            let depth = (heading.depth() + 1).min(4);
+            expected.push((format!("H{}", depth), heading.text.clone(), heading.page + offset, Some(heading.page)));
+        }
+    }
+    let found: Vec<_> = merged.outline.iter()
+        .map(|heading| (heading.level.clone(), heading.text.clone(), heading.page, heading.volume_page))
+        .collect();
+    assert_eq!(found, expected);
+
+    let forewords: Vec<usize> = merged.outline.iter().filter(|heading| heading.text == "Foreword").map(|heading| heading.page).collect();
+    assert_eq!(forewords, [1, 4]);
+    assert!(merged.outline.iter().any(|heading| heading.text == "2. Testing and Acceptance" && heading.page == 5));
+}
+
+#[test]
+fn the_format_flag_applies_to_the_merged_outline() {
+    let volumes = Volumes::new("format");
+    let merged = volumes.merge(&["--format", "adobe1a"]);
+    let mut keys: Vec<&str> = merged.as_object().unwrap().keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, ["outline", "title"]);
+    let outline = merged["outline"].as_array().unwrap();
+    assert!(outline.iter().all(|heading| heading.as_object().unwrap().len() == 3));
+    let forewords = outline.iter().filter(|heading| heading["text"] == "Foreword").count();
+    assert_eq!(forewords, 2);
+    assert!(volumes.merge(&[])["documents"].is_array());
+}
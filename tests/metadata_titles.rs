@@ -0,0 +1,64 @@
+// A metadata title that fails the plausibility check, such as a word
+// processor's "Document1", gives way to the first page's title, and is still
+// listed among the title candidates.
+
+mod common;
+
+use adobe1a::titles::{metadata_title_score, PLAUSIBLE};
+use adobe1a::{config::ExtractorConfig, extract_outline_from_bytes, timing::Stats, Outline, TitleSource};
+use common::pdf_builder::{Font, Page, PdfBuilder, Style};
+
+fn extract(info_title: &str) -> Outline {
+    let pdf = PdfBuilder::new()
+        .page(Page::new().styled_heading(Style::new(Font::Helvetica, 24.0), "Quarterly Budget Review"))
+        .info_title(info_title)
+        .build();
+    let config = ExtractorConfig { quick: Some(15), title_candidates: true, ..ExtractorConfig::default() };
+    extract_outline_from_bytes(&pdf, "budget", &config, &mut Stats::new()).unwrap()
+}
+
+#[test]
+fn a_placeholder_metadata_title_gives_way_to_the_first_page() {
+    let outline = extract("Document1");
+    assert_eq!((outline.title.as_str(), outline.title_source), ("Quarterly Budget Review", TitleSource::Content));
+    assert!(outline.title_candidates.iter()
+        .any(|candidate| candidate.text == "Document1" && candidate.source == "metadata"));
+}
+
+#[test]
+fn a_plausible_metadata_title_is_used() {
+    let outline = extract("Quarterly Budget Review 2024");
+    assert_eq!((outline.title.as_str(), outline.title_source), ("Quarterly Budget Review 2024", TitleSource::Metadata));
+}
+
+#[test]
+fn placeholders_and_file_names_are_not_trusted() {
+    let opening = "ANNUAL REPORT\n2023–24\nPrepared for the Board of Directors\nContents";
+    for (title, trusted) in [
+        // Placeholders and generator leftovers
+        ("", false),
+        ("Untitled", false),
+        ("untitled-1", false),
+        ("Document1", false),
+        ("Slide 1", false),
+        ("PowerPoint Presentation", false),
+        ("Microsoft Word - Annual Report.docx", false),
+        ("annual_report_FINAL_v3.pdf", false),
+        ("C:\\Users\\jsmith\\Desktop\\report.doc", false),
+        ("IMG_0042", false),
+        ("2023-11-04", false),
+        ("report_final", false),
+        // A lone word is trusted only when the opening pages show it
+        ("Overview", false),
+        ("Report", true),
+        // Real titles, found on the opening pages or not
+        ("Annual Report 2023–24", true),
+        ("Annual report", true),
+        ("Guidelines for Grant Applications", true),
+    ] {
+        assert_eq!(metadata_title_score(title, opening) >= PLAUSIBLE, trusted, "{:?}", title);
+    }
+    // Finding the title on the opening pages raises the score
+    assert!(metadata_title_score("Annual Report 2023–24", opening)
+        > metadata_title_score("Guidelines for Grant Applications", opening));
+}
@@ -0,0 +1,38 @@
+// A page pdf-extract returns no text for, as it does for a font encoding it
+// doesn't support, is named in a `pages_missing_text` warning. With the
+// `auto` engine, the font engine reads those pages instead.
+
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::{extract_outline, timing::Stats, Outline};
+
+// Page 2 of this fixture is set in a composite font without a ToUnicode map
+fn extract(engine: Engine) -> Outline {
+    let config = ExtractorConfig { engine, ..ExtractorConfig::default() };
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/hybrid-fonts.pdf");
+    extract_outline(path.as_ref(), &config, &mut Stats::new()).unwrap()
+}
+
+fn headings(outline: &Outline) -> Vec<String> {
+    outline.outline.iter().map(|h| format!("{} p{}", h.text, h.page)).collect()
+}
+
+#[test]
+fn the_text_engine_names_pages_it_got_no_text_for() {
+    if !Engine::Text.is_built() {
+        return;
+    }
+    let outline = extract(Engine::Text);
+    assert_eq!(headings(&outline), ["1. Introduction p1", "3. Findings p3"]);
+    let warning = outline.warnings.iter().find(|w| w.code == "pages_missing_text").unwrap();
+    assert!(warning.message.starts_with("pdf-extract returned no text for page 2,"), "{}", warning.message);
+}
+
+#[test]
+fn the_auto_engine_reads_those_pages_with_the_font_engine() {
+    if !Engine::Text.is_built() || !Engine::Font.is_built() {
+        return;
+    }
+    let outline = extract(Engine::Auto);
+    assert_eq!(headings(&outline), ["1. Introduction p1", "2. Methods p2", "2.1 Site Selection p2", "3. Findings p3"]);
+    assert_eq!(outline.engine, ["text", "font"]);
+}
@@ -0,0 +1,34 @@
+// An outline with no headings says whether the pages read held any text,
+// looking only at those pages: a blank page read on its own is a scan
+// needing OCR, whatever the pages after it hold.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::{Extractor, ExtractorBuilder, Outline};
+use common::pdf_builder::{Page, PdfBuilder};
+
+fn extract(builder: ExtractorBuilder, pdf: &[u8]) -> Outline {
+    builder.build().unwrap().extract_bytes(pdf).unwrap()
+}
+
+fn no_text(outline: &Outline) -> Option<&str> {
+    outline.warnings.iter().find(|warning| warning.code == "no_text").map(|warning| warning.message.as_str())
+}
+
+#[test]
+fn only_the_pages_read_are_checked_for_text() {
+    let pdf = PdfBuilder::new()
+        .page(Page::new())
+        .page(Page::new().heading(1, "1. Introduction").body(20).heading(1, "2. Scope").body(20))
+        .build();
+    for engine in [Engine::Text, Engine::Font].into_iter().filter(|engine| engine.is_built()) {
+        let first = extract(Extractor::builder().engine(engine).pages("1".parse().unwrap()), &pdf);
+        assert!(first.outline.is_empty());
+        assert_eq!(no_text(&first), Some("the pages read hold no text; a scan needs OCR first"), "{:?}", engine);
+
+        let whole = extract(Extractor::builder().engine(engine), &pdf);
+        assert!(!whole.outline.is_empty());
+        assert_eq!(no_text(&whole), None);
+    }
+}
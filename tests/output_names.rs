@@ -0,0 +1,70 @@
+// When `--output` is a directory, each input's output is named after its
+// stem. Inputs from different directories sharing a stem, in any case, get a
+// short hash of their path appended so neither overwrites the other.
+
+mod common;
+
+use std::process::Command;
+
+#[test]
+fn a_single_file_into_a_directory_is_named_after_its_stem() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-output-names-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("annual-report.pdf");
+    std::fs::write(&input, common::numbered_report()).unwrap();
+    let output = dir.join("out");
+    let run = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+            .arg("--input").arg(&input)
+            .arg("--output").arg(format!("{}/", output.display()))
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+
+    let first = run(&[]);
+    assert!(first.status.success());
+    let expected = output.join("annual-report.json");
+    assert!(String::from_utf8_lossy(&first.stdout).contains(&expected.display().to_string()));
+    assert!(expected.exists());
+
+    let refused = run(&[]);
+    assert!(!refused.status.success());
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("already exists; pass --force to overwrite it"));
+    assert!(run(&["--force"]).status.success());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn shared_stems_get_a_hash_of_their_path() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-stem-collisions-{}", std::process::id()));
+    let inputs = [dir.join("north").join("report.pdf"), dir.join("south").join("Report.pdf"), dir.join("south").join("memo.pdf")];
+    for input in &inputs {
+        std::fs::create_dir_all(input.parent().unwrap()).unwrap();
+        std::fs::write(input, common::numbered_report()).unwrap();
+    }
+    let list = dir.join("inputs.txt");
+    let paths: Vec<String> = inputs.iter().map(|input| input.display().to_string()).collect();
+    std::fs::write(&list, paths.join("\n")).unwrap();
+    let output = dir.join("out");
+    let status = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+        .arg("--input-list").arg(&list)
+        .arg("--output").arg(&output)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let mut names: Vec<String> = std::fs::read_dir(&output).unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort_unstable();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(names.len(), 3, "{:?}", names);
+    assert!(names.contains(&"memo.json".to_string()), "{:?}", names);
+    for stem in ["Report", "report"] {
+        let hash = names.iter()
+            .find_map(|name| name.strip_prefix(&format!("{}-", stem))?.strip_suffix(".json"))
+            .unwrap_or_else(|| panic!("no {} output in {:?}", stem, names));
+        assert!(hash.len() == 8 && hash.chars().all(|c| c.is_ascii_hexdigit()), "{}", hash);
+    }
+}
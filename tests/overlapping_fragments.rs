@@ -0,0 +1,45 @@
+// pdf-extract can emit a line twice: as overlapping fragments differing in
+// whitespace or case, or on both sides of a page break. Either way the
+// heading is listed once, on the page where it first appears.
+
+use adobe1a::config::ExtractorConfig;
+use adobe1a::{analyze_text, Outline, TextOptions};
+
+const BODY: &str = "The harbour board met to review the year's work on the quay walls and the channel.";
+
+fn extract(pages: &[&[&str]]) -> Outline {
+    let text: Vec<String> = pages.iter().map(|lines| lines.join("\n")).collect();
+    let config = ExtractorConfig { page_heading_limit: 0, ..ExtractorConfig::default() };
+    analyze_text(&text.join("\n\u{c}"), TextOptions { config, ..TextOptions::default() })
+}
+
+fn headings(outline: &Outline) -> Vec<(&str, usize)> {
+    outline.outline.iter().map(|heading| (heading.text.as_str(), heading.page)).collect()
+}
+
+#[test]
+fn fragments_of_one_line_are_one_heading() {
+    for repeat in ["2.  Methods", " 2. Methods ", "2.\tMethods", "2. METHODS"] {
+        let outline = extract(&[&["Harbour Plan", BODY, "1. Scope", BODY, "2. Methods", repeat, BODY, BODY]]);
+        assert_eq!(headings(&outline), [("1. Scope", 1), ("2. Methods", 1)], "{:?}", repeat);
+    }
+}
+
+#[test]
+fn a_line_repeated_across_a_page_break_is_one_heading() {
+    let outline = extract(&[
+        &["Harbour Plan", BODY, "1. Scope", BODY, BODY, "2. Methods"],
+        &["2. Methods", BODY, BODY, "3. Results", BODY],
+    ]);
+    assert_eq!(headings(&outline), [("1. Scope", 1), ("2. Methods", 1), ("3. Results", 2)]);
+}
+
+#[test]
+fn a_heading_at_the_top_of_the_next_page_is_kept() {
+    // Only the same line on both sides of the break is a repeat
+    let outline = extract(&[
+        &["Harbour Plan", BODY, "1. Scope", BODY, BODY],
+        &["2. Methods", BODY, BODY],
+    ]);
+    assert_eq!(headings(&outline), [("1. Scope", 1), ("2. Methods", 2)]);
+}
@@ -0,0 +1,43 @@
+// A directory run leaves outputs that already exist alone unless `--force`
+// is given, counting each as that file's failure, and still processes the
+// files whose outputs are new.
+
+mod common;
+
+use std::process::Command;
+
+#[test]
+fn a_batch_keeps_existing_outputs_without_force() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-overwriting-{}", std::process::id()));
+    let (input, output) = (dir.join("input"), dir.join("output"));
+    for dir in [&input, &output] {
+        std::fs::create_dir_all(dir).unwrap();
+    }
+    std::fs::write(input.join("kept.pdf"), common::numbered_report()).unwrap();
+    std::fs::write(input.join("new.pdf"), common::numbered_report()).unwrap();
+    std::fs::write(output.join("kept.json"), "{}").unwrap();
+    let summary = dir.join("summary.json");
+    let run = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+            .arg("--input").arg(&input)
+            .arg("--output").arg(&output)
+            .arg("--summary").arg(&summary)
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+
+    let refused = run(&[]);
+    assert!(!refused.status.success());
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("kept.json already exists; pass --force to overwrite it"));
+    assert_eq!(std::fs::read_to_string(output.join("kept.json")).unwrap(), "{}");
+    assert!(output.join("new.json").exists());
+    let summary_json: serde_json::Value = serde_json::from_slice(&std::fs::read(&summary).unwrap()).unwrap();
+    let status = |file: &str| summary_json["files"].as_array().unwrap().iter()
+        .find(|f| f["path"].as_str().unwrap().ends_with(file)).unwrap()["status"].clone();
+    assert_eq!((status("kept.pdf"), status("new.pdf")), ("failed".into(), "ok".into()));
+
+    assert!(run(&["--force"]).status.success());
+    assert_ne!(std::fs::read_to_string(output.join("kept.json")).unwrap(), "{}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,62 @@
+// Every heading the text engine finds is on the page it's given: the page
+// whose runs, as lopdf reads them, hold its text. pdf-extract reads each
+// page on its own for this; its text of a whole document has no page
+// breaks to split at.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::{layout, Extractor};
+
+#[test]
+fn text_engine_headings_are_on_their_own_pages() {
+    if !Engine::Text.is_built() {
+        return;
+    }
+    let extractor = Extractor::builder().engine(Engine::Text).build().unwrap();
+    for (name, pdf) in common::fixtures() {
+        let outline = extractor.extract_bytes(&pdf).unwrap();
+        let runs = layout::extract_runs_from_bytes(&pdf).unwrap();
+        let page_text = |page: usize| -> String { runs.iter().filter(|run| run.page == page).map(|run| key(&run.text)).collect() };
+        for heading in &outline.outline {
+            let page = page_text(heading.page);
+            // A chapter number joined to the title opening the next page
+            // starts on its page and ends on the next
+            let words: Vec<&str> = heading.text.split_whitespace().collect();
+            let split = (1..words.len()).any(|k| {
+                page.contains(&key(&words[..k].concat())) && page_text(heading.page + 1).contains(&key(&words[k..].concat()))
+            });
+            assert!(page.contains(&key(&heading.text)) || split,
+                "{}: {:?} isn't on page {}", name, heading.text, heading.page);
+        }
+    }
+}
+
+#[test]
+fn sparse_pages_keep_their_page_numbers() {
+    if !Engine::Text.is_built() {
+        return;
+    }
+    let pdf = common::sparse_pages();
+    let outline = Extractor::builder().engine(Engine::Text).build().unwrap().extract_bytes(&pdf).unwrap();
+    let pages: Vec<(&str, usize)> = outline.outline.iter().map(|h| (h.text.as_str(), h.page)).collect();
+    assert_eq!(pages, [("1. Scope", 2), ("2. Schedule", 2), ("2.1 Dredging", 3), ("3. Budget", 4), ("3.1 Contingency", 4)]);
+    assert!(outline.warnings.is_empty(), "{:?}", outline.warnings);
+}
+
+// The whole document's text, as pdf-extract gives it in one string, has
+// neither form feeds nor a run of blank lines between pages, so splitting
+// it would put every heading on page 1
+#[cfg(feature = "engine-text")]
+#[test]
+fn whole_document_text_has_no_page_breaks() {
+    let text = pdf_extract::extract_text_from_mem(&common::sparse_pages()).unwrap();
+    assert!(!text.contains('\x0C'));
+    assert_eq!(text.trim().split("\n\n\n").count(), 1);
+}
+
+// Letters and digits only, lowercased, as a heading's cleaned-up text and
+// the runs it came from share them
+fn key(text: &str) -> String {
+    text.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
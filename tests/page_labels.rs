@@ -0,0 +1,64 @@
+// Each heading gives the label printed on its page, from the catalog's
+// /PageLabels number tree, beside its physical page number: roman numerals
+// for front matter, a prefix and letters for appendices, and so on. Without
+// page labels there's no `page_label` at all.
+
+mod common;
+
+use adobe1a::{Extractor, Outline};
+use common::pdf_builder::{Page, PdfBuilder};
+
+// `pages` pages, each opening with a numbered heading
+fn pages(pages: usize) -> PdfBuilder {
+    (1..=pages).fold(PdfBuilder::new(), |builder, page| {
+        builder.page(Page::new().heading(1, &format!("{}. Section {}", page, "ABCDEFGHIJKL".chars().nth(page - 1).unwrap())).body(4))
+    })
+}
+
+fn labels(outline: &Outline) -> Vec<(usize, Option<&str>)> {
+    outline.outline.iter().map(|heading| (heading.page, heading.page_label.as_deref())).collect()
+}
+
+fn extract(builder: PdfBuilder) -> Outline {
+    Extractor::builder().build().unwrap().extract_bytes(&builder.build()).unwrap()
+}
+
+#[test]
+fn each_style_prefix_and_start() {
+    let pdf = pages(12)
+        .page_labels(1, Some("r"), None, None)
+        .page_labels(4, Some("D"), None, Some(1))
+        .page_labels(6, Some("A"), Some("A-"), Some(26))
+        .page_labels(8, None, Some("Cover"), None)
+        .page_labels(9, Some("R"), None, Some(1990))
+        .page_labels(11, Some("a"), None, Some(2))
+        .page_labels(12, Some("D"), Some("p. "), Some(40));
+    assert_eq!(labels(&extract(pdf)), [
+        (1, Some("i")), (2, Some("ii")), (3, Some("iii")),
+        (4, Some("1")), (5, Some("2")),
+        (6, Some("A-Z")), (7, Some("A-AA")),
+        (8, Some("Cover")),
+        (9, Some("MCMXC")), (10, Some("MCMXCI")),
+        (11, Some("b")),
+        (12, Some("p. 40")),
+    ]);
+}
+
+#[test]
+fn a_start_below_one_counts_from_one() {
+    let pdf = pages(2).page_labels(1, Some("D"), None, Some(0));
+    assert_eq!(labels(&extract(pdf)), [(1, Some("1")), (2, Some("2"))]);
+}
+
+#[test]
+fn letters_repeat_past_z() {
+    let pdf = pages(2).page_labels(1, Some("a"), None, Some(52));
+    assert_eq!(labels(&extract(pdf)), [(1, Some("zz")), (2, Some("aaa"))]);
+}
+
+#[test]
+fn without_page_labels_there_is_no_label() {
+    let outline = extract(pages(2));
+    assert_eq!(labels(&outline), [(1, None), (2, None)]);
+    assert!(!outline.to_json_string(true).unwrap().contains("page_label"));
+}
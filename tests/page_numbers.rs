@@ -0,0 +1,47 @@
+// Headings never report a page past the end of the document. Plain text is
+// split into pages at form feeds, and text without any is a single page,
+// not pages guessed from runs of blank lines. A PDF's page count is its
+// page tree's.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::{analyze_text, layout, Extractor, TextOptions};
+
+const BODY: &str = "The committee reviewed the figures for each region in turn.";
+
+#[test]
+fn form_feeds_separate_the_pages() {
+    let text = format!("1. Scope\n{BODY}\n\x0C2. Schedule\n{BODY}\n\x0C\x0C3. Budget\n{BODY}\n");
+    let outline = analyze_text(&text, TextOptions::default());
+    let pages: Vec<_> = outline.outline.iter().map(|h| (h.text.as_str(), h.page)).collect();
+    assert_eq!(pages, [("1. Scope", 1), ("2. Schedule", 2), ("3. Budget", 4)]);
+    assert_eq!(outline.page_count, Some(4));
+}
+
+#[test]
+fn text_without_form_feeds_is_one_page() {
+    let text = format!("1. Scope\n{BODY}\n\n\n\n2. Schedule\n{BODY}\n\n\n\n3. Budget\n{BODY}\n");
+    let outline = analyze_text(&text, TextOptions::default());
+    assert_eq!(outline.outline.len(), 3);
+    assert!(outline.outline.iter().all(|heading| heading.page == 1));
+    assert_eq!(outline.page_count, Some(1));
+}
+
+#[test]
+fn no_heading_is_past_the_last_page() {
+    for engine in [Engine::Text, Engine::Font] {
+        if !engine.is_built() {
+            continue;
+        }
+        let extractor = Extractor::builder().engine(engine).build().unwrap();
+        for (name, pdf) in common::fixtures().into_iter().chain([("long-report".to_string(), common::long_report())]) {
+            let outline = extractor.extract_bytes(&pdf).unwrap();
+            let pages = layout::extract_runs_from_bytes(&pdf).unwrap().iter().map(|run| run.page).max().unwrap_or(0);
+            let page_count = outline.page_count.unwrap();
+            assert!(page_count >= pages, "{} ({:?}): {} pages counted, text on page {}", name, engine, page_count, pages);
+            assert!(outline.outline.iter().all(|heading| (1..=page_count).contains(&heading.page)),
+                "{} ({:?}): a heading is past page {}", name, engine, page_count);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// The font engine reads where on the page a line is. Lines in the bands at
+// the top and bottom of the page, running headers and page numbers, are
+// never headings, and a heading at the top of a page whose previous page
+// ended mid-section gains `top_of_page_bonus`.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::{Extractor, ExtractorBuilder, Outline};
+use common::pdf_builder::{Font, Page, PdfBuilder, Style, PAGE_HEIGHT, PAGE_WIDTH};
+
+// Chapters that each fill their page to the foot, under a bold running
+// header and over a bold page number
+fn chapters() -> Vec<u8> {
+    let furniture = Style::new(Font::HelveticaBold, 12.0);
+    let chapters = [("1. Scope", 40), ("2. Funding", 40), ("3. Delivery", 10)];
+    chapters.iter().enumerate()
+        .fold(PdfBuilder::new(), |builder, (i, &(heading, lines))| {
+            builder.page(Page::new()
+                .text_at(furniture, 72.0, PAGE_HEIGHT - 30.0, "Harbour Authority Plan")
+                .text_at(furniture, PAGE_WIDTH / 2.0, 24.0, &format!("Page {}", i + 1))
+                .styled_heading(Style::new(Font::HelveticaBold, 12.0), heading)
+                .body(lines))
+        })
+        .build()
+}
+
+fn extract(builder: ExtractorBuilder) -> Outline {
+    builder.engine(Engine::Font).build().unwrap().extract_bytes(&chapters()).unwrap()
+}
+
+fn texts(outline: &Outline) -> Vec<&str> {
+    outline.outline.iter().map(|heading| heading.text.as_str()).collect()
+}
+
+#[test]
+fn running_headers_and_page_numbers_are_left_out() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    assert_eq!(texts(&extract(Extractor::builder())), ["1. Scope", "2. Funding", "3. Delivery"]);
+    // Without the bands, the furniture is read as text like any other
+    let unbanded = extract(Extractor::builder().margin_band(0.0));
+    assert!(texts(&unbanded).iter().any(|text| text.contains("Harbour") || text.starts_with("Page")), "{:?}", texts(&unbanded));
+}
+
+#[test]
+fn a_heading_continuing_from_a_full_page_gains_the_top_of_page_bonus() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let confidence = |outline: &Outline, text: &str| outline.outline.iter().find(|h| h.text == text).unwrap().confidence;
+    let (with, without) = (extract(Extractor::builder()), extract(Extractor::builder().top_of_page_bonus(0.0)));
+    assert!(confidence(&with, "2. Funding") > confidence(&without, "2. Funding"));
+    assert!(confidence(&with, "3. Delivery") > confidence(&without, "3. Delivery"));
+    // The first page follows nothing
+    assert_eq!(confidence(&with, "1. Scope"), confidence(&without, "1. Scope"));
+}
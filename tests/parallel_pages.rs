@@ -0,0 +1,47 @@
+// The font engine decodes pages in parallel on the rayon pool and puts
+// their candidates back in page order, so its output is the same as when
+// the pages are read one after another on a single thread. The same goes
+// for `layout::extract_runs`.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::{layout, Extractor};
+
+// The outline as JSON, extracted on a pool of `threads`
+fn extract(pdf: &[u8], threads: usize) -> String {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+    let extractor = Extractor::builder()
+        .engine(Engine::Font)
+        .include_sources(true)
+        .include_raw_scores(true)
+        .title_candidates(true)
+        .positions(true)
+        .build()
+        .unwrap();
+    pool.install(|| extractor.extract_bytes(pdf).unwrap().to_json_string(true).unwrap())
+}
+
+#[test]
+fn parallel_pages_give_the_sequential_outline() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let fixtures = common::fixtures().into_iter().chain([("long-report".to_string(), common::long_report())]);
+    for (name, pdf) in fixtures {
+        assert_eq!(extract(&pdf, 8), extract(&pdf, 1), "{}: the parallel outline differs from the sequential one", name);
+    }
+}
+
+#[test]
+fn parallel_runs_come_in_page_order() {
+    let runs = |pdf: &[u8], threads: usize| {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+        let doc = lopdf::Document::load_mem(pdf).unwrap();
+        pool.install(|| serde_json::to_string(&layout::extract_runs(&doc)).unwrap())
+    };
+    let fixtures = common::fixtures().into_iter().chain([("long-report".to_string(), common::long_report())]);
+    for (name, pdf) in fixtures {
+        assert_eq!(runs(&pdf, 8), runs(&pdf, 1), "{}: the parallel runs differ from the sequential ones", name);
+    }
+}
@@ -0,0 +1,112 @@
+// A portfolio's attached PDFs are outlined too. By default each comes after
+// the cover sheet's headings under an H1 named after it, its own headings
+// one level down; `Portfolio::Split` outlines each on its own and
+// `Portfolio::Ignore` only the cover sheet. Attachments that aren't PDFs are
+// skipped with a warning.
+
+mod common;
+
+use std::process::Command;
+use adobe1a::config::{ExtractorConfig, Portfolio};
+use adobe1a::{extract_outline_from_bytes, portfolio, timing::Stats, Outline};
+use common::pdf_builder::{Page, PdfBuilder};
+
+fn budget() -> Vec<u8> {
+    PdfBuilder::new()
+        .page(Page::new().title("Harbour Board Annual Budget").body(2).heading(1, "1. Income").body(3).heading(2, "1.1 Grants").body(3))
+        .page(Page::new().heading(1, "2. Spending").body(4))
+        .build()
+}
+
+// A cover sheet with two PDFs and a note attached
+fn submission() -> Vec<u8> {
+    PdfBuilder::new()
+        .page(Page::new().title("Tender Submission for Harbour Works").body(2).heading(1, "1. Cover Letter").body(4))
+        .attachment("budget.pdf", budget())
+        .attachment("notes.txt", b"Bring two printed copies.".to_vec())
+        .attachment("report.pdf", common::numbered_report())
+        .portfolio()
+        .build()
+}
+
+fn extract(pdf: &[u8], portfolio: Portfolio) -> Outline {
+    let config = ExtractorConfig { portfolio, ..ExtractorConfig::default() };
+    extract_outline_from_bytes(pdf, "submission", &config, &mut Stats::new()).unwrap()
+}
+
+fn headings(outline: &Outline) -> Vec<(String, String, usize)> {
+    outline.outline.iter().map(|heading| (heading.level.clone(), heading.text.clone(), heading.page)).collect()
+}
+
+// `pdf`'s headings one level down, under an H1 named `name`
+fn attached(name: &str, pdf: &[u8]) -> Vec<(String, String, usize)> {
+    let outline = extract(pdf, Portfolio::Combine);
+    let demoted = headings(&outline).into_iter().map(|(level, text, page)| {
+        let depth: usize = level[1..].parse().unwrap();
+        (format!("H{}", depth + 1), text, page)
+    });
+    std::iter::once(("H1".to_string(), name.to_string(), 1)).chain(demoted).collect()
+}
+
+#[test]
+fn attachments_are_combined_after_the_cover_sheet() {
+    let pdf = submission();
+    let cover = extract(&pdf, Portfolio::Ignore);
+    assert!(cover.outline.iter().any(|heading| heading.text == "1. Cover Letter"));
+    assert_eq!(cover.page_count, Some(1));
+
+    let combined = extract(&pdf, Portfolio::Combine);
+    let expected: Vec<_> = headings(&cover).into_iter()
+        .chain(attached("budget.pdf", &budget()))
+        .chain(attached("report.pdf", &common::numbered_report()))
+        .collect();
+    assert_eq!(headings(&combined), expected);
+    assert_eq!(combined.title, cover.title);
+    // Pages count within each attachment
+    assert_eq!(combined.page_count, None);
+
+    let skipped: Vec<_> = combined.warnings.iter().filter(|w| w.code == "portfolio_attachment_skipped").collect();
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].message, "attachment \"notes.txt\" is not a PDF and was skipped");
+}
+
+#[test]
+fn attachments_can_be_outlined_on_their_own() {
+    let config = ExtractorConfig { portfolio: Portfolio::Split, ..ExtractorConfig::default() };
+    let (parts, warnings) = portfolio::extract_parts(&submission(), &config, &mut Stats::new()).unwrap();
+    let names: Vec<_> = parts.iter().map(|part| part.name.as_str()).collect();
+    assert_eq!(names, ["budget.pdf", "report.pdf"]);
+    assert_eq!(parts[0].outline.title, "Harbour Board Annual Budget");
+    assert_eq!(headings(&parts[0].outline), headings(&extract(&budget(), Portfolio::Combine)));
+    let codes: Vec<_> = warnings.iter().map(|w| w.code.as_str()).collect();
+    assert_eq!(codes, ["portfolio_attachment_skipped"]);
+
+    // Attachments without a /Collection don't make a portfolio
+    let (parts, warnings) = portfolio::extract_parts(&common::attachments(), &config, &mut Stats::new()).unwrap();
+    assert!(parts.is_empty() && warnings.is_empty());
+}
+
+#[test]
+fn split_attachments_are_written_next_to_the_output() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-portfolios-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("submission.pdf");
+    std::fs::write(&input, submission()).unwrap();
+    let output = dir.join("out.json");
+    let run = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+        .arg("--input").arg(&input)
+        .arg("--output").arg(&output)
+        .args(["--portfolio", "split"])
+        .output()
+        .unwrap();
+    assert!(run.status.success(), "{}", String::from_utf8_lossy(&run.stderr));
+
+    let read = |name: &str| Outline::from_json_str(&std::fs::read_to_string(dir.join(name)).unwrap()).unwrap();
+    let cover = read("out.json");
+    assert_eq!(cover.title, "Tender Submission for Harbour Works");
+    assert!(cover.outline.iter().all(|heading| heading.text != "budget.pdf" && heading.page == 1));
+    assert_eq!(read("out.budget.json").title, "Harbour Board Annual Budget");
+    assert!(!read("out.report.json").outline.is_empty());
+    assert!(!dir.join("out.notes.json").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
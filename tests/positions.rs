@@ -0,0 +1,90 @@
+// With positions on, each heading gets a `y_fraction`: how far down its
+// page it is, from 0 at the top to 1 at the bottom of the page as
+// displayed. Headings read from the text take the top of the run they start
+// in; headings read from bookmarks take the destination's top.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::{Extractor, Heading};
+use common::pdf_builder::{Page, PdfBuilder};
+use lopdf::{Document, Object};
+
+fn positions(builder: adobe1a::ExtractorBuilder, pdf: &[u8]) -> Vec<Heading> {
+    builder.positions(true).build().unwrap().extract_bytes(pdf).unwrap().outline
+}
+
+fn find<'a>(headings: &'a [Heading], text: &str) -> &'a Heading {
+    headings.iter().find(|heading| heading.text == text).unwrap_or_else(|| panic!("{:?} in {:?}", text, headings))
+}
+
+#[test]
+fn headings_found_in_the_text_land_where_they_are_drawn() {
+    let pdf = common::numbered_report();
+    for engine in [Engine::Text, Engine::Font].into_iter().filter(|engine| engine.is_built()) {
+        let headings = positions(Extractor::builder().engine(engine), &pdf);
+        // The first heading of page 2, just under the running header
+        let methods = find(&headings, "2. Methods").y_fraction.expect("a position");
+        assert!((0.05..0.2).contains(&methods), "{:?}: {}", engine, methods);
+        // Further down the page in reading order
+        for page in headings.chunk_by(|a, b| a.page == b.page) {
+            let ys: Vec<f64> = page.iter().map(|heading| heading.y_fraction.unwrap()).collect();
+            assert!(ys.windows(2).all(|pair| pair[0] < pair[1]), "{:?}: {:?}", engine, ys);
+            assert!(ys.iter().all(|y| (0.0..=1.0).contains(y)));
+        }
+
+        let plain = Extractor::builder().engine(engine).build().unwrap().extract_bytes(&pdf).unwrap();
+        assert!(plain.outline.iter().all(|heading| heading.y_fraction.is_none()));
+    }
+}
+
+// Two pages of headings, each also a bookmark, with page 2 turned a quarter
+// clockwise. The bookmarks open at `destinations`, by title, or show their
+// whole page.
+fn bookmarked(destinations: &[(&str, Vec<Object>)]) -> Vec<u8> {
+    let titles: [(&str, u32); 5] = [("Preface", 1), ("1. Scope", 1), ("2. Terms", 1), ("3. Fees", 2), ("Index", 2)];
+    let mut builder = PdfBuilder::new()
+        .page(Page::new().heading(1, "1. Scope").body(30).heading(1, "2. Terms").body(5))
+        .page(Page::new().heading(1, "3. Fees").body(10));
+    for (title, page) in titles {
+        builder = builder.bookmark(1, title, page as usize);
+    }
+    let mut doc = Document::load_mem(&builder.build()).unwrap();
+    let pages = doc.get_pages();
+    doc.get_dictionary_mut(pages[&2]).unwrap().set("Rotate", 90);
+    for (title, destination) in destinations {
+        let page = titles.iter().find(|(t, _)| t == title).unwrap().1;
+        let item = doc.objects.values_mut()
+            .filter_map(|object| object.as_dict_mut().ok())
+            .find(|dict| dict.get(b"Title").is_ok_and(|t| t.as_str().is_ok_and(|t| t == title.as_bytes())))
+            .unwrap();
+        item.set("Dest", [vec![pages[&page].into()], destination.clone()].concat());
+    }
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn bookmarks_land_at_their_destinations() {
+    let pdf = bookmarked(&[
+        // Above the page: clamped to its top
+        ("Preface", vec!["XYZ".into(), 0.into(), 900.into(), Object::Null]),
+        ("1. Scope", vec!["XYZ".into(), 72.into(), 594.into(), Object::Null]),
+        // On the turned page, its left edge is what's displayed at the top:
+        // 153pt across the 612pt width is a quarter of the way down
+        ("3. Fees", vec!["XYZ".into(), 153.into(), 0.into(), Object::Null]),
+        // Without a left edge there's nothing to place it by
+        ("Index", vec!["XYZ".into(), Object::Null, 500.into(), Object::Null]),
+    ]);
+    let headings = positions(Extractor::builder().quick(10).include_sources(true), &pdf);
+    assert!(headings.iter().all(|heading| heading.sources == ["bookmarks"]));
+    assert_eq!(find(&headings, "Preface").y_fraction, Some(0.0));
+    assert_eq!(find(&headings, "1. Scope").y_fraction, Some(0.25));
+    assert_eq!(find(&headings, "3. Fees").y_fraction, Some(0.25));
+    // A bookmark to the whole page takes its heading's place in the text
+    let terms = find(&headings, "2. Terms").y_fraction.expect("a position from the text");
+    assert!(terms > 0.5, "{}", terms);
+    // Neither the destination nor the text places it
+    assert_eq!(find(&headings, "Index").y_fraction, None);
+}
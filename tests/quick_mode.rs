@@ -0,0 +1,82 @@
+// Quick mode reads only the first pages of a PDF, or its bookmarks when it
+// has any, and marks an outline that may be missing headings as `partial`.
+
+mod common;
+
+use std::process::Command;
+use adobe1a::{Extractor, Outline};
+use common::pdf_builder::{Page, PdfBuilder};
+
+fn extract(pdf: &[u8], quick: Option<usize>) -> Outline {
+    let builder = Extractor::builder().include_sources(true);
+    let builder = match quick {
+        Some(pages) => builder.quick(pages),
+        None => builder,
+    };
+    builder.build().unwrap().extract_bytes(pdf).unwrap()
+}
+
+fn headings(outline: &Outline) -> Vec<(&str, &str, usize)> {
+    outline.outline.iter().map(|heading| (heading.level.as_str(), heading.text.as_str(), heading.page)).collect()
+}
+
+#[test]
+fn only_the_window_is_read() {
+    let pdf = common::long_report();
+    let full = extract(&pdf, None);
+    assert!(!full.partial);
+    assert!(full.outline.iter().any(|heading| heading.page > 5));
+
+    let quick = extract(&pdf, Some(5));
+    assert!(quick.partial);
+    assert_eq!(quick.title, full.title);
+    let window: Vec<_> = headings(&full).into_iter().filter(|&(_, _, page)| page <= 5).collect();
+    assert_eq!(headings(&quick), window);
+    assert!(window.len() >= 5);
+}
+
+#[test]
+fn a_document_inside_the_window_is_complete() {
+    let pdf = common::numbered_report();
+    let quick = extract(&pdf, Some(15));
+    assert!(!quick.partial);
+    assert_eq!(headings(&quick), headings(&extract(&pdf, None)));
+}
+
+#[test]
+fn bookmarks_are_the_whole_outline() {
+    let pdf = (1..=30)
+        .fold(PdfBuilder::new(), |builder, chapter| {
+            let title = format!("Chapter {}", chapter);
+            builder.page(Page::new().heading(1, &title).body(20)).bookmark(1, &title, chapter)
+        })
+        .bookmark(2, "30.1 Index of Terms", 30)
+        .build();
+    let quick = extract(&pdf, Some(5));
+    assert!(!quick.partial);
+    assert_eq!(quick.engine, ["bookmarks"]);
+    assert!(quick.outline.iter().all(|heading| heading.sources == ["bookmarks"]));
+    assert_eq!(quick.outline.len(), 31);
+    assert_eq!(headings(&quick)[29..], [("H1", "Chapter 30", 30), ("H2", "30.1 Index of Terms", 30)]);
+}
+
+#[test]
+fn the_cli_marks_a_partial_output() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-quick-mode-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("report.pdf");
+    std::fs::write(&input, common::long_report()).unwrap();
+    let output = dir.join("report.json");
+    let run = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+        .arg("--input").arg(&input)
+        .arg("--output").arg(&output)
+        .args(["--quick", "--quick-pages", "3"])
+        .output()
+        .unwrap();
+    assert!(run.status.success(), "{}", String::from_utf8_lossy(&run.stderr));
+
+    let outline = Outline::from_json_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+    assert!(outline.partial);
+    assert!(!outline.outline.is_empty() && outline.outline.iter().all(|heading| heading.page <= 3));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
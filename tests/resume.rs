@@ -0,0 +1,55 @@
+// `--resume` skips the inputs whose outputs are up to date, counting them as
+// cached in the summary, and reprocesses the rest: a changed input, and an
+// output a crash left truncated. `--force` reprocesses everything.
+
+mod common;
+
+use std::process::Command;
+
+#[test]
+fn resume_skips_only_up_to_date_outputs() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-resume-{}", std::process::id()));
+    let (input, output) = (dir.join("input"), dir.join("output"));
+    std::fs::create_dir_all(&input).unwrap();
+    // Backdated, so the outputs are newer even where timestamps are coarse
+    let earlier = std::time::SystemTime::now() - std::time::Duration::from_secs(60);
+    for name in ["kept", "changed", "truncated"] {
+        let path = input.join(format!("{}.pdf", name));
+        std::fs::write(&path, common::numbered_report()).unwrap();
+        std::fs::File::options().write(true).open(&path).unwrap().set_modified(earlier).unwrap();
+    }
+    let summary = dir.join("summary.json");
+    let run = |extra: &[&str]| {
+        let status = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+            .arg("--input").arg(&input)
+            .arg("--output").arg(&output)
+            .arg("--summary").arg(&summary)
+            .args(extra)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success());
+        serde_json::from_slice::<serde_json::Value>(&std::fs::read(&summary).unwrap()).unwrap()
+    };
+    let status = |summary: &serde_json::Value, file: &str| summary["files"].as_array().unwrap().iter()
+        .find(|f| f["path"].as_str().unwrap().ends_with(file)).unwrap()["status"].as_str().unwrap().to_string();
+
+    run(&[]);
+    std::fs::write(input.join("changed.pdf"), common::two_page_memo()).unwrap();
+    let complete = std::fs::read(output.join("truncated.json")).unwrap();
+    std::fs::write(output.join("truncated.json"), &complete[..complete.len() / 2]).unwrap();
+
+    let resumed = run(&["--resume"]);
+    assert_eq!(status(&resumed, "kept.pdf"), "cached");
+    assert_eq!(status(&resumed, "changed.pdf"), "ok");
+    assert_eq!(status(&resumed, "truncated.pdf"), "ok");
+    assert_eq!((resumed["totals"]["cached"].as_u64(), resumed["totals"]["ok"].as_u64()), (Some(1), Some(2)));
+    assert_eq!(std::fs::read(output.join("truncated.json")).unwrap(), complete);
+    let changed: serde_json::Value = serde_json::from_slice(&std::fs::read(output.join("changed.json")).unwrap()).unwrap();
+    assert_eq!(changed["document_id"].as_str(), Some(adobe1a::ids::document_id(&common::two_page_memo()).as_str()));
+
+    let forced = run(&["--resume", "--force"]);
+    assert_eq!(forced["totals"]["cached"].as_u64(), Some(0));
+    assert_eq!(forced["totals"]["ok"].as_u64(), Some(3));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,57 @@
+// Roman numerals number headings by case and nesting: uppercase romans are
+// H1, letters and lowercase romans nest under the numbered heading around
+// them. A malformed numeral ("IC") isn't one, and "I." opening a sentence
+// isn't a heading.
+
+use adobe1a::config::ExtractorConfig;
+use adobe1a::{analyze_text, TextOptions};
+
+const BODY: &str = "The harbour board met to review the year's work on the quay walls and the channel.";
+
+// Each line as a heading with a paragraph under it, and the (text, level)
+// of the headings found
+fn levels(lines: &[&str]) -> Vec<(String, String)> {
+    let mut text = format!("Harbour Development Plan\n\n{}\n\n", BODY);
+    for line in lines {
+        text.push_str(&format!("{}\n\n{}\n\n", line, BODY));
+    }
+    let config = ExtractorConfig { page_heading_limit: 0, ..ExtractorConfig::default() };
+    analyze_text(&text, TextOptions { config, ..TextOptions::default() }).outline.into_iter()
+        .map(|heading| (heading.text, heading.level))
+        .collect()
+}
+
+#[test]
+fn numbering_schemes_nest() {
+    for (lines, expected) in [
+        // Letters under a roman numeral, lowercase romans under a letter
+        (&["I. Introduction", "A. Purpose of the Plan", "B. Audience", "i. Port Users", "ii. Residents", "II. Scope of Work"][..],
+            &[("I. Introduction", "H1"), ("A. Purpose of the Plan", "H2"), ("B. Audience", "H2"),
+                ("i. Port Users", "H3"), ("ii. Residents", "H3"), ("II. Scope of Work", "H1")][..]),
+        // Lowercase romans one below uppercase, whatever the case of the text
+        (&["IV. SCOPE OF WORK", "iv. Supporting Details"], &[("IV. SCOPE OF WORK", "H1"), ("iv. Supporting Details", "H2")]),
+        // With nothing enclosing them, letters and lowercase romans are H2
+        (&["A. Funding", "B. Delivery"], &[("A. Funding", "H2"), ("B. Delivery", "H2")]),
+        (&["iii. Funding"], &[("iii. Funding", "H2")]),
+        // "C." after "B." is a letter, "V." after "IV." a numeral
+        (&["I. Scope", "A. Quay Walls", "B. Channel", "C. Buoys"],
+            &[("I. Scope", "H1"), ("A. Quay Walls", "H2"), ("B. Channel", "H2"), ("C. Buoys", "H2")]),
+        (&["IV. Funding", "V. Delivery"], &[("IV. Funding", "H1"), ("V. Delivery", "H1")]),
+        // Decimals carry their own depth under any scheme
+        (&["II. Methods", "2.1 Surveys"], &[("II. Methods", "H1"), ("2.1 Surveys", "H2")]),
+    ] {
+        let expected: Vec<(String, String)> = expected.iter().map(|&(t, l)| (t.to_string(), l.to_string())).collect();
+        assert_eq!(levels(lines), expected, "{:?}", lines);
+    }
+}
+
+#[test]
+fn what_only_looks_like_a_numeral() {
+    // A lone "I." opening a sentence
+    let found = levels(&["I. Scope", "I. think the plan is sound and we should adopt it"]);
+    assert_eq!(found.len(), 1, "{:?}", found);
+    // "IC" is no numeral, so it neither nests nor closes the "IV." around it
+    let found = levels(&["IV. Funding", "IC. Budget Review", "A. Grants"]);
+    assert!(found.iter().all(|(text, level)| !(text.starts_with("IC.") && level == "H1")), "{:?}", found);
+    assert!(found.contains(&("A. Grants".to_string(), "H2".to_string())), "{:?}", found);
+}
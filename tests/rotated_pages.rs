@@ -0,0 +1,79 @@
+// Runs on a page with /Rotate come out in upright page space, as the page
+// is displayed, so a landscape appendix in a portrait report keeps its
+// headings at the top of its page and in reading order.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::layout::{extract_runs_from_bytes, TextRun};
+use adobe1a::Extractor;
+use common::pdf_builder::{self, Page, PdfBuilder};
+use lopdf::content::{Content, Operation};
+use lopdf::Object;
+
+// Displayed height of the turned page: the 612pt width of its MediaBox
+const LANDSCAPE_HEIGHT: f64 = 612.0;
+
+// How far down the upright page the appendix is drawn, since the portrait
+// layout is taller than the landscape page
+const SHIFT: f64 = 180.0;
+
+// Headings of the same length on both pages, so their runs are as wide
+fn appendix(letter: &str, heading: &str, subheading: &str) -> Page {
+    Page::new()
+        .heading(1, &format!("Appendix {} {}", letter, heading))
+        .body(3)
+        .heading(2, &format!("{}.1 {}", letter, subheading))
+        .body(3)
+}
+
+// A portrait page, then the same page content drawn sideways on a page
+// turned a quarter clockwise, so it reads upright once displayed
+fn mixed() -> Vec<u8> {
+    let mut doc = PdfBuilder::new()
+        .page(appendix("A", "Charts", "Wave Height"))
+        .page(appendix("B", "Tables", "Tide Levels"))
+        .document();
+    let pages = doc.get_pages();
+    let page = doc.get_dictionary_mut(pages[&2]).unwrap();
+    page.set("Rotate", 90);
+    let contents = page.get(b"Contents").unwrap().as_reference().unwrap();
+    let stream = doc.get_object_mut(contents).unwrap().as_stream_mut().unwrap();
+    let mut content = Content::decode(&stream.content).unwrap();
+    // What the layout put at (u, v) is drawn at user space (792 - v, u),
+    // which the turned page displays at (u, v - SHIFT)
+    content.operations.insert(0, Operation::new("cm", [0, 1, -1, 0, 792, 0].map(Object::from).to_vec()));
+    stream.set_content(content.encode().unwrap());
+    pdf_builder::save(&mut doc)
+}
+
+#[test]
+fn runs_on_a_turned_page_are_upright() {
+    let runs = extract_runs_from_bytes(&mixed()).unwrap();
+    let (portrait, turned): (Vec<&TextRun>, Vec<&TextRun>) = runs.iter().partition(|run| run.page == 1);
+    assert_eq!(portrait.len(), turned.len());
+    for (portrait, turned) in portrait.iter().zip(&turned) {
+        assert_eq!(portrait.text.len(), turned.text.len());
+        assert!((turned.x - portrait.x).abs() < 1e-6, "{:?} {:?}", portrait, turned);
+        assert!((turned.y - (portrait.y - SHIFT)).abs() < 1e-6, "{:?} {:?}", portrait, turned);
+        assert!((turned.width - portrait.width).abs() < 1e-6);
+    }
+    // The heading is near the top of the landscape page as displayed
+    let heading = turned.iter().find(|run| run.text == "Appendix B Tables").unwrap();
+    assert!(heading.y > LANDSCAPE_HEIGHT * 0.8 && heading.y < LANDSCAPE_HEIGHT, "{}", heading.y);
+}
+
+// The font engine only: pdf-extract, which the text engine reads, doesn't
+// break lines on a turned page
+#[test]
+fn headings_on_a_turned_page_are_placed_as_displayed() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let outline = Extractor::builder().engine(Engine::Font).positions(true).build().unwrap().extract_bytes(&mixed()).unwrap();
+    let pages: Vec<_> = outline.outline.iter().map(|heading| (heading.text.as_str(), heading.page)).collect();
+    assert_eq!(pages, [("Appendix A Charts", 1), ("A.1 Wave Height", 1), ("Appendix B Tables", 2), ("B.1 Tide Levels", 2)]);
+
+    let turned: Vec<f64> = outline.outline[2..].iter().map(|heading| heading.y_fraction.expect("a position")).collect();
+    assert!(turned[0] < 0.15 && turned[1] > turned[0], "{:?}", turned);
+}
@@ -0,0 +1,40 @@
+// Both engines decide whether a line may be a heading with the same filter,
+// so a line the text engine keeps the font engine keeps too, and the other
+// way round. The cases are the rules the engines used to disagree on.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::{analyze_text, Extractor, Outline, TextOptions};
+use common::pdf_builder::{Page, PdfBuilder, Style};
+
+// Kept: three bytes is long enough, "Sponsor" doesn't end in the connective
+// "or", and a numbered heading may run past six words
+const KEPT: [&str; 3] = ["1. Aim", "2. Project Sponsor", "3. Introduction to the Foundation Level Extensions"];
+// Dropped: a sentence, a line ending in a connective, and one too long
+const DROPPED: [&str; 3] = [
+    "4. The board met on Tuesday and agreed the plan for the coming year.",
+    "5. Works on the quay and",
+    "6. Review of the harbour board's programme of works on the quay walls, the channel, the pilot station, the buoys, the dredging and the new ferry berth",
+];
+
+fn texts(outline: &Outline) -> Vec<&str> {
+    outline.outline.iter().map(|heading| heading.text.as_str()).collect()
+}
+
+#[test]
+fn both_engines_keep_and_drop_the_same_lines() {
+    let lines = KEPT.iter().chain(DROPPED.iter());
+    let body = "The harbour board met to review the year's work on the quay walls and the channel.";
+    let text: String = lines.clone().map(|line| format!("{}\n{}\n", line, body)).collect();
+    let from_text = analyze_text(&text, TextOptions::default());
+    assert_eq!(texts(&from_text), KEPT);
+
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let page = lines.fold(Page::new(), |page, line| page.styled_heading(Style::heading(1), line).body(2));
+    let pdf = PdfBuilder::new().page(page).build();
+    let from_font = Extractor::builder().engine(Engine::Font).build().unwrap().extract_bytes(&pdf).unwrap();
+    assert_eq!(texts(&from_font), KEPT);
+}
@@ -0,0 +1,120 @@
+// Memos and letters are read with the short-document rules: headings
+// without blank lines around them, and the subject line as the title
+// rather than the letterhead or an address line.
+
+mod common;
+
+use adobe1a::config::{Engine, ShortDocument};
+use adobe1a::{analyze_text, Extractor, Outline, TextOptions};
+
+fn outline(engine: Engine, short_document: ShortDocument, pdf: &[u8]) -> Outline {
+    let extractor = Extractor::builder().engine(engine).short_document(short_document).build().unwrap();
+    extractor.extract_bytes(pdf).unwrap()
+}
+
+fn headings(outline: &Outline) -> Vec<(&str, &str, usize)> {
+    outline.outline.iter().map(|h| (h.level.as_str(), h.text.as_str(), h.page)).collect()
+}
+
+#[test]
+fn two_page_memo() {
+    let pdf = common::two_page_memo();
+    for engine in [Engine::Text, Engine::Font] {
+        if engine.is_built() {
+            assert_eq!(outline(engine, ShortDocument::Auto, &pdf).title, "Contract Amendment No. 4", "{:?}", engine);
+        }
+    }
+    if !Engine::Text.is_built() {
+        return;
+    }
+    let memo = outline(Engine::Text, ShortDocument::Auto, &pdf);
+    assert_eq!(headings(&memo), [
+        ("H2", "Summary of Changes", 1),
+        ("H2", "Budget Impact", 1),
+        ("H2", "Next Steps", 2),
+        ("H2", "Questions", 2),
+    ]);
+    // Read as a report, the address block's lines compete for the title
+    // and the headings have no blank lines to stand between
+    let report = outline(Engine::Text, ShortDocument::Never, &pdf);
+    assert_ne!(report.title, memo.title);
+    assert!(report.outline.len() < memo.outline.len(), "{:?}", headings(&report));
+}
+
+#[test]
+fn one_page_letter() {
+    let pdf = common::letter();
+    for engine in [Engine::Text, Engine::Font] {
+        if engine.is_built() {
+            let letter = outline(engine, ShortDocument::Auto, &pdf);
+            assert_eq!(letter.title, "Application for Planning Permission at 22 Mill Lane", "{:?}", engine);
+        }
+    }
+    if !Engine::Text.is_built() {
+        return;
+    }
+    assert_eq!(headings(&outline(Engine::Text, ShortDocument::Auto, &pdf)), [("H2", "Supporting Documents", 1)]);
+}
+
+// The threshold decides what auto reads as short; a longer document keeps
+// the report rules unless told otherwise
+#[test]
+fn page_threshold() {
+    if !Engine::Text.is_built() {
+        return;
+    }
+    let pdf = common::two_page_memo();
+    let title = |pages: usize| {
+        let extractor = Extractor::builder().engine(Engine::Text).short_document_pages(pages).build().unwrap();
+        extractor.extract_bytes(&pdf).unwrap().title
+    };
+    assert_eq!(title(2), "Contract Amendment No. 4");
+    assert_ne!(title(1), "Contract Amendment No. 4");
+    assert_eq!(outline(Engine::Text, ShortDocument::Always, &common::numbered_report()).title, "Northwind Annual Review");
+}
+
+// A list marker in front of a heading ("• ", "– ", "* ") is left out of its
+// text, and a bulleted line that doesn't read as a title stays a list item.
+// The font engine also nests the agenda's unnumbered headings by their
+// indentation.
+#[test]
+fn agenda_bullets_and_indents() {
+    let pdf = include_bytes!("../fixtures/agenda.pdf");
+    for engine in [Engine::Text, Engine::Font].into_iter().filter(|engine| engine.is_built()) {
+        let agenda = outline(engine, ShortDocument::Auto, pdf);
+        let name = format!("{:?}", engine).to_lowercase();
+        assert_eq!((agenda.engine, agenda.extractor_version.as_str()), (vec![name], adobe1a::EXTRACTOR_VERSION));
+        assert!(agenda.outline.iter().any(|heading| heading.text == "3. Any Other Business"), "{:?}", engine);
+        assert!(agenda.outline.iter().all(|heading| heading.text.starts_with(char::is_alphanumeric)), "{:?}", engine);
+        assert!(!agenda.outline.iter().any(|heading| heading.text.contains("charter")), "{:?}", engine);
+    }
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let agenda = outline(Engine::Font, ShortDocument::Auto, pdf);
+    let nested: Vec<(&str, &str)> = agenda.outline.iter()
+        .filter(|heading| heading.depth() > 2)
+        .map(|heading| (heading.level.as_str(), heading.text.as_str()))
+        .collect();
+    assert_eq!(nested, [("H3", "Project Governance"), ("H4", "Steering Committee"), ("H3", "Risk Register"),
+                        ("H4", "Vendor Exposure"), ("H3", "Budget Review"), ("H4", "Capital Requests")]);
+}
+
+// Text from another converter, without blank lines around its headings:
+// read as a report, the subject line isn't the title and nothing stands apart
+#[test]
+fn memo_text_with_and_without_the_short_rules() {
+    let memo = "MEMORANDUM\nTO: All Department Heads\nFROM: Office of the City Manager\n\
+                RE: Contract Amendment No. 4\nSummary of Changes:\n\
+                The amendment extends the maintenance term by two years at the current rates.\n";
+    let outline = |short_document| {
+        let config = Extractor::builder().short_document(short_document).build().unwrap().config().clone();
+        analyze_text(memo, TextOptions { config, ..TextOptions::default() })
+    };
+    let short = outline(ShortDocument::Auto);
+    assert_eq!(short.title, "Contract Amendment No. 4");
+    assert_eq!(short.outline[0].text, "Summary of Changes");
+    let long = outline(ShortDocument::Never);
+    assert_eq!(long.title, "TO: All Department Heads");
+    assert!(long.outline.is_empty());
+}
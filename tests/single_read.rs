@@ -0,0 +1,90 @@
+// The input file is read once and the same bytes handed to both engines,
+// and on the command line to the attachments a portfolio is split into.
+// It's given here as a named pipe, which yields its contents to one reader
+// only: a second read would wait for a writer that never comes.
+#![cfg(all(unix, not(feature = "mmap")))]
+
+mod common;
+
+use std::io::Write;
+use std::process::Command;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use adobe1a::config::Engine;
+use adobe1a::{Extractor, Outline};
+use common::pdf_builder::{Page, PdfBuilder};
+
+#[test]
+fn both_engines_read_the_file_once() {
+    // Page 2 needs the font engine after the text engine
+    if !Engine::Text.is_built() || !Engine::Font.is_built() {
+        return;
+    }
+    let pdf = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/hybrid-fonts.pdf")).unwrap();
+    let dir = std::env::temp_dir().join(format!("adobe1a-single-read-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let pipe = dir.join("hybrid-fonts.pdf");
+    assert!(Command::new("mkfifo").arg(&pipe).status().unwrap().success());
+
+    let writer = {
+        let pipe = pipe.clone();
+        thread::spawn(move || std::fs::OpenOptions::new().write(true).open(&pipe).unwrap().write_all(&pdf).unwrap())
+    };
+    let (sender, receiver) = mpsc::channel();
+    {
+        let pipe = pipe.clone();
+        thread::spawn(move || {
+            let extractor = Extractor::builder().build().unwrap();
+            sender.send(extractor.extract_path(&pipe).map_err(|e| e.to_string())).unwrap();
+        });
+    }
+    let outline = receiver.recv_timeout(Duration::from_secs(60))
+        .expect("extraction waited on the pipe: the file was read more than once")
+        .unwrap();
+    writer.join().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(outline.engine, ["text", "font"]);
+    assert!(outline.outline.iter().any(|heading| heading.text == "2. Methods"));
+}
+
+#[test]
+fn a_split_portfolio_is_read_once() {
+    let pdf = PdfBuilder::new()
+        .page(Page::new().title("Tender Submission for Harbour Works").body(2).heading(1, "1. Cover Letter").body(4))
+        .attachment("report.pdf", common::numbered_report())
+        .portfolio()
+        .build();
+    let dir = std::env::temp_dir().join(format!("adobe1a-single-read-split-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let pipe = dir.join("submission.pdf");
+    assert!(Command::new("mkfifo").arg(&pipe).status().unwrap().success());
+
+    let writer = {
+        let pipe = pipe.clone();
+        thread::spawn(move || std::fs::OpenOptions::new().write(true).open(&pipe).unwrap().write_all(&pdf).unwrap())
+    };
+    let mut run = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+        .arg("--input").arg(&pipe)
+        .arg("--output").arg(dir.join("out.json"))
+        .args(["--portfolio", "split"])
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let status = (0..600).find_map(|_| {
+        thread::sleep(Duration::from_millis(100));
+        run.try_wait().unwrap()
+    });
+    if status.is_none() {
+        run.kill().unwrap();
+    }
+    writer.join().unwrap();
+    let status = status.expect("extraction waited on the pipe: the file was read more than once");
+    assert!(status.success());
+
+    let read = |name: &str| Outline::from_json_str(&std::fs::read_to_string(dir.join(name)).unwrap()).unwrap();
+    assert_eq!(read("out.json").title, "Tender Submission for Harbour Works");
+    assert!(!read("out.report.json").outline.is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,88 @@
+// "Chapter One", "Part Two: Governance" and "Section Eleven" are section
+// headings like "Chapter 1": chapters and parts H1, sections H2, in capitals
+// or title case. The same words opening a sentence are prose.
+
+mod common;
+
+use adobe1a::{Extractor, Outline};
+use common::pdf_builder::{Page, PdfBuilder, Style};
+
+const ORDINALS: [&str; 20] = [
+    "One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten",
+    "Eleven", "Twelve", "Thirteen", "Fourteen", "Fifteen", "Sixteen", "Seventeen", "Eighteen", "Nineteen", "Twenty",
+];
+
+fn extract(pdf: &[u8]) -> Outline {
+    Extractor::builder().max_headings(usize::MAX).build().unwrap().extract_bytes(pdf).unwrap()
+}
+
+fn levels(outline: &Outline) -> Vec<(&str, &str)> {
+    outline.outline.iter().map(|heading| (heading.text.as_str(), heading.level.as_str())).collect()
+}
+
+// A novel of twenty chapters, one to a page
+fn novel() -> Vec<u8> {
+    ORDINALS.iter()
+        .fold(PdfBuilder::new().page(Page::new().title("The Harbour Master").body(4)), |book, ordinal| {
+            book.page(Page::new().line(Style::heading(1), &format!("Chapter {}", ordinal)).body(20))
+        })
+        .build()
+}
+
+#[test]
+fn a_novel_gives_every_chapter() {
+    let outline = extract(&novel());
+    let expected: Vec<String> = ORDINALS.iter().map(|ordinal| format!("Chapter {}", ordinal)).collect();
+    let found = levels(&outline);
+    assert_eq!(found.iter().map(|&(text, _)| text).collect::<Vec<_>>(), expected);
+    assert!(found.iter().all(|&(_, level)| level == "H1"), "{:?}", found);
+}
+
+#[test]
+fn a_contract_gives_parts_and_sections() {
+    let pdf = PdfBuilder::new()
+        .page(Page::new()
+            .title("Harbour Services Agreement")
+            // The builder's fonts have no em dash
+            .line(Style::heading(1), "PART ONE - PARTIES")
+            .body(3)
+            .line(Style::heading(1), "PART TWO - DEFINITIONS")
+            .body(3)
+            .line(Style::heading(2), "Section Eleven Payment Terms")
+            .body(3)
+            .line(Style::heading(1), "Part Three: Governance")
+            .body(3)
+            .line(Style::BODY, "Chapter one of this agreement describes the parties and their duties.")
+            .body(3))
+        .build();
+    // The font engine may list the title line too
+    let outline = extract(&pdf);
+    let found: Vec<_> = levels(&outline).into_iter().filter(|&(text, _)| text != "Harbour Services Agreement").collect();
+    assert_eq!(found, [
+        ("PART ONE - PARTIES", "H1"),
+        ("PART TWO - DEFINITIONS", "H1"),
+        ("Section Eleven Payment Terms", "H2"),
+        ("Part Three: Governance", "H1"),
+    ]);
+}
+
+#[test]
+fn the_text_engine_reads_them_too() {
+    let text = "\
+Harbour Services Agreement
+
+The parties agree to the terms set out in the parts and sections that follow.
+
+PART TWO — DEFINITIONS
+
+The words defined in this part have the same meaning wherever they appear.
+
+Section Eleven Payment Terms
+
+Invoices are paid within thirty days of the date on which they are received.
+
+Chapter one of this agreement describes the parties and their duties.
+";
+    let outline = adobe1a::analyze_text(text, adobe1a::TextOptions::default());
+    assert_eq!(levels(&outline), [("PART TWO — DEFINITIONS", "H1"), ("Section Eleven Payment Terms", "H2")]);
+}
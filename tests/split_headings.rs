@@ -0,0 +1,64 @@
+// A chapter number alone at the foot of a page and the title opening the
+// next page are one heading, on the number's page.
+
+mod common;
+
+use adobe1a::config::{Engine, ShortDocument};
+use adobe1a::Extractor;
+use common::pdf_builder::{Page, PdfBuilder, Style};
+
+// The PDFs are two pages of a long handbook, not a memo: read as a memo,
+// their running header isn't told apart from the text of the page
+fn headings(engine: Engine, pdf: &[u8]) -> Vec<(String, usize)> {
+    let extractor = Extractor::builder().engine(engine).short_document(ShortDocument::Never).build().unwrap();
+    let outline = extractor.extract_bytes(pdf).unwrap();
+    outline.outline.into_iter().map(|h| (h.text, h.page)).collect()
+}
+
+#[test]
+fn chapter_number_joins_the_title_on_the_next_page() {
+    let pdf = common::split_chapter();
+    for engine in [Engine::Text, Engine::Font] {
+        if !engine.is_built() {
+            continue;
+        }
+        let headings = headings(engine, &pdf);
+        assert!(headings.contains(&("CHAPTER 4 IMPLEMENTATION".to_string(), 1)), "{:?}: {:?}", engine, headings);
+        assert!(!headings.iter().any(|(text, _)| text == "CHAPTER 4" || text == "IMPLEMENTATION"), "{:?}: {:?}", engine, headings);
+    }
+}
+
+// A numbered heading opening the next page has a marker of its own, and a
+// chapter number with text below it isn't at the foot of its page
+#[test]
+fn only_a_bare_number_at_the_foot_takes_an_unnumbered_title() {
+    let numbered_next = PdfBuilder::new()
+        .page(Page::new()
+            .heading(1, "CHAPTER 3: PLANNING")
+            .body(34)
+            .text_at(Style::heading(1), 72.0, 80.0, "CHAPTER 4"))
+        .page(Page::new()
+            .heading(2, "4.1 Site Preparation")
+            .body(10))
+        .build();
+    let text_below = PdfBuilder::new()
+        .page(Page::new()
+            .heading(1, "CHAPTER 3: PLANNING")
+            .body(33)
+            .heading(1, "CHAPTER 4")
+            .body(1))
+        .page(Page::new()
+            .heading(1, "IMPLEMENTATION")
+            .body(10))
+        .build();
+    for engine in [Engine::Text, Engine::Font] {
+        if !engine.is_built() {
+            continue;
+        }
+        for pdf in [&numbered_next, &text_below] {
+            let headings = headings(engine, pdf);
+            assert!(headings.contains(&("CHAPTER 4".to_string(), 1)), "{:?}: {:?}", engine, headings);
+            assert!(!headings.iter().any(|(text, _)| text.starts_with("CHAPTER 4 ")), "{:?}: {:?}", engine, headings);
+        }
+    }
+}
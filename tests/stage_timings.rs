@@ -0,0 +1,65 @@
+// Extraction times each pipeline stage. `--stats` prints the times as a
+// table on stderr and `--stats-json` adds them to the outline as `stats`,
+// in pipeline order with their sum.
+
+use std::process::Command;
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::extract_outline;
+use adobe1a::timing::{Stage, Stats};
+use serde_json::Value;
+
+const AGENDA: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/agenda.pdf");
+
+#[test]
+fn stats_serialize_each_stage_in_order_with_their_sum() {
+    let mut stats = Stats::new();
+    extract_outline(AGENDA.as_ref(), &ExtractorConfig::default(), &mut stats).unwrap();
+    let json = serde_json::to_value(&stats).unwrap();
+    let keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+    let mut expected: Vec<String> = Stage::ALL.iter().map(|stage| format!("{}_ms", stage.name())).collect();
+    expected.extend(["total_ms".to_string(), "peak_memory_bytes".to_string()]);
+    assert_eq!(keys, expected);
+
+    let sum: f64 = Stage::ALL.iter().map(|stage| json[format!("{}_ms", stage.name())].as_f64().unwrap()).sum();
+    assert!((json["total_ms"].as_f64().unwrap() - sum).abs() < 1e-6);
+    assert!(json["file_read_ms"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn each_engine_times_its_own_stages() {
+    for (engine, own, other) in [(Engine::Text, Stage::TextExtraction, Stage::CandidateClassification), (Engine::Font, Stage::RunExtraction, Stage::TextExtraction)] {
+        if !engine.is_built() {
+            continue;
+        }
+        let mut stats = Stats::new();
+        extract_outline(AGENDA.as_ref(), &ExtractorConfig { engine, ..ExtractorConfig::default() }, &mut stats).unwrap();
+        let json = serde_json::to_value(&stats).unwrap();
+        assert!(json[format!("{}_ms", own.name())].as_f64().unwrap() > 0.0, "{:?}", engine);
+        assert_eq!(json[format!("{}_ms", other.name())].as_f64().unwrap(), 0.0, "{:?}", engine);
+    }
+}
+
+#[test]
+fn the_command_line_prints_and_embeds_the_stats() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-stage-timings-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let output = dir.join("agenda.json");
+    let run = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+        .arg("--input").arg(AGENDA)
+        .arg("--output").arg(&output)
+        .args(["--stats", "--stats-json"])
+        .output()
+        .unwrap();
+    assert!(run.status.success(), "{}", String::from_utf8_lossy(&run.stderr));
+
+    let stderr = String::from_utf8_lossy(&run.stderr);
+    for stage in Stage::ALL {
+        assert!(stderr.lines().any(|line| line.starts_with(stage.name())), "no {} in\n{}", stage.name(), stderr);
+    }
+    assert!(stderr.lines().any(|line| line.starts_with("total ")));
+
+    let outline: Value = serde_json::from_slice(&std::fs::read(&output).unwrap()).unwrap();
+    assert!(outline["stats"]["total_ms"].as_f64().unwrap() > 0.0);
+    assert!(outline["stats"].get("wall_ms").is_none());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
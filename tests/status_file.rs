@@ -0,0 +1,107 @@
+// A small batch, with a pause after each file, reports its progress in the
+// status file as it goes, and a reader polling the file throughout only
+// ever sees whole JSON documents.
+
+mod common;
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use adobe1a::output::KeyStyle;
+use adobe1a::status::StatusFile;
+use adobe1a::summary::{BatchSummary, FileSummary};
+use adobe1a::timing::Stats;
+use adobe1a::Extractor;
+use serde_json::Value;
+
+// The artificial time each file takes
+const DELAY: Duration = Duration::from_millis(30);
+
+fn read(path: &Path) -> Value {
+    serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap()
+}
+
+#[test]
+fn status_follows_a_batch_to_completion() {
+    let path = std::env::temp_dir().join(format!("adobe1a-status-{}.json", std::process::id()));
+    let pdfs = common::generated();
+    let status = StatusFile::create(&path, pdfs.len(), Duration::ZERO, KeyStyle::Snake).unwrap();
+    assert_eq!(read(&path)["files_completed"], 0);
+    assert_eq!(read(&path)["eta_ms"], Value::Null);
+
+    let done = AtomicBool::new(false);
+    let files = thread::scope(|scope| {
+        // Every read parses, however the writes fall
+        let reader = scope.spawn(|| {
+            let mut reads = 0;
+            while !done.load(Ordering::Relaxed) {
+                let value = read(&path);
+                assert_eq!(value["files_total"], pdfs.len());
+                reads += 1;
+            }
+            reads
+        });
+        let extractor = Extractor::default();
+        let mut files = Vec::new();
+        for (i, (name, pdf)) in pdfs.iter().enumerate() {
+            let name = Path::new(name);
+            status.started(name);
+            assert_eq!(read(&path)["current"], serde_json::json!([name.display().to_string()]));
+            thread::sleep(DELAY);
+            let file = match extractor.extract_bytes(pdf) {
+                Ok(outline) => FileSummary::succeeded(name, &outline, Stats::new()),
+                Err(e) => FileSummary::failed(name, &e),
+            };
+            status.finished(&file, DELAY);
+            files.push(file);
+
+            let value = read(&path);
+            assert_eq!(value["state"], "running");
+            assert_eq!(value["files_completed"], i + 1);
+            assert_eq!(value["current"], serde_json::json!([]));
+            assert_eq!(value["files"][i]["path"], name.display().to_string());
+            assert_eq!(value["files"][i]["duration_ms"], 30.0);
+            assert!(value["eta_ms"].as_f64().unwrap() >= 0.0);
+            assert!(value["summary"].is_null());
+        }
+        done.store(true, Ordering::Relaxed);
+        assert!(reader.join().unwrap() > 0);
+        files
+    });
+
+    status.complete(&BatchSummary::new(files)).unwrap();
+    let value = read(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(value["state"], "complete");
+    assert_eq!(value["files_completed"], pdfs.len());
+    assert_eq!(value["eta_ms"], 0.0);
+    assert_eq!(value["failures"], serde_json::json!([]));
+    assert_eq!(value["summary"]["totals"]["files"], pdfs.len());
+}
+
+// Between writes the file keeps its last status, until the run completes
+#[test]
+fn writes_wait_for_the_interval() {
+    let path = std::env::temp_dir().join(format!("adobe1a-status-interval-{}.json", std::process::id()));
+    let status = StatusFile::create(&path, 2, Duration::from_secs(3600), KeyStyle::Camel).unwrap();
+    let error = anyhow::anyhow!("broken xref table");
+    let file = FileSummary::failed(Path::new("a.pdf"), &error);
+    status.started(Path::new("a.pdf"));
+    status.finished(&file, DELAY);
+    assert_eq!(read(&path)["filesCompleted"], 0);
+
+    status.complete(&BatchSummary::new(vec![file])).unwrap();
+    let value = read(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(value["filesCompleted"], 1);
+    assert_eq!(value["failures"], serde_json::json!([{ "path": "a.pdf", "error": "broken xref table" }]));
+}
+
+#[test]
+fn an_unwritable_path_fails_at_once() {
+    let path = std::env::temp_dir().join("adobe1a-missing-dir").join("status.json");
+    let error = StatusFile::create(&path, 1, Duration::ZERO, KeyStyle::Snake).map(|_| ()).unwrap_err();
+    assert!(format!("{:#}", error).starts_with("Failed to write status file"), "{:#}", error);
+}
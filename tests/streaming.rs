@@ -0,0 +1,43 @@
+// `Extractor::extract_pages` sends the title after the first page, then each
+// page's headings, and stopping it from the sink leaves the result holding
+// exactly what was sent.
+
+#![cfg(feature = "engine-font")]
+
+mod common;
+
+use std::ops::ControlFlow;
+use adobe1a::{ExtractEvent, Extractor, Heading};
+
+fn extract(path: &std::path::Path, stop: impl Fn(&ExtractEvent) -> bool) -> (Vec<Heading>, adobe1a::Outline) {
+    let mut sent = Vec::new();
+    let outline = Extractor::default().extract_pages(path, |event| {
+        let stopping = stop(&event);
+        if let ExtractEvent::Page(page) = event {
+            sent.extend(page.headings);
+        }
+        if stopping { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+    }).unwrap();
+    (sent, outline)
+}
+
+#[test]
+fn the_result_holds_what_the_sink_was_sent() {
+    let path = std::env::temp_dir().join(format!("adobe1a-streaming-{}.pdf", std::process::id()));
+    std::fs::write(&path, common::numbered_report()).unwrap();
+
+    let (sent, outline) = extract(&path, |_| false);
+    assert!(sent.iter().any(|heading| heading.page == 1));
+    assert_eq!(outline.outline, sent);
+
+    // Stopped at the title, before page 1's headings were sent
+    let (sent, outline) = extract(&path, |event| matches!(event, ExtractEvent::Title { .. }));
+    assert!(sent.is_empty());
+    assert!(outline.outline.is_empty());
+    assert!(!outline.title.is_empty());
+
+    let (sent, outline) = extract(&path, |event| matches!(event, ExtractEvent::Page(page) if page.page == 2));
+    assert!(sent.iter().all(|heading| heading.page <= 2) && sent.iter().any(|heading| heading.page == 2));
+    assert_eq!(outline.outline, sent);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,16 @@
+// Text inside a table isn't a heading however it's styled: the bold row
+// labels of a page-long table are skipped, and only the section heading
+// above the table is kept.
+
+#![cfg(feature = "engine-font")]
+
+use std::ops::ControlFlow;
+use adobe1a::Extractor;
+
+#[test]
+fn table_row_labels_are_not_headings() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/table-page.pdf");
+    let outline = Extractor::default().extract_pages(path.as_ref(), |_| ControlFlow::Continue(())).unwrap();
+    let texts: Vec<_> = outline.outline.iter().map(|h| h.text.as_str()).collect();
+    assert_eq!(texts, ["3. Financial Results"]);
+}
@@ -0,0 +1,96 @@
+// Text runs name their font by its /BaseFont, without a subset prefix, and
+// read bold and italic from that name, not from the page's resource name
+// for the font ("F1"), which says nothing about it. Each run carries where
+// it starts, about how wide it is, its colour and whether it is drawn, and
+// runs come page by page in the order they are drawn.
+
+mod common;
+
+use adobe1a::layout::{extract_runs_from_bytes, TextRun};
+use common::pdf_builder::{self, Font, Page, PdfBuilder, Style};
+use lopdf::{dictionary, Object, Stream};
+
+fn run<'a>(runs: &'a [TextRun], text: &str) -> &'a TextRun {
+    runs.iter().find(|run| run.text == text).unwrap_or_else(|| panic!("no run {:?} in {:?}", text, runs))
+}
+
+#[test]
+fn runs_name_their_base_font() {
+    let pdf = PdfBuilder::new()
+        .page(Page::new().heading(1, "1. Introduction").line(Style::BODY, "Plain text"))
+        .build();
+    let runs = extract_runs_from_bytes(&pdf).unwrap();
+    let heading = run(&runs, "1. Introduction");
+    assert_eq!((heading.font.as_str(), heading.is_bold, heading.is_italic), ("Helvetica-Bold", true, false));
+    let body = run(&runs, "Plain text");
+    assert_eq!((body.font.as_str(), body.is_bold, body.is_italic), ("Helvetica", false, false));
+}
+
+#[test]
+fn a_subset_prefix_is_left_out() {
+    let mut doc = PdfBuilder::new()
+        .page(Page::new().line(Style::new(Font::TimesItalic, 12.0), "Field notes"))
+        .document();
+    for object in doc.objects.values_mut() {
+        if let Ok(font) = object.as_dict_mut() {
+            if font.get(b"BaseFont").is_ok() {
+                font.set("BaseFont", "ABCDEF+Times-Italic");
+            }
+        }
+    }
+    let runs = extract_runs_from_bytes(&pdf_builder::save(&mut doc)).unwrap();
+    let notes = run(&runs, "Field notes");
+    assert_eq!((notes.font.as_str(), notes.is_italic), ("Times-Italic", true));
+}
+
+#[test]
+fn an_undefined_font_is_named_by_its_resource() {
+    let mut doc = PdfBuilder::new().page(Page::new().line(Style::BODY, "Defined")).document();
+    let page = *doc.get_pages().values().next().unwrap();
+    let undefined = doc.add_object(Stream::new(dictionary! {}, b"BT /F9 10 Tf 72 600 Td (Undefined) Tj ET".to_vec()));
+    let contents = doc.get_dictionary(page).unwrap().get(b"Contents").unwrap().clone();
+    doc.get_dictionary_mut(page).unwrap().set("Contents", vec![contents, Object::Reference(undefined)]);
+    let runs = extract_runs_from_bytes(&pdf_builder::save(&mut doc)).unwrap();
+    assert_eq!(run(&runs, "Defined").font, "Helvetica");
+    assert_eq!(run(&runs, "Undefined").font, "F9");
+}
+
+#[test]
+fn runs_carry_position_width_colour_and_visibility() {
+    let pdf = PdfBuilder::new()
+        .page(Page::new()
+            .text_at(Style::BODY, 100.0, 500.0, "Placed")
+            .text_at(Style::BODY, 100.0, 480.0, "Placed twice over")
+            .color(0.8, 0.0, 0.0)
+            .text_at(Style::BODY, 300.0, 500.0, "Red")
+            .color(0.0, 0.0, 0.0)
+            .invisible()
+            .text_at(Style::BODY, 72.0, 400.0, "Hidden layer")
+            .visible())
+        .build();
+    let runs = extract_runs_from_bytes(&pdf).unwrap();
+
+    let placed = run(&runs, "Placed");
+    assert_eq!((placed.x, placed.y, placed.size, placed.page), (100.0, 500.0, 10.0, 1));
+    assert!(placed.width > 20.0 && placed.width < 40.0, "{}", placed.width);
+    // Width grows with the text
+    assert!(run(&runs, "Placed twice over").width > 2.0 * placed.width);
+    assert_eq!(placed.color, [0.0, 0.0, 0.0]);
+    // Colours are approximate: PDF numbers are read as f32
+    let red = run(&runs, "Red").color;
+    assert!(red.iter().zip([0.8, 0.0, 0.0]).all(|(a, b)| (a - b).abs() < 1e-3), "{:?}", red);
+    assert!(placed.visible);
+    assert!(!run(&runs, "Hidden layer").visible);
+}
+
+#[test]
+fn runs_come_by_page_then_drawing_order() {
+    // Each page draws its lowest line first
+    let page = |n: usize| Page::new()
+        .text_at(Style::BODY, 72.0, 100.0, &format!("Foot {}", n))
+        .text_at(Style::BODY, 72.0, 700.0, &format!("Head {}", n));
+    let pdf = PdfBuilder::new().page(page(1)).page(page(2)).build();
+    let runs = extract_runs_from_bytes(&pdf).unwrap();
+    let order: Vec<(usize, &str)> = runs.iter().map(|run| (run.page, run.text.as_str())).collect();
+    assert_eq!(order, [(1, "Foot 1"), (1, "Head 1"), (2, "Foot 2"), (2, "Head 2")]);
+}
@@ -0,0 +1,72 @@
+// The public types can be shared between threads: an `Rc` or `RefCell`
+// finding its way into any of them fails to compile here. One extractor
+// used by several threads at once gives each the outline it would give a
+// single thread.
+
+mod common;
+
+use std::thread;
+use adobe1a::calibration::Calibration;
+use adobe1a::config::ExtractorConfig;
+use adobe1a::levels::LevelOverrides;
+use adobe1a::lexicon::Lexicon;
+use adobe1a::status::StatusFile;
+use adobe1a::timing::Stats;
+use adobe1a::transform::Transform;
+use adobe1a::{BuildError, ExtractEvent, Extractor, ExtractorBuilder, Heading, Outline, PageResult, TextOptions, Warning};
+
+const fn assert_send_sync<T: Send + Sync>() {}
+
+const _: () = {
+    assert_send_sync::<Extractor>();
+    assert_send_sync::<ExtractorBuilder>();
+    assert_send_sync::<ExtractorConfig>();
+    assert_send_sync::<BuildError>();
+    assert_send_sync::<ExtractEvent>();
+    assert_send_sync::<PageResult>();
+    assert_send_sync::<Outline>();
+    assert_send_sync::<Heading>();
+    assert_send_sync::<Warning>();
+    assert_send_sync::<Stats>();
+    assert_send_sync::<Lexicon>();
+    assert_send_sync::<LevelOverrides>();
+    assert_send_sync::<Calibration>();
+    assert_send_sync::<Transform>();
+    assert_send_sync::<TextOptions>();
+    assert_send_sync::<StatusFile>();
+};
+
+// Threads sharing the one extractor
+const THREADS: usize = 4;
+
+#[test]
+fn a_shared_extractor_gives_the_single_threaded_results() {
+    let extractor = Extractor::builder()
+        .include_sources(true)
+        .include_raw_scores(true)
+        .title_candidates(true)
+        .build()
+        .unwrap();
+    let fixtures = common::fixtures();
+    let extract = |pdf: &[u8]| extractor.extract_bytes(pdf).unwrap().to_json_string(true).unwrap();
+    let expected: Vec<String> = fixtures.iter().map(|(_, pdf)| extract(pdf)).collect();
+
+    thread::scope(|scope| {
+        let runs: Vec<_> = (0..THREADS)
+            .map(|n| {
+                let (fixtures, expected, extract) = (&fixtures, &expected, &extract);
+                scope.spawn(move || {
+                    // Each thread starts on a different fixture, so different
+                    // documents are in progress at the same time
+                    for i in (0..fixtures.len()).map(|i| (i + n) % fixtures.len()) {
+                        assert_eq!(extract(&fixtures[i].1), expected[i],
+                            "{}: the outline differs when extracted alongside other documents", fixtures[i].0);
+                    }
+                })
+            })
+            .collect();
+        for run in runs {
+            run.join().unwrap();
+        }
+    });
+}
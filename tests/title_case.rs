@@ -0,0 +1,69 @@
+// A line in title case is a heading: every word but the stopwords
+// capitalized, and the first and last words too. Hyphenated compounds go by
+// their first part and acronyms count as capitalized. Ordinary sentences,
+// however many capitals they carry, are not.
+
+use adobe1a::config::ExtractorConfig;
+use adobe1a::lexicon::Lexicon;
+use adobe1a::{analyze_text, TextOptions};
+
+const BODY: &str = "The harbour board met to review the year's work on the quay walls and the channel.";
+
+// Whether `line`, standing on its own between paragraphs, is a heading
+fn is_heading(line: &str) -> bool {
+    is_heading_with(line, Lexicon::default())
+}
+
+fn is_heading_with(line: &str, lexicon: Lexicon) -> bool {
+    let text = format!("Harbour Development Plan\n\n{}\n\n{}\n\n{}\n\n{}\n", BODY, line, BODY, BODY);
+    let config = ExtractorConfig { page_heading_limit: 0, lexicon, ..ExtractorConfig::default() };
+    analyze_text(&text, TextOptions { config, ..TextOptions::default() }).outline.iter().any(|heading| heading.text == line)
+}
+
+#[test]
+fn title_case_headings() {
+    for line in [
+        "Scope of the Assessment",
+        "Terms and Conditions",
+        "Roles and Responsibilities",
+        "Risks to the Delivery Programme",
+        "Long-term Maintenance Plans",
+        "State-of-the-Art Dredging",
+        "Review of GIS Data",
+        "Funding for the New Ferry Berth",
+        "Lessons Learned from the Pilot",
+        "Quay Walls and Mooring Points",
+        "Environmental Impact of Dredging",
+        "Appointment of the Harbour Master",
+    ] {
+        assert!(is_heading(line), "{:?} should be a heading", line);
+    }
+}
+
+#[test]
+fn ordinary_sentences() {
+    for line in [
+        "The board met in March to agree the plan.",
+        "Work on the quay walls starts in the spring",
+        "Dredging costs rose by a third last year",
+        "Most of the buoys were replaced after the storms",
+        "We asked the port users what they needed",
+        "Please send comments to the harbour office",
+        "The channel was surveyed by the Navy in May",
+        "Ferry services will run as normal",
+        "Residents raised concerns about noise at night",
+        "All contractors must hold a valid permit",
+        "Repairs to the Pier were finished on time",
+        "Several options were considered by the board",
+    ] {
+        assert!(!is_heading(line), "{:?} should not be a heading", line);
+    }
+}
+
+#[test]
+fn stopwords_come_from_the_lexicon() {
+    let line = "Scope of the Assessment";
+    let mut lexicon = Lexicon::default();
+    lexicon.stopwords.retain(|word| word != "of");
+    assert!(!is_heading_with(line, lexicon));
+}
@@ -0,0 +1,64 @@
+// When nothing on the first page reads as a title, the title is made from
+// the file name, not a placeholder. Text lopdf can't decode, which it marks
+// "?Identity-H Unimplemented?", is never taken for the title.
+
+mod common;
+
+use adobe1a::config::{Engine, ExtractorConfig};
+use adobe1a::{extract_outline, extract_outline_from_bytes, timing::Stats, Outline, TitleSource};
+use common::pdf_builder::{self, Page, PdfBuilder, Style};
+use lopdf::{dictionary, Object, Stream};
+
+// The font engine scores the first page's lines, as lopdf reads them, for the title
+fn extract(pdf: &[u8], name: &str) -> Outline {
+    let config = ExtractorConfig { engine: Engine::Font, title_candidates: true, ..ExtractorConfig::default() };
+    extract_outline_from_bytes(pdf, name, &config, &mut Stats::new()).unwrap()
+}
+
+#[test]
+fn an_untitled_document_is_named_after_its_file() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-title-fallback-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("final_RFP_v3_CLEAN_(2).pdf");
+    // Nothing but a page number
+    let page = Page::new().text_at(Style::BODY, 300.0, 36.0, "12");
+    std::fs::write(&path, PdfBuilder::new().page(page).build()).unwrap();
+    let outline = extract_outline(&path, &ExtractorConfig::default(), &mut Stats::new()).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!((outline.title.as_str(), outline.title_source), ("RFP", TitleSource::Filename));
+}
+
+#[test]
+fn undecodable_text_is_not_taken_for_the_title() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    // A large line in a composite font lopdf has no decoder for, above body text
+    let mut doc = PdfBuilder::new().page(Page::new().body(3)).document();
+    let page = *doc.get_pages().values().next().unwrap();
+    let descriptor = doc.add_object(dictionary! {
+        "Type" => "FontDescriptor", "FontName" => "NotoSansCJK", "Flags" => 4,
+        "FontBBox" => vec![0.into(), (-120).into(), 1000.into(), 880.into()],
+        "ItalicAngle" => 0, "Ascent" => 880, "Descent" => -120, "CapHeight" => 730, "StemV" => 80,
+    });
+    let descendant = doc.add_object(dictionary! {
+        "Type" => "Font", "Subtype" => "CIDFontType2", "BaseFont" => "NotoSansCJK", "DW" => 1000,
+        "FontDescriptor" => descriptor,
+        "CIDSystemInfo" => dictionary! {
+            "Registry" => Object::string_literal("Adobe"), "Ordering" => Object::string_literal("Identity"), "Supplement" => 0,
+        },
+    });
+    let font = doc.add_object(dictionary! {
+        "Type" => "Font", "Subtype" => "Type0", "BaseFont" => "NotoSansCJK", "Encoding" => "Identity-H",
+        "DescendantFonts" => vec![descendant.into()],
+    });
+    let resources = doc.get_dictionary(page).unwrap().get(b"Resources").and_then(Object::as_reference).unwrap();
+    doc.get_dictionary_mut(resources).unwrap().get_mut(b"Font").and_then(Object::as_dict_mut).unwrap().set("CJK", font);
+    let heading = doc.add_object(Stream::new(dictionary! {}, b"BT /CJK 28 Tf 72 750 Td <00410042> Tj ET".to_vec()));
+    let body = doc.get_dictionary(page).unwrap().get(b"Contents").unwrap().clone();
+    doc.get_dictionary_mut(page).unwrap().set("Contents", vec![heading.into(), body]);
+
+    let outline = extract(&pdf_builder::save(&mut doc), "site-survey");
+    assert!(!outline.title.contains("Unimplemented"), "{}", outline.title);
+    assert!(outline.title_candidates.iter().all(|candidate| !candidate.text.contains("Unimplemented")));
+}
@@ -0,0 +1,107 @@
+// The first page's lines are scored as titles on their own, and the best of
+// them join the largest text and the metadata titles as the outline's title
+// candidates: at most five distinct texts, best first.
+
+mod common;
+
+use adobe1a::config::ExtractorConfig;
+use adobe1a::lexicon::Lexicon;
+use adobe1a::titles::extract_document_title;
+use adobe1a::{extract_outline_from_bytes, timing::Stats, Outline};
+use common::pdf_builder::{Page, PdfBuilder, Style};
+
+fn scores(lines: &[&str], all_caps: bool) -> Vec<(String, i32)> {
+    extract_document_title(lines, &Lexicon::default(), all_caps)
+}
+
+fn score(scored: &[(String, i32)], line: &str) -> Option<i32> {
+    scored.iter().find(|(text, _)| text == line).map(|(_, score)| *score)
+}
+
+#[test]
+fn a_title_case_line_near_the_top_scores_best() {
+    let scored = scores(&[
+        "Harbour Authority",
+        "Annual Report on Harbour Operations",
+        "This report sets out the work done in the year and what it cost the authority.",
+    ], false);
+    assert_eq!(scored[0].0, "Annual Report on Harbour Operations");
+    assert!(scored.windows(2).all(|pair| pair[0].1 >= pair[1].1), "{:?}", scored);
+}
+
+#[test]
+fn page_furniture_and_addresses_are_never_titles() {
+    let scored = scores(&[
+        "Page 1 of 12",
+        "See https://harbour.example/plan",
+        "Visit www.harbour.example",
+        "Contact plans@harbour.example",
+        "© Harbour Authority 2024",
+        "Table of Contents",
+        "Harbour Development Plan",
+    ], false);
+    assert_eq!(scored.iter().map(|(text, _)| text.as_str()).collect::<Vec<_>>(), ["Harbour Development Plan"]);
+}
+
+#[test]
+fn lower_lines_score_less() {
+    let line = "Harbour Development Plan";
+    let first = score(&scores(&[line], false), line).unwrap();
+    let filler = ["-"; 10];
+    let tenth = score(&scores(&[&filler[..], &[line]].concat(), false), line).unwrap();
+    assert!(tenth < first, "{} at the top, {} tenth", first, tenth);
+}
+
+#[test]
+fn capitals_earn_nothing_in_an_all_caps_document() {
+    let line = "HARBOUR DEVELOPMENT PLAN";
+    let mixed = score(&scores(&[line], false), line).unwrap();
+    let all_caps = score(&scores(&[line], true), line).unwrap_or(0);
+    assert!(all_caps < mixed, "{} in mixed case, {} in capitals", mixed, all_caps);
+}
+
+#[test]
+fn nothing_scores_on_an_empty_page() {
+    assert!(scores(&[], false).is_empty());
+    assert!(scores(&["", "  ", "3"], false).is_empty());
+}
+
+fn candidates(title_candidates: bool) -> Outline {
+    let pdf = PdfBuilder::new()
+        .info_title("Harbour Plan 2024")
+        .page(Page::new()
+            .title("Harbour Development Plan")
+            .line(Style::BODY, "Prepared for the Harbour Authority Board")
+            .line(Style::BODY, "Strategy and Investment Programme")
+            .line(Style::BODY, "Ports and Waterways Directorate")
+            .line(Style::BODY, "Approved for Public Consultation")
+            .line(Style::BODY, "Issued Under the Harbours Act")
+            .body(3))
+        .build();
+    let config = ExtractorConfig { title_candidates, ..ExtractorConfig::default() };
+    extract_outline_from_bytes(&pdf, "plan", &config, &mut Stats::new()).unwrap()
+}
+
+#[test]
+fn candidates_come_from_every_source_best_first() {
+    let outline = candidates(true);
+    let found = &outline.title_candidates;
+    assert!(!found.is_empty() && found.len() <= 5, "{:?}", found);
+    assert!(found.windows(2).all(|pair| pair[0].score >= pair[1].score), "{:?}", found);
+    assert!(found.iter().all(|candidate| (0.0..=1.0).contains(&candidate.score)));
+    for source in ["heuristic", "metadata"] {
+        assert!(found.iter().any(|candidate| candidate.source == source), "no {} candidate in {:?}", source, found);
+    }
+    // The largest text is the heading line the heuristics also found, and
+    // each text is listed once
+    let mut texts: Vec<&str> = found.iter().map(|candidate| candidate.text.as_str()).collect();
+    texts.sort_unstable();
+    texts.dedup();
+    assert_eq!(texts.len(), found.len());
+    assert!(texts.contains(&"Harbour Development Plan"));
+}
+
+#[test]
+fn candidates_are_left_out_unless_asked_for() {
+    assert!(candidates(false).title_candidates.is_empty());
+}
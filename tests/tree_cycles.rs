@@ -0,0 +1,65 @@
+// The PDF's trees are walked with a guard: a page, bookmark or number tree
+// that loops back on itself is read once and noted in a `tree_cycle`
+// warning, instead of hanging the extraction or exhausting the stack.
+
+mod common;
+
+use adobe1a::{config::ExtractorConfig, extract_outline_from_bytes, timing::Stats, Outline};
+use common::pdf_builder::{self, Font, Page, PdfBuilder, Style};
+use lopdf::{dictionary, Object};
+
+// Two pages with a heading each, whose page tree lists itself among its
+// kids, whose bookmarks point at each other, and whose page label tree is
+// its own child
+fn looping() -> Vec<u8> {
+    let large = Style::new(Font::Helvetica, 24.0);
+    let mut doc = PdfBuilder::new()
+        .page(Page::new().styled_heading(large, "Introduction").body(4))
+        .page(Page::new().styled_heading(large, "Methods").body(4))
+        .bookmark(1, "Introduction", 1)
+        .bookmark(1, "Methods", 2)
+        .document();
+
+    let catalog = doc.catalog().unwrap().clone();
+    let pages = catalog.get(b"Pages").and_then(Object::as_reference).unwrap();
+    let page_tree = doc.get_dictionary_mut(pages).unwrap();
+    let mut kids = page_tree.get(b"Kids").and_then(Object::as_array).unwrap().clone();
+    kids.push(pages.into());
+    page_tree.set("Kids", kids);
+
+    let outlines = catalog.get(b"Outlines").and_then(Object::as_reference).unwrap();
+    let first = doc.get_dictionary(outlines).unwrap().get(b"First").and_then(Object::as_reference).unwrap();
+    let last = doc.get_dictionary(outlines).unwrap().get(b"Last").and_then(Object::as_reference).unwrap();
+    doc.get_dictionary_mut(last).unwrap().set("Next", first);
+
+    let labels = doc.new_object_id();
+    doc.objects.insert(labels, dictionary! {
+        "Nums" => vec![0.into(), dictionary! { "S" => "r" }.into()], "Kids" => vec![labels.into()],
+    }.into());
+    let root = doc.trailer.get(b"Root").and_then(Object::as_reference).unwrap();
+    doc.get_dictionary_mut(root).unwrap().set("PageLabels", labels);
+    pdf_builder::save(&mut doc)
+}
+
+fn extract(pdf: &[u8], config: &ExtractorConfig) -> Outline {
+    extract_outline_from_bytes(pdf, "loops", config, &mut Stats::new()).unwrap()
+}
+
+#[test]
+fn looping_trees_are_read_once_with_a_warning() {
+    let config = ExtractorConfig { quick: Some(15), ..ExtractorConfig::default() };
+    let outline = extract(&looping(), &config);
+    assert_eq!(outline.page_count, Some(2));
+    let headings: Vec<_> = outline.outline.iter().map(|h| (h.text.as_str(), h.page_label.as_deref())).collect();
+    assert_eq!(headings, [("Introduction", Some("i")), ("Methods", Some("ii"))]);
+    assert_eq!(outline.warnings.iter().filter(|w| w.code == "tree_cycle").count(), 3);
+}
+
+#[test]
+fn the_engines_get_the_repaired_page_tree() {
+    // Without bookmarks to go on
+    let outline = extract(&looping(), &ExtractorConfig::default());
+    assert_eq!(outline.page_count, Some(2));
+    assert!(outline.outline.iter().all(|heading| heading.page <= 2));
+    assert!(outline.warnings.iter().any(|w| w.code == "tree_cycle"));
+}
@@ -0,0 +1,73 @@
+// `--print-tree` draws the outline as an indented tree, page numbers
+// right-aligned and long headings cut to the width with an ellipsis. Piped
+// output is 80 columns without colour.
+
+mod common;
+
+use std::process::Command;
+use adobe1a::output::{render_tree, TreeStyle};
+use adobe1a::{Heading, Outline};
+
+fn outline() -> Outline {
+    let heading = |level: &str, text: &str, page: usize, confidence: f64| {
+        Heading { level: level.into(), text: text.into(), page, confidence, ..Default::default() }
+    };
+    Outline {
+        title: "Harbour Development Plan".into(),
+        outline: vec![
+            heading("H1", "1. Introduction", 1, 0.9),
+            heading("H2", "1.1 Scope", 1, 0.7),
+            heading("H3", "1.1.1 Quay Walls, Channel Dredging, Pilot Station and the New Ferry Berth", 2, 0.5),
+            heading("H2", "1.2 Funding", 3, 0.8),
+            heading("H1", "2. Delivery", 12, 0.9),
+            // Two levels down from its parent hangs one level below it
+            heading("H3", "2.1.1 Phasing", 120, 0.6),
+        ],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn the_tree_without_colour() {
+    let tree = render_tree(&outline(), &TreeStyle { width: 60, color: false });
+    assert_eq!(tree, "\
+Harbour Development Plan
+├── 1. Introduction                                     p. 1
+│   ├── 1.1 Scope                                       p. 1
+│   │   └── 1.1.1 Quay Walls, Channel Dredging, Pilot…  p. 2
+│   └── 1.2 Funding                                     p. 3
+└── 2. Delivery                                        p. 12
+    └── 2.1.1 Phasing                                 p. 120
+");
+}
+
+#[test]
+fn colour_follows_confidence() {
+    let tree = render_tree(&outline(), &TreeStyle { width: 60, color: true });
+    assert!(tree.contains("\x1b[32m1. Introduction\x1b[0m"));
+    assert!(tree.contains("\x1b[33m1.1 Scope\x1b[0m"));
+    assert!(tree.contains("\x1b[31m1.1.1 Quay"));
+}
+
+#[test]
+fn piped_output_is_plain() {
+    let dir = std::env::temp_dir().join(format!("adobe1a-tree-printout-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("report.pdf");
+    std::fs::write(&input, common::numbered_report()).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_adobe1a"))
+        .arg("--input").arg(&input)
+        .args(["--print-tree", "--no-output-file"])
+        .env_remove("NO_COLOR")
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let outline = adobe1a::Extractor::builder().build().unwrap().extract_bytes(&common::numbered_report()).unwrap();
+    // The tree, then the usual line saying the file was processed
+    let tree = render_tree(&outline, &TreeStyle { width: 80, color: false });
+    assert!(stdout.starts_with(&tree), "{}", stdout);
+    assert!(!stdout.contains('\x1b'));
+}
@@ -0,0 +1,96 @@
+// A heading with a rule under it, about as wide as its text, gains
+// `underline_bonus`. Rules stacked close together are a table's grid, and a
+// rule running far past its line's text is a divider; neither underlines.
+
+mod common;
+
+use adobe1a::config::Engine;
+use adobe1a::{Extractor, ExtractorBuilder, Outline};
+use common::pdf_builder::{Font, Page, PdfBuilder, Style};
+
+const ROWS: [&str; 4] = [
+    "Harbour Dredging Works",
+    "Quay Wall Repairs",
+    "Channel Buoy Renewal",
+    "Pilot Boat Refit",
+];
+
+fn report() -> Vec<u8> {
+    let bold = Style::new(Font::HelveticaBold, 12.0);
+    // A table of bold rows, each over a rule as wide as its text
+    let table = ROWS.iter().enumerate().fold(Page::new(), |page, (i, row)| {
+        let y = 300.0 - 18.0 * i as f64;
+        page.text_at(bold, 72.0, y, row)
+            .rect(72.0, y - 4.0, row.len() as f64 * 6.0, 1.0)
+    });
+    let page = table
+        .underlined(bold, "Project Background")
+        .body(4)
+        .underlined(bold, "Delivery Approach")
+        .body(4)
+        // A divider across the page under a short line
+        .text_at(bold, 72.0, 400.0, "Site Access")
+        .rect(72.0, 396.0, 468.0, 1.0);
+    PdfBuilder::new().page(page).build()
+}
+
+fn extract(builder: ExtractorBuilder) -> Outline {
+    builder
+        .engine(Engine::Font)
+        .include_raw_scores(true)
+        .build()
+        .unwrap()
+        .extract_bytes(&report())
+        .unwrap()
+}
+
+fn confidence(outline: &Outline, text: &str) -> Option<f64> {
+    outline
+        .outline
+        .iter()
+        .find(|heading| heading.text == text)
+        .map(|heading| heading.confidence)
+}
+
+#[test]
+fn an_underlined_heading_gains_the_bonus() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let (with, without) = (
+        extract(Extractor::builder()),
+        extract(Extractor::builder().underline_bonus(0.0)),
+    );
+    for heading in ["Project Background", "Delivery Approach"] {
+        let (with, without) = (
+            confidence(&with, heading).unwrap(),
+            confidence(&without, heading).unwrap(),
+        );
+        assert!(
+            (with - (without + 0.1).min(1.0)).abs() < 1e-9,
+            "{}: {} without the bonus, {} with it",
+            heading,
+            without,
+            with
+        );
+    }
+}
+
+#[test]
+fn table_rules_and_dividers_dont_underline() {
+    if !Engine::Font.is_built() {
+        return;
+    }
+    let (with, without) = (
+        extract(Extractor::builder()),
+        extract(Extractor::builder().underline_bonus(0.0)),
+    );
+    for line in ROWS.iter().chain(["Site Access"].iter()) {
+        assert_eq!(
+            confidence(&with, line).unwrap(),
+            confidence(&without, line).unwrap(),
+            "{}",
+            line
+        );
+    }
+}
@@ -0,0 +1,39 @@
+// Extraction reports what a PDF's XMP packet records as the output's
+// `metadata`, falling back on the info dictionary for what it leaves out,
+// and lists the XMP title among the title candidates. A packet that can't
+// be read is a warning.
+
+mod common;
+
+use adobe1a::{config::ExtractorConfig, extract_outline_from_bytes, timing::Stats, Outline};
+use common::pdf_builder::{Font, Page, PdfBuilder, Style};
+
+const PACKET: &str = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <rdf:Description><dc:title><rdf:Alt><rdf:li xml:lang="fr">Rapport de Gestion</rdf:li></rdf:Alt></dc:title></rdf:Description>
+</rdf:RDF>"#;
+
+fn extract(packet: &str) -> Outline {
+    let pdf = PdfBuilder::new()
+        .page(Page::new().styled_heading(Style::new(Font::Helvetica, 24.0), "Introduction"))
+        .info_author("Records Office")
+        .xmp(packet)
+        .build();
+    let config = ExtractorConfig { title_candidates: true, ..ExtractorConfig::default() };
+    extract_outline_from_bytes(&pdf, "rapport", &config, &mut Stats::new()).unwrap()
+}
+
+#[test]
+fn the_xmp_title_is_reported_and_a_candidate() {
+    let outline = extract(PACKET);
+    assert_eq!(outline.metadata.title.as_deref(), Some("Rapport de Gestion"));
+    assert!(outline.title_candidates.iter().any(|c| c.text == "Rapport de Gestion" && c.source == "xmp"));
+    // The packet names no creator, so the info dictionary's author is used
+    assert_eq!(outline.metadata.authors, ["Records Office"]);
+}
+
+#[test]
+fn a_malformed_packet_is_a_warning() {
+    let outline = extract("<rdf:RDF><dc:title>");
+    assert!(outline.warnings.iter().any(|w| w.code == "malformed_xmp"));
+    assert_eq!(outline.metadata.authors, ["Records Office"]);
+}